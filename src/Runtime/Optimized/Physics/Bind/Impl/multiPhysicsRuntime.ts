@@ -27,6 +27,9 @@ export interface IMultiPhysicsRuntimeCreationOptions {
      * Whether to allow dynamic rigid body shadows (default: false)
      *
      * If disabled, rigid body shadow creation will be allowed only if the rigid body physics mode is set to Static or Kinematic
+     *
+     * Dynamic shadows read the motion states of another world, so if enabled, worlds are stepped in parallel only while the motion state buffer is used
+     * (buffered evaluation or `preserveBackBuffer`), otherwise they are stepped one by one
      */
     allowDynamicShadow?: boolean;
 
@@ -33,7 +33,10 @@ export const enum Constants {
  * additionalDamping: uint8 : offset 128
  * noContactResponse: uint8 : offset 129
  * disableDeactivation: uint8 : offset 130
- * padding: uint8[13] : offset 131
+ * padding: uint8[1] : offset 131
+ * ccdMotionThreshold: float32 : offset 132
+ * ccdSweptSphereRadius: float32 : offset 136
+ * padding: uint8[4] : offset 140
  *
  * --size: 144
  */
@@ -54,7 +57,9 @@ export const enum RigidBodyConstructionInfoOffsets {
     CollisionMask = 0x7E,
     AdditionalDamping = 0x80,
     NoContactResponse = 0x81,
-    DisableDeactivation = 0x82
+    DisableDeactivation = 0x82,
+    CcdMotionThreshold = 0x84,
+    CcdSweptSphereRadius = 0x88
 }
 
 
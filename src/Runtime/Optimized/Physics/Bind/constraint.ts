@@ -125,6 +125,32 @@ export abstract class Constraint {
         return this._inner.ptr;
     }
 
+    /**
+     * Enables or disables the constraint
+     *
+     * Disabled constraints are skipped by the solver but stay in the world
+     *
+     * If the constraint is added to a physics world, this operation will wait for the world evaluation to finish
+     * @param enabled true to enable the constraint, false to disable it
+     */
+    public setEnabled(enabled: boolean): void {
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        this.runtime.wasmInstance.constraintSetEnabled(this._inner.ptr, enabled);
+    }
+
+    /**
+     * Gets whether the constraint is enabled
+     * @returns true if the constraint is enabled
+     */
+    public isEnabled(): boolean {
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        return this.runtime.wasmInstance.constraintIsEnabled(this._inner.ptr);
+    }
+
     /**
      * @internal
      */
@@ -159,6 +185,7 @@ export abstract class Constraint {
 }
 
 const MatrixBufferSize = 16 * Constants.A32BytesPerElement;
+const Vector3BufferSize = 3 * Constants.A32BytesPerElement;
 
 export const enum ConstraintParams {
     ConstraintERP = 1,
@@ -467,3 +494,339 @@ export class Generic6DofSpringConstraint extends Generic6DofConstraintBase {
         this.runtime.wasmInstance.constraintSetDamping(this._inner.ptr, index, damping);
     }
 }
+
+/**
+ * Point2PointConstraint is a ball-and-socket joint that keeps the pivot points of two rigid bodies at the same world position
+ */
+export class Point2PointConstraint extends Constraint {
+    /**
+     * Creates a new Point2PointConstraint
+     * @param runtime physics runtime
+     * @param bodyA rigid body A
+     * @param bodyB rigid body B
+     * @param pivotInA pivot in local space of body A
+     * @param pivotInB pivot in local space of body B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyA: RigidBody,
+        bodyB: RigidBody,
+        pivotInA: DeepImmutable<Vector3>,
+        pivotInB: DeepImmutable<Vector3>
+    );
+
+    /**
+     * Creates a new Point2PointConstraint
+     * @param runtime physics runtime
+     * @param bodyBundle rigid body bundle
+     * @param bodyIndices indices of the rigid bodies in the bundle
+     * @param pivotInA pivot in local space of body A
+     * @param pivotInB pivot in local space of body B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyBundle: RigidBodyBundle,
+        bodyIndices: readonly [number, number],
+        pivotInA: DeepImmutable<Vector3>,
+        pivotInB: DeepImmutable<Vector3>
+    );
+
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyAOrBundle: RigidBody | RigidBodyBundle,
+        bodyBOrIndices: RigidBody | readonly [number, number],
+        pivotInA: DeepImmutable<Vector3>,
+        pivotInB: DeepImmutable<Vector3>
+    ) {
+        const wasmInstance = runtime.wasmInstance;
+        const pivotInABufferPtr = wasmInstance.allocateBuffer(Vector3BufferSize);
+        const pivotInABuffer = wasmInstance.createTypedArray(Float32Array, pivotInABufferPtr, Vector3BufferSize / Constants.A32BytesPerElement);
+        pivotInA.toArray(pivotInABuffer.array);
+
+        const pivotInBBufferPtr = wasmInstance.allocateBuffer(Vector3BufferSize);
+        const pivotInBBuffer = wasmInstance.createTypedArray(Float32Array, pivotInBBufferPtr, Vector3BufferSize / Constants.A32BytesPerElement);
+        pivotInB.toArray(pivotInBBuffer.array);
+
+        const isBundleParam = Array.isArray(bodyBOrIndices);
+
+        const ptr = isBundleParam
+            ? wasmInstance.createPoint2PointConstraintFromBundle(
+                bodyAOrBundle.ptr,
+                bodyBOrIndices[0],
+                bodyBOrIndices[1],
+                pivotInABufferPtr,
+                pivotInBBufferPtr
+            )
+            : wasmInstance.createPoint2PointConstraint(
+                bodyAOrBundle.ptr,
+                (bodyBOrIndices as RigidBody).ptr,
+                pivotInABufferPtr,
+                pivotInBBufferPtr
+            );
+
+        wasmInstance.deallocateBuffer(pivotInABufferPtr, Vector3BufferSize);
+        wasmInstance.deallocateBuffer(pivotInBBufferPtr, Vector3BufferSize);
+
+        const bodyReference = isBundleParam
+            ? (bodyAOrBundle as RigidBodyBundle)
+            : [bodyAOrBundle as RigidBody, bodyBOrIndices as RigidBody] as const;
+
+        super(runtime, ptr, bodyReference);
+    }
+}
+
+/**
+ * ConeTwistConstraint is a constraint that limits the swing and twist of body B around the x axis of the constraint frame
+ *
+ * It is useful for shoulders and other ragdoll joints
+ */
+export class ConeTwistConstraint extends Constraint {
+    /**
+     * Creates a new ConeTwistConstraint
+     * @param runtime physics runtime
+     * @param bodyA rigid body A
+     * @param bodyB rigid body B
+     * @param frameA local frame A
+     * @param frameB local frame B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyA: RigidBody,
+        bodyB: RigidBody,
+        frameA: Matrix,
+        frameB: Matrix
+    );
+
+    /**
+     * Creates a new ConeTwistConstraint
+     * @param runtime physics runtime
+     * @param bodyBundle rigid body bundle
+     * @param bodyIndices indices of the rigid bodies in the bundle
+     * @param frameA local frame A
+     * @param frameB local frame B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyBundle: RigidBodyBundle,
+        bodyIndices: readonly [number, number],
+        frameA: Matrix,
+        frameB: Matrix
+    );
+
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyAOrBundle: RigidBody | RigidBodyBundle,
+        bodyBOrIndices: RigidBody | readonly [number, number],
+        frameA: Matrix,
+        frameB: Matrix
+    ) {
+        const wasmInstance = runtime.wasmInstance;
+        const frameABufferPtr = wasmInstance.allocateBuffer(MatrixBufferSize);
+        const frameABuffer = wasmInstance.createTypedArray(Float32Array, frameABufferPtr, MatrixBufferSize / Constants.A32BytesPerElement);
+        frameA.copyToArray(frameABuffer.array);
+
+        const frameBBufferPtr = wasmInstance.allocateBuffer(MatrixBufferSize);
+        const frameBBuffer = wasmInstance.createTypedArray(Float32Array, frameBBufferPtr, MatrixBufferSize / Constants.A32BytesPerElement);
+        frameB.copyToArray(frameBBuffer.array);
+
+        const isBundleParam = Array.isArray(bodyBOrIndices);
+
+        const ptr = isBundleParam
+            ? wasmInstance.createConeTwistConstraintFromBundle(
+                bodyAOrBundle.ptr,
+                bodyBOrIndices[0],
+                bodyBOrIndices[1],
+                frameABufferPtr,
+                frameBBufferPtr
+            )
+            : wasmInstance.createConeTwistConstraint(
+                bodyAOrBundle.ptr,
+                (bodyBOrIndices as RigidBody).ptr,
+                frameABufferPtr,
+                frameBBufferPtr
+            );
+
+        wasmInstance.deallocateBuffer(frameABufferPtr, MatrixBufferSize);
+        wasmInstance.deallocateBuffer(frameBBufferPtr, MatrixBufferSize);
+
+        const bodyReference = isBundleParam
+            ? (bodyAOrBundle as RigidBodyBundle)
+            : [bodyAOrBundle as RigidBody, bodyBOrIndices as RigidBody] as const;
+
+        super(runtime, ptr, bodyReference);
+    }
+}
+
+/**
+ * SliderConstraint is a constraint that allows body B to slide along and rotate around the x axis of the constraint frame
+ */
+export class SliderConstraint extends Constraint {
+    /**
+     * Creates a new SliderConstraint
+     * @param runtime physics runtime
+     * @param bodyA rigid body A
+     * @param bodyB rigid body B
+     * @param frameA local frame A
+     * @param frameB local frame B
+     * @param useLinearReferenceFrameA if true, the linear reference frame is set to body A, otherwise it is set to body B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyA: RigidBody,
+        bodyB: RigidBody,
+        frameA: Matrix,
+        frameB: Matrix,
+        useLinearReferenceFrameA: boolean
+    );
+
+    /**
+     * Creates a new SliderConstraint
+     * @param runtime physics runtime
+     * @param bodyBundle rigid body bundle
+     * @param bodyIndices indices of the rigid bodies in the bundle
+     * @param frameA local frame A
+     * @param frameB local frame B
+     * @param useLinearReferenceFrameA if true, the linear reference frame is set to body A, otherwise it is set to body B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyBundle: RigidBodyBundle,
+        bodyIndices: readonly [number, number],
+        frameA: Matrix,
+        frameB: Matrix,
+        useLinearReferenceFrameA: boolean
+    );
+
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyAOrBundle: RigidBody | RigidBodyBundle,
+        bodyBOrIndices: RigidBody | readonly [number, number],
+        frameA: Matrix,
+        frameB: Matrix,
+        useLinearReferenceFrameA: boolean
+    ) {
+        const wasmInstance = runtime.wasmInstance;
+        const frameABufferPtr = wasmInstance.allocateBuffer(MatrixBufferSize);
+        const frameABuffer = wasmInstance.createTypedArray(Float32Array, frameABufferPtr, MatrixBufferSize / Constants.A32BytesPerElement);
+        frameA.copyToArray(frameABuffer.array);
+
+        const frameBBufferPtr = wasmInstance.allocateBuffer(MatrixBufferSize);
+        const frameBBuffer = wasmInstance.createTypedArray(Float32Array, frameBBufferPtr, MatrixBufferSize / Constants.A32BytesPerElement);
+        frameB.copyToArray(frameBBuffer.array);
+
+        const isBundleParam = Array.isArray(bodyBOrIndices);
+
+        const ptr = isBundleParam
+            ? wasmInstance.createSliderConstraintFromBundle(
+                bodyAOrBundle.ptr,
+                bodyBOrIndices[0],
+                bodyBOrIndices[1],
+                frameABufferPtr,
+                frameBBufferPtr,
+                useLinearReferenceFrameA
+            )
+            : wasmInstance.createSliderConstraint(
+                bodyAOrBundle.ptr,
+                (bodyBOrIndices as RigidBody).ptr,
+                frameABufferPtr,
+                frameBBufferPtr,
+                useLinearReferenceFrameA
+            );
+
+        wasmInstance.deallocateBuffer(frameABufferPtr, MatrixBufferSize);
+        wasmInstance.deallocateBuffer(frameBBufferPtr, MatrixBufferSize);
+
+        const bodyReference = isBundleParam
+            ? (bodyAOrBundle as RigidBodyBundle)
+            : [bodyAOrBundle as RigidBody, bodyBOrIndices as RigidBody] as const;
+
+        super(runtime, ptr, bodyReference);
+    }
+}
+
+/**
+ * HingeConstraint is a constraint that allows body B to rotate only around the z axis of the constraint frame
+ */
+export class HingeConstraint extends Constraint {
+    /**
+     * Creates a new HingeConstraint
+     * @param runtime physics runtime
+     * @param bodyA rigid body A
+     * @param bodyB rigid body B
+     * @param frameA local frame A
+     * @param frameB local frame B
+     * @param useReferenceFrameA if true, the reference frame is set to body A, otherwise it is set to body B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyA: RigidBody,
+        bodyB: RigidBody,
+        frameA: Matrix,
+        frameB: Matrix,
+        useReferenceFrameA: boolean
+    );
+
+    /**
+     * Creates a new HingeConstraint
+     * @param runtime physics runtime
+     * @param bodyBundle rigid body bundle
+     * @param bodyIndices indices of the rigid bodies in the bundle
+     * @param frameA local frame A
+     * @param frameB local frame B
+     * @param useReferenceFrameA if true, the reference frame is set to body A, otherwise it is set to body B
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyBundle: RigidBodyBundle,
+        bodyIndices: readonly [number, number],
+        frameA: Matrix,
+        frameB: Matrix,
+        useReferenceFrameA: boolean
+    );
+
+    public constructor(
+        runtime: IPhysicsRuntime,
+        bodyAOrBundle: RigidBody | RigidBodyBundle,
+        bodyBOrIndices: RigidBody | readonly [number, number],
+        frameA: Matrix,
+        frameB: Matrix,
+        useReferenceFrameA: boolean
+    ) {
+        const wasmInstance = runtime.wasmInstance;
+        const frameABufferPtr = wasmInstance.allocateBuffer(MatrixBufferSize);
+        const frameABuffer = wasmInstance.createTypedArray(Float32Array, frameABufferPtr, MatrixBufferSize / Constants.A32BytesPerElement);
+        frameA.copyToArray(frameABuffer.array);
+
+        const frameBBufferPtr = wasmInstance.allocateBuffer(MatrixBufferSize);
+        const frameBBuffer = wasmInstance.createTypedArray(Float32Array, frameBBufferPtr, MatrixBufferSize / Constants.A32BytesPerElement);
+        frameB.copyToArray(frameBBuffer.array);
+
+        const isBundleParam = Array.isArray(bodyBOrIndices);
+
+        const ptr = isBundleParam
+            ? wasmInstance.createHingeConstraintFromBundle(
+                bodyAOrBundle.ptr,
+                bodyBOrIndices[0],
+                bodyBOrIndices[1],
+                frameABufferPtr,
+                frameBBufferPtr,
+                useReferenceFrameA
+            )
+            : wasmInstance.createHingeConstraint(
+                bodyAOrBundle.ptr,
+                (bodyBOrIndices as RigidBody).ptr,
+                frameABufferPtr,
+                frameBBufferPtr,
+                useReferenceFrameA
+            );
+
+        wasmInstance.deallocateBuffer(frameABufferPtr, MatrixBufferSize);
+        wasmInstance.deallocateBuffer(frameBBufferPtr, MatrixBufferSize);
+
+        const bodyReference = isBundleParam
+            ? (bodyAOrBundle as RigidBodyBundle)
+            : [bodyAOrBundle as RigidBody, bodyBOrIndices as RigidBody] as const;
+
+        super(runtime, ptr, bodyReference);
+    }
+}
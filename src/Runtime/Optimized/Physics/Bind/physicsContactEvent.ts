@@ -0,0 +1,156 @@
+import { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
+import type { Nullable } from "@babylonjs/core/types";
+
+import type { IBulletWasmInstance } from "./bulletWasmInstance";
+import { Constants } from "./constants";
+import type { PhysicsQueryBodyMap } from "./physicsQuery";
+import type { RigidBody } from "./rigidBody";
+import type { RigidBodyBundle } from "./rigidBodyBundle";
+
+/**
+ * State of the contact pair
+ */
+export const enum PhysicsContactEventState {
+    /**
+     * Pair started touching in this step
+     */
+    Begin = 0,
+    /**
+     * Pair was touching in the previous step too
+     */
+    Persist = 1,
+    /**
+     * Pair stopped touching in this step
+     */
+    End = 2
+}
+
+/**
+ * Contact event between two bodies
+ *
+ * Bodies are null if they are not added through the world wrapper (e.g. bodies of the MMD models) or already removed
+ */
+export class PhysicsContactEvent {
+    /**
+     * First body of the pair
+     */
+    public bodyA: Nullable<RigidBody | RigidBodyBundle>;
+
+    /**
+     * Index of the first body in the rigid body bundle, -1 for rigid body
+     */
+    public bodyIndexA: number;
+
+    /**
+     * Second body of the pair
+     */
+    public bodyB: Nullable<RigidBody | RigidBodyBundle>;
+
+    /**
+     * Index of the second body in the rigid body bundle, -1 for rigid body
+     */
+    public bodyIndexB: number;
+
+    /**
+     * Deepest contact point on the second body in world space
+     */
+    public readonly point: Vector3;
+
+    /**
+     * Contact normal in world space, points from the second body to the first body
+     */
+    public readonly normal: Vector3;
+
+    /**
+     * Sum of the applied impulses over the sub steps of the step, zero for end event
+     */
+    public impulse: number;
+
+    /**
+     * State of the contact pair
+     */
+    public state: PhysicsContactEventState;
+
+    /**
+     * Creates a new empty contact event
+     */
+    public constructor() {
+        this.bodyA = null;
+        this.bodyIndexA = -1;
+        this.bodyB = null;
+        this.bodyIndexB = -1;
+        this.point = new Vector3();
+        this.normal = new Vector3();
+        this.impulse = 0;
+        this.state = PhysicsContactEventState.Begin;
+    }
+}
+
+/**
+ * ContactEvent representations
+ *
+ * ownerA: *uint32 : offset 0
+ * bodyIndexA: int32 : offset 4
+ * ownerB: *uint32 : offset 8
+ * bodyIndexB: int32 : offset 12
+ * point: float32[3] : offset 16
+ * normal: float32[3] : offset 28
+ * impulse: float32 : offset 40
+ * state: uint32 : offset 44
+ *
+ * --size: 48
+ */
+const enum ContactEventLayout {
+    Size = 48,
+    SizeInInt32Array = Size / Constants.A32BytesPerElement,
+    OwnerA = 0,
+    BodyIndexA = 1,
+    OwnerB = 2,
+    BodyIndexB = 3,
+    Point = 4,
+    Normal = 7,
+    Impulse = 10,
+    State = 11
+}
+
+/**
+ * Reads the contact event buffer of the wasm side
+ *
+ * @param wasmInstance bullet wasm instance
+ * @param ptr contact event buffer pointer
+ * @param count number of contact events
+ * @param bodyMap body map to resolve the event owners
+ * @returns contact events
+ *
+ * @internal
+ */
+export function ReadPhysicsContactEvents(
+    wasmInstance: IBulletWasmInstance,
+    ptr: number,
+    count: number,
+    bodyMap: PhysicsQueryBodyMap
+): PhysicsContactEvent[] {
+    if (ptr === 0 || count === 0) {
+        return [];
+    }
+
+    const length = count * ContactEventLayout.SizeInInt32Array;
+    const uint32Array = wasmInstance.createTypedArray(Uint32Array, ptr, length).array;
+    const int32Array = wasmInstance.createTypedArray(Int32Array, ptr, length).array;
+    const float32Array = wasmInstance.createTypedArray(Float32Array, ptr, length).array;
+
+    const events: PhysicsContactEvent[] = new Array(count);
+    for (let i = 0; i < count; ++i) {
+        const offset = i * ContactEventLayout.SizeInInt32Array;
+        const event = events[i] = new PhysicsContactEvent();
+        event.bodyA = bodyMap.get(uint32Array[offset + ContactEventLayout.OwnerA]);
+        event.bodyIndexA = int32Array[offset + ContactEventLayout.BodyIndexA];
+        event.bodyB = bodyMap.get(uint32Array[offset + ContactEventLayout.OwnerB]);
+        event.bodyIndexB = int32Array[offset + ContactEventLayout.BodyIndexB];
+        event.point.fromArray(float32Array, offset + ContactEventLayout.Point);
+        event.normal.fromArray(float32Array, offset + ContactEventLayout.Normal);
+        event.impulse = float32Array[offset + ContactEventLayout.Impulse];
+        event.state = uint32Array[offset + ContactEventLayout.State];
+    }
+    return events;
+}
@@ -0,0 +1,62 @@
+import type { IBulletWasmInstance } from "./bulletWasmInstance";
+
+/**
+ * Debug draw categories of the physics world, can be combined with bitwise or
+ */
+export const enum PhysicsDebugDrawCategory {
+    /**
+     * Debug draw is disabled
+     */
+    None = 0,
+    /**
+     * Collider wireframes
+     */
+    Wireframe = 1 << 0,
+    /**
+     * Collider axis aligned bounding boxes
+     */
+    Aabb = 1 << 1,
+    /**
+     * Contact points
+     */
+    ContactPoints = 1 << 2,
+    /**
+     * Constraint frames
+     */
+    ConstraintFrames = 1 << 3,
+    /**
+     * Constraint limits
+     */
+    ConstraintLimits = 1 << 4
+}
+
+/**
+ * Number of float32 elements per debug line, position xyz and color rgb of the two vertices
+ */
+export const PhysicsDebugLineStride = 12;
+
+/**
+ * Number of float32 elements per debug point, position xyz and color rgb
+ */
+export const PhysicsDebugPointStride = 6;
+
+/**
+ * Copies the debug draw buffer of the wasm side
+ *
+ * @param wasmInstance bullet wasm instance
+ * @param ptr debug draw buffer pointer
+ * @param length number of float32 elements
+ * @returns copied buffer which stays valid after the next step
+ *
+ * @internal
+ */
+export function ReadPhysicsDebugDrawBuffer(
+    wasmInstance: IBulletWasmInstance,
+    ptr: number,
+    length: number
+): Float32Array {
+    if (ptr === 0 || length === 0) {
+        return new Float32Array(0);
+    }
+    return wasmInstance.createTypedArray(Float32Array, ptr, length).array.slice();
+}
@@ -0,0 +1,169 @@
+import { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
+import type { DeepImmutable, Nullable } from "@babylonjs/core/types";
+
+import type { IBulletWasmInstance } from "./bulletWasmInstance";
+import { Constants } from "./constants";
+import type { RigidBody } from "./rigidBody";
+import type { RigidBodyBundle } from "./rigidBodyBundle";
+
+/**
+ * Result of the physics world query
+ */
+export class PhysicsQueryHit {
+    /**
+     * Whether something was hit
+     */
+    public hasHit: boolean;
+
+    /**
+     * Rigid body or rigid body bundle that was hit
+     *
+     * Null if nothing was hit or the body is not added through the world wrapper (e.g. bodies of the MMD models)
+     */
+    public body: Nullable<RigidBody | RigidBodyBundle>;
+
+    /**
+     * Index of the body in the rigid body bundle, -1 for rigid body
+     */
+    public bodyIndex: number;
+
+    /**
+     * Hit fraction along the ray or sweep, signed distance for contact test
+     */
+    public fraction: number;
+
+    /**
+     * Hit point in world space
+     */
+    public readonly point: Vector3;
+
+    /**
+     * Hit normal in world space
+     */
+    public readonly normal: Vector3;
+
+    /**
+     * Creates a new empty query hit
+     */
+    public constructor() {
+        this.hasHit = false;
+        this.body = null;
+        this.bodyIndex = -1;
+        this.fraction = 1;
+        this.point = new Vector3();
+        this.normal = new Vector3();
+    }
+}
+
+/**
+ * Resolves the body pointers reported by the wasm side to their owners
+ *
+ * The same body can be referenced multiple times when it is shadowed into multiple worlds
+ *
+ * @internal
+ */
+export class PhysicsQueryBodyMap {
+    private readonly _map: Map<number, [RigidBody | RigidBodyBundle, number]>;
+
+    public constructor() {
+        this._map = new Map<number, [RigidBody | RigidBodyBundle, number]>();
+    }
+
+    public add(body: RigidBody | RigidBodyBundle): void {
+        const entry = this._map.get(body.ptr);
+        if (entry !== undefined) {
+            entry[1] += 1;
+        } else {
+            this._map.set(body.ptr, [body, 1]);
+        }
+    }
+
+    public remove(body: RigidBody | RigidBodyBundle): void {
+        const entry = this._map.get(body.ptr);
+        if (entry === undefined) {
+            return;
+        }
+        entry[1] -= 1;
+        if (entry[1] === 0) {
+            this._map.delete(body.ptr);
+        }
+    }
+
+    public get(ptr: number): Nullable<RigidBody | RigidBodyBundle> {
+        return this._map.get(ptr)?.[0] ?? null;
+    }
+
+    public clear(): void {
+        this._map.clear();
+    }
+}
+
+/**
+ * Query hit size in float32 elements, fraction, point xyz and normal xyz
+ */
+const QueryHitSize = 7;
+
+/**
+ * Runs a query with temporary input and output buffers and reads back the hits
+ *
+ * @param wasmInstance bullet wasm instance
+ * @param inputs query input floats, copied to the wasm memory
+ * @param capacity maximum number of hits to read
+ * @param bodyMap body map to resolve the hit owners
+ * @param query performs the query and returns the number of hits written
+ * @returns query hits
+ *
+ * @internal
+ */
+export function RunPhysicsQuery(
+    wasmInstance: IBulletWasmInstance,
+    inputs: DeepImmutable<ArrayLike<number>>,
+    capacity: number,
+    bodyMap: PhysicsQueryBodyMap,
+    query: (inputsPtr: number, outBodiesPtr: number, outBodyIndicesPtr: number, outHitsPtr: number) => number
+): PhysicsQueryHit[] {
+    if (capacity === 0) {
+        return [];
+    }
+
+    // zero sized allocation is not allowed
+    const inputsSize = inputs.length * Constants.A32BytesPerElement;
+    const inputsPtr = inputsSize === 0 ? 0 : wasmInstance.allocateBuffer(inputsSize);
+    if (inputsPtr !== 0) {
+        wasmInstance.createTypedArray(Float32Array, inputsPtr, inputs.length).array.set(inputs);
+    }
+
+    const outBodiesSize = capacity * Constants.A32BytesPerElement;
+    const outBodiesPtr = wasmInstance.allocateBuffer(outBodiesSize);
+    const outBodyIndicesSize = capacity * Constants.A32BytesPerElement;
+    const outBodyIndicesPtr = wasmInstance.allocateBuffer(outBodyIndicesSize);
+    const outHitsSize = capacity * QueryHitSize * Constants.A32BytesPerElement;
+    const outHitsPtr = wasmInstance.allocateBuffer(outHitsSize);
+
+    const hitCount = query(inputsPtr, outBodiesPtr, outBodyIndicesPtr, outHitsPtr);
+
+    const outBodies = wasmInstance.createTypedArray(Uint32Array, outBodiesPtr, capacity).array;
+    const outBodyIndices = wasmInstance.createTypedArray(Int32Array, outBodyIndicesPtr, capacity).array;
+    const outHits = wasmInstance.createTypedArray(Float32Array, outHitsPtr, capacity * QueryHitSize).array;
+
+    const hits: PhysicsQueryHit[] = new Array(hitCount);
+    for (let i = 0; i < hitCount; ++i) {
+        const hit = hits[i] = new PhysicsQueryHit();
+        hit.hasHit = outBodies[i] !== 0;
+        hit.body = hit.hasHit ? bodyMap.get(outBodies[i]) : null;
+        hit.bodyIndex = outBodyIndices[i];
+        const offset = i * QueryHitSize;
+        hit.fraction = outHits[offset];
+        hit.point.set(outHits[offset + 1], outHits[offset + 2], outHits[offset + 3]);
+        hit.normal.set(outHits[offset + 4], outHits[offset + 5], outHits[offset + 6]);
+    }
+
+    if (inputsPtr !== 0) {
+        wasmInstance.deallocateBuffer(inputsPtr, inputsSize);
+    }
+    wasmInstance.deallocateBuffer(outBodiesPtr, outBodiesSize);
+    wasmInstance.deallocateBuffer(outBodyIndicesPtr, outBodyIndicesSize);
+    wasmInstance.deallocateBuffer(outHitsPtr, outHitsSize);
+
+    return hits;
+}
@@ -1,16 +1,25 @@
-import type { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
+import type { Matrix, Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
+import type { DeepImmutable, Nullable } from "@babylonjs/core/types";
 
 import type { IBulletWasmInstance } from "./bulletWasmInstance";
+import { Constants } from "./constants";
 import type { IPhysicsRuntime } from "./Impl/IPhysicsRuntime";
 
 class PhysicsShapeInner {
     private readonly _wasmInstance: WeakRef<IBulletWasmInstance>;
     private _ptr: number;
+    private _childReferences: Nullable<readonly PhysicsShape[]>;
     private _referenceCount: number;
 
-    public constructor(wasmInstance: WeakRef<IBulletWasmInstance>, ptr: number) {
+    public constructor(wasmInstance: WeakRef<IBulletWasmInstance>, ptr: number, childReferences: Nullable<readonly PhysicsShape[]>) {
         this._wasmInstance = wasmInstance;
         this._ptr = ptr;
+        this._childReferences = childReferences;
+        if (childReferences !== null) {
+            for (let i = 0; i < childReferences.length; ++i) {
+                childReferences[i].addReference();
+            }
+        }
         this._referenceCount = 0;
     }
 
@@ -27,6 +36,13 @@ class PhysicsShapeInner {
         this._wasmInstance.deref()?.destroyShape(this._ptr);
 
         this._ptr = 0;
+
+        if (this._childReferences !== null) {
+            for (let i = 0; i < this._childReferences.length; ++i) {
+                this._childReferences[i].removeReference();
+            }
+            this._childReferences = null;
+        }
     }
 
     public get ptr(): number {
@@ -56,9 +72,9 @@ export abstract class PhysicsShape {
 
     protected readonly _inner: PhysicsShapeInner;
 
-    protected constructor(runtime: IPhysicsRuntime, ptr: number) {
+    protected constructor(runtime: IPhysicsRuntime, ptr: number, childReferences: Nullable<readonly PhysicsShape[]> = null) {
         this.runtime = runtime;
-        this._inner = new PhysicsShapeInner(new WeakRef(runtime.wasmInstance), ptr);
+        this._inner = new PhysicsShapeInner(new WeakRef(runtime.wasmInstance), ptr, childReferences);
 
         let registry = PhysicsShapeRegistryMap.get(runtime.wasmInstance);
         if (registry === undefined) {
@@ -133,7 +149,7 @@ export class PhysicsSphereShape extends PhysicsShape {
 }
 
 /**
- * Cylinder shape
+ * Capsule shape
  */
 export class PhysicsCapsuleShape extends PhysicsShape {
     /**
@@ -163,3 +179,247 @@ export class PhysicsStaticPlaneShape extends PhysicsShape {
         super(runtime, ptr);
     }
 }
+
+/**
+ * Cylinder shape
+ */
+export class PhysicsCylinderShape extends PhysicsShape {
+    /**
+     * Creates a new cylinder shape
+     * @param runtime physics runtime
+     * @param halfExtents half extents of the cylinder shape
+     */
+    public constructor(runtime: IPhysicsRuntime, halfExtents: Vector3) {
+        const ptr = runtime.wasmInstance.createCylinderShape(halfExtents.x, halfExtents.y, halfExtents.z);
+        super(runtime, ptr);
+    }
+}
+
+/**
+ * Cone shape
+ */
+export class PhysicsConeShape extends PhysicsShape {
+    /**
+     * Creates a new cone shape
+     * @param runtime physics runtime
+     * @param radius radius of the cone shape
+     * @param height height of the cone shape
+     */
+    public constructor(runtime: IPhysicsRuntime, radius: number, height: number) {
+        const ptr = runtime.wasmInstance.createConeShape(radius, height);
+        super(runtime, ptr);
+    }
+}
+
+/**
+ * Convex hull shape
+ */
+export class PhysicsConvexHullShape extends PhysicsShape {
+    /**
+     * Creates a new convex hull shape
+     * @param runtime physics runtime
+     * @param points xyz array of the hull points
+     */
+    public constructor(runtime: IPhysicsRuntime, points: DeepImmutable<ArrayLike<number>>) {
+        if (points.length === 0 || points.length % 3 !== 0) {
+            throw new Error("Convex hull points must be non-empty xyz array");
+        }
+
+        const wasmInstance = runtime.wasmInstance;
+        const bufferSize = points.length * Constants.A32BytesPerElement;
+        const bufferPtr = wasmInstance.allocateBuffer(bufferSize);
+        const buffer = wasmInstance.createTypedArray(Float32Array, bufferPtr, points.length);
+        buffer.array.set(points);
+
+        const ptr = wasmInstance.createConvexHullShape(bufferPtr, points.length / 3);
+
+        wasmInstance.deallocateBuffer(bufferPtr, bufferSize);
+
+        super(runtime, ptr);
+    }
+}
+
+/**
+ * Child of the compound shape
+ */
+export interface IPhysicsCompoundShapeChild {
+    /**
+     * Child shape
+     *
+     * The child shape can not be disposed while the compound shape is alive
+     */
+    shape: PhysicsShape;
+
+    /**
+     * Transform of the child shape in the compound shape space
+     */
+    localTransform: DeepImmutable<Matrix>;
+}
+
+/**
+ * Compound shape
+ */
+export class PhysicsCompoundShape extends PhysicsShape {
+    /**
+     * Creates a new compound shape
+     * @param runtime physics runtime
+     * @param children child shapes and their local transforms
+     */
+    public constructor(runtime: IPhysicsRuntime, children: readonly IPhysicsCompoundShapeChild[]) {
+        const wasmInstance = runtime.wasmInstance;
+        const childCount = children.length;
+        if (childCount === 0) {
+            throw new Error("Compound shape must have at least one child");
+        }
+
+        for (let i = 0; i < childCount; ++i) {
+            const shape = children[i].shape;
+            if (shape.runtime.wasmInstance !== wasmInstance) {
+                throw new Error("Cannot create compound shape with shapes from different wasm instance");
+            }
+            if (shape.ptr === 0) {
+                throw new Error("Cannot create compound shape with disposed shape");
+            }
+        }
+
+        const shapeBufferSize = childCount * Constants.A32BytesPerElement;
+        const shapeBufferPtr = wasmInstance.allocateBuffer(shapeBufferSize);
+        const shapeBuffer = wasmInstance.createTypedArray(Uint32Array, shapeBufferPtr, childCount);
+
+        const transformBufferSize = childCount * 16 * Constants.A32BytesPerElement;
+        const transformBufferPtr = wasmInstance.allocateBuffer(transformBufferSize);
+        const transformBuffer = wasmInstance.createTypedArray(Float32Array, transformBufferPtr, childCount * 16);
+
+        const shapes: PhysicsShape[] = new Array(childCount);
+        for (let i = 0; i < childCount; ++i) {
+            const child = children[i];
+            shapes[i] = child.shape;
+            shapeBuffer.array[i] = child.shape.ptr;
+            child.localTransform.copyToArray(transformBuffer.array, i * 16);
+        }
+
+        const ptr = wasmInstance.createCompoundShape(shapeBufferPtr, transformBufferPtr, childCount);
+
+        wasmInstance.deallocateBuffer(shapeBufferPtr, shapeBufferSize);
+        wasmInstance.deallocateBuffer(transformBufferPtr, transformBufferSize);
+
+        super(runtime, ptr, shapes);
+    }
+}
+
+/**
+ * Triangle mesh shape
+ *
+ * Concave shape, it can only be used for static or kinematic rigid bodies
+ */
+export class PhysicsTriangleMeshShape extends PhysicsShape {
+    /**
+     * Creates a new triangle mesh shape
+     *
+     * @param runtime physics runtime
+     * @param vertices xyz array of the mesh vertices
+     * @param indices triangle list indices of the mesh
+     * @throws {Error} if the vertices are not xyz array or the indices are out of range
+     */
+    public constructor(runtime: IPhysicsRuntime, vertices: DeepImmutable<ArrayLike<number>>, indices: DeepImmutable<ArrayLike<number>>) {
+        if (vertices.length === 0 || vertices.length % 3 !== 0) {
+            throw new Error("Triangle mesh vertices must be non-empty xyz array");
+        }
+        if (indices.length === 0 || indices.length % 3 !== 0) {
+            throw new Error("Triangle mesh indices must be non-empty triangle list");
+        }
+
+        const wasmInstance = runtime.wasmInstance;
+
+        const vertexBufferSize = vertices.length * Constants.A32BytesPerElement;
+        const vertexBufferPtr = wasmInstance.allocateBuffer(vertexBufferSize);
+        wasmInstance.createTypedArray(Float32Array, vertexBufferPtr, vertices.length).array.set(vertices);
+
+        const indexBufferSize = indices.length * Constants.A32BytesPerElement;
+        const indexBufferPtr = wasmInstance.allocateBuffer(indexBufferSize);
+        wasmInstance.createTypedArray(Uint32Array, indexBufferPtr, indices.length).array.set(indices);
+
+        let ptr: number;
+        try {
+            ptr = wasmInstance.createTriangleMeshShape(vertexBufferPtr, vertices.length / 3, indexBufferPtr, indices.length);
+        } finally {
+            wasmInstance.deallocateBuffer(vertexBufferPtr, vertexBufferSize);
+            wasmInstance.deallocateBuffer(indexBufferPtr, indexBufferSize);
+        }
+
+        super(runtime, ptr);
+    }
+}
+
+/**
+ * Scaled triangle mesh shape
+ *
+ * Shares the bvh of the child triangle mesh shape, so the same mesh can be placed with different scales
+ */
+export class PhysicsScaledTriangleMeshShape extends PhysicsShape {
+    /**
+     * Creates a new scaled triangle mesh shape
+     *
+     * @param runtime physics runtime
+     * @param childShape triangle mesh shape to scale, it can not be disposed while the scaled shape is alive
+     * @param scaling scaling of the child shape
+     */
+    public constructor(runtime: IPhysicsRuntime, childShape: PhysicsTriangleMeshShape, scaling: Vector3) {
+        if (childShape.runtime.wasmInstance !== runtime.wasmInstance) {
+            throw new Error("Cannot create scaled shape with shape from different wasm instance");
+        }
+        if (childShape.ptr === 0) {
+            throw new Error("Cannot create scaled shape with disposed shape");
+        }
+
+        const ptr = runtime.wasmInstance.createScaledTriangleMeshShape(childShape.ptr, scaling.x, scaling.y, scaling.z);
+        super(runtime, ptr, [childShape]);
+    }
+}
+
+/**
+ * Heightfield terrain shape
+ *
+ * Concave shape, it can only be used for static or kinematic rigid bodies
+ */
+export class PhysicsHeightfieldShape extends PhysicsShape {
+    /**
+     * Creates a new heightfield terrain shape
+     *
+     * @param runtime physics runtime
+     * @param width number of height samples along the x axis
+     * @param length number of height samples along the z axis
+     * @param heights row major `width * length` array of the heights
+     * @param minHeight minimum height of the terrain
+     * @param maxHeight maximum height of the terrain
+     * @param scaling grid spacing for x, z and height scale for y
+     * @throws {Error} if the heights does not match `width * length` or the heightfield is smaller than 2x2
+     */
+    public constructor(
+        runtime: IPhysicsRuntime,
+        width: number,
+        length: number,
+        heights: DeepImmutable<ArrayLike<number>>,
+        minHeight: number,
+        maxHeight: number,
+        scaling: Vector3
+    ) {
+        if (width < 2 || length < 2 || heights.length !== width * length) {
+            throw new Error("Heightfield must have at least 2x2 heights and match width * length");
+        }
+
+        const wasmInstance = runtime.wasmInstance;
+        const bufferSize = heights.length * Constants.A32BytesPerElement;
+        const bufferPtr = wasmInstance.allocateBuffer(bufferSize);
+        wasmInstance.createTypedArray(Float32Array, bufferPtr, heights.length).array.set(heights);
+
+        let ptr: number;
+        try {
+            ptr = wasmInstance.createHeightfieldShape(width, length, bufferPtr, minHeight, maxHeight, scaling.x, scaling.y, scaling.z);
+        } finally {
+            wasmInstance.deallocateBuffer(bufferPtr, bufferSize);
+        }
+
+        super(runtime, ptr);
+    }
+}
@@ -1,9 +1,13 @@
 import type { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
-import type { DeepImmutable } from "@babylonjs/core/types";
+import type { DeepImmutable, Nullable } from "@babylonjs/core/types";
 
 import type { IBulletWasmInstance } from "./bulletWasmInstance";
 import type { Constraint } from "./constraint";
 import type { IPhysicsRuntime } from "./Impl/IPhysicsRuntime";
+import { type PhysicsContactEvent, ReadPhysicsContactEvents } from "./physicsContactEvent";
+import { type PhysicsDebugDrawCategory, PhysicsDebugLineStride, PhysicsDebugPointStride, ReadPhysicsDebugDrawBuffer } from "./physicsDebugDraw";
+import { PhysicsQueryBodyMap, type PhysicsQueryHit, RunPhysicsQuery } from "./physicsQuery";
+import { type IPhysicsWorldStats, ReadPhysicsWorldStats } from "./physicsWorldStats";
 import type { RigidBody } from "./rigidBody";
 import type { RigidBodyBundle } from "./rigidBodyBundle";
 
@@ -15,6 +19,8 @@ class PhysicsWorldInner {
     private readonly _rigidBodyBundleReferences: Set<RigidBodyBundle>;
     private readonly _constraintReferences: Set<Constraint>;
 
+    private readonly _queryBodyMap: PhysicsQueryBodyMap;
+
     private _referenceCount: number;
 
     public constructor(runtime: WeakRef<IPhysicsRuntime>, ptr: number) {
@@ -25,6 +31,8 @@ class PhysicsWorldInner {
         this._rigidBodyBundleReferences = new Set<RigidBodyBundle>();
         this._constraintReferences = new Set<Constraint>();
 
+        this._queryBodyMap = new PhysicsQueryBodyMap();
+
         this._referenceCount = 0;
     }
 
@@ -58,12 +66,18 @@ class PhysicsWorldInner {
             constraint.setWorldReference(null);
         }
         this._constraintReferences.clear();
+
+        this._queryBodyMap.clear();
     }
 
     public get ptr(): number {
         return this._ptr;
     }
 
+    public get queryBodyMap(): PhysicsQueryBodyMap {
+        return this._queryBodyMap;
+    }
+
     public addReference(): void {
         this._referenceCount += 1;
     }
@@ -79,11 +93,13 @@ class PhysicsWorldInner {
 
         rigidBody.setWorldReference(this);
         this._rigidBodyReferences.add(rigidBody);
+        this._queryBodyMap.add(rigidBody);
         return true;
     }
 
     public removeRigidBodyReference(rigidBody: RigidBody): boolean {
         if (this._rigidBodyReferences.delete(rigidBody)) {
+            this._queryBodyMap.remove(rigidBody);
             rigidBody.setWorldReference(null);
             return true;
         }
@@ -97,11 +113,13 @@ class PhysicsWorldInner {
 
         rigidBodyBundle.setWorldReference(this);
         this._rigidBodyBundleReferences.add(rigidBodyBundle);
+        this._queryBodyMap.add(rigidBodyBundle);
         return true;
     }
 
     public removeRigidBodyBundleReference(rigidBodyBundle: RigidBodyBundle): boolean {
         if (this._rigidBodyBundleReferences.delete(rigidBodyBundle)) {
+            this._queryBodyMap.remove(rigidBodyBundle);
             rigidBodyBundle.setWorldReference(null);
             return true;
         }
@@ -339,4 +357,235 @@ export class PhysicsWorld {
         }
         return false;
     }
+
+    /**
+     * Casts rays and finds the closest hit of each ray
+     *
+     * This operation performs waiting for the lock before executing
+     * @param rays `count` pairs of from xyz and to xyz
+     * @param group collision group of the rays
+     * @param mask collision mask of the rays
+     * @returns hit of each ray, `hasHit` is false if the ray did not hit anything
+     */
+    public rayTestBatch(rays: DeepImmutable<ArrayLike<number>>, group = 0xFFFF, mask = 0xFFFF): PhysicsQueryHit[] {
+        if (rays.length % 6 !== 0) {
+            throw new Error("Rays must be pairs of from xyz and to xyz");
+        }
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const count = rays.length / 6;
+        return RunPhysicsQuery(wasmInstance, rays, count, this._inner.queryBodyMap, (inputsPtr, outBodiesPtr, outBodyIndicesPtr, outHitsPtr) => {
+            wasmInstance.physicsWorldRayTestBatch(this._inner.ptr, inputsPtr, count, group, mask, outBodiesPtr, outBodyIndicesPtr, outHitsPtr);
+            return count;
+        });
+    }
+
+    /**
+     * Casts a ray and finds the closest hit
+     *
+     * This operation performs waiting for the lock before executing
+     * @param from start of the ray
+     * @param to end of the ray
+     * @param group collision group of the ray
+     * @param mask collision mask of the ray
+     * @returns hit of the ray, `hasHit` is false if the ray did not hit anything
+     */
+    public rayTest(from: DeepImmutable<Vector3>, to: DeepImmutable<Vector3>, group = 0xFFFF, mask = 0xFFFF): PhysicsQueryHit {
+        return this.rayTestBatch([from.x, from.y, from.z, to.x, to.y, to.z], group, mask)[0];
+    }
+
+    /**
+     * Sweeps spheres and finds the closest hit of each sweep
+     *
+     * This operation performs waiting for the lock before executing
+     * @param sweeps `count` tuples of from xyz, to xyz and radius
+     * @param group collision group of the spheres
+     * @param mask collision mask of the spheres
+     * @returns hit of each sweep, `hasHit` is false if the sweep did not hit anything
+     */
+    public sphereSweepTestBatch(sweeps: DeepImmutable<ArrayLike<number>>, group = 0xFFFF, mask = 0xFFFF): PhysicsQueryHit[] {
+        if (sweeps.length % 7 !== 0) {
+            throw new Error("Sweeps must be tuples of from xyz, to xyz and radius");
+        }
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const count = sweeps.length / 7;
+        return RunPhysicsQuery(wasmInstance, sweeps, count, this._inner.queryBodyMap, (inputsPtr, outBodiesPtr, outBodyIndicesPtr, outHitsPtr) => {
+            wasmInstance.physicsWorldSphereSweepTestBatch(this._inner.ptr, inputsPtr, count, group, mask, outBodiesPtr, outBodyIndicesPtr, outHitsPtr);
+            return count;
+        });
+    }
+
+    /**
+     * Finds the bodies overlapping a sphere
+     *
+     * Only the deepest contact of each body is reported
+     *
+     * This operation performs waiting for the lock before executing
+     * @param center center of the sphere
+     * @param radius radius of the sphere
+     * @param group collision group of the sphere
+     * @param mask collision mask of the sphere
+     * @param capacity maximum number of contacts to report
+     * @returns contacts, `fraction` is the signed distance
+     */
+    public sphereContactTest(center: DeepImmutable<Vector3>, radius: number, group = 0xFFFF, mask = 0xFFFF, capacity = 64): PhysicsQueryHit[] {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        return RunPhysicsQuery(wasmInstance, [], capacity, this._inner.queryBodyMap, (_inputsPtr, outBodiesPtr, outBodyIndicesPtr, outHitsPtr) => {
+            return wasmInstance.physicsWorldSphereContactTest(
+                this._inner.ptr,
+                center.x, center.y, center.z,
+                radius,
+                group, mask,
+                outBodiesPtr, outBodyIndicesPtr, outHitsPtr,
+                capacity
+            );
+        });
+    }
+
+    /**
+     * Enables or disables collecting contact events on each step
+     *
+     * Contacts of every sub step are collected, so a pair touching only between sub steps is still reported
+     *
+     * This operation performs waiting for the lock before executing
+     * @param enabled Whether to collect contact events
+     */
+    public setContactEventsEnabled(enabled: boolean): void {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        this._runtime.wasmInstance.physicsWorldSetContactEventsEnabled(this._inner.ptr, enabled);
+    }
+
+    /**
+     * Gets the contact events of the last step
+     *
+     * This operation performs waiting for the lock before executing
+     * @returns contact events, empty if contact events are disabled
+     */
+    public getContactEvents(): PhysicsContactEvent[] {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const ptr = wasmInstance.physicsWorldGetContactEventsPtr(this._inner.ptr);
+        const count = wasmInstance.physicsWorldGetContactEventCount(this._inner.ptr);
+        return ReadPhysicsContactEvents(wasmInstance, ptr, count, this._inner.queryBodyMap);
+    }
+
+    /**
+     * Enables or disables the stats collection of the world
+     *
+     * This operation performs waiting for the lock before executing
+     * @param enabled whether to collect stats on each step
+     */
+    public setStatsEnabled(enabled: boolean): void {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        this._runtime.wasmInstance.physicsWorldSetStatsEnabled(this._inner.ptr, enabled);
+    }
+
+    /**
+     * Gets the stats of the last step
+     *
+     * This operation performs waiting for the lock before executing
+     * @returns stats, or null if stats are disabled
+     */
+    public getStats(): Nullable<IPhysicsWorldStats> {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        return ReadPhysicsWorldStats(wasmInstance, wasmInstance.physicsWorldGetStatsPtr(this._inner.ptr));
+    }
+
+    /**
+     * Sets the debug draw categories of the world
+     *
+     * Debug geometry is rebuilt on each step while any category is enabled
+     *
+     * This operation performs waiting for the lock before executing
+     * @param categories combination of `PhysicsDebugDrawCategory`, `PhysicsDebugDrawCategory.None` disables debug draw
+     */
+    public setDebugDrawCategories(categories: PhysicsDebugDrawCategory): void {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        this._runtime.wasmInstance.physicsWorldSetDebugDrawCategories(this._inner.ptr, categories);
+    }
+
+    /**
+     * Gets the debug lines of the last step
+     *
+     * This operation performs waiting for the lock before executing
+     * @returns line list, each line is `PhysicsDebugLineStride` floats of position xyz and color rgb for the two vertices
+     */
+    public getDebugLines(): Float32Array {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const ptr = wasmInstance.physicsWorldGetDebugLinesPtr(this._inner.ptr);
+        const count = wasmInstance.physicsWorldGetDebugLineCount(this._inner.ptr);
+        return ReadPhysicsDebugDrawBuffer(wasmInstance, ptr, count * PhysicsDebugLineStride);
+    }
+
+    /**
+     * Gets the debug contact points of the last step
+     *
+     * This operation performs waiting for the lock before executing
+     * @returns point list, each point is `PhysicsDebugPointStride` floats of position xyz and color rgb
+     */
+    public getDebugPoints(): Float32Array {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const ptr = wasmInstance.physicsWorldGetDebugPointsPtr(this._inner.ptr);
+        const count = wasmInstance.physicsWorldGetDebugPointCount(this._inner.ptr);
+        return ReadPhysicsDebugDrawBuffer(wasmInstance, ptr, count * PhysicsDebugPointStride);
+    }
+
+    /**
+     * Captures body transforms, velocities, kinematic states and constraint impulses of the world
+     *
+     * This operation performs waiting for the lock before executing
+     * @returns snapshot data which can be passed to `restoreSnapshot`
+     */
+    public takeSnapshot(): Uint8Array {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const size = wasmInstance.physicsWorldGetSnapshotSize(this._inner.ptr);
+        const bufferPtr = wasmInstance.allocateBuffer(size);
+        try {
+            if (!wasmInstance.physicsWorldWriteSnapshot(this._inner.ptr, bufferPtr, size)) {
+                throw new Error("Failed to write physics world snapshot");
+            }
+            return wasmInstance.createTypedArray(Uint8Array, bufferPtr, size).array.slice();
+        } finally {
+            wasmInstance.deallocateBuffer(bufferPtr, size);
+        }
+    }
+
+    /**
+     * Restores the state captured by `takeSnapshot`
+     *
+     * This operation performs waiting for the lock before executing
+     * @param snapshot snapshot data
+     * @returns false without changing the world if objects were added or removed since the snapshot was taken
+     */
+    public restoreSnapshot(snapshot: Uint8Array): boolean {
+        this._nullCheck();
+        this._runtime.lock.wait();
+        const wasmInstance = this._runtime.wasmInstance;
+        const size = snapshot.byteLength;
+        if (size === 0) {
+            return false;
+        }
+        const bufferPtr = wasmInstance.allocateBuffer(size);
+        wasmInstance.createTypedArray(Uint8Array, bufferPtr, size).array.set(snapshot);
+        const result = wasmInstance.physicsWorldRestoreSnapshot(this._inner.ptr, bufferPtr, size);
+        wasmInstance.deallocateBuffer(bufferPtr, size);
+        return result;
+    }
 }
@@ -0,0 +1,94 @@
+import type { Nullable } from "@babylonjs/core/types";
+
+import type { IBulletWasmInstance } from "./bulletWasmInstance";
+
+/**
+ * Physics world statistics
+ */
+export interface IPhysicsWorldStats {
+    /**
+     * Number of collision objects in the world
+     */
+    objectCount: number;
+
+    /**
+     * Number of constraints in the world
+     */
+    constraintCount: number;
+
+    /**
+     * Number of overlapping pairs of the broadphase
+     */
+    overlappingPairCount: number;
+
+    /**
+     * Number of contact manifolds
+     */
+    manifoldCount: number;
+
+    /**
+     * Number of simulation islands
+     */
+    islandCount: number;
+
+    /**
+     * Number of sub steps of the last step
+     */
+    subStepCount: number;
+
+    /**
+     * Time spent in the last step in milliseconds
+     */
+    stepTime: number;
+}
+
+/**
+ * PhysicsWorldStats representations
+ *
+ * objectCount: int32 : offset 0
+ * constraintCount: int32 : offset 4
+ * overlappingPairCount: int32 : offset 8
+ * manifoldCount: int32 : offset 12
+ * islandCount: int32 : offset 16
+ * subStepCount: int32 : offset 20
+ * stepTime: float32 : offset 24
+ *
+ * --size: 28
+ */
+const enum PhysicsWorldStatsLayout {
+    SizeInInt32Array = 7,
+    ObjectCount = 0,
+    ConstraintCount = 1,
+    OverlappingPairCount = 2,
+    ManifoldCount = 3,
+    IslandCount = 4,
+    SubStepCount = 5,
+    StepTime = 6
+}
+
+/**
+ * Reads the physics world stats of the wasm side
+ *
+ * @param wasmInstance bullet wasm instance
+ * @param ptr stats pointer
+ * @returns stats, or null if the pointer is null
+ *
+ * @internal
+ */
+export function ReadPhysicsWorldStats(wasmInstance: IBulletWasmInstance, ptr: number): Nullable<IPhysicsWorldStats> {
+    if (ptr === 0) {
+        return null;
+    }
+
+    const int32Array = wasmInstance.createTypedArray(Int32Array, ptr, PhysicsWorldStatsLayout.SizeInInt32Array).array;
+    const float32Array = wasmInstance.createTypedArray(Float32Array, ptr, PhysicsWorldStatsLayout.SizeInInt32Array).array;
+    return {
+        objectCount: int32Array[PhysicsWorldStatsLayout.ObjectCount],
+        constraintCount: int32Array[PhysicsWorldStatsLayout.ConstraintCount],
+        overlappingPairCount: int32Array[PhysicsWorldStatsLayout.OverlappingPairCount],
+        manifoldCount: int32Array[PhysicsWorldStatsLayout.ManifoldCount],
+        islandCount: int32Array[PhysicsWorldStatsLayout.IslandCount],
+        subStepCount: int32Array[PhysicsWorldStatsLayout.SubStepCount],
+        stepTime: float32Array[PhysicsWorldStatsLayout.StepTime]
+    };
+}
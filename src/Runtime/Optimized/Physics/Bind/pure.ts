@@ -11,8 +11,12 @@ export * from "./constraint";
 export * from "./constructionInfoDataMask";
 export * from "./motionType";
 export * from "./multiPhysicsWorld";
+export * from "./physicsContactEvent";
+export * from "./physicsDebugDraw";
+export * from "./physicsQuery";
 export * from "./physicsShape";
 export * from "./physicsWorld";
+export * from "./physicsWorldStats";
 export * from "./rigidBody";
 export * from "./rigidBodyBundle";
 export * from "./rigidBodyConstructionInfo";
@@ -1013,4 +1013,49 @@ export class RigidBody {
         this._inner.setShapeReference(shape);
         this.runtime.wasmInstance.rigidBodySetShape(this._inner.ptr, shape.ptr);
     }
+
+    /**
+     * Set continuous collision detection parameters of the rigid body
+     *
+     * Ccd is enabled when the body moves more than the motion threshold in a single step
+     *
+     * This operation is always synchronized
+     * @param motionThreshold Motion threshold, 0 to disable ccd
+     * @param sweptSphereRadius Radius of the sphere swept for ccd
+     */
+    public setCcd(motionThreshold: number, sweptSphereRadius: number): void {
+        this._nullCheck();
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        this.runtime.wasmInstance.rigidBodySetCcd(this._inner.ptr, motionThreshold, sweptSphereRadius);
+    }
+
+    /**
+     * Get continuous collision detection motion threshold of the rigid body
+     *
+     * This operation is always synchronized
+     * @returns Motion threshold
+     */
+    public getCcdMotionThreshold(): number {
+        this._nullCheck();
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        return this.runtime.wasmInstance.rigidBodyGetCcdMotionThreshold(this._inner.ptr);
+    }
+
+    /**
+     * Get continuous collision detection swept sphere radius of the rigid body
+     *
+     * This operation is always synchronized
+     * @returns Swept sphere radius
+     */
+    public getCcdSweptSphereRadius(): number {
+        this._nullCheck();
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        return this.runtime.wasmInstance.rigidBodyGetCcdSweptSphereRadius(this._inner.ptr);
+    }
 }
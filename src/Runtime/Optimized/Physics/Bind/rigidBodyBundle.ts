@@ -1275,4 +1275,61 @@ export class RigidBodyBundle {
         this._inner.setShapeReference(index, shape);
         this.runtime.wasmInstance.rigidBodyBundleSetShape(this._inner.ptr, index, shape.ptr);
     }
+
+    /**
+     * Set continuous collision detection parameters of the rigid body at the given index
+     *
+     * Ccd is enabled when the body moves more than the motion threshold in a single step
+     *
+     * This operation is always synchronized
+     * @param index Index of the rigid body
+     * @param motionThreshold Motion threshold, 0 to disable ccd
+     * @param sweptSphereRadius Radius of the sphere swept for ccd
+     */
+    public setCcd(index: number, motionThreshold: number, sweptSphereRadius: number): void {
+        this._nullCheck();
+        if (index < 0 || this._count <= index) {
+            throw new RangeError("Index out of range");
+        }
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        this.runtime.wasmInstance.rigidBodyBundleSetCcd(this._inner.ptr, index, motionThreshold, sweptSphereRadius);
+    }
+
+    /**
+     * Get continuous collision detection motion threshold of the rigid body at the given index
+     *
+     * This operation is always synchronized
+     * @param index Index of the rigid body
+     * @returns Motion threshold
+     */
+    public getCcdMotionThreshold(index: number): number {
+        this._nullCheck();
+        if (index < 0 || this._count <= index) {
+            throw new RangeError("Index out of range");
+        }
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        return this.runtime.wasmInstance.rigidBodyBundleGetCcdMotionThreshold(this._inner.ptr, index);
+    }
+
+    /**
+     * Get continuous collision detection swept sphere radius of the rigid body at the given index
+     *
+     * This operation is always synchronized
+     * @param index Index of the rigid body
+     * @returns Swept sphere radius
+     */
+    public getCcdSweptSphereRadius(index: number): number {
+        this._nullCheck();
+        if (index < 0 || this._count <= index) {
+            throw new RangeError("Index out of range");
+        }
+        if (this._inner.hasReferences) {
+            this.runtime.lock.wait();
+        }
+        return this.runtime.wasmInstance.rigidBodyBundleGetCcdSweptSphereRadius(this._inner.ptr, index);
+    }
 }
@@ -169,6 +169,12 @@ export class RigidBodyConstructionInfo {
         // disableDeactivation
         uint8Ptr[RigidBodyConstructionInfoOffsets.DisableDeactivation / Constants.A8BytesPerElement] = +false;
 
+        // ccdMotionThreshold
+        float32Ptr[RigidBodyConstructionInfoOffsets.CcdMotionThreshold / Constants.A32BytesPerElement] = 0.0;
+
+        // ccdSweptSphereRadius
+        float32Ptr[RigidBodyConstructionInfoOffsets.CcdSweptSphereRadius / Constants.A32BytesPerElement] = 0.0;
+
 
         // finalization registry
         let registry = RigidBodyConstructionInfoRegistryMap.get(wasmInstance);
@@ -471,4 +477,34 @@ export class RigidBodyConstructionInfo {
         this._nullCheck();
         this._uint8Ptr.array[RigidBodyConstructionInfoOffsets.DisableDeactivation / Constants.A8BytesPerElement] = +value;
     }
+
+    /**
+     * The continuous collision detection motion threshold of the rigid body
+     *
+     * CCD is performed when the body moves more than this distance in one step, zero disables CCD
+     */
+    public get ccdMotionThreshold(): number {
+        this._nullCheck();
+        return this._float32Ptr.array[RigidBodyConstructionInfoOffsets.CcdMotionThreshold / Constants.A32BytesPerElement];
+    }
+
+    public set ccdMotionThreshold(value: number) {
+        this._nullCheck();
+        this._float32Ptr.array[RigidBodyConstructionInfoOffsets.CcdMotionThreshold / Constants.A32BytesPerElement] = value;
+    }
+
+    /**
+     * The continuous collision detection swept sphere radius of the rigid body
+     *
+     * Should be smaller than the shape so the swept sphere stays inside the body
+     */
+    public get ccdSweptSphereRadius(): number {
+        this._nullCheck();
+        return this._float32Ptr.array[RigidBodyConstructionInfoOffsets.CcdSweptSphereRadius / Constants.A32BytesPerElement];
+    }
+
+    public set ccdSweptSphereRadius(value: number) {
+        this._nullCheck();
+        this._float32Ptr.array[RigidBodyConstructionInfoOffsets.CcdSweptSphereRadius / Constants.A32BytesPerElement] = value;
+    }
 }
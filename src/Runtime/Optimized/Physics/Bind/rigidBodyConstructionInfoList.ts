@@ -186,6 +186,12 @@ export class RigidBodyConstructionInfoList {
 
             // disableDeactivation
             uint8Ptr[(offset + RigidBodyConstructionInfoOffsets.DisableDeactivation) / Constants.A8BytesPerElement] = +false;
+
+            // ccdMotionThreshold
+            float32Ptr[(offset + RigidBodyConstructionInfoOffsets.CcdMotionThreshold) / Constants.A32BytesPerElement] = 0.0;
+
+            // ccdSweptSphereRadius
+            float32Ptr[(offset + RigidBodyConstructionInfoOffsets.CcdSweptSphereRadius) / Constants.A32BytesPerElement] = 0.0;
         }
 
         // finalization registry
@@ -649,4 +655,48 @@ export class RigidBodyConstructionInfoList {
         const offset = n * Constants.RigidBodyConstructionInfoSize;
         this._uint8Ptr.array[(offset + RigidBodyConstructionInfoOffsets.DisableDeactivation) / Constants.A8BytesPerElement] = +value;
     }
+
+    /**
+     * Get continuous collision detection motion threshold of the rigid body at index n
+     * @param n The index of the rigid body
+     * @returns The CCD motion threshold of the rigid body, zero means CCD is disabled
+     */
+    public getCcdMotionThreshold(n: number): number {
+        this._nullCheck();
+        const offset = n * Constants.RigidBodyConstructionInfoSize;
+        return this._float32Ptr.array[(offset + RigidBodyConstructionInfoOffsets.CcdMotionThreshold) / Constants.A32BytesPerElement];
+    }
+
+    /**
+     * Set continuous collision detection motion threshold of the rigid body at index n
+     * @param n The index of the rigid body
+     * @param value The CCD motion threshold of the rigid body, zero disables CCD
+     */
+    public setCcdMotionThreshold(n: number, value: number): void {
+        this._nullCheck();
+        const offset = n * Constants.RigidBodyConstructionInfoSize;
+        this._float32Ptr.array[(offset + RigidBodyConstructionInfoOffsets.CcdMotionThreshold) / Constants.A32BytesPerElement] = value;
+    }
+
+    /**
+     * Get continuous collision detection swept sphere radius of the rigid body at index n
+     * @param n The index of the rigid body
+     * @returns The CCD swept sphere radius of the rigid body
+     */
+    public getCcdSweptSphereRadius(n: number): number {
+        this._nullCheck();
+        const offset = n * Constants.RigidBodyConstructionInfoSize;
+        return this._float32Ptr.array[(offset + RigidBodyConstructionInfoOffsets.CcdSweptSphereRadius) / Constants.A32BytesPerElement];
+    }
+
+    /**
+     * Set continuous collision detection swept sphere radius of the rigid body at index n
+     * @param n The index of the rigid body
+     * @param value The CCD swept sphere radius of the rigid body
+     */
+    public setCcdSweptSphereRadius(n: number, value: number): void {
+        this._nullCheck();
+        const offset = n * Constants.RigidBodyConstructionInfoSize;
+        this._float32Ptr.array[(offset + RigidBodyConstructionInfoOffsets.CcdSweptSphereRadius) / Constants.A32BytesPerElement] = value;
+    }
 }
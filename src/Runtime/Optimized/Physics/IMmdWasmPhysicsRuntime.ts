@@ -1,6 +1,10 @@
 import type { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
 import type { DeepImmutable, Nullable } from "@babylonjs/core/types";
 
+import type { PmxObject } from "@/Loader/Parser/pmxObject";
+
+import type { MmdWasmModel } from "../mmdWasmModel";
+import type { IPhysicsWorldStats } from "./Bind/physicsWorldStats";
 import type { IMmdWasmPhysicsRuntimeImplCreationOptions, MmdWasmPhysicsRuntimeImpl } from "./mmdWasmPhysicsRuntimeImpl";
 
 /**
@@ -43,6 +47,351 @@ export interface IMmdWasmPhysicsRuntime {
      */
     getGravity(result?: Vector3): Nullable<Vector3>;
 
+    /**
+     * Get the stats of the physics world in the last step
+     *
+     * Stats are collected while `MmdWasmRuntime.profilingEnabled` is true
+     *
+     * @param worldId world id
+     * @returns stats, or null if the world does not exist or profiling is disabled
+     */
+    getStats(worldId: number): Nullable<IPhysicsWorldStats>;
+
+    /**
+     * Let dynamic rigid bodies of the model sleep when their velocities stay below the thresholds
+     *
+     * Sleeping islands wake up when a bone driven rigid body joined to them moves
+     *
+     * Changes are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param enabled whether to let the rigid bodies sleep
+     * @param linearThreshold linear velocity threshold (default 0.8)
+     * @param angularThreshold angular velocity threshold (default 1.0)
+     */
+    setMmdModelSleeping(mmdModel: MmdWasmModel, enabled: boolean, linearThreshold?: number, angularThreshold?: number): void;
+
+    /**
+     * Enable continuous collision detection for fast moving rigid bodies of the model
+     *
+     * Overrides `ccd` physics creation option
+     *
+     * Changes are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param motionThresholdScale ccd is enabled when a body moves more than this relative to its inner radius per step, 0 disables ccd
+     * @param sweptSphereRadiusScale swept sphere radius relative to the inner radius of each rigid body
+     */
+    setMmdModelCcd(mmdModel: MmdWasmModel, motionThresholdScale: number, sweptSphereRadiusScale: number): void;
+
+    /**
+     * Show dynamic rigid bodies of the model at the frame time when the frame rate is higher than the physics rate
+     *
+     * Rigid bodies are shown between the previous and the last fixed step by the time that is not simulated yet, so they lag one fixed step behind
+     *
+     * Changes are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param enabled whether to enable interpolation
+     */
+    setMmdModelInterpolation(mmdModel: MmdWasmModel, enabled: boolean): void;
+
+    /**
+     * Set the mass of the rigid body, local inertia is recomputed from the shape
+     *
+     * Rigid body edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @param mass mass, must be positive
+     * @returns false if the rigid body is failed to create or the rigid body is not dynamic
+     */
+    setMmdModelRigidBodyMass(mmdModel: MmdWasmModel, rigidBodyIndex: number, mass: number): boolean;
+
+    /**
+     * Get the mass of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns mass, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyMass(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Set the damping of the rigid body
+     *
+     * Rigid body edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @param linearDamping linear damping
+     * @param angularDamping angular damping
+     * @returns false if the rigid body is failed to create
+     */
+    setMmdModelRigidBodyDamping(mmdModel: MmdWasmModel, rigidBodyIndex: number, linearDamping: number, angularDamping: number): boolean;
+
+    /**
+     * Get the linear damping of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns linear damping, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyLinearDamping(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Get the angular damping of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns angular damping, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyAngularDamping(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Set the friction of the rigid body
+     *
+     * Rigid body edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @param friction friction
+     * @returns false if the rigid body is failed to create
+     */
+    setMmdModelRigidBodyFriction(mmdModel: MmdWasmModel, rigidBodyIndex: number, friction: number): boolean;
+
+    /**
+     * Get the friction of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns friction, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyFriction(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Set the restitution of the rigid body
+     *
+     * Rigid body edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @param restitution restitution
+     * @returns false if the rigid body is failed to create
+     */
+    setMmdModelRigidBodyRestitution(mmdModel: MmdWasmModel, rigidBodyIndex: number, restitution: number): boolean;
+
+    /**
+     * Get the restitution of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns restitution, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyRestitution(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Set the collision filter of the rigid body
+     *
+     * Rigid body edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @param group collision group bits
+     * @param mask collision mask bits
+     * @returns false if the rigid body is failed to create
+     */
+    setMmdModelRigidBodyCollisionFilter(mmdModel: MmdWasmModel, rigidBodyIndex: number, group: number, mask: number): boolean;
+
+    /**
+     * Get the collision group bits of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns collision group bits, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyCollisionGroup(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Get the collision mask bits of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns collision mask bits, or null if the rigid body is failed to create
+     */
+    getMmdModelRigidBodyCollisionMask(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number>;
+
+    /**
+     * Set the physics mode of the rigid body
+     *
+     * Rigid bodies created as dynamic can switch between follow bone, physics and physics with bone.
+     * Rigid bodies created as follow bone can not change their physics mode
+     *
+     * Rigid body edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @param physicsMode physics mode
+     * @returns false if the rigid body is failed to create or the rigid body can not change its physics mode
+     */
+    setMmdModelRigidBodyPhysicsMode(mmdModel: MmdWasmModel, rigidBodyIndex: number, physicsMode: PmxObject.RigidBody.PhysicsMode): boolean;
+
+    /**
+     * Get the physics mode of the rigid body
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param rigidBodyIndex PMX rigid body index
+     * @returns physics mode, or null if the rigid body is failed to create or the rigid body is static
+     */
+    getMmdModelRigidBodyPhysicsMode(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<PmxObject.RigidBody.PhysicsMode>;
+
+    /**
+     * Set the linear limit of the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param lower lower limit
+     * @param upper upper limit
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointLinearLimit(mmdModel: MmdWasmModel, jointIndex: number, lower: DeepImmutable<Vector3>, upper: DeepImmutable<Vector3>): boolean;
+
+    /**
+     * Set the angular limit of the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param lower lower limit in radians
+     * @param upper upper limit in radians
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointAngularLimit(mmdModel: MmdWasmModel, jointIndex: number, lower: DeepImmutable<Vector3>, upper: DeepImmutable<Vector3>): boolean;
+
+    /**
+     * Set the linear spring stiffness of the joint, spring of the axis is disabled if its stiffness is zero
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param stiffness stiffness of each axis
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointLinearStiffness(mmdModel: MmdWasmModel, jointIndex: number, stiffness: DeepImmutable<Vector3>): boolean;
+
+    /**
+     * Set the angular spring stiffness of the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param stiffness stiffness of each axis
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointAngularStiffness(mmdModel: MmdWasmModel, jointIndex: number, stiffness: DeepImmutable<Vector3>): boolean;
+
+    /**
+     * Set the linear spring damping of the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param damping damping of each axis
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointLinearDamping(mmdModel: MmdWasmModel, jointIndex: number, damping: DeepImmutable<Vector3>): boolean;
+
+    /**
+     * Set the angular spring damping of the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param damping damping of each axis
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointAngularDamping(mmdModel: MmdWasmModel, jointIndex: number, damping: DeepImmutable<Vector3>): boolean;
+
+    /**
+     * Set the stop ERP of all 6 axes of the joint (MMD default 0.475)
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param value stop ERP
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointStopErp(mmdModel: MmdWasmModel, jointIndex: number, value: number): boolean;
+
+    /**
+     * Set the stop CFM of all 6 axes of the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param value stop CFM
+     * @returns false if the joint is failed to create or the joint kind does not support the parameter
+     */
+    setMmdModelJointStopCfm(mmdModel: MmdWasmModel, jointIndex: number, value: number): boolean;
+
+    /**
+     * Enable or disable the joint
+     *
+     * Joint edits are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @param enabled whether the joint is enabled
+     * @returns false if the joint is failed to create
+     */
+    setMmdModelJointEnabled(mmdModel: MmdWasmModel, jointIndex: number, enabled: boolean): boolean;
+
+    /**
+     * Get whether the joint is enabled
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @param jointIndex PMX joint index
+     * @returns whether the joint is enabled, or null if the joint is failed to create
+     */
+    isMmdModelJointEnabled(mmdModel: MmdWasmModel, jointIndex: number): Nullable<boolean>;
+
+    /**
+     * Blend rigid bodies between animation and physics when the physics toggle of the bone changes
+     *
+     * While animation has control, rigid bodies are driven toward their bones by velocity scaled by the gains
+     *
+     * Changes are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param transitionTime transition time in seconds, zero switches immediately
+     * @param forceGain linear drive gain (default 30)
+     * @param torqueGain angular drive gain (default 30)
+     */
+    setMmdModelPhysicsBlend(mmdModel: MmdWasmModel, transitionTime: number, forceGain?: number, torqueGain?: number): void;
+
+    /**
+     * Switch the model into ragdoll mode
+     *
+     * Every follow bone body and the bodies generated for bones without rigid body become dynamic,
+     * returning to animation blends over the transition time of `setMmdModelPhysicsBlend`
+     *
+     * Changes are applied before the next physics step
+     * @param mmdModel MMD model created by this runtime
+     * @param enabled whether to enable ragdoll mode
+     * @returns false if the model is created without `ragdoll` physics creation option
+     */
+    setMmdModelRagdoll(mmdModel: MmdWasmModel, enabled: boolean): boolean;
+
+    /**
+     * Get whether the model is in ragdoll mode
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @returns whether ragdoll mode is enabled, or null if the model is created without `ragdoll` physics creation option
+     */
+    isMmdModelRagdollEnabled(mmdModel: MmdWasmModel): Nullable<boolean>;
+
+    /**
+     * Run physics of the model for fixed steps while its animation is held at the current frame
+     *
+     * Other models are not advanced and their dynamic rigid bodies are held in place during the steps, so this can be used after seek to settle the rigid bodies without disturbing them
+     *
+     * This operation performs waiting for the lock before executing
+     * @param mmdModel MMD model created by this runtime
+     * @param stepCount number of fixed time steps to simulate
+     * @param initialDamping damping at the first step, fades back to the damping of each rigid body over the steps (default 0)
+     */
+    preRollMmdModel(mmdModel: MmdWasmModel, stepCount: number, initialDamping?: number): void;
+
     /**
      * Get the physics runtime full implementation object
      *
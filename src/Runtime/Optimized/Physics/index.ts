@@ -5,6 +5,7 @@ export * from "./Bind/index";
 export * from "./IMmdWasmPhysicsRuntime";
 export * from "./IPhysicsClock";
 export * from "./mmdBulletPhysics";
+export * from "./mmdWasmModelPhysicsCreationOptions";
 export * from "./mmdWasmPhysics";
 export * from "./mmdWasmPhysicsMetadataEncoder";
 export * from "./mmdWasmPhysicsRuntime";
@@ -0,0 +1,74 @@
+import type { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
+import type { DeepImmutable } from "@babylonjs/core/types";
+
+import type { IMmdModelPhysicsCreationOptions } from "../../mmdRuntime";
+
+/**
+ * Continuous collision detection options of the mmd model
+ */
+export interface IMmdWasmModelCcdOptions {
+    /**
+     * Motion threshold relative to the inner radius of each rigid body
+     *
+     * Ccd is enabled when a body moves more than this per step, 0 disables ccd
+     */
+    motionThresholdScale: number;
+
+    /**
+     * Swept sphere radius relative to the inner radius of each rigid body
+     */
+    sweptSphereRadiusScale: number;
+}
+
+/**
+ * Ragdoll options of the mmd model
+ *
+ * Follow bone bodies become dynamic in ragdoll mode, and capsules are generated for bones without rigid body under them
+ */
+export interface IMmdWasmModelRagdollOptions {
+    /**
+     * Mass of follow bone bodies that have zero mass and of generated bodies (default: 1)
+     */
+    defaultMass?: number;
+
+    /**
+     * Angular lower limit of the joints generated between ragdoll bodies in radians (default: (-PI / 4, -PI / 4, -PI / 4))
+     */
+    angularLowerLimit?: DeepImmutable<Vector3>;
+
+    /**
+     * Angular upper limit of the joints generated between ragdoll bodies in radians (default: (PI / 4, PI / 4, PI / 4))
+     */
+    angularUpperLimit?: DeepImmutable<Vector3>;
+
+    /**
+     * Radius of generated capsules relative to the bone length (default: 0.2)
+     */
+    radiusScale?: number;
+
+    /**
+     * Bones shorter than this get no generated body and follow their parent body (default: 0.5)
+     */
+    minBoneLength?: number;
+}
+
+/**
+ * Physics creation options of the mmd model that are only supported by the wasm physics runtime
+ *
+ * Pass this as `buildPhysics` of `MmdWasmRuntime.createMmdModel`, options are encoded into the extensions section of the model metadata
+ */
+export interface IMmdWasmModelPhysicsCreationOptions extends IMmdModelPhysicsCreationOptions {
+    /**
+     * Continuous collision detection options (default: undefined)
+     *
+     * If undefined, ccd is disabled
+     */
+    ccd?: IMmdWasmModelCcdOptions;
+
+    /**
+     * Ragdoll options (default: undefined)
+     *
+     * If undefined, the model can not switch into ragdoll mode
+     */
+    ragdoll?: IMmdWasmModelRagdollOptions;
+}
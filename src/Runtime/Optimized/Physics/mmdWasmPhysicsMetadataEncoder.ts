@@ -7,8 +7,16 @@ import type { ILogger } from "@/Runtime/ILogger";
 
 import type { IMmdModelPhysicsCreationOptions } from "../../mmdRuntime";
 import { MmdMetadataEncoder } from "../mmdMetadataEncoder";
+import type { IMmdWasmModelPhysicsCreationOptions } from "./mmdWasmModelPhysicsCreationOptions";
 import type { MmdWasmPhysicsRuntime } from "./mmdWasmPhysicsRuntime";
 
+const enum ExtensionChunkTag {
+    Ragdoll = 2,
+    Ccd = 3
+}
+
+const DefaultRagdollAngularLimit = Math.PI / 4;
+
 /**
  * @internal
  */
@@ -178,4 +186,58 @@ export class MmdWasmPhysicsMetadataEncoder extends MmdMetadataEncoder {
             serializer.setFloat32Array(joint.springRotation); // springRotation
         }
     }
+
+    protected override _computeExtensionsSize(metadata: Nullable<MmdModelMetadata>): number {
+        if (metadata === null) return 0;
+
+        let dataLength = 0;
+
+        const ragdoll = (this._encodePhysicsOptions as IMmdWasmModelPhysicsCreationOptions).ragdoll;
+        if (ragdoll !== undefined) {
+            dataLength += 4 // tag
+                + 4 // size
+                + 4 // defaultMass
+                + 4 * 3 // angularLowerLimit
+                + 4 * 3 // angularUpperLimit
+                + 4 // radiusScale
+                + 4; // minBoneLength
+        }
+
+        const ccd = (this._encodePhysicsOptions as IMmdWasmModelPhysicsCreationOptions).ccd;
+        if (ccd !== undefined) {
+            dataLength += 4 // tag
+                + 4 // size
+                + 4 // motionThresholdScale
+                + 4; // sweptSphereRadiusScale
+        }
+
+        return dataLength;
+    }
+
+    protected override _encodeExtensions(serializer: AlignedDataSerializer, metadata: Nullable<MmdModelMetadata>): void {
+        if (metadata === null) return;
+
+        const ragdoll = (this._encodePhysicsOptions as IMmdWasmModelPhysicsCreationOptions).ragdoll;
+        if (ragdoll !== undefined) {
+            MmdMetadataEncoder._EncodeChunkHeader(serializer, ExtensionChunkTag.Ragdoll, 4 + 4 * 3 * 2 + 4 + 4);
+            serializer.setFloat32(ragdoll.defaultMass ?? 1); // defaultMass
+            const angularLowerLimit = ragdoll.angularLowerLimit;
+            serializer.setFloat32(angularLowerLimit?.x ?? -DefaultRagdollAngularLimit); // angularLowerLimit x
+            serializer.setFloat32(angularLowerLimit?.y ?? -DefaultRagdollAngularLimit); // angularLowerLimit y
+            serializer.setFloat32(angularLowerLimit?.z ?? -DefaultRagdollAngularLimit); // angularLowerLimit z
+            const angularUpperLimit = ragdoll.angularUpperLimit;
+            serializer.setFloat32(angularUpperLimit?.x ?? DefaultRagdollAngularLimit); // angularUpperLimit x
+            serializer.setFloat32(angularUpperLimit?.y ?? DefaultRagdollAngularLimit); // angularUpperLimit y
+            serializer.setFloat32(angularUpperLimit?.z ?? DefaultRagdollAngularLimit); // angularUpperLimit z
+            serializer.setFloat32(ragdoll.radiusScale ?? 0.2); // radiusScale
+            serializer.setFloat32(ragdoll.minBoneLength ?? 0.5); // minBoneLength
+        }
+
+        const ccd = (this._encodePhysicsOptions as IMmdWasmModelPhysicsCreationOptions).ccd;
+        if (ccd !== undefined) {
+            MmdMetadataEncoder._EncodeChunkHeader(serializer, ExtensionChunkTag.Ccd, 4 + 4);
+            serializer.setFloat32(ccd.motionThresholdScale); // motionThresholdScale
+            serializer.setFloat32(ccd.sweptSphereRadiusScale); // sweptSphereRadiusScale
+        }
+    }
 }
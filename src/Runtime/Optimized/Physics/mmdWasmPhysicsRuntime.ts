@@ -1,10 +1,13 @@
 import { Vector3 } from "@babylonjs/core/Maths/math.vector.pure";
 import type { DeepImmutable, Nullable } from "@babylonjs/core/types";
 
+import { PmxObject } from "@/Loader/Parser/pmxObject";
+
 import type { IWasmTypedArray } from "../Misc/IWasmTypedArray";
 import type { IMmdWasmInstance } from "../mmdWasmInstance";
 import type { MmdWasmModel } from "../mmdWasmModel";
 import { type IPhysicsInitializeSet, type MmdWasmRuntime } from "../mmdWasmRuntime";
+import { type IPhysicsWorldStats, ReadPhysicsWorldStats } from "./Bind/physicsWorldStats";
 import type { IMmdWasmPhysicsRuntime } from "./IMmdWasmPhysicsRuntime";
 import type { IMmdWasmPhysicsRuntimeImplCreationOptions, MmdWasmPhysicsRuntimeImpl } from "./mmdWasmPhysicsRuntimeImpl";
 
@@ -122,6 +125,13 @@ export class MmdWasmPhysicsRuntime implements IMmdWasmPhysicsRuntime {
         }
     }
 
+    public getStats(worldId: number): Nullable<IPhysicsWorldStats> {
+        this._nullCheck();
+        this._mmdRuntime.lock.wait();
+        const wasmInstance = this._mmdRuntime.wasmInstance;
+        return ReadPhysicsWorldStats(wasmInstance, wasmInstance.multiPhysicsWorldGetStatsPtr(this._physicsWorldPtr, worldId));
+    }
+
     public setMmdModelsWorldMatrix(mmdModels: MmdWasmModel[]): void {
         // set world matrix is thread safe because world matrix applied on before physics step
         const wasmInternal = this._mmdRuntime.wasmInternal;
@@ -136,6 +146,179 @@ export class MmdWasmPhysicsRuntime implements IMmdWasmPhysicsRuntime {
         }
     }
 
+    public setMmdModelSleeping(mmdModel: MmdWasmModel, enabled: boolean, linearThreshold = 0.8, angularThreshold = 1.0): void {
+        // this operation is thread safe because options are applied on before physics step
+        this._mmdRuntime.wasmInternal.setMmdModelPhysicsSleeping(mmdModel.ptr, enabled, linearThreshold, angularThreshold);
+    }
+
+    public setMmdModelCcd(mmdModel: MmdWasmModel, motionThresholdScale: number, sweptSphereRadiusScale: number): void {
+        // this operation is thread safe because options are applied on before physics step
+        this._mmdRuntime.wasmInternal.setMmdModelPhysicsCcd(mmdModel.ptr, motionThresholdScale, sweptSphereRadiusScale);
+    }
+
+    public setMmdModelInterpolation(mmdModel: MmdWasmModel, enabled: boolean): void {
+        // this operation is thread safe because options are applied on before physics step
+        this._mmdRuntime.wasmInternal.setMmdModelPhysicsInterpolation(mmdModel.ptr, enabled);
+    }
+
+    public setMmdModelRigidBodyMass(mmdModel: MmdWasmModel, rigidBodyIndex: number, mass: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelRigidBodyMass(mmdModel.ptr, rigidBodyIndex, mass);
+    }
+
+    public getMmdModelRigidBodyMass(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyMass(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public setMmdModelRigidBodyDamping(mmdModel: MmdWasmModel, rigidBodyIndex: number, linearDamping: number, angularDamping: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelRigidBodyDamping(mmdModel.ptr, rigidBodyIndex, linearDamping, angularDamping);
+    }
+
+    public getMmdModelRigidBodyLinearDamping(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyLinearDamping(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public getMmdModelRigidBodyAngularDamping(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyAngularDamping(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public setMmdModelRigidBodyFriction(mmdModel: MmdWasmModel, rigidBodyIndex: number, friction: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelRigidBodyFriction(mmdModel.ptr, rigidBodyIndex, friction);
+    }
+
+    public getMmdModelRigidBodyFriction(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyFriction(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public setMmdModelRigidBodyRestitution(mmdModel: MmdWasmModel, rigidBodyIndex: number, restitution: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelRigidBodyRestitution(mmdModel.ptr, rigidBodyIndex, restitution);
+    }
+
+    public getMmdModelRigidBodyRestitution(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyRestitution(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public setMmdModelRigidBodyCollisionFilter(mmdModel: MmdWasmModel, rigidBodyIndex: number, group: number, mask: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelRigidBodyCollisionFilter(mmdModel.ptr, rigidBodyIndex, group, mask);
+    }
+
+    public getMmdModelRigidBodyCollisionGroup(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyCollisionGroup(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public getMmdModelRigidBodyCollisionMask(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<number> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.getMmdModelRigidBodyCollisionMask(mmdModel.ptr, rigidBodyIndex) ?? null;
+    }
+
+    public setMmdModelRigidBodyPhysicsMode(mmdModel: MmdWasmModel, rigidBodyIndex: number, physicsMode: PmxObject.RigidBody.PhysicsMode): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelRigidBodyPhysicsMode(mmdModel.ptr, rigidBodyIndex, physicsMode);
+    }
+
+    public getMmdModelRigidBodyPhysicsMode(mmdModel: MmdWasmModel, rigidBodyIndex: number): Nullable<PmxObject.RigidBody.PhysicsMode> {
+        this._mmdRuntime.lock.wait();
+        const physicsMode = this._mmdRuntime.wasmInternal.getMmdModelRigidBodyPhysicsMode(mmdModel.ptr, rigidBodyIndex);
+        // static mode only exists on the wasm side
+        if (physicsMode === undefined || PmxObject.RigidBody.PhysicsMode.PhysicsWithBone < physicsMode) return null;
+        return physicsMode;
+    }
+
+    public setMmdModelJointLinearLimit(mmdModel: MmdWasmModel, jointIndex: number, lower: DeepImmutable<Vector3>, upper: DeepImmutable<Vector3>): boolean {
+        this._nullCheck();
+        this._mmdRuntime.lock.wait();
+        const [lowerPtr, upperPtr] = this._writeVector3Pair(lower, upper);
+        return this._mmdRuntime.wasmInternal.setMmdModelJointLinearLimit(mmdModel.ptr, jointIndex, lowerPtr, upperPtr);
+    }
+
+    public setMmdModelJointAngularLimit(mmdModel: MmdWasmModel, jointIndex: number, lower: DeepImmutable<Vector3>, upper: DeepImmutable<Vector3>): boolean {
+        this._nullCheck();
+        this._mmdRuntime.lock.wait();
+        const [lowerPtr, upperPtr] = this._writeVector3Pair(lower, upper);
+        return this._mmdRuntime.wasmInternal.setMmdModelJointAngularLimit(mmdModel.ptr, jointIndex, lowerPtr, upperPtr);
+    }
+
+    /**
+     * Writes two vectors to the world matrix buffer which is used as a scratch buffer
+     */
+    private _writeVector3Pair(a: DeepImmutable<Vector3>, b: DeepImmutable<Vector3>): [number, number] {
+        const buffer = this._worldMatrixBuffer.array;
+        a.toArray(buffer, 0);
+        b.toArray(buffer, 3);
+        return [buffer.byteOffset, buffer.byteOffset + 3 * Float32Array.BYTES_PER_ELEMENT];
+    }
+
+    public setMmdModelJointLinearStiffness(mmdModel: MmdWasmModel, jointIndex: number, stiffness: DeepImmutable<Vector3>): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointLinearStiffness(mmdModel.ptr, jointIndex, stiffness.x, stiffness.y, stiffness.z);
+    }
+
+    public setMmdModelJointAngularStiffness(mmdModel: MmdWasmModel, jointIndex: number, stiffness: DeepImmutable<Vector3>): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointAngularStiffness(mmdModel.ptr, jointIndex, stiffness.x, stiffness.y, stiffness.z);
+    }
+
+    public setMmdModelJointLinearDamping(mmdModel: MmdWasmModel, jointIndex: number, damping: DeepImmutable<Vector3>): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointLinearDamping(mmdModel.ptr, jointIndex, damping.x, damping.y, damping.z);
+    }
+
+    public setMmdModelJointAngularDamping(mmdModel: MmdWasmModel, jointIndex: number, damping: DeepImmutable<Vector3>): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointAngularDamping(mmdModel.ptr, jointIndex, damping.x, damping.y, damping.z);
+    }
+
+    public setMmdModelJointStopErp(mmdModel: MmdWasmModel, jointIndex: number, value: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointStopErp(mmdModel.ptr, jointIndex, value);
+    }
+
+    public setMmdModelJointStopCfm(mmdModel: MmdWasmModel, jointIndex: number, value: number): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointStopCfm(mmdModel.ptr, jointIndex, value);
+    }
+
+    public setMmdModelJointEnabled(mmdModel: MmdWasmModel, jointIndex: number, enabled: boolean): boolean {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.setMmdModelJointEnabled(mmdModel.ptr, jointIndex, enabled);
+    }
+
+    public isMmdModelJointEnabled(mmdModel: MmdWasmModel, jointIndex: number): Nullable<boolean> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.isMmdModelJointEnabled(mmdModel.ptr, jointIndex) ?? null;
+    }
+
+    public setMmdModelPhysicsBlend(mmdModel: MmdWasmModel, transitionTime: number, forceGain = 30, torqueGain = 30): void {
+        // this operation is thread safe because options are applied on before physics step
+        this._mmdRuntime.wasmInternal.setMmdModelPhysicsBlend(mmdModel.ptr, transitionTime, forceGain, torqueGain);
+    }
+
+    public setMmdModelRagdoll(mmdModel: MmdWasmModel, enabled: boolean): boolean {
+        // this operation is thread safe because options are applied on before physics step
+        return this._mmdRuntime.wasmInternal.setMmdModelRagdoll(mmdModel.ptr, enabled);
+    }
+
+    public isMmdModelRagdollEnabled(mmdModel: MmdWasmModel): Nullable<boolean> {
+        this._mmdRuntime.lock.wait();
+        return this._mmdRuntime.wasmInternal.isMmdModelRagdollEnabled(mmdModel.ptr) ?? null;
+    }
+
+    public preRollMmdModel(mmdModel: MmdWasmModel, stepCount: number, initialDamping = 0): void {
+        this._nullCheck();
+        this._mmdRuntime.lock.wait(); // pre roll must not run while buffered evaluation is running
+        this._mmdRuntime.wasmInternal.preRollMmdModelPhysics(mmdModel.ptr, stepCount, initialDamping);
+    }
+
     public getImpl(implCtor: typeof MmdWasmPhysicsRuntimeImpl, options: IMmdWasmPhysicsRuntimeImplCreationOptions = {}): MmdWasmPhysicsRuntimeImpl {
         if (this._impl === null) {
             this._impl = new implCtor(this._mmdRuntime, this._gravity, options);
@@ -14,6 +14,16 @@ import type { IMmdModelPhysicsCreationOptions } from "../mmdRuntime";
 /**
  * mmd model metadata representation in binary
  *
+ * magic: uint8[4] // "MMDM"
+ * version: uint32 // 1
+ * sectionCount: uint32
+ * {
+ *  kind: uint32 // 0: bones, 1: morphs, 2: physics, 3: extensions (optional)
+ *  offset: uint32 // byte offset from the start of the metadata
+ *  size: uint32 // byte size of the section
+ * }[sectionCount]
+ *
+ * -- bones section
  * boneCount: uint32
  * appendTransformCount: uint32
  * ikCount: uint32
@@ -49,6 +59,7 @@ import type { IMmdModelPhysicsCreationOptions } from "../mmdRuntime";
  *  }
  * }[boneCount]
  *
+ * -- morphs section
  * morphCount: uint32
  * { // if boneMorph
  *  kind: uint8
@@ -67,6 +78,7 @@ import type { IMmdModelPhysicsCreationOptions } from "../mmdRuntime";
  *  ratios: float32[indexCount]
  * }[morphCount]
  *
+ * -- physics section
  * physicsInfoKind: uint8 // 0: no physics, 1: striped rigid bodies, 2: full physics
  * -- padding: uint8
  * -- padding: uint16
@@ -125,12 +137,44 @@ import type { IMmdModelPhysicsCreationOptions } from "../mmdRuntime";
  *   springRotation: float32[3]
  *  }[jointCount]
  * }
+ *
+ * -- extensions section (optional)
+ * {
+ *  tag: uint32
+ *  size: uint32
+ *  payload: uint8[size]
+ *  -- padding: uint8[(4 - size % 4) % 4]
+ * }[] // until the end of the section, chunks with unknown tag are skipped
+ *
+ *
+ * { // if tag === 2 (ragdoll), ragdoll bodies are derived from the rigid bodies and the bone hierarchy
+ *  defaultMass: float32 // mass of follow bone bodies that have zero mass and of generated bodies
+ *  angularLowerLimit: float32[3] // limits of generated joints
+ *  angularUpperLimit: float32[3]
+ *  radiusScale: float32 // radius of bodies generated for bones without rigid body, relative to the bone length
+ *  minBoneLength: float32 // shorter bones get no generated body
+ * }
+ *
+ * { // if tag === 3 (continuous collision detection)
+ *  motionThresholdScale: float32 // relative to the inner radius of each body, 0 disables ccd
+ *  sweptSphereRadiusScale: float32 // relative to the inner radius of each body
+ * }
  */
 
+const enum MetadataSectionKind {
+    Bones = 0,
+    Morphs = 1,
+    Physics = 2,
+    Extensions = 3
+}
+
 /**
  * @internal
  */
 export class MmdMetadataEncoder {
+    public static readonly Magic = 0x4D444D4D; // "MMDM" in little endian
+    public static readonly Version = 1;
+
     protected readonly _logger: ILogger;
     protected _encodePhysicsOptions: IMmdModelPhysicsCreationOptions | boolean;
 
@@ -165,6 +209,7 @@ export class MmdMetadataEncoder {
             }
 
             this._encodePhysicsOptions = {
+                ...options, // keep options of derived encoders
                 worldId: validatedWorldId,
                 kinematicSharedWorldIds: validatedKinematicSharedWorldIds,
                 disableOffsetForConstraintFrame: options.disableOffsetForConstraintFrame
@@ -172,6 +217,19 @@ export class MmdMetadataEncoder {
         }
     }
 
+    private static _GetSectionCount(extensionsSize: number): number {
+        return extensionsSize === 0
+            ? 3 // bones, morphs, physics
+            : 4; // bones, morphs, physics, extensions
+    }
+
+    protected _computeHeaderSize(extensionsSize: number): number {
+        return 4 // magic
+            + 4 // version
+            + 4 // sectionCount
+            + (4 + 4 + 4) * MmdMetadataEncoder._GetSectionCount(extensionsSize); // sections
+    }
+
     protected _computeBonesSize(metadata: MmdModelMetadata): number {
         let dataLength = 4 // boneCount
             + 4 // appendTransformCount
@@ -270,12 +328,39 @@ export class MmdMetadataEncoder {
         return dataLength;
     }
 
+    /**
+     * Compute the size of the extensions section
+     *
+     * Extensions section is omitted if the size is zero
+     * @param _metadata metadata of the model, null if physics is not encoded
+     * @returns byte size of all chunks including chunk headers and paddings
+     */
+    protected _computeExtensionsSize(_metadata: Nullable<MmdModelMetadata>): number {
+        return 0;
+    }
+
     public computeSize(mmdMesh: MmdMesh): number {
-        const metadata = mmdMesh.metadata;
+        return this._computeSize(mmdMesh.metadata);
+    }
 
-        const dataLength = this._computeBonesSize(metadata)
+    /**
+     * Compute the size of the metadata that only has the physics creation options
+     * @param mmdMesh mmd mesh
+     * @returns byte size of the metadata
+     */
+    public computePhysicsOptionsSize(mmdMesh: MmdMesh): number {
+        return this._computeSize(MmdMetadataEncoder._StripModelData(mmdMesh.metadata));
+    }
+
+    private _computeSize(metadata: MmdModelMetadata): number {
+        const physicsMetadata = this._encodePhysicsOptions ? metadata : null;
+
+        const extensionsSize = this._computeExtensionsSize(physicsMetadata);
+        const dataLength = this._computeHeaderSize(extensionsSize)
+            + this._computeBonesSize(metadata)
             + this._computeMorphsSize(metadata)
-            + this._computePhysicsSize(this._encodePhysicsOptions ? metadata : null);
+            + this._computePhysicsSize(physicsMetadata)
+            + extensionsSize;
 
         return dataLength;
     }
@@ -450,15 +535,97 @@ export class MmdMetadataEncoder {
         }
     }
 
+    /**
+     * Encode chunks of the extensions section
+     *
+     * Written size must be equal to `_computeExtensionsSize`
+     * @param _serializer serializer positioned at the start of the extensions section
+     * @param _metadata metadata of the model, null if physics is not encoded
+     */
+    protected _encodeExtensions(_serializer: AlignedDataSerializer, _metadata: Nullable<MmdModelMetadata>): void {
+        // no extension chunks by default
+    }
+
+    /**
+     * Encode an extension chunk header, payload must be padded to 4 bytes after this
+     * @param serializer serializer
+     * @param tag chunk tag
+     * @param payloadSize byte size of the payload without padding
+     */
+    protected static _EncodeChunkHeader(serializer: AlignedDataSerializer, tag: number, payloadSize: number): void {
+        serializer.setUint32(tag); // tag
+        serializer.setUint32(payloadSize); // size
+    }
+
+    private _encodeSection(serializer: AlignedDataSerializer, baseOffset: number, kind: MetadataSectionKind, startOffset: number, endOffset: number): void {
+        serializer.setUint32(kind); // kind
+        serializer.setUint32(startOffset - baseOffset); // offset
+        serializer.setUint32(endOffset - startOffset); // size
+    }
+
+    /**
+     * Strip bones, morphs, rigid bodies and joints from the metadata so only the physics creation options are encoded
+     * @param metadata metadata of the model
+     * @returns metadata without model data
+     */
+    private static _StripModelData(metadata: MmdModelMetadata): MmdModelMetadata {
+        return {
+            ...metadata,
+            bones: [],
+            morphs: [],
+            rigidBodies: [],
+            joints: []
+        };
+    }
+
     public encode(mmdMesh: MmdMesh, linkedBones: IMmdRuntimeLinkedBone[], buffer: Uint8Array): Int32Array {
-        const metadata = mmdMesh.metadata;
+        return this._encode(mmdMesh.metadata, mmdMesh, linkedBones, buffer);
+    }
+
+    /**
+     * Encode the physics creation options as the metadata without bones, morphs, rigid bodies and joints
+     *
+     * It is used when the model data is read from the model file on the wasm side
+     * @param mmdMesh mmd mesh, its world matrix is encoded as the model initial world matrix
+     * @param buffer buffer of `computePhysicsOptionsSize` bytes
+     */
+    public encodePhysicsOptions(mmdMesh: MmdMesh, buffer: Uint8Array): void {
+        this._encode(MmdMetadataEncoder._StripModelData(mmdMesh.metadata), mmdMesh, [], buffer);
+    }
+
+    private _encode(metadata: MmdModelMetadata, rootTransform: TransformNode, linkedBones: IMmdRuntimeLinkedBone[], buffer: Uint8Array): Int32Array {
+        const physicsMetadata = this._encodePhysicsOptions ? metadata : null;
+        const sectionCount = MmdMetadataEncoder._GetSectionCount(this._computeExtensionsSize(physicsMetadata));
 
         const serializer = new AlignedDataSerializer(buffer.buffer);
         serializer.offset = buffer.byteOffset;
 
+        serializer.setUint32(MmdMetadataEncoder.Magic); // magic
+        serializer.setUint32(MmdMetadataEncoder.Version); // version
+        serializer.setUint32(sectionCount); // sectionCount
+        const sectionTableOffset = serializer.offset;
+        serializer.offset += (4 + 4 + 4) * sectionCount; // sections are written after encoding
+
+        const bonesOffset = serializer.offset;
         this._encodeBones(serializer, metadata, linkedBones);
+        const morphsOffset = serializer.offset;
         const wasmMorphMap = this._encodeMorphs(serializer, metadata);
-        this._encodePhysics(serializer, this._encodePhysicsOptions ? metadata : null, mmdMesh);
+        const physicsOffset = serializer.offset;
+        this._encodePhysics(serializer, physicsMetadata, rootTransform);
+        const extensionsOffset = serializer.offset;
+        if (sectionCount === 4) {
+            this._encodeExtensions(serializer, physicsMetadata);
+        }
+        const endOffset = serializer.offset;
+
+        serializer.offset = sectionTableOffset;
+        this._encodeSection(serializer, buffer.byteOffset, MetadataSectionKind.Bones, bonesOffset, morphsOffset);
+        this._encodeSection(serializer, buffer.byteOffset, MetadataSectionKind.Morphs, morphsOffset, physicsOffset);
+        this._encodeSection(serializer, buffer.byteOffset, MetadataSectionKind.Physics, physicsOffset, extensionsOffset);
+        if (sectionCount === 4) {
+            this._encodeSection(serializer, buffer.byteOffset, MetadataSectionKind.Extensions, extensionsOffset, endOffset);
+        }
+        serializer.offset = endOffset;
 
         return wasmMorphMap;
     }
@@ -0,0 +1,19 @@
+/**
+ * Model file format that can be parsed on the wasm side
+ */
+export enum MmdModelFileFormat {
+    /**
+     * PMX 2.0 / 2.1
+     */
+    Pmx = 0,
+
+    /**
+     * PMD, converted to PMX semantics
+     */
+    Pmd = 1,
+
+    /**
+     * BPMX
+     */
+    Bpmx = 2,
+}
@@ -0,0 +1,70 @@
+/**
+ * Kind of the issue found by the model metadata validation
+ */
+export enum MmdModelValidationIssueKind {
+    /**
+     * Parent chain of the bone is looped
+     *
+     * subjectIndex: bone index that closes the cycle, relatedIndex: its parent bone index
+     */
+    CyclicParentChain = 0,
+
+    /**
+     * Append transform chain of the bone is looped
+     *
+     * subjectIndex: bone index that closes the cycle, relatedIndex: its append transform parent bone index
+     */
+    CyclicAppendTransform = 1,
+
+    /**
+     * IK chain contains its own target or the IK bone
+     *
+     * subjectIndex: IK bone index, relatedIndex: IK link bone index
+     */
+    IkChainContainsTarget = 2,
+
+    /**
+     * Dynamic rigid body has zero or negative mass
+     *
+     * subjectIndex: rigid body index, relatedIndex: linked bone index
+     */
+    ZeroMassDynamicBody = 3,
+
+    /**
+     * Joint connects a rigid body to itself
+     *
+     * subjectIndex: joint index, relatedIndex: rigid body index
+     */
+    SelfJoint = 4,
+
+    /**
+     * Bone is transformed before its parent
+     *
+     * subjectIndex: bone index, relatedIndex: parent bone index
+     */
+    TransformOrderInversion = 5
+}
+
+/**
+ * Single issue found by the model metadata validation
+ */
+export interface IMmdModelValidationIssue {
+    /**
+     * Kind of the issue
+     */
+    readonly kind: MmdModelValidationIssueKind;
+
+    /**
+     * Index of the bone, rigid body or joint that has the issue
+     *
+     * Meaning depends on `kind`
+     */
+    readonly subjectIndex: number;
+
+    /**
+     * Index of the related bone or rigid body
+     *
+     * Meaning depends on `kind`
+     */
+    readonly relatedIndex: number;
+}
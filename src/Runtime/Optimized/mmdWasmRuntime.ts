@@ -22,6 +22,9 @@ import { WasmSpinlock } from "./Misc/wasmSpinlock";
 import { MmdMetadataEncoder } from "./mmdMetadataEncoder";
 import type { IMmdWasmInstance } from "./mmdWasmInstance";
 import { MmdWasmModel } from "./mmdWasmModel";
+import { MmdModelFileFormat } from "./mmdWasmModelFileFormat";
+import type { IMmdModelValidationIssue, MmdModelValidationIssueKind } from "./mmdWasmModelValidation";
+import type { IMmdWasmModelProfile, IMmdWasmRuntimeProfile } from "./mmdWasmRuntimeProfile";
 import type { IMmdWasmPhysicsRuntime } from "./Physics/IMmdWasmPhysicsRuntime";
 import type { IPhysicsClock } from "./Physics/IPhysicsClock";
 import type { MmdWasmPhysics } from "./Physics/mmdWasmPhysics";
@@ -102,6 +105,7 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
     public autoPhysicsInitialization: boolean;
 
     private _loggingEnabled: boolean;
+    private _profilingEnabled: boolean;
 
     /** @internal */
     public log: (message: string) => void;
@@ -190,6 +194,7 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
         this.autoPhysicsInitialization = true;
 
         this._loggingEnabled = false;
+        this._profilingEnabled = false;
         this.log = this._logDisabled;
         this.warn = this._warnDisabled;
         this.error = this._errorDisabled;
@@ -345,6 +350,95 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
         skeleton: IMmdLinkedBoneContainer,
         options: IMmdModelCreationOptions<TMaterial> = {}
     ): MmdWasmModel {
+        this._setDefaultCreationOptions(options);
+
+        return this._createMmdModel(mmdMesh, skeleton, options, "metadata is malformed", (): [number, Int32Array] => {
+            const metadataEncoder = this._mmdMetadataEncoder;
+            metadataEncoder.setEncodePhysicsOptions(options.buildPhysics!);
+
+            const metadataSize = metadataEncoder.computeSize(mmdMesh);
+
+            const metadataBufferPtr = this.wasmInstance.allocateBuffer(metadataSize);
+
+            const metadataBuffer = this.wasmInstance.createTypedArray(Uint8Array, metadataBufferPtr, metadataSize);
+            const wasmMorphIndexMap = metadataEncoder.encode(mmdMesh, skeleton.bones, metadataBuffer.array);
+
+            const mmdModelPtr = this.wasmInternal.createMmdModel(metadataBufferPtr, metadataSize);
+            this.wasmInstance.deallocateBuffer(metadataBufferPtr, metadataSize);
+            return [mmdModelPtr, wasmMorphIndexMap];
+        });
+    }
+
+    /**
+     * Create MMD model from mesh and the model file that the mesh is loaded from
+     *
+     * The file is parsed and encoded to the model metadata on the wasm side instead of `MmdMetadataEncoder`,
+     * the mesh is still used for binding bones and morphs to the scene so it must be loaded from the same file
+     *
+     * Physics creation options are applied in the same way as `createMmdModel`,
+     * the mesh world matrix at this call is used as the model initial world matrix of the physics
+     * @param mmdSkinnedMesh MmdSkinnedMesh
+     * @param fileData PMX, PMD or BPMX file bytes
+     * @param fileFormat format of the file
+     * @param options Creation options
+     * @returns MMD model
+     * @throws {Error} if mesh is not `MmdSkinnedMesh`, the file is malformed or the file has different morphs from the mesh
+     */
+    public createMmdModelFromFile<TMaterial extends Material>(
+        mmdSkinnedMesh: Mesh,
+        fileData: ArrayBufferView,
+        fileFormat: MmdModelFileFormat,
+        options: IMmdModelCreationOptions<TMaterial> = {}
+    ): MmdWasmModel {
+        if (!MmdMesh.isMmdSkinnedMesh(mmdSkinnedMesh)) throw new Error("Mesh validation failed.");
+        this._setDefaultCreationOptions(options);
+
+        const mmdMesh = mmdSkinnedMesh;
+        return this._createMmdModel(mmdMesh, mmdMesh.metadata.skeleton, options, "file is malformed or does not match the mesh", (): [number, Int32Array] => {
+            const metadataEncoder = this._mmdMetadataEncoder;
+            metadataEncoder.setEncodePhysicsOptions(options.buildPhysics!);
+
+            const physicsOptionsSize = metadataEncoder.computePhysicsOptionsSize(mmdMesh);
+            const physicsOptionsBufferPtr = this.wasmInstance.allocateBuffer(physicsOptionsSize);
+            const physicsOptionsBuffer = this.wasmInstance.createTypedArray(Uint8Array, physicsOptionsBufferPtr, physicsOptionsSize);
+            metadataEncoder.encodePhysicsOptions(mmdMesh, physicsOptionsBuffer.array);
+
+            const fileSize = fileData.byteLength;
+            const fileBufferPtr = this.wasmInstance.allocateBuffer(fileSize);
+            const fileBuffer = this.wasmInstance.createTypedArray(Uint8Array, fileBufferPtr, fileSize);
+            fileBuffer.array.set(new Uint8Array(fileData.buffer, fileData.byteOffset, fileSize));
+
+            // wasm morph index map is computed from the file morphs
+            const morphCount = mmdMesh.metadata.morphs.length;
+            const wasmMorphIndexMapByteSize = morphCount * Int32Array.BYTES_PER_ELEMENT;
+            const wasmMorphIndexMapPtr = morphCount !== 0 ? this.wasmInstance.allocateBuffer(wasmMorphIndexMapByteSize) : 0;
+
+            const wasmRuntime = this.wasmInternal;
+            const createMmdModelFromFile = fileFormat === MmdModelFileFormat.Pmx
+                ? wasmRuntime.createMmdModelFromPmx
+                : fileFormat === MmdModelFileFormat.Pmd
+                    ? wasmRuntime.createMmdModelFromPmd
+                    : wasmRuntime.createMmdModelFromBpmx;
+            const mmdModelPtr = createMmdModelFromFile.call(
+                wasmRuntime,
+                fileBufferPtr, fileSize,
+                physicsOptionsBufferPtr, physicsOptionsSize,
+                wasmMorphIndexMapPtr, morphCount
+            );
+
+            let wasmMorphIndexMap = new Int32Array(0);
+            if (morphCount !== 0) {
+                wasmMorphIndexMap = this.wasmInstance.createTypedArray(Int32Array, wasmMorphIndexMapPtr, morphCount).array.slice();
+                this.wasmInstance.deallocateBuffer(wasmMorphIndexMapPtr, wasmMorphIndexMapByteSize);
+            }
+
+            this.wasmInstance.deallocateBuffer(fileBufferPtr, fileSize);
+            this.wasmInstance.deallocateBuffer(physicsOptionsBufferPtr, physicsOptionsSize);
+            return [mmdModelPtr, wasmMorphIndexMap];
+        });
+    }
+
+    private _setDefaultCreationOptions<TMaterial extends Material>(options: IMmdModelCreationOptions<TMaterial>): void {
         if (options.materialProxyConstructor === undefined) {
             options.materialProxyConstructor = MmdRuntimeShared.MaterialProxyConstructor;
             if (options.materialProxyConstructor === null) {
@@ -357,7 +451,15 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
         if (options.trimMetadata === undefined) {
             options.trimMetadata = true;
         }
+    }
 
+    private _createMmdModel<TMaterial extends Material>(
+        mmdMesh: MmdSkinnedMesh,
+        skeleton: IMmdLinkedBoneContainer,
+        options: IMmdModelCreationOptions<TMaterial>,
+        malformedReason: string,
+        createWasmModel: () => [number, Int32Array]
+    ): MmdWasmModel {
         this.lock.wait(); // ensure that the runtime is not evaluating animations
 
         const wasmRuntime = this.wasmInternal;
@@ -369,17 +471,15 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
             this._usingWasmBackBuffer = false;
         }
 
-        const metadataEncoder = this._mmdMetadataEncoder;
-        metadataEncoder.setEncodePhysicsOptions(options.buildPhysics);
-
-        const metadataSize = metadataEncoder.computeSize(mmdMesh);
-
-        const metadataBufferPtr = this.wasmInstance.allocateBuffer(metadataSize);
-
-        const metadataBuffer = this.wasmInstance.createTypedArray(Uint8Array, metadataBufferPtr, metadataSize);
-        const wasmMorphIndexMap = metadataEncoder.encode(mmdMesh, skeleton.bones, metadataBuffer.array);
-
-        const mmdModelPtr = wasmRuntime.createMmdModel(metadataBufferPtr, metadataSize);
+        const [mmdModelPtr, wasmMorphIndexMap] = createWasmModel();
+        if (mmdModelPtr === 0) {
+            if (usingWasmBackBuffer) {
+                wasmRuntime.swapWorldMatrixBuffer();
+                this._usingWasmBackBuffer = true;
+            }
+            this._flushWasmDiagnosticLog();
+            throw new Error(`Failed to create mmd model: ${malformedReason}`);
+        }
 
         const model = new MmdWasmModel(
             this,
@@ -398,14 +498,12 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
                     }
                     : null
                 : null,
-            options.trimMetadata
+            options.trimMetadata!
         );
         this._models.push(model);
 
         this._getPhysicsInitializeSet().add(model);
 
-        this.wasmInstance.deallocateBuffer(metadataBufferPtr, metadataSize);
-
         // desync again
         if (usingWasmBackBuffer) {
             wasmRuntime.swapWorldMatrixBuffer();
@@ -422,6 +520,106 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
         return model;
     }
 
+    /**
+     * Validate MMD model without creating it
+     *
+     * Metadata is encoded in the same way as `createMmdModel`, so the result describes the model that would be created
+     * @param mmdSkinnedMesh MmdSkinnedMesh
+     * @param options Creation options
+     * @returns Validation issues, or null if the metadata is malformed
+     * @throws {Error} if mesh is not `MmdSkinnedMesh`
+     */
+    public validateMmdModel<TMaterial extends Material>(
+        mmdSkinnedMesh: Mesh,
+        options: IMmdModelCreationOptions<TMaterial> = {}
+    ): Nullable<IMmdModelValidationIssue[]> {
+        if (!MmdMesh.isMmdSkinnedMesh(mmdSkinnedMesh)) throw new Error("Mesh validation failed.");
+
+        this.lock.wait(); // ensure that the runtime is not evaluating animations
+
+        const metadataEncoder = this._mmdMetadataEncoder;
+        // world ids do not affect the validation, fixed id keeps the next world id of the physics runtime
+        // other physics options are kept so the metadata is same as the one `createMmdModel` encodes
+        metadataEncoder.setEncodePhysicsOptions(
+            options.buildPhysics === false
+                ? false
+                : typeof options.buildPhysics === "object"
+                    ? { ...options.buildPhysics, worldId: 0 }
+                    : { worldId: 0 }
+        );
+
+        const metadataSize = metadataEncoder.computeSize(mmdSkinnedMesh);
+        const metadataBufferPtr = this.wasmInstance.allocateBuffer(metadataSize);
+        const metadataBuffer = this.wasmInstance.createTypedArray(Uint8Array, metadataBufferPtr, metadataSize);
+        metadataEncoder.encode(mmdSkinnedMesh, mmdSkinnedMesh.metadata.skeleton.bones, metadataBuffer.array);
+
+        const issues = this._validateMetadataBuffer(metadataBufferPtr, metadataSize);
+        this.wasmInstance.deallocateBuffer(metadataBufferPtr, metadataSize);
+        return issues;
+    }
+
+    /**
+     * Validate serialized MMD model metadata without creating a model
+     *
+     * This is useful for rejecting broken models before they are loaded into the scene
+     * @param serializedMetadata Metadata in the format of `MmdMetadataEncoder`
+     * @returns Validation issues, or null if the metadata is malformed
+     */
+    public validateMmdModelMetadata(serializedMetadata: Uint8Array): Nullable<IMmdModelValidationIssue[]> {
+        const metadataSize = serializedMetadata.byteLength;
+        if (metadataSize === 0) return null;
+
+        this.lock.wait(); // ensure that the runtime is not evaluating animations
+
+        const metadataBufferPtr = this.wasmInstance.allocateBuffer(metadataSize);
+        const metadataBuffer = this.wasmInstance.createTypedArray(Uint8Array, metadataBufferPtr, metadataSize);
+        metadataBuffer.array.set(serializedMetadata);
+
+        const issues = this._validateMetadataBuffer(metadataBufferPtr, metadataSize);
+        this.wasmInstance.deallocateBuffer(metadataBufferPtr, metadataSize);
+        return issues;
+    }
+
+    /**
+     * Get the hash of the bone world matrices of all models and the physics state
+     *
+     * Only available when the wasm runtime is built with the `deterministic` feature
+     * @returns State hash, or null if the runtime is not built with the `deterministic` feature
+     */
+    public getStateHash(): Nullable<bigint> {
+        const wasmInternal = this.wasmInternal as typeof this.wasmInternal & { getStateHash?: () => bigint };
+        if (wasmInternal.getStateHash === undefined) return null;
+
+        this.lock.wait(); // ensure that the runtime is not evaluating animations
+
+        return wasmInternal.getStateHash();
+    }
+
+    private _validateMetadataBuffer(metadataBufferPtr: number, metadataSize: number): Nullable<IMmdModelValidationIssue[]> {
+        const resultPtr = this.wasmInternal.validateMmdModelMetadata(metadataBufferPtr, metadataSize);
+        if (resultPtr === 0) {
+            this._flushWasmDiagnosticLog();
+            return null;
+        }
+
+        // result is { ptr: u32, len: u32 } and each issue is { kind: u8, padding: u8[3], subjectIndex: i32, relatedIndex: i32 }
+        const [issueArrayPtr, issueCount] = this.wasmInstance.createTypedArray(Uint32Array, resultPtr, 2).array;
+        const issues: IMmdModelValidationIssue[] = [];
+        if (0 < issueCount) {
+            const issueArray = this.wasmInstance.createTypedArray(Int32Array, issueArrayPtr, issueCount * 3).array;
+            for (let i = 0; i < issueCount; ++i) {
+                issues.push({
+                    kind: (issueArray[i * 3] & 0xFF) as MmdModelValidationIssueKind,
+                    subjectIndex: issueArray[i * 3 + 1],
+                    relatedIndex: issueArray[i * 3 + 2]
+                });
+            }
+        }
+
+        this._flushWasmDiagnosticLog();
+        return issues;
+    }
+
     /**
      * Destroy MMD model
      *
@@ -1248,6 +1446,66 @@ export class MmdWasmRuntime implements IMmdRuntime<MmdWasmModel> {
         }
     }
 
+    /**
+     * Enable or disable stage timing collection of the runtime and each model (default: false)
+     *
+     * Physics world stats are collected as well when physics is enabled
+     *
+     * Each timing costs a call to `performance.now`, so keep it disabled in production
+     */
+    public get profilingEnabled(): boolean {
+        return this._profilingEnabled;
+    }
+
+    public set profilingEnabled(value: boolean) {
+        if (this._profilingEnabled === value) return;
+        this._profilingEnabled = value;
+
+        this.lock.wait(); // ensure that the runtime is not evaluating animations
+        this.wasmInternal.setProfilingEnabled(value);
+    }
+
+    /**
+     * Get the stage timings of the last `beforePhysics`
+     *
+     * @returns Runtime profile, or null if profiling is disabled
+     */
+    public getProfile(): Nullable<IMmdWasmRuntimeProfile> {
+        this.lock.wait(); // ensure that the runtime is not evaluating animations
+
+        const profilePtr = this.wasmInternal.getProfilePtr();
+        if (profilePtr === 0) return null;
+
+        const profile = this.wasmInstance.createTypedArray(Float32Array, profilePtr, 2).array;
+        return {
+            models: profile[0],
+            physics: profile[1]
+        };
+    }
+
+    /**
+     * Get the stage timings of the model in the last frame
+     *
+     * @param mmdModel MMD model created by this runtime
+     * @returns Model profile, or null if profiling is disabled
+     */
+    public getMmdModelProfile(mmdModel: MmdWasmModel): Nullable<IMmdWasmModelProfile> {
+        this.lock.wait(); // ensure that the runtime is not evaluating animations
+
+        const profilePtr = this.wasmInternal.getMmdModelProfilePtr(mmdModel.ptr);
+        if (profilePtr === 0) return null;
+
+        const profile = this.wasmInstance.createTypedArray(Float32Array, profilePtr, 6).array;
+        return {
+            animation: profile[0],
+            morph: profile[1],
+            boneUpdate: profile[2],
+            ik: profile[3],
+            syncBodies: profile[4],
+            syncBones: profile[5]
+        };
+    }
+
     private _logEnabled(message: string): void {
         Logger.Log(message);
     }
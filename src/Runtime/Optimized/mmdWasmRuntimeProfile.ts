@@ -0,0 +1,51 @@
+/**
+ * Wall time of the MMD WASM runtime stages in milliseconds
+ *
+ * Models are updated in parallel, so the per model times do not add up to `models`
+ */
+export interface IMmdWasmRuntimeProfile {
+    /**
+     * Animation, morph and bone update of all models
+     */
+    models: number;
+
+    /**
+     * Sync bodies, physics step and sync bones of all models
+     */
+    physics: number;
+}
+
+/**
+ * Stage timings of a MMD WASM model in milliseconds
+ */
+export interface IMmdWasmModelProfile {
+    /**
+     * Animation evaluation
+     */
+    animation: number;
+
+    /**
+     * Morph update
+     */
+    morph: number;
+
+    /**
+     * Bone update excluding IK
+     */
+    boneUpdate: number;
+
+    /**
+     * IK solve
+     */
+    ik: number;
+
+    /**
+     * Synchronization of kinematic rigid bodies with the bones
+     */
+    syncBodies: number;
+
+    /**
+     * Synchronization of the bones with dynamic rigid bodies
+     */
+    syncBones: number;
+}
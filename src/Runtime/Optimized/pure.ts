@@ -14,6 +14,9 @@ export * from "./Physics/pure";
 export * from "./mmdMetadataEncoder";
 export * from "./mmdWasmInstance";
 export * from "./mmdWasmModel";
+export * from "./mmdWasmModelFileFormat";
+export * from "./mmdWasmModelValidation";
 export * from "./mmdWasmMorphController";
 export * from "./mmdWasmRuntime";
+export * from "./mmdWasmRuntimeProfile";
 export * from "./mmdWasmRuntimeBone";
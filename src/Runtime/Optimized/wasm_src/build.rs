@@ -39,6 +39,11 @@ fn main() {
             .define("__wasm32__", None);
 
         
+        // do not fuse multiply and add, so the same input gives the same bits
+        #[cfg(feature = "deterministic")]
+        build
+            .flag("-ffp-contract=off");
+
         #[cfg(debug_assertions)]
         build
             .define("BT_DEBUG", None);
@@ -109,6 +114,7 @@ fn main() {
                 "bullet_src/BulletCollision/CollisionShapes/btStaticPlaneShape.cpp",
                 "bullet_src/BulletCollision/CollisionShapes/btStridingMeshInterface.cpp",
                 "bullet_src/BulletCollision/CollisionShapes/btTriangleCallback.cpp",
+                "bullet_src/BulletCollision/CollisionShapes/btTriangleIndexVertexArray.cpp",
                 "bullet_src/BulletCollision/CollisionShapes/btTriangleMeshShape.cpp",
                 "bullet_src/BulletCollision/NarrowPhaseCollision/btContinuousConvexCollision.cpp",
                 "bullet_src/BulletCollision/NarrowPhaseCollision/btConvexCast.cpp",
@@ -49,3 +49,105 @@ extern "C" void bw_destroy_staticplaneshape(void* shape)
     btStaticPlaneShape* s = static_cast<btStaticPlaneShape*>(shape);
     delete s;
 }
+
+extern "C" void* bw_create_cylindershape(float x, float y, float z)
+{
+    btCylinderShape* shape = new btCylinderShape(btVector3(x, y, z));
+    return shape;
+}
+
+extern "C" void bw_destroy_cylindershape(void* shape)
+{
+    btCylinderShape* s = static_cast<btCylinderShape*>(shape);
+    delete s;
+}
+
+extern "C" void* bw_create_coneshape(float radius, float height)
+{
+    btConeShape* shape = new btConeShape(radius, height);
+    return shape;
+}
+
+extern "C" void bw_destroy_coneshape(void* shape)
+{
+    btConeShape* s = static_cast<btConeShape*>(shape);
+    delete s;
+}
+
+extern "C" void* bw_create_convexhullshape(float* pointsBuffer, int pointCount)
+{
+    btConvexHullShape* shape = new btConvexHullShape(pointsBuffer, pointCount, sizeof(float) * 3);
+    return shape;
+}
+
+extern "C" void bw_destroy_convexhullshape(void* shape)
+{
+    btConvexHullShape* s = static_cast<btConvexHullShape*>(shape);
+    delete s;
+}
+
+extern "C" void* bw_create_compoundshape(int initialChildCapacity)
+{
+    btCompoundShape* shape = new btCompoundShape(true, initialChildCapacity);
+    return shape;
+}
+
+extern "C" void bw_destroy_compoundshape(void* shape)
+{
+    btCompoundShape* s = static_cast<btCompoundShape*>(shape);
+    delete s;
+}
+
+extern "C" void bw_compoundshape_add_child_shape(void* shape, float* localTransformBuffer, void* childShape)
+{
+    btCompoundShape* s = static_cast<btCompoundShape*>(shape);
+    btTransform localTransform;
+    localTransform.setFromOpenGLMatrix(localTransformBuffer);
+    s->addChildShape(localTransform, static_cast<btCollisionShape*>(childShape));
+}
+
+// vertex and index buffers are referenced, not copied, so they must outlive the shape
+
+extern "C" void* bw_create_bvhtrianglemeshshape(float* vertexBuffer, int vertexCount, int* indexBuffer, int triangleCount)
+{
+    btTriangleIndexVertexArray* meshInterface = new btTriangleIndexVertexArray(triangleCount, indexBuffer, sizeof(int) * 3, vertexCount, vertexBuffer, sizeof(float) * 3);
+    btBvhTriangleMeshShape* shape = new btBvhTriangleMeshShape(meshInterface, true, true);
+    return shape;
+}
+
+extern "C" void bw_destroy_bvhtrianglemeshshape(void* shape)
+{
+    btBvhTriangleMeshShape* s = static_cast<btBvhTriangleMeshShape*>(shape);
+    btStridingMeshInterface* meshInterface = s->getMeshInterface();
+    delete s;
+    delete meshInterface;
+}
+
+extern "C" void* bw_create_scaledbvhtrianglemeshshape(void* childShape, float x, float y, float z)
+{
+    btBvhTriangleMeshShape* child = static_cast<btBvhTriangleMeshShape*>(childShape);
+    btScaledBvhTriangleMeshShape* shape = new btScaledBvhTriangleMeshShape(child, btVector3(x, y, z));
+    return shape;
+}
+
+extern "C" void bw_destroy_scaledbvhtrianglemeshshape(void* shape)
+{
+    btScaledBvhTriangleMeshShape* s = static_cast<btScaledBvhTriangleMeshShape*>(shape);
+    delete s;
+}
+
+// height buffer is referenced, not copied, so it must outlive the shape
+// the shape origin is the center of the bounding box, so the collider is vertically centered at (minHeight + maxHeight) / 2
+
+extern "C" void* bw_create_heightfieldterrainshape(int width, int length, float* heightBuffer, float minHeight, float maxHeight, float scaleX, float scaleY, float scaleZ)
+{
+    btHeightfieldTerrainShape* shape = new btHeightfieldTerrainShape(width, length, heightBuffer, 1.0f, minHeight, maxHeight, 1, PHY_FLOAT, false);
+    shape->setLocalScaling(btVector3(scaleX, scaleY, scaleZ));
+    return shape;
+}
+
+extern "C" void bw_destroy_heightfieldterrainshape(void* shape)
+{
+    btHeightfieldTerrainShape* s = static_cast<btHeightfieldTerrainShape*>(shape);
+    delete s;
+}
@@ -103,3 +103,214 @@ extern "C" void bw_generic6dofspringconstraint_set_damping(void* constraint, uin
     btGeneric6DofSpringConstraint* c = static_cast<btGeneric6DofSpringConstraint*>(constraint);
     c->setDamping(index, damping);
 }
+
+// Point to point ctor and dtor
+
+extern "C" void* bw_create_point2pointconstraint(void* bodyA, void* bodyB, float* pivotInABuffer, float* pivotInBBuffer)
+{
+    bwRigidBody* a = static_cast<bwRigidBody*>(bodyA);
+    bwRigidBody* b = static_cast<bwRigidBody*>(bodyB);
+    btVector3 pivotInA(pivotInABuffer[0], pivotInABuffer[1], pivotInABuffer[2]);
+    btVector3 pivotInB(pivotInBBuffer[0], pivotInBBuffer[1], pivotInBBuffer[2]);
+    btPoint2PointConstraint* constraint = new btPoint2PointConstraint(*a->getBody(), *b->getBody(), pivotInA, pivotInB);
+    return constraint;
+}
+
+extern "C" void bw_destroy_point2pointconstraint(void* constraint)
+{
+    btPoint2PointConstraint* c = static_cast<btPoint2PointConstraint*>(constraint);
+    delete c;
+}
+
+// Cone twist ctor and dtor
+
+extern "C" void* bw_create_conetwistconstraint(void* bodyA, void* bodyB, float* frameABuffer, float* frameBBuffer)
+{
+    bwRigidBody* a = static_cast<bwRigidBody*>(bodyA);
+    bwRigidBody* b = static_cast<bwRigidBody*>(bodyB);
+    btTransform frameA;
+    frameA.setFromOpenGLMatrix(frameABuffer);
+    btTransform frameB;
+    frameB.setFromOpenGLMatrix(frameBBuffer);
+    btConeTwistConstraint* constraint = new btConeTwistConstraint(*a->getBody(), *b->getBody(), frameA, frameB);
+    return constraint;
+}
+
+extern "C" void bw_destroy_conetwistconstraint(void* constraint)
+{
+    btConeTwistConstraint* c = static_cast<btConeTwistConstraint*>(constraint);
+    delete c;
+}
+
+// Cone twist methods
+
+extern "C" void bw_conetwistconstraint_set_limit(void* constraint, float swingSpan1, float swingSpan2, float twistSpan, float softness, float biasFactor, float relaxationFactor)
+{
+    btConeTwistConstraint* c = static_cast<btConeTwistConstraint*>(constraint);
+    c->setLimit(swingSpan1, swingSpan2, twistSpan, softness, biasFactor, relaxationFactor);
+}
+
+extern "C" void bw_conetwistconstraint_set_damping(void* constraint, float damping)
+{
+    btConeTwistConstraint* c = static_cast<btConeTwistConstraint*>(constraint);
+    c->setDamping(damping);
+}
+
+extern "C" void bw_conetwistconstraint_set_fix_thresh(void* constraint, float fixThresh)
+{
+    btConeTwistConstraint* c = static_cast<btConeTwistConstraint*>(constraint);
+    c->setFixThresh(fixThresh);
+}
+
+// Slider ctor and dtor
+
+extern "C" void* bw_create_sliderconstraint(void* bodyA, void* bodyB, float* frameABuffer, float* frameBBuffer, uint8_t useLinearReferenceFrameA)
+{
+    bwRigidBody* a = static_cast<bwRigidBody*>(bodyA);
+    bwRigidBody* b = static_cast<bwRigidBody*>(bodyB);
+    btTransform frameA;
+    frameA.setFromOpenGLMatrix(frameABuffer);
+    btTransform frameB;
+    frameB.setFromOpenGLMatrix(frameBBuffer);
+    btSliderConstraint* constraint = new btSliderConstraint(*a->getBody(), *b->getBody(), frameA, frameB, useLinearReferenceFrameA);
+    return constraint;
+}
+
+extern "C" void bw_destroy_sliderconstraint(void* constraint)
+{
+    btSliderConstraint* c = static_cast<btSliderConstraint*>(constraint);
+    delete c;
+}
+
+// Slider methods
+
+extern "C" void bw_sliderconstraint_set_linear_limit(void* constraint, float lower, float upper)
+{
+    btSliderConstraint* c = static_cast<btSliderConstraint*>(constraint);
+    c->setLowerLinLimit(lower);
+    c->setUpperLinLimit(upper);
+}
+
+extern "C" void bw_sliderconstraint_set_angular_limit(void* constraint, float lower, float upper)
+{
+    btSliderConstraint* c = static_cast<btSliderConstraint*>(constraint);
+    c->setLowerAngLimit(lower);
+    c->setUpperAngLimit(upper);
+}
+
+extern "C" void bw_sliderconstraint_set_linear_motor(void* constraint, uint8_t onOff, float targetVelocity, float maxForce)
+{
+    btSliderConstraint* c = static_cast<btSliderConstraint*>(constraint);
+    c->setPoweredLinMotor(onOff != 0);
+    c->setTargetLinMotorVelocity(targetVelocity);
+    c->setMaxLinMotorForce(maxForce);
+}
+
+extern "C" void bw_sliderconstraint_set_angular_motor(void* constraint, uint8_t onOff, float targetVelocity, float maxForce)
+{
+    btSliderConstraint* c = static_cast<btSliderConstraint*>(constraint);
+    c->setPoweredAngMotor(onOff != 0);
+    c->setTargetAngMotorVelocity(targetVelocity);
+    c->setMaxAngMotorForce(maxForce);
+}
+
+// Hinge ctor and dtor
+
+extern "C" void* bw_create_hingeconstraint(void* bodyA, void* bodyB, float* frameABuffer, float* frameBBuffer, uint8_t useReferenceFrameA)
+{
+    bwRigidBody* a = static_cast<bwRigidBody*>(bodyA);
+    bwRigidBody* b = static_cast<bwRigidBody*>(bodyB);
+    btTransform frameA;
+    frameA.setFromOpenGLMatrix(frameABuffer);
+    btTransform frameB;
+    frameB.setFromOpenGLMatrix(frameBBuffer);
+    btHingeConstraint* constraint = new btHingeConstraint(*a->getBody(), *b->getBody(), frameA, frameB, useReferenceFrameA);
+    return constraint;
+}
+
+extern "C" void bw_destroy_hingeconstraint(void* constraint)
+{
+    btHingeConstraint* c = static_cast<btHingeConstraint*>(constraint);
+    delete c;
+}
+
+// Hinge methods
+
+extern "C" void bw_hingeconstraint_set_limit(void* constraint, float low, float high, float softness, float biasFactor, float relaxationFactor)
+{
+    btHingeConstraint* c = static_cast<btHingeConstraint*>(constraint);
+    c->setLimit(low, high, softness, biasFactor, relaxationFactor);
+}
+
+extern "C" void bw_hingeconstraint_enable_angular_motor(void* constraint, uint8_t enableMotor, float targetVelocity, float maxMotorImpulse)
+{
+    btHingeConstraint* c = static_cast<btHingeConstraint*>(constraint);
+    c->enableAngularMotor(enableMotor != 0, targetVelocity, maxMotorImpulse);
+}
+
+// Common constraint methods
+
+extern "C" void bw_constraint_set_enabled(void* constraint, uint8_t enabled)
+{
+    btTypedConstraint* c = static_cast<btTypedConstraint*>(constraint);
+    c->setEnabled(enabled != 0);
+}
+
+extern "C" uint8_t bw_constraint_is_enabled(const void* constraint)
+{
+    const btTypedConstraint* c = static_cast<const btTypedConstraint*>(constraint);
+    return c->isEnabled();
+}
+
+struct bwConstraintState final
+{
+    float m_appliedImpulse;
+    // warm start impulses of generic 6 DOF, zero for other types
+    float m_linearAccumulatedImpulse[3];
+    float m_angularAccumulatedImpulse[3];
+};
+
+extern "C" void bw_constraint_get_state(void* constraint, void* out)
+{
+    btTypedConstraint* c = static_cast<btTypedConstraint*>(constraint);
+    bwConstraintState* state = static_cast<bwConstraintState*>(out);
+
+    state->m_appliedImpulse = c->internalGetAppliedImpulse();
+    for (int i = 0; i < 3; ++i)
+    {
+        state->m_linearAccumulatedImpulse[i] = 0.0f;
+        state->m_angularAccumulatedImpulse[i] = 0.0f;
+    }
+
+    const btTypedConstraintType type = c->getConstraintType();
+    if (type == D6_CONSTRAINT_TYPE || type == D6_SPRING_CONSTRAINT_TYPE)
+    {
+        btGeneric6DofConstraint* d6 = static_cast<btGeneric6DofConstraint*>(c);
+        const btVector3& linear = d6->getTranslationalLimitMotor()->m_accumulatedImpulse;
+        for (int i = 0; i < 3; ++i)
+        {
+            state->m_linearAccumulatedImpulse[i] = linear[i];
+            state->m_angularAccumulatedImpulse[i] = d6->getRotationalLimitMotor(i)->m_accumulatedImpulse;
+        }
+    }
+}
+
+extern "C" void bw_constraint_set_state(void* constraint, const void* in)
+{
+    btTypedConstraint* c = static_cast<btTypedConstraint*>(constraint);
+    const bwConstraintState* state = static_cast<const bwConstraintState*>(in);
+
+    c->internalSetAppliedImpulse(state->m_appliedImpulse);
+
+    const btTypedConstraintType type = c->getConstraintType();
+    if (type == D6_CONSTRAINT_TYPE || type == D6_SPRING_CONSTRAINT_TYPE)
+    {
+        btGeneric6DofConstraint* d6 = static_cast<btGeneric6DofConstraint*>(c);
+        btVector3& linear = d6->getTranslationalLimitMotor()->m_accumulatedImpulse;
+        for (int i = 0; i < 3; ++i)
+        {
+            linear[i] = state->m_linearAccumulatedImpulse[i];
+            d6->getRotationalLimitMotor(i)->m_accumulatedImpulse = state->m_angularAccumulatedImpulse[i];
+        }
+    }
+}
@@ -26,6 +26,194 @@ class bwOverlapFilterCallback final : public btOverlapFilterCallback
     }
 };
 
+struct bwQueryHit final
+{
+    // source bwRigidBody, shadows are reported as their source
+    void* m_body;
+    // hit fraction for ray and sweep, signed distance for contact
+    float m_fraction;
+    float m_point[3];
+    float m_normal[3];
+};
+
+struct bwContactPoint final
+{
+    // source bwRigidBody of each side, shadows are reported as their source
+    void* m_body0;
+    void* m_body1;
+    // deepest point on body1, normal points from body1 to body0
+    float m_point[3];
+    float m_normal[3];
+    // sum of the applied impulses of the manifold
+    float m_impulse;
+};
+
+class bwContactQueryCallback final : public btCollisionWorld::ContactResultCallback
+{
+private:
+    const btCollisionObject* m_queryObject;
+    bwQueryHit* m_out;
+    int m_capacity;
+
+public:
+    int m_count;
+
+    bwContactQueryCallback(const btCollisionObject* queryObject, bwQueryHit* out, int capacity) :
+        m_queryObject(queryObject),
+        m_out(out),
+        m_capacity(capacity),
+        m_count(0)
+    {
+    }
+
+    virtual btScalar addSingleResult(
+        btManifoldPoint& cp,
+        const btCollisionObjectWrapper* colObj0Wrap,
+        int partId0,
+        int index0,
+        const btCollisionObjectWrapper* colObj1Wrap,
+        int partId1,
+        int index1
+    ) override
+    {
+        (void)partId0;
+        (void)index0;
+        (void)partId1;
+        (void)index1;
+
+        if (m_capacity <= m_count)
+        {
+            return 0.0f;
+        }
+
+        // report the point on the other body with the normal pointing out of it
+        const bool queryIsA = colObj0Wrap->getCollisionObject() == m_queryObject;
+        const btCollisionObject* other = queryIsA ? colObj1Wrap->getCollisionObject() : colObj0Wrap->getCollisionObject();
+        const btVector3& point = queryIsA ? cp.getPositionWorldOnB() : cp.getPositionWorldOnA();
+        const btVector3 normal = queryIsA ? cp.m_normalWorldOnB : -cp.m_normalWorldOnB;
+
+        bwQueryHit& hit = m_out[m_count];
+        hit.m_body = other->getUserPointer();
+        hit.m_fraction = cp.getDistance();
+        hit.m_point[0] = point.x();
+        hit.m_point[1] = point.y();
+        hit.m_point[2] = point.z();
+        hit.m_normal[0] = normal.x();
+        hit.m_normal[1] = normal.y();
+        hit.m_normal[2] = normal.z();
+        m_count += 1;
+
+        return 0.0f;
+    }
+};
+
+struct bwPhysicsWorldStats final
+{
+    int32_t m_objectCount;
+    int32_t m_constraintCount;
+    int32_t m_overlappingPairCount;
+    int32_t m_manifoldCount;
+    int32_t m_islandCount;
+    // accumulated over the steps since the previous read
+    int32_t m_subStepCount;
+    float m_stepTime; // milliseconds
+};
+
+enum class bwDebugDrawCategory : uint32_t
+{
+    WIREFRAME = 1 << 0,
+    AABB = 1 << 1,
+    CONTACT_POINTS = 1 << 2,
+    CONSTRAINT_FRAMES = 1 << 3,
+    CONSTRAINT_LIMITS = 1 << 4
+};
+
+// collects debug geometry into flat buffers instead of drawing
+// each vertex is position xyz followed by color rgb, lines are vertex pairs
+class bwDebugDrawer final : public btIDebugDraw
+{
+private:
+    int m_debugMode;
+
+public:
+    btAlignedObjectArray<float> m_lines;
+    btAlignedObjectArray<float> m_points;
+
+private:
+    static void pushVertex(btAlignedObjectArray<float>& buffer, const btVector3& position, const btVector3& color)
+    {
+        buffer.push_back(position.x());
+        buffer.push_back(position.y());
+        buffer.push_back(position.z());
+        buffer.push_back(color.x());
+        buffer.push_back(color.y());
+        buffer.push_back(color.z());
+    }
+
+public:
+    bwDebugDrawer() :
+        m_debugMode(DBG_NoDebug)
+    {
+    }
+
+    bwDebugDrawer(bwDebugDrawer const&) = delete;
+    bwDebugDrawer& operator=(bwDebugDrawer const&) = delete;
+
+    void setCategories(uint32_t categories)
+    {
+        int mode = DBG_NoDebug;
+        if (categories & static_cast<uint32_t>(bwDebugDrawCategory::WIREFRAME)) mode |= DBG_DrawWireframe;
+        if (categories & static_cast<uint32_t>(bwDebugDrawCategory::AABB)) mode |= DBG_DrawAabb;
+        if (categories & static_cast<uint32_t>(bwDebugDrawCategory::CONTACT_POINTS)) mode |= DBG_DrawContactPoints;
+        if (categories & static_cast<uint32_t>(bwDebugDrawCategory::CONSTRAINT_FRAMES)) mode |= DBG_DrawConstraints;
+        if (categories & static_cast<uint32_t>(bwDebugDrawCategory::CONSTRAINT_LIMITS)) mode |= DBG_DrawConstraintLimits;
+        m_debugMode = mode;
+    }
+
+    void clear()
+    {
+        // keep the capacity, the buffers are refilled every step
+        m_lines.resizeNoInitialize(0);
+        m_points.resizeNoInitialize(0);
+    }
+
+    virtual void drawLine(const btVector3& from, const btVector3& to, const btVector3& color) override
+    {
+        pushVertex(m_lines, from, color);
+        pushVertex(m_lines, to, color);
+    }
+
+    virtual void drawContactPoint(const btVector3& pointOnB, const btVector3& normalOnB, btScalar distance, int lifeTime, const btVector3& color) override
+    {
+        (void)normalOnB;
+        (void)distance;
+        (void)lifeTime;
+
+        pushVertex(m_points, pointOnB, color);
+    }
+
+    virtual void reportErrorWarning(const char* warningString) override
+    {
+        (void)warningString;
+    }
+
+    virtual void draw3dText(const btVector3& location, const char* textString) override
+    {
+        (void)location;
+        (void)textString;
+    }
+
+    virtual void setDebugMode(int debugMode) override
+    {
+        m_debugMode = debugMode;
+    }
+
+    virtual int getDebugMode() const override
+    {
+        return m_debugMode;
+    }
+};
+
 class bwPhysicsWorld final
 {
 private:
@@ -36,8 +224,62 @@ private:
     btCollisionDispatcher m_dispatcher;
     btSequentialImpulseConstraintSolver m_solver;
     btDiscreteDynamicsWorld m_world;
+    bwDebugDrawer m_debugDrawer;
+    btClock m_clock;
+    int m_subStepCount;
+    unsigned long long m_stepTime; // microseconds, measured only while stats are enabled
+    bool m_statsEnabled;
+    // touching manifolds of every sub step since the last clearContacts
+    btAlignedObjectArray<bwContactPoint> m_contacts;
 
 private:
+    static void contactTickCallback(btDynamicsWorld* world, btScalar timeStep)
+    {
+        (void)timeStep;
+        static_cast<bwPhysicsWorld*>(world->getWorldUserInfo())->appendContacts();
+    }
+
+    // one contact per touching manifold of the current sub step
+    void appendContacts()
+    {
+        const int numManifolds = m_dispatcher.getNumManifolds();
+        for (int i = 0; i < numManifolds; ++i)
+        {
+            const btPersistentManifold* manifold = m_dispatcher.getManifoldByIndexInternal(i);
+
+            int deepest = -1;
+            btScalar deepestDistance = 0.0f;
+            btScalar impulse = 0.0f;
+            for (int j = 0; j < manifold->getNumContacts(); ++j)
+            {
+                const btManifoldPoint& cp = manifold->getContactPoint(j);
+                if (cp.getDistance() < deepestDistance)
+                {
+                    deepest = j;
+                    deepestDistance = cp.getDistance();
+                }
+                impulse += cp.getAppliedImpulse();
+            }
+
+            if (deepest < 0)
+            {
+                continue; // not touching
+            }
+
+            const btManifoldPoint& cp = manifold->getContactPoint(deepest);
+            bwContactPoint& contact = m_contacts.expandNonInitializing();
+            contact.m_body0 = manifold->getBody0()->getUserPointer();
+            contact.m_body1 = manifold->getBody1()->getUserPointer();
+            contact.m_point[0] = cp.getPositionWorldOnB().x();
+            contact.m_point[1] = cp.getPositionWorldOnB().y();
+            contact.m_point[2] = cp.getPositionWorldOnB().z();
+            contact.m_normal[0] = cp.m_normalWorldOnB.x();
+            contact.m_normal[1] = cp.m_normalWorldOnB.y();
+            contact.m_normal[2] = cp.m_normalWorldOnB.z();
+            contact.m_impulse = impulse;
+        }
+    }
+
     void makeBodyKinematic(bwRigidBody* body)
     {
         btRigidBody* btBody = body->getBody();
@@ -112,7 +354,10 @@ public:
         m_collisionConfig(),
         m_dispatcher(&m_collisionConfig),
         m_solver(),
-        m_world(&m_dispatcher, &m_broadphase, &m_solver, &m_collisionConfig)
+        m_world(&m_dispatcher, &m_broadphase, &m_solver, &m_collisionConfig),
+        m_subStepCount(0),
+        m_stepTime(0),
+        m_statsEnabled(false)
     {
         m_broadphasePairCache.setOverlapFilterCallback(&m_overlapFilterCallback);
     }
@@ -136,7 +381,57 @@ public:
 
     void stepSimulation(btScalar timeStep, int maxSubSteps, btScalar fixedTimeStep)
     {
-        m_world.stepSimulation(timeStep, maxSubSteps, fixedTimeStep);
+        if (!m_statsEnabled)
+        {
+            m_subStepCount += m_world.stepSimulation(timeStep, maxSubSteps, fixedTimeStep);
+            return;
+        }
+
+        m_clock.reset();
+        m_subStepCount += m_world.stepSimulation(timeStep, maxSubSteps, fixedTimeStep);
+        m_stepTime += m_clock.getTimeMicroseconds();
+    }
+
+    void setStatsEnabled(bool enabled)
+    {
+        m_statsEnabled = enabled;
+    }
+
+    // resets the accumulated step counters
+    void getStats(bwPhysicsWorldStats* out)
+    {
+        const btCollisionObjectArray& objects = m_world.getCollisionObjectArray();
+
+        // island tags are union find roots, so count the distinct ones
+        btAlignedObjectArray<int> islandTags;
+        for (int i = 0; i < objects.size(); ++i)
+        {
+            const int tag = objects[i]->getIslandTag();
+            if (0 <= tag)
+            {
+                islandTags.push_back(tag);
+            }
+        }
+        islandTags.quickSort(btAlignedObjectArray<int>::less());
+        int islandCount = 0;
+        for (int i = 0; i < islandTags.size(); ++i)
+        {
+            if (i == 0 || islandTags[i] != islandTags[i - 1])
+            {
+                islandCount += 1;
+            }
+        }
+
+        out->m_objectCount = objects.size();
+        out->m_constraintCount = m_world.getNumConstraints();
+        out->m_overlappingPairCount = m_broadphasePairCache.getNumOverlappingPairs();
+        out->m_manifoldCount = m_dispatcher.getNumManifolds();
+        out->m_islandCount = islandCount;
+        out->m_subStepCount = m_subStepCount;
+        out->m_stepTime = static_cast<float>(m_stepTime) / 1000.0f;
+
+        m_subStepCount = 0;
+        m_stepTime = 0;
     }
 
     void addRigidBody(bwRigidBody* body)
@@ -240,11 +535,134 @@ public:
         }
     }
 
+    bool rayTest(const btVector3& from, const btVector3& to, uint16_t group, uint16_t mask, bwQueryHit* out) const
+    {
+        btCollisionWorld::ClosestRayResultCallback callback(from, to);
+        callback.m_collisionFilterGroup = group;
+        callback.m_collisionFilterMask = mask;
+        m_world.rayTest(from, to, callback);
+
+        if (!callback.hasHit())
+        {
+            return false;
+        }
+
+        out->m_body = callback.m_collisionObject->getUserPointer();
+        out->m_fraction = callback.m_closestHitFraction;
+        out->m_point[0] = callback.m_hitPointWorld.x();
+        out->m_point[1] = callback.m_hitPointWorld.y();
+        out->m_point[2] = callback.m_hitPointWorld.z();
+        out->m_normal[0] = callback.m_hitNormalWorld.x();
+        out->m_normal[1] = callback.m_hitNormalWorld.y();
+        out->m_normal[2] = callback.m_hitNormalWorld.z();
+        return true;
+    }
+
+    bool sphereSweepTest(const btVector3& from, const btVector3& to, btScalar radius, uint16_t group, uint16_t mask, bwQueryHit* out) const
+    {
+        btSphereShape shape(radius);
+        btTransform fromTransform = btTransform::getIdentity();
+        fromTransform.setOrigin(from);
+        btTransform toTransform = btTransform::getIdentity();
+        toTransform.setOrigin(to);
+
+        btCollisionWorld::ClosestConvexResultCallback callback(from, to);
+        callback.m_collisionFilterGroup = group;
+        callback.m_collisionFilterMask = mask;
+        m_world.convexSweepTest(&shape, fromTransform, toTransform, callback);
+
+        if (!callback.hasHit())
+        {
+            return false;
+        }
+
+        out->m_body = callback.m_hitCollisionObject->getUserPointer();
+        out->m_fraction = callback.m_closestHitFraction;
+        out->m_point[0] = callback.m_hitPointWorld.x();
+        out->m_point[1] = callback.m_hitPointWorld.y();
+        out->m_point[2] = callback.m_hitPointWorld.z();
+        out->m_normal[0] = callback.m_hitNormalWorld.x();
+        out->m_normal[1] = callback.m_hitNormalWorld.y();
+        out->m_normal[2] = callback.m_hitNormalWorld.z();
+        return true;
+    }
+
+    int sphereContactTest(const btVector3& center, btScalar radius, uint16_t group, uint16_t mask, bwQueryHit* out, int capacity)
+    {
+        btSphereShape shape(radius);
+        btCollisionObject object;
+        object.setCollisionShape(&shape);
+        btTransform transform = btTransform::getIdentity();
+        transform.setOrigin(center);
+        object.setWorldTransform(transform);
+
+        bwContactQueryCallback callback(&object, out, capacity);
+        callback.m_collisionFilterGroup = group;
+        callback.m_collisionFilterMask = mask;
+        m_world.contactTest(&object, callback);
+
+        return callback.m_count;
+    }
+
+    // contacts are collected after every sub step while enabled
+    void setContactCollectionEnabled(bool enabled)
+    {
+        m_world.setInternalTickCallback(enabled ? contactTickCallback : nullptr, this, false);
+        m_contacts.clear();
+    }
+
+    void clearContacts()
+    {
+        m_contacts.clear();
+    }
+
+    // returns the number of collected contacts, only up to capacity are written
+    int collectContacts(bwContactPoint* out, int capacity)
+    {
+        const int count = m_contacts.size();
+        for (int i = 0; i < count && i < capacity; ++i)
+        {
+            out[i] = m_contacts[i];
+        }
+        return count;
+    }
+
+    void setDebugDrawCategories(uint32_t categories)
+    {
+        m_debugDrawer.setCategories(categories);
+        m_debugDrawer.clear();
+        // bullet skips all debug draw work when no drawer is set
+        m_world.setDebugDrawer(categories != 0 ? &m_debugDrawer : nullptr);
+    }
+
+    void debugDraw()
+    {
+        m_debugDrawer.clear();
+        m_world.debugDrawWorld();
+    }
+
+    const bwDebugDrawer& getDebugDrawer() const
+    {
+        return m_debugDrawer;
+    }
+
     void cleanBodyProxyFromPairs(bwRigidBody* body)
     {
         btBroadphaseProxy* proxy = body->getBody()->getBroadphaseHandle();
         m_broadphasePairCache.cleanProxyFromPairs(proxy, &m_dispatcher);
     }
+
+    void refreshBodyCollisionFilter(bwRigidBody* body)
+    {
+        btRigidBody* btBody = body->getBody();
+        btBroadphaseProxy* proxy = btBody->getBroadphaseHandle();
+        // keep the static filter bit which is managed by motion type and kinematic toggle
+        const int staticFilter = proxy->m_collisionFilterGroup & (btBroadphaseProxy::StaticFilter << 16);
+        proxy->m_collisionFilterGroup = body->getCollisionGroup() | staticFilter;
+        proxy->m_collisionFilterMask = static_cast<int16_t>(body->getCollisionMask());
+        m_broadphasePairCache.cleanProxyFromPairs(proxy, &m_dispatcher);
+        m_world.refreshBroadphaseProxy(btBody);
+    }
 };
 
 // for better complier optimization, we don't separate the .cpp file
@@ -258,6 +676,16 @@ void bwRigidBody::setShape(btCollisionShape* shape)
     }
 }
 
+// rigid body shadows in other worlds keep the filter they were added with
+void bwRigidBody::setCollisionFilter(uint16_t group, uint16_t mask)
+{
+    m_collisionGroup = group;
+    m_collisionMask = mask;
+    if (m_world) {
+        m_world->refreshBodyCollisionFilter(this);
+    }
+}
+
 extern "C" void* bw_create_world()
 {
     bwPhysicsWorld* world = new bwPhysicsWorld();
@@ -337,3 +765,86 @@ extern "C" void bw_world_set_body_kinematic_toggle(void* world, void* body, uint
     bwRigidBody* b = static_cast<bwRigidBody*>(body);
     w->setBodyKinematicToggle(b, value != 0);
 }
+
+extern "C" uint8_t bw_world_ray_test(void* world, float fromX, float fromY, float fromZ, float toX, float toY, float toZ, uint16_t group, uint16_t mask, void* out)
+{
+    const bwPhysicsWorld* w = static_cast<const bwPhysicsWorld*>(world);
+    bwQueryHit* o = static_cast<bwQueryHit*>(out);
+    return w->rayTest(btVector3(fromX, fromY, fromZ), btVector3(toX, toY, toZ), group, mask, o);
+}
+
+extern "C" uint8_t bw_world_sphere_sweep_test(void* world, float fromX, float fromY, float fromZ, float toX, float toY, float toZ, float radius, uint16_t group, uint16_t mask, void* out)
+{
+    const bwPhysicsWorld* w = static_cast<const bwPhysicsWorld*>(world);
+    bwQueryHit* o = static_cast<bwQueryHit*>(out);
+    return w->sphereSweepTest(btVector3(fromX, fromY, fromZ), btVector3(toX, toY, toZ), radius, group, mask, o);
+}
+
+extern "C" int bw_world_sphere_contact_test(void* world, float x, float y, float z, float radius, uint16_t group, uint16_t mask, void* out, int capacity)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    bwQueryHit* o = static_cast<bwQueryHit*>(out);
+    return w->sphereContactTest(btVector3(x, y, z), radius, group, mask, o, capacity);
+}
+
+extern "C" void bw_world_set_contact_collection_enabled(void* world, uint8_t enabled)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    w->setContactCollectionEnabled(enabled != 0);
+}
+
+extern "C" void bw_world_clear_contacts(void* world)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    w->clearContacts();
+}
+
+extern "C" int bw_world_collect_contacts(void* world, void* out, int capacity)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    bwContactPoint* o = static_cast<bwContactPoint*>(out);
+    return w->collectContacts(o, capacity);
+}
+
+extern "C" void bw_world_set_debug_draw_categories(void* world, uint32_t categories)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    w->setDebugDrawCategories(categories);
+}
+
+extern "C" void bw_world_debug_draw(void* world)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    w->debugDraw();
+}
+
+// returns the number of floats, 12 per line
+extern "C" int bw_world_get_debug_lines(void* world, const float** out)
+{
+    const bwPhysicsWorld* w = static_cast<const bwPhysicsWorld*>(world);
+    const btAlignedObjectArray<float>& lines = w->getDebugDrawer().m_lines;
+    *out = lines.size() != 0 ? &lines[0] : nullptr;
+    return lines.size();
+}
+
+// returns the number of floats, 6 per point
+extern "C" int bw_world_get_debug_points(void* world, const float** out)
+{
+    const bwPhysicsWorld* w = static_cast<const bwPhysicsWorld*>(world);
+    const btAlignedObjectArray<float>& points = w->getDebugDrawer().m_points;
+    *out = points.size() != 0 ? &points[0] : nullptr;
+    return points.size();
+}
+
+extern "C" void bw_world_set_stats_enabled(void* world, uint8_t enabled)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    w->setStatsEnabled(enabled != 0);
+}
+
+extern "C" void bw_world_get_stats(void* world, void* out)
+{
+    bwPhysicsWorld* w = static_cast<bwPhysicsWorld*>(world);
+    bwPhysicsWorldStats* o = static_cast<bwPhysicsWorldStats*>(out);
+    w->getStats(o);
+}
@@ -1,6 +1,7 @@
 #pragma once
 
 #include "btBulletDynamicsMinimal.h"
+#include "LinearMath/btTransformUtil.h"
 #include "bwMotionState.h"
 
 class bwPhysicsWorld;
@@ -52,6 +53,10 @@ struct bwRigidBodyConstructionInfo final
     // sleeping/deactivation for them during the animation. This means Bullet dynamics world will get the
     // new worldtransform from the btMotionState every simulation frame.
     uint8_t m_disableDeactivation; // bool
+
+    // continuous collision detection, disabled when motion threshold is zero
+    float m_ccdMotionThreshold;
+    float m_ccdSweptSphereRadius;
 };
 
 class bwRigidBody;
@@ -94,8 +99,8 @@ private:
     bwMotionState* const m_motionState;
     bwPhysicsWorld* m_world;
     btRigidBody m_body;
-    const uint16_t m_collisionGroup;
-    const uint16_t m_collisionMask;
+    uint16_t m_collisionGroup;
+    uint16_t m_collisionMask;
     const bwRigidBodyMotionType m_motionType;
 public:
     bool m_temporalKinematic;
@@ -159,6 +164,8 @@ public:
         m_temporalKinematic(false),
         m_kinematicToggle(info->m_motionType == static_cast<uint8_t>(bwRigidBodyMotionType::KINEMATIC))
     {
+        // queries resolve hit collision objects to the owner through user pointer
+        m_body.setUserPointer(this);
         m_body.setSleepingThresholds(info->m_linearSleepingThreshold, info->m_angularSleepingThreshold);
         if (info->m_disableDeactivation)
         {
@@ -178,6 +185,9 @@ public:
         {
             m_body.setCollisionFlags(m_body.getCollisionFlags() | btCollisionObject::CF_NO_CONTACT_RESPONSE);
         }
+
+        m_body.setCcdMotionThreshold(info->m_ccdMotionThreshold);
+        m_body.setCcdSweptSphereRadius(info->m_ccdSweptSphereRadius);
     }
 
     bwRigidBody(bwRigidBody const&) = delete;
@@ -212,6 +222,8 @@ public:
         return m_collisionMask;
     }
 
+    void setCollisionFilter(uint16_t group, uint16_t mask);
+
     void setDamping(float linearDamping, float angularDamping)
     {
         m_body.setDamping(linearDamping, angularDamping);
@@ -227,6 +239,22 @@ public:
         return m_body.getAngularDamping();
     }
 
+    void setCcd(float motionThreshold, float sweptSphereRadius)
+    {
+        m_body.setCcdMotionThreshold(motionThreshold);
+        m_body.setCcdSweptSphereRadius(sweptSphereRadius);
+    }
+
+    float getCcdMotionThreshold() const
+    {
+        return m_body.getCcdMotionThreshold();
+    }
+
+    float getCcdSweptSphereRadius() const
+    {
+        return m_body.getCcdSweptSphereRadius();
+    }
+
     void setMassProps(float mass, const float* localInertia)
     {
         if (localInertia != nullptr)
@@ -244,6 +272,36 @@ public:
         return m_body.getMass();
     }
 
+    void setMassWithInertia(float mass)
+    {
+        btAssert(m_motionType == bwRigidBodyMotionType::DYNAMIC);
+
+        btVector3 localInertia(0.0f, 0.0f, 0.0f);
+        m_shape->calculateLocalInertia(mass, localInertia);
+        m_body.setMassProps(mass, localInertia);
+        m_body.updateInertiaTensor();
+    }
+
+    void setFriction(float friction)
+    {
+        m_body.setFriction(friction);
+    }
+
+    float getFriction() const
+    {
+        return m_body.getFriction();
+    }
+
+    void setRestitution(float restitution)
+    {
+        m_body.setRestitution(restitution);
+    }
+
+    float getRestitution() const
+    {
+        return m_body.getRestitution();
+    }
+
     btVector3 getLocalInertia() const
     {
         return m_body.getLocalInertia();
@@ -349,6 +407,27 @@ public:
         m_body.setAngularVelocity(btVector3(velocity[0], velocity[1], velocity[2]));
     }
 
+    // kinematic bodies must never sleep, bullet does not read their motion state while sleeping
+    void setSleeping(bool enabled, float linearThreshold, float angularThreshold)
+    {
+        btAssert(m_motionType == bwRigidBodyMotionType::DYNAMIC);
+
+        m_body.setSleepingThresholds(linearThreshold, angularThreshold);
+        m_body.forceActivationState(enabled ? ACTIVE_TAG : DISABLE_DEACTIVATION);
+        m_body.setDeactivationTime(0.0f);
+    }
+
+    bool isSleeping() const
+    {
+        return m_body.getActivationState() == ISLAND_SLEEPING;
+    }
+
+    void wakeUp()
+    {
+        // forced since temporal kinematic bodies have the kinematic flag
+        m_body.activate(true);
+    }
+
     btVector3 getVelocityInLocalPoint(const float* relativePosition) const
     {
         return m_body.getVelocityInLocalPoint(btVector3(relativePosition[0], relativePosition[1], relativePosition[2]));
@@ -392,6 +471,9 @@ public:
 
 bwRigidBodyShadow::bwRigidBodyShadow(bwRigidBody* source, bwMotionState* motionState) : m_source(source), m_body(createRigidBodyConstructionInfo(source, motionState))
 {
+    // shadow is reported as the source body by queries
+    m_body.setUserPointer(source);
+
     if (source->getMotionType() == bwRigidBodyMotionType::STATIC)
     {
         m_body.setCollisionFlags(m_body.getCollisionFlags() | btCollisionObject::CF_STATIC_OBJECT);
@@ -463,6 +545,24 @@ extern "C" float bw_rigidbody_get_angular_damping(const void* body)
     return b->getAngularDamping();
 }
 
+extern "C" void bw_rigidbody_set_ccd(void* body, float motionThreshold, float sweptSphereRadius)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->setCcd(motionThreshold, sweptSphereRadius);
+}
+
+extern "C" float bw_rigidbody_get_ccd_motion_threshold(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->getCcdMotionThreshold();
+}
+
+extern "C" float bw_rigidbody_get_ccd_swept_sphere_radius(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->getCcdSweptSphereRadius();
+}
+
 extern "C" void bw_rigidbody_set_mass_props(void* body, float mass, const float* localInertia)
 {
     bwRigidBody* b = static_cast<bwRigidBody*>(body);
@@ -475,6 +575,54 @@ extern "C" float bw_rigidbody_get_mass(const void* body)
     return b->getMass();
 }
 
+extern "C" void bw_rigidbody_set_mass_with_inertia(void* body, float mass)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->setMassWithInertia(mass);
+}
+
+extern "C" void bw_rigidbody_set_friction(void* body, float friction)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->setFriction(friction);
+}
+
+extern "C" float bw_rigidbody_get_friction(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->getFriction();
+}
+
+extern "C" void bw_rigidbody_set_restitution(void* body, float restitution)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->setRestitution(restitution);
+}
+
+extern "C" float bw_rigidbody_get_restitution(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->getRestitution();
+}
+
+extern "C" void bw_rigidbody_set_collision_filter(void* body, uint16_t group, uint16_t mask)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->setCollisionFilter(group, mask);
+}
+
+extern "C" uint16_t bw_rigidbody_get_collision_group(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->getCollisionGroup();
+}
+
+extern "C" uint16_t bw_rigidbody_get_collision_mask(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->getCollisionMask();
+}
+
 extern "C" void bw_rigidbody_get_local_inertia(const void* body, float* localInertia)
 {
     const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
@@ -622,6 +770,24 @@ extern "C" void bw_rigidbody_set_angular_velocity(void* body, const float* veloc
     b->setAngularVelocity(velocity);
 }
 
+extern "C" void bw_rigidbody_set_sleeping(void* body, uint8_t enabled, float linearThreshold, float angularThreshold)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->setSleeping(enabled != 0, linearThreshold, angularThreshold);
+}
+
+extern "C" uint8_t bw_rigidbody_is_sleeping(const void* body)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    return b->isSleeping();
+}
+
+extern "C" void bw_rigidbody_wake_up(void* body)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    b->wakeUp();
+}
+
 extern "C" void bw_rigidbody_get_velocity_in_local_point(const void* body, const float* relativePosition, float* velocity)
 {
     const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
@@ -658,6 +824,68 @@ extern "C" void* bw_rigidbody_get_world_transform_ptr(void* body)
     return &b->getWorldTransform();
 }
 
+struct bwRigidBodyState final
+{
+    float m_transform[16];
+    float m_linearVelocity[3];
+    float m_angularVelocity[3];
+    int32_t m_activationState;
+    float m_deactivationTime;
+};
+
+extern "C" void bw_rigidbody_get_state(const void* body, void* out)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    bwRigidBodyState* state = static_cast<bwRigidBodyState*>(out);
+    const btRigidBody* btBody = b->getBody();
+
+    btBody->getWorldTransform().getOpenGLMatrix(state->m_transform);
+    const btVector3& linearVelocity = btBody->getLinearVelocity();
+    state->m_linearVelocity[0] = linearVelocity.x();
+    state->m_linearVelocity[1] = linearVelocity.y();
+    state->m_linearVelocity[2] = linearVelocity.z();
+    const btVector3& angularVelocity = btBody->getAngularVelocity();
+    state->m_angularVelocity[0] = angularVelocity.x();
+    state->m_angularVelocity[1] = angularVelocity.y();
+    state->m_angularVelocity[2] = angularVelocity.z();
+    state->m_activationState = btBody->getActivationState();
+    state->m_deactivationTime = btBody->getDeactivationTime();
+}
+
+extern "C" void bw_rigidbody_set_state(void* body, const void* in)
+{
+    bwRigidBody* b = static_cast<bwRigidBody*>(body);
+    const bwRigidBodyState* state = static_cast<const bwRigidBodyState*>(in);
+    btRigidBody* btBody = b->getBody();
+
+    btTransform transform;
+    transform.setFromOpenGLMatrix(state->m_transform);
+    const btVector3 linearVelocity(state->m_linearVelocity[0], state->m_linearVelocity[1], state->m_linearVelocity[2]);
+    const btVector3 angularVelocity(state->m_angularVelocity[0], state->m_angularVelocity[1], state->m_angularVelocity[2]);
+
+    btBody->setWorldTransform(transform);
+    btBody->setInterpolationWorldTransform(transform);
+    btBody->setLinearVelocity(linearVelocity);
+    btBody->setInterpolationLinearVelocity(linearVelocity);
+    btBody->setAngularVelocity(angularVelocity);
+    btBody->setInterpolationAngularVelocity(angularVelocity);
+    btBody->forceActivationState(state->m_activationState);
+    btBody->setDeactivationTime(state->m_deactivationTime);
+
+    // kinematic body reads the transform from the motion state, others write it on step
+    if (!btBody->isStaticOrKinematicObject() && btBody->getMotionState() != nullptr)
+    {
+        btBody->getMotionState()->setWorldTransform(transform);
+    }
+}
+
+// transform at the end of the last fixed step, motion state holds the one interpolated by bullet
+extern "C" void bw_rigidbody_get_step_transform(const void* body, float* out)
+{
+    const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
+    b->getBody()->getInterpolationWorldTransform().getOpenGLMatrix(out);
+}
+
 extern "C" uint8_t bw_rigidbody_get_motion_type(const void* body)
 {
     const bwRigidBody* b = static_cast<const bwRigidBody*>(body);
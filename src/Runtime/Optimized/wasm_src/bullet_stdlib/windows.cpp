@@ -1,18 +1,21 @@
 #include <windows.h>
 
+extern "C" double bw_performance_now();
+
 BOOL QueryPerformanceCounter(LARGE_INTEGER* lpPerformanceCount) {
-    return 0;
+    lpPerformanceCount->QuadPart = (LONGLONG)(bw_performance_now() * 1000.0);
+    return 1;
 }
 
 BOOL QueryPerformanceFrequency(LARGE_INTEGER* lpFrequency) {
-    return 0;
+    lpFrequency->QuadPart = 1000000;
+    return 1;
 }
 
 DWORD GetTickCount(VOID) {
-    return 0;
+    return (DWORD)bw_performance_now();
 }
 
 ULONGLONG GetTickCount64(VOID) {
-    return 0;
+    return (ULONGLONG)bw_performance_now();
 }
-
@@ -25,7 +25,8 @@ typedef union _LARGE_INTEGER {
 
 #define __declspec(x) // for compile __declspec(thread) to nothing
 
-// time related functions is only used in profiling, so we can just return 0
+// time related functions are only used in profiling (btClock)
+// counter is in microseconds from performance.now()
 
 BOOL QueryPerformanceCounter(LARGE_INTEGER* lpPerformanceCount);
 
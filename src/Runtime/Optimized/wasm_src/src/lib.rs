@@ -1,5 +1,8 @@
 #![cfg_attr(all(debug_assertions, feature = "physics"), feature(c_variadic))]
 
+#[cfg(all(feature = "deterministic", feature = "fast-math"))]
+compile_error!("feature \"deterministic\" can not be used with \"fast-math\"");
+
 mod animation;
 mod mmd_model;
 
@@ -7,8 +10,13 @@ mod mmd_model;
 mod physics;
 
 mod diagnostic;
+mod loader;
 mod mmd_model_metadata;
+mod mmd_model_validation;
 mod mmd_runtime;
+mod profiler;
+#[cfg(feature = "deterministic")]
+mod state_hash;
 mod unchecked_slice;
 
 use wasm_bindgen::prelude::*;
@@ -0,0 +1,749 @@
+use crate::mmd_model_metadata::BoneFlag;
+
+use super::LoaderError;
+use super::data_deserializer::DataDeserializer;
+use super::pmx_object::{PmxAppendTransform, PmxBone, PmxIk, PmxIkLink, PmxJoint, PmxMorph, PmxMorphKind, PmxObject, PmxRigidBody};
+
+const BPMX_MIN_VERSION: [u8; 3] = [3, 0, 0]; // inclusive
+const BPMX_MAX_VERSION: [u8; 3] = [3, 1, 0]; // exclusive
+
+/// Offsets of each data block from the start of the file, 0 means the block does not exist
+struct BpmxDataPositions {
+    bone: u32,
+    morph: u32,
+    rigid_body: u32,
+    joint: u32,
+}
+
+pub(crate) struct BpmxReader<'a> {
+    deserializer: DataDeserializer<'a>,
+}
+
+impl<'a> BpmxReader<'a> {
+    /// Parse BPMX 3.0 file
+    ///
+    /// Blocks are addressed by the header, so geometries, images, textures,
+    /// materials and display frames are never touched
+    pub(crate) fn read(bytes: &'a [u8]) -> Result<PmxObject, LoaderError> {
+        let mut reader = Self {
+            deserializer: DataDeserializer::new(bytes),
+        };
+
+        let data_positions = reader.read_header()?;
+
+        let bones = if data_positions.bone != 0 {
+            reader.read_bones(data_positions.bone as usize)?
+        } else {
+            Vec::new()
+        };
+        let morphs = if data_positions.morph != 0 {
+            reader.read_morphs(data_positions.morph as usize)?
+        } else {
+            Vec::new()
+        };
+        let rigid_bodies = if data_positions.rigid_body != 0 {
+            reader.read_rigid_bodies(data_positions.rigid_body as usize)?
+        } else {
+            Vec::new()
+        };
+        let joints = if data_positions.joint != 0 {
+            reader.read_joints(data_positions.joint as usize)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(PmxObject {
+            bones,
+            morphs,
+            rigid_bodies,
+            joints,
+        })
+    }
+
+    fn read_header(&mut self) -> Result<BpmxDataPositions, LoaderError> {
+        let signature = self.deserializer.read_bytes(4)?;
+        if signature != b"BPMX" {
+            return Err(LoaderError::InvalidSignature);
+        }
+
+        let version = [
+            self.deserializer.read::<u8>()?,
+            self.deserializer.read::<u8>()?,
+            self.deserializer.read::<u8>()?,
+        ];
+        if version < BPMX_MIN_VERSION || BPMX_MAX_VERSION <= version {
+            return Err(LoaderError::UnsupportedVersion(version));
+        }
+        self.deserializer.skip(1)?; // padding
+
+        // header can be shorter than the current layout, missing positions are treated as 0
+        let size_of_header = self.deserializer.read::<u32>()? as usize;
+        let mut positions = [0u32; 10];
+        for position in positions.iter_mut().take(size_of_header / 4) {
+            *position = self.deserializer.read::<u32>()?;
+        }
+        let [
+            _model_info,
+            _mesh,
+            _image,
+            _texture,
+            _material,
+            bone,
+            morph,
+            _display_frame,
+            rigid_body,
+            joint,
+        ] = positions;
+
+        Ok(BpmxDataPositions {
+            bone,
+            morph,
+            rigid_body,
+            joint,
+        })
+    }
+
+    fn read_count(&mut self, min_element_size: usize) -> Result<usize, LoaderError> {
+        let count = self.deserializer.read::<u32>()? as usize;
+        // prevents huge allocation from broken data
+        self.deserializer.check_array(min_element_size, count)?;
+        Ok(count)
+    }
+
+    /// Strings are utf-8 and padded to 4 bytes
+    fn read_string_bytes(&mut self) -> Result<&'a [u8], LoaderError> {
+        let length = self.deserializer.read::<u32>()? as usize;
+        let bytes = self.deserializer.read_bytes(length)?;
+        self.deserializer.skip((4 - length % 4) % 4)?; // padding
+        Ok(bytes)
+    }
+
+    fn read_string(&mut self) -> Result<String, LoaderError> {
+        Ok(String::from_utf8_lossy(self.read_string_bytes()?).into_owned())
+    }
+
+    fn skip_string(&mut self) -> Result<(), LoaderError> {
+        self.read_string_bytes().map(|_| ())
+    }
+
+    fn read_bones(&mut self, position: usize) -> Result<Vec<PmxBone>, LoaderError> {
+        self.deserializer.seek(position);
+
+        let count = self.read_count(4 * 2 + 4 * 3 + 4 + 4 + 2 + 2)?;
+        let mut bones = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let name = self.read_string()?;
+            self.skip_string()?; // english name
+            let position = self.deserializer.read_vector()?;
+            let parent_bone_index = self.deserializer.read::<i32>()?;
+            let transform_order = self.deserializer.read::<i32>()?;
+            let flag = self.deserializer.read::<u16>()?;
+            self.deserializer.skip(2)?; // padding
+
+            // tail position
+            if flag & BoneFlag::UseBoneIndexAsTailPosition as u16 != 0 {
+                self.deserializer.skip(4)?;
+            } else {
+                self.deserializer.skip(4 * 3)?;
+            }
+
+            let append_transform = if flag & BoneFlag::HasAppendMove as u16 != 0 || flag & BoneFlag::HasAppendRotate as u16 != 0 {
+                Some(PmxAppendTransform {
+                    parent_index: self.deserializer.read::<i32>()?,
+                    ratio: self.deserializer.read::<f32>()?,
+                })
+            } else {
+                None
+            };
+
+            let axis_limit = if flag & BoneFlag::HasAxisLimit as u16 != 0 {
+                Some(self.deserializer.read_vector()?)
+            } else {
+                None
+            };
+
+            if flag & BoneFlag::HasLocalVector as u16 != 0 {
+                self.deserializer.skip(4 * 3 * 2)?; // x axis, z axis
+            }
+
+            if flag & BoneFlag::IsExternalParentTransformed as u16 != 0 {
+                self.deserializer.skip(4)?; // external parent key
+            }
+
+            let ik = if flag & BoneFlag::IsIkEnabled as u16 != 0 {
+                let target = self.deserializer.read::<i32>()?;
+                let iteration = self.deserializer.read::<i32>()?;
+                let rotation_constraint = self.deserializer.read::<f32>()?;
+                let link_count = self.read_count(4 + 4)?;
+                let mut links = Vec::with_capacity(link_count);
+                for _ in 0..link_count {
+                    let target = self.deserializer.read::<i32>()?;
+                    let has_limitation = self.deserializer.read::<u8>()? == 1;
+                    self.deserializer.skip(3)?; // padding
+                    let limitation = if has_limitation {
+                        Some((self.deserializer.read_vector()?, self.deserializer.read_vector()?))
+                    } else {
+                        None
+                    };
+                    links.push(PmxIkLink {
+                        target,
+                        limitation,
+                    });
+                }
+                Some(PmxIk {
+                    target,
+                    iteration,
+                    rotation_constraint,
+                    links,
+                })
+            } else {
+                None
+            };
+
+            bones.push(PmxBone {
+                name,
+                position,
+                parent_bone_index,
+                transform_order,
+                flag,
+                append_transform,
+                axis_limit,
+                ik,
+            });
+        }
+
+        Ok(bones)
+    }
+
+    fn skip_mesh_morph_elements(&mut self, offset_size: usize) -> Result<(), LoaderError> {
+        let mesh_count = self.read_count(4 + 4)?;
+        for _ in 0..mesh_count {
+            self.deserializer.skip(4)?; // mesh index
+            let element_count = self.read_count(4 + offset_size)?;
+            self.deserializer.skip((4 + offset_size) * element_count)?;
+        }
+        Ok(())
+    }
+
+    fn read_morphs(&mut self, position: usize) -> Result<Vec<PmxMorph>, LoaderError> {
+        self.deserializer.seek(position);
+
+        let count = self.read_count(4 * 2 + 1 + 1 + 2 + 4)?;
+        let mut morphs = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            self.skip_string()?; // name
+            self.skip_string()?; // english name
+            self.deserializer.skip(1)?; // category
+            let kind = self.deserializer.read::<u8>()?;
+            self.deserializer.skip(2)?; // padding
+
+            let morph = if kind == PmxMorphKind::GroupMorph as u8 {
+                let element_count = self.read_count(4 + 4)?;
+                let mut indices = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    indices.push(self.deserializer.read::<i32>()?);
+                }
+                let mut ratios = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    ratios.push(self.deserializer.read::<f32>()?);
+                }
+                PmxMorph::Group {
+                    indices,
+                    ratios,
+                }
+            } else if kind == PmxMorphKind::BoneMorph as u8 {
+                let element_count = self.read_count(4 + 4 * 3 + 4 * 4)?;
+                let mut indices = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    indices.push(self.deserializer.read::<i32>()?);
+                }
+                let mut positions = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    positions.push(self.deserializer.read_vector()?);
+                }
+                let mut rotations = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    rotations.push(self.deserializer.read_quaternion()?);
+                }
+                PmxMorph::Bone {
+                    indices,
+                    positions,
+                    rotations,
+                }
+            } else if kind == PmxMorphKind::VertexMorph as u8 {
+                self.skip_mesh_morph_elements(4 * 3)?;
+                PmxMorph::Other
+            } else if kind == PmxMorphKind::UvMorph as u8 ||
+                kind == PmxMorphKind::AdditionalUvMorph1 as u8 ||
+                kind == PmxMorphKind::AdditionalUvMorph2 as u8 ||
+                kind == PmxMorphKind::AdditionalUvMorph3 as u8 ||
+                kind == PmxMorphKind::AdditionalUvMorph4 as u8
+            {
+                self.skip_mesh_morph_elements(4 * 4)?;
+                PmxMorph::Other
+            } else if kind == PmxMorphKind::MaterialMorph as u8 {
+                let element_size =
+                    4 + // material index
+                    1 + // operation type
+                    3 + // padding
+                    4 * 4 + // diffuse
+                    4 * 3 + // specular
+                    4 + // shininess
+                    4 * 3 + // ambient
+                    4 * 4 + // edge color
+                    4 + // edge size
+                    4 * 4 + // texture color
+                    4 * 4 + // sphere texture color
+                    4 * 4; // toon texture color
+                let element_count = self.read_count(element_size)?;
+                self.deserializer.skip(element_size * element_count)?;
+                PmxMorph::Other
+            } else {
+                return Err(LoaderError::InvalidMorphKind(kind));
+            };
+
+            morphs.push(morph);
+        }
+
+        Ok(morphs)
+    }
+
+    fn read_rigid_bodies(&mut self, position: usize) -> Result<Vec<PmxRigidBody>, LoaderError> {
+        self.deserializer.seek(position);
+
+        let count = self.read_count(4 * 2 + 4 + 2 + 1 + 1 + 4 * 3 * 3 + 4 * 5 + 4)?;
+        let mut rigid_bodies = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let name = self.read_string()?;
+            self.skip_string()?; // english name
+            let bone_index = self.deserializer.read::<i32>()?;
+            // order of collision mask and group is different from pmx for alignment
+            let collision_mask = self.deserializer.read::<u16>()?;
+            let collision_group = self.deserializer.read::<u8>()?;
+            let shape_type = self.deserializer.read::<u8>()?;
+            let shape_size = self.deserializer.read_vector()?;
+            let shape_position = self.deserializer.read_vector()?;
+            let shape_rotation = self.deserializer.read_vector()?;
+            let mass = self.deserializer.read::<f32>()?;
+            let linear_damping = self.deserializer.read::<f32>()?;
+            let angular_damping = self.deserializer.read::<f32>()?;
+            let repulsion = self.deserializer.read::<f32>()?;
+            let friction = self.deserializer.read::<f32>()?;
+            let physics_mode = self.deserializer.read::<u8>()?;
+            self.deserializer.skip(3)?; // padding
+
+            rigid_bodies.push(PmxRigidBody {
+                name,
+                bone_index,
+                collision_group,
+                collision_mask,
+                shape_type,
+                shape_size,
+                shape_position,
+                shape_rotation,
+                mass,
+                linear_damping,
+                angular_damping,
+                repulsion,
+                friction,
+                physics_mode,
+            });
+        }
+
+        Ok(rigid_bodies)
+    }
+
+    fn read_joints(&mut self, position: usize) -> Result<Vec<PmxJoint>, LoaderError> {
+        self.deserializer.seek(position);
+
+        let count = self.read_count(4 * 2 + 4 + 4 + 4 + 4 * 3 * 8)?;
+        let mut joints = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            self.skip_string()?; // name
+            self.skip_string()?; // english name
+            let kind = self.deserializer.read::<u8>()?;
+            self.deserializer.skip(3)?; // padding
+
+            joints.push(PmxJoint {
+                kind,
+                rigidbody_index_a: self.deserializer.read::<i32>()?,
+                rigidbody_index_b: self.deserializer.read::<i32>()?,
+                position: self.deserializer.read_vector()?,
+                rotation: self.deserializer.read_vector()?,
+                position_min: self.deserializer.read_vector()?,
+                position_max: self.deserializer.read_vector()?,
+                rotation_min: self.deserializer.read_vector()?,
+                rotation_max: self.deserializer.read_vector()?,
+                spring_position: self.deserializer.read_vector()?,
+                spring_rotation: self.deserializer.read_vector()?,
+            });
+        }
+
+        Ok(joints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Quat, Vec3A};
+
+    use crate::diagnostic::Diagnostic;
+
+    use super::super::pmx_metadata_encoder::{PmxMetadataEncodeOptions, PmxMetadataEncoder};
+    use super::*;
+
+    const HEADER_POSITION_COUNT: usize = 10;
+    const BONE_POSITION_INDEX: usize = 5;
+    const MORPH_POSITION_INDEX: usize = 6;
+
+    #[derive(Default)]
+    struct BpmxWriter {
+        bytes: Vec<u8>,
+    }
+
+    impl BpmxWriter {
+        fn u8(&mut self, value: u8) {
+            self.bytes.push(value);
+        }
+
+        fn u16(&mut self, value: u16) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn u32(&mut self, value: u32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn i32(&mut self, value: i32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn f32(&mut self, value: f32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn vector(&mut self, value: Vec3A) {
+            for component in value.to_array() {
+                self.f32(component);
+            }
+        }
+
+        fn zeros(&mut self, size: usize) {
+            self.bytes.resize(self.bytes.len() + size, 0);
+        }
+
+        fn string(&mut self, text: &str) {
+            self.u32(text.len() as u32);
+            self.bytes.extend_from_slice(text.as_bytes());
+            self.zeros((4 - text.len() % 4) % 4);
+        }
+    }
+
+    /// Bones with every optional field, a morph of each kind and a jointed rigid body pair
+    fn sample_model() -> PmxObject {
+        let bone = |name: &str, position: Vec3A, parent_bone_index: i32, flag: u16| PmxBone {
+            name: name.to_owned(),
+            position,
+            parent_bone_index,
+            transform_order: 0,
+            flag: flag | BoneFlag::IsRotatable as u16,
+            append_transform: None,
+            axis_limit: None,
+            ik: None,
+        };
+
+        let mut bones = vec![
+            bone("センター", Vec3A::new(0.0, 8.0, 0.0), -1, BoneFlag::UseBoneIndexAsTailPosition as u16 | BoneFlag::IsMovable as u16),
+            bone("左足", Vec3A::new(1.0, 5.0, 0.2), 0, BoneFlag::HasLocalVector as u16 | BoneFlag::IsExternalParentTransformed as u16),
+            bone("左足ＩＫ", Vec3A::new(1.0, 1.0, 0.0), 0, BoneFlag::IsMovable as u16 | BoneFlag::IsIkEnabled as u16),
+            bone("左ダミー", Vec3A::new(1.5, 5.0, 0.0), 1, BoneFlag::HasAppendRotate as u16 | BoneFlag::HasAxisLimit as u16),
+        ];
+        bones[2].transform_order = 1;
+        bones[2].ik = Some(PmxIk {
+            target: 1,
+            iteration: 40,
+            rotation_constraint: 2.0,
+            links: vec![
+                PmxIkLink {
+                    target: 1,
+                    limitation: Some((Vec3A::new(-std::f32::consts::PI, 0.0, 0.0), Vec3A::new(-0.008, 0.0, 0.0))),
+                },
+                PmxIkLink {
+                    target: 0,
+                    limitation: None,
+                },
+            ],
+        });
+        bones[3].append_transform = Some(PmxAppendTransform {
+            parent_index: 1,
+            ratio: 0.5,
+        });
+        bones[3].axis_limit = Some(Vec3A::X);
+
+        let rigid_body = |name: &str, bone_index: i32, physics_mode: u8| PmxRigidBody {
+            name: name.to_owned(),
+            bone_index,
+            collision_group: 1,
+            collision_mask: 0xFFFE,
+            shape_type: 2,
+            shape_size: Vec3A::new(0.5, 4.0, 0.0),
+            shape_position: Vec3A::new(1.0, 7.5, 0.0),
+            shape_rotation: Vec3A::new(0.0, 0.0, 0.1),
+            mass: 1.0,
+            linear_damping: 0.5,
+            angular_damping: 0.5,
+            repulsion: 0.0,
+            friction: 0.5,
+            physics_mode,
+        };
+
+        PmxObject {
+            bones,
+            morphs: vec![
+                PmxMorph::Other,
+                PmxMorph::Bone {
+                    indices: vec![1, 3],
+                    positions: vec![Vec3A::Y, Vec3A::ZERO],
+                    rotations: vec![Quat::IDENTITY, Quat::from_rotation_x(0.5)],
+                },
+                PmxMorph::Group {
+                    indices: vec![1, 0],
+                    ratios: vec![1.0, 0.5],
+                },
+            ],
+            rigid_bodies: vec![rigid_body("左足", 1, 0), rigid_body("左ダミー", 3, 1)],
+            joints: vec![PmxJoint {
+                kind: 0,
+                rigidbody_index_a: 0,
+                rigidbody_index_b: 1,
+                position: Vec3A::new(1.0, 5.0, 0.0),
+                rotation: Vec3A::ZERO,
+                position_min: Vec3A::ZERO,
+                position_max: Vec3A::ZERO,
+                rotation_min: Vec3A::splat(-0.5),
+                rotation_max: Vec3A::splat(0.5),
+                spring_position: Vec3A::ZERO,
+                spring_rotation: Vec3A::splat(10.0),
+            }],
+        }
+    }
+
+    /// Write the model as BPMX 3.0
+    ///
+    /// Only bone, morph, rigid body and joint blocks are written, other block positions are 0.
+    /// `PmxMorph::Other` is written as a vertex morph
+    fn write_bpmx(pmx: &PmxObject) -> Vec<u8> {
+        let mut w = BpmxWriter::default();
+
+        w.bytes.extend_from_slice(b"BPMX");
+        w.bytes.extend_from_slice(&[3, 0, 0]);
+        w.u8(0); // padding
+        w.u32(4 * HEADER_POSITION_COUNT as u32);
+        let positions_offset = w.bytes.len();
+        w.zeros(4 * HEADER_POSITION_COUNT);
+        let mut positions = [0u32; HEADER_POSITION_COUNT];
+
+        positions[BONE_POSITION_INDEX] = w.bytes.len() as u32;
+        w.u32(pmx.bones.len() as u32);
+        for bone in &pmx.bones {
+            w.string(&bone.name);
+            w.string("");
+            w.vector(bone.position);
+            w.i32(bone.parent_bone_index);
+            w.i32(bone.transform_order);
+            w.u16(bone.flag);
+            w.u16(0); // padding
+            if bone.flag & BoneFlag::UseBoneIndexAsTailPosition as u16 != 0 {
+                w.i32(-1);
+            } else {
+                w.vector(Vec3A::Y);
+            }
+            if let Some(append_transform) = &bone.append_transform {
+                w.i32(append_transform.parent_index);
+                w.f32(append_transform.ratio);
+            }
+            if let Some(axis_limit) = bone.axis_limit {
+                w.vector(axis_limit);
+            }
+            if bone.flag & BoneFlag::HasLocalVector as u16 != 0 {
+                w.vector(Vec3A::X);
+                w.vector(Vec3A::Z);
+            }
+            if bone.flag & BoneFlag::IsExternalParentTransformed as u16 != 0 {
+                w.i32(0);
+            }
+            if let Some(ik) = &bone.ik {
+                w.i32(ik.target);
+                w.i32(ik.iteration);
+                w.f32(ik.rotation_constraint);
+                w.u32(ik.links.len() as u32);
+                for link in &ik.links {
+                    w.i32(link.target);
+                    w.u8(link.limitation.is_some() as u8);
+                    w.zeros(3); // padding
+                    if let Some((minimum_angle, maximum_angle)) = link.limitation {
+                        w.vector(minimum_angle);
+                        w.vector(maximum_angle);
+                    }
+                }
+            }
+        }
+
+        positions[MORPH_POSITION_INDEX] = w.bytes.len() as u32;
+        w.u32(pmx.morphs.len() as u32);
+        for morph in &pmx.morphs {
+            w.string("モーフ");
+            w.string("");
+            w.u8(1); // category
+            match morph {
+                PmxMorph::Group { indices, ratios } => {
+                    w.u8(PmxMorphKind::GroupMorph as u8);
+                    w.u16(0); // padding
+                    w.u32(indices.len() as u32);
+                    indices.iter().for_each(|index| w.i32(*index));
+                    ratios.iter().for_each(|ratio| w.f32(*ratio));
+                }
+                PmxMorph::Bone { indices, positions, rotations } => {
+                    w.u8(PmxMorphKind::BoneMorph as u8);
+                    w.u16(0); // padding
+                    w.u32(indices.len() as u32);
+                    indices.iter().for_each(|index| w.i32(*index));
+                    positions.iter().for_each(|position| w.vector(*position));
+                    for rotation in rotations {
+                        rotation.to_array().into_iter().for_each(|component| w.f32(component));
+                    }
+                }
+                PmxMorph::Other => {
+                    w.u8(PmxMorphKind::VertexMorph as u8);
+                    w.u16(0); // padding
+                    w.u32(1); // mesh count
+                    w.u32(0); // mesh index
+                    w.u32(2); // element count
+                    for i in 0..2 {
+                        w.i32(i);
+                        w.vector(Vec3A::ONE);
+                    }
+                }
+            }
+        }
+
+        positions[8] = w.bytes.len() as u32;
+        w.u32(pmx.rigid_bodies.len() as u32);
+        for rigid_body in &pmx.rigid_bodies {
+            w.string(&rigid_body.name);
+            w.string("");
+            w.i32(rigid_body.bone_index);
+            w.u16(rigid_body.collision_mask);
+            w.u8(rigid_body.collision_group);
+            w.u8(rigid_body.shape_type);
+            w.vector(rigid_body.shape_size);
+            w.vector(rigid_body.shape_position);
+            w.vector(rigid_body.shape_rotation);
+            w.f32(rigid_body.mass);
+            w.f32(rigid_body.linear_damping);
+            w.f32(rigid_body.angular_damping);
+            w.f32(rigid_body.repulsion);
+            w.f32(rigid_body.friction);
+            w.u8(rigid_body.physics_mode);
+            w.zeros(3); // padding
+        }
+
+        positions[9] = w.bytes.len() as u32;
+        w.u32(pmx.joints.len() as u32);
+        for joint in &pmx.joints {
+            w.string("ジョイント");
+            w.string("");
+            w.u8(joint.kind);
+            w.zeros(3); // padding
+            w.i32(joint.rigidbody_index_a);
+            w.i32(joint.rigidbody_index_b);
+            for vector in [
+                joint.position,
+                joint.rotation,
+                joint.position_min,
+                joint.position_max,
+                joint.rotation_min,
+                joint.rotation_max,
+                joint.spring_position,
+                joint.spring_rotation,
+            ] {
+                w.vector(vector);
+            }
+        }
+
+        for (i, position) in positions.iter().enumerate() {
+            let offset = positions_offset + 4 * i;
+            w.bytes[offset..offset + 4].copy_from_slice(&position.to_le_bytes());
+        }
+
+        w.bytes
+    }
+
+    fn encode(pmx: &PmxObject) -> Vec<u8> {
+        let mut diagnostic = Diagnostic::new();
+        PmxMetadataEncoder::encode(pmx, PmxMetadataEncodeOptions::default_physics(), &mut diagnostic.writer()).unwrap()
+    }
+
+    #[test]
+    fn same_metadata_as_source_model() {
+        let model = sample_model();
+        let bpmx = BpmxReader::read(&write_bpmx(&model)).unwrap();
+
+        let names = bpmx.bones.iter().map(|bone| bone.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["センター", "左足", "左足ＩＫ", "左ダミー"]);
+        let ik = bpmx.bones[2].ik.as_ref().expect("ik is not read");
+        assert_eq!(ik.links[0].limitation, Some((Vec3A::new(-std::f32::consts::PI, 0.0, 0.0), Vec3A::new(-0.008, 0.0, 0.0))));
+        assert!(matches!(bpmx.morphs[0], PmxMorph::Other));
+        assert_eq!(bpmx.rigid_bodies[1].name, "左ダミー");
+
+        assert_eq!(encode(&bpmx), encode(&model));
+    }
+
+    #[test]
+    fn short_header_omits_blocks() {
+        let mut bytes = write_bpmx(&sample_model());
+        // header without rigid body and joint positions
+        bytes[8..12].copy_from_slice(&(4 * (HEADER_POSITION_COUNT as u32 - 2)).to_le_bytes());
+        let header_end = 12 + 4 * (HEADER_POSITION_COUNT - 2);
+        bytes.drain(header_end..header_end + 8);
+        for index in [BONE_POSITION_INDEX, MORPH_POSITION_INDEX] {
+            let offset = 12 + 4 * index;
+            let position = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+            bytes[offset..offset + 4].copy_from_slice(&(position - 8).to_le_bytes());
+        }
+
+        let bpmx = BpmxReader::read(&bytes).unwrap();
+        assert_eq!(bpmx.bones.len(), 4);
+        assert_eq!(bpmx.morphs.len(), 3);
+        assert!(bpmx.rigid_bodies.is_empty() && bpmx.joints.is_empty());
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        let bytes = write_bpmx(&sample_model());
+        for version in [[2, 9, 0], [3, 1, 0], [4, 0, 0]] {
+            let mut bytes = bytes.clone();
+            bytes[4..7].copy_from_slice(&version);
+            assert!(matches!(BpmxReader::read(&bytes), Err(LoaderError::UnsupportedVersion(v)) if v == version));
+        }
+
+        let mut invalid_signature = bytes.clone();
+        invalid_signature[0..4].copy_from_slice(b"PMX ");
+        assert!(matches!(BpmxReader::read(&invalid_signature), Err(LoaderError::InvalidSignature)));
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let bytes = write_bpmx(&sample_model());
+        for length in 0..bytes.len() {
+            assert!(
+                matches!(BpmxReader::read(&bytes[..length]), Err(LoaderError::UnexpectedEnd { .. })),
+                "truncated at {} of {} bytes was accepted", length, bytes.len()
+            );
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use glam::{Quat, Vec3A};
+use num_traits::FromBytes;
+
+use super::LoaderError;
+
+pub(super) struct DataDeserializer<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DataDeserializer<'a> {
+    pub(super) fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+        }
+    }
+
+    pub(super) fn bytes_available(&self) -> usize {
+        self.bytes.len().saturating_sub(self.offset)
+    }
+
+    /// Move to absolute offset, out of range offset is reported by the next read
+    pub(super) fn seek(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    #[inline]
+    fn check(&self, size: usize) -> Result<std::ops::Range<usize>, LoaderError> {
+        match self.offset.checked_add(size) {
+            Some(end) if end <= self.bytes.len() => Ok(self.offset..end),
+            _ => Err(LoaderError::UnexpectedEnd {
+                offset: self.offset,
+                size,
+            }),
+        }
+    }
+
+    /// Check that `n` elements of `element_size` bytes are available without consuming them
+    ///
+    /// Used to reject broken counts before allocating
+    pub(super) fn check_array(&self, element_size: usize, n: usize) -> Result<(), LoaderError> {
+        match element_size.checked_mul(n) {
+            Some(size) => self.check(size).map(|_| ()),
+            None => Err(LoaderError::UnexpectedEnd {
+                offset: self.offset,
+                size: usize::MAX,
+            }),
+        }
+    }
+
+    pub(super) fn skip(&mut self, size: usize) -> Result<(), LoaderError> {
+        let range = self.check(size)?;
+        self.offset = range.end;
+        Ok(())
+    }
+
+    pub(super) fn read_bytes(&mut self, size: usize) -> Result<&'a [u8], LoaderError> {
+        let range = self.check(size)?;
+        self.offset = range.end;
+        Ok(&self.bytes[range])
+    }
+
+    pub(super) fn read<'b, T>(&'b mut self) -> Result<T, LoaderError>
+    where
+        T: FromBytes,
+        <T as FromBytes>::Bytes: 'b,
+        &'b [u8]: TryInto<&'b <T as FromBytes>::Bytes>,
+        <&'b [u8] as TryInto<&'b <T as FromBytes>::Bytes>>::Error: std::fmt::Debug,
+    {
+        let range = self.check(std::mem::size_of::<T>())?;
+        self.offset = range.end;
+        let value = T::from_le_bytes(self.bytes[range].as_ref().try_into().unwrap());
+        Ok(value)
+    }
+
+    pub(super) fn read_vector(&mut self) -> Result<Vec3A, LoaderError> {
+        Ok(Vec3A::new(
+            self.read::<f32>()?,
+            self.read::<f32>()?,
+            self.read::<f32>()?,
+        ))
+    }
+
+    pub(super) fn read_quaternion(&mut self) -> Result<Quat, LoaderError> {
+        Ok(Quat::from_xyzw(
+            self.read::<f32>()?,
+            self.read::<f32>()?,
+            self.read::<f32>()?,
+            self.read::<f32>()?,
+        ))
+    }
+}
@@ -0,0 +1,656 @@
+#[cfg(feature = "physics")]
+use glam::Mat4;
+
+#[cfg(feature = "physics")]
+use crate::mmd_model_metadata::{CcdMetadata, ExtensionChunkTag, JointMetadata, RagdollMetadata};
+use crate::mmd_model_metadata::{BoneFlag, BoneMetadata, MetadataSectionKind, MorphKind, MorphMetadata, PhysicsInfoKind, RigidBodyMetadata};
+
+use super::LoaderError;
+use super::metadata_serializer::MetadataSerializer;
+
+pub(crate) enum PhysicsMetadata {
+    NoPhysics,
+    StripedRigidbodies(Vec<RigidBodyMetadata>),
+    #[cfg(feature = "physics")]
+    FullPhysics(FullPhysicsMetadata),
+}
+
+#[cfg(feature = "physics")]
+pub(crate) struct FullPhysicsMetadata {
+    pub(crate) physics_world_id: u32,
+    pub(crate) kinematic_shared_physics_world_ids: Vec<u32>,
+    pub(crate) model_initial_world_matrix: Mat4,
+    pub(crate) disable_offset_for_constraint_frame: bool,
+    pub(crate) rigid_bodies: Vec<RigidBodyMetadata>,
+    pub(crate) joints: Vec<JointMetadata>,
+    pub(crate) ragdoll: Option<RagdollMetadata>,
+    pub(crate) ccd: Option<CcdMetadata>,
+}
+
+/// Builder for the format read by `BoneMetadataReader` and the following readers
+///
+/// Bone flags that select optional fields are derived from the fields themselves,
+/// so the output can always be read back
+pub(crate) struct MetadataBuilder {
+    bones: Vec<BoneMetadata>,
+    morphs: Vec<MorphMetadata>,
+    physics: PhysicsMetadata,
+}
+
+impl MetadataBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            bones: Vec::new(),
+            morphs: Vec::new(),
+            physics: PhysicsMetadata::NoPhysics,
+        }
+    }
+
+    pub(crate) fn add_bone(&mut self, bone: BoneMetadata) {
+        self.bones.push(bone);
+    }
+
+    pub(crate) fn add_morph(&mut self, morph: MorphMetadata) {
+        self.morphs.push(morph);
+    }
+
+    pub(crate) fn set_physics(&mut self, physics: PhysicsMetadata) {
+        self.physics = physics;
+    }
+
+    /// Serialize the metadata
+    ///
+    /// Fails if element arrays of a morph have different lengths since they are stored with one count
+    pub(crate) fn build(&self) -> Result<Vec<u8>, LoaderError> {
+        let mut serializer = MetadataSerializer::new();
+
+        serializer.begin_section(MetadataSectionKind::Bones);
+        self.write_bones(&mut serializer);
+        serializer.begin_section(MetadataSectionKind::Morphs);
+        self.write_morphs(&mut serializer)?;
+        serializer.begin_section(MetadataSectionKind::Physics);
+        self.write_physics(&mut serializer);
+
+        #[cfg(feature = "physics")]
+        if let PhysicsMetadata::FullPhysics(physics) = &self.physics {
+            if physics.ragdoll.is_some() || physics.ccd.is_some() {
+                serializer.begin_section(MetadataSectionKind::Extensions);
+            }
+            if let Some(ragdoll) = &physics.ragdoll {
+                Self::write_ragdoll(&mut serializer, ragdoll);
+            }
+            if let Some(ccd) = &physics.ccd {
+                Self::write_ccd(&mut serializer, ccd);
+            }
+        }
+
+        Ok(serializer.finish())
+    }
+
+    /// Append transform is only read when append move or append rotate flag is set
+    fn has_append_transform(bone: &BoneMetadata) -> bool {
+        bone.append_transform.is_some() && bone.flag & (BoneFlag::HasAppendMove as u16 | BoneFlag::HasAppendRotate as u16) != 0
+    }
+
+    fn write_bones(&self, serializer: &mut MetadataSerializer) {
+        let append_transform_count = self.bones.iter().filter(|bone| Self::has_append_transform(bone)).count();
+        let ik_count = self.bones.iter().filter(|bone| bone.ik.is_some()).count();
+
+        serializer.write_u32(self.bones.len() as u32); // bone count
+        serializer.write_u32(append_transform_count as u32); // append transform count
+        serializer.write_u32(ik_count as u32); // ik count
+
+        for bone in &self.bones {
+            let mut flag = bone.flag & !(BoneFlag::HasAxisLimit as u16 | BoneFlag::IsIkEnabled as u16);
+            if bone.append_transform.is_none() {
+                flag &= !(BoneFlag::HasAppendMove as u16 | BoneFlag::HasAppendRotate as u16);
+            }
+            if bone.axis_limit.is_some() {
+                flag |= BoneFlag::HasAxisLimit as u16;
+            }
+            if bone.ik.is_some() {
+                flag |= BoneFlag::IsIkEnabled as u16;
+            }
+
+            serializer.write_vector(bone.rest_position); // rest position
+            serializer.write_matrix(&bone.absolute_inverse_bind_matrix); // absolute inverse bind matrix
+            serializer.write_i32(bone.parent_bone_index); // parent bone index
+            serializer.write_i32(bone.transform_order); // transform order
+            serializer.write_u16(flag); // flag
+            serializer.write_padding(2);
+
+            if let Some(append_transform) = bone.append_transform.as_ref().filter(|_| Self::has_append_transform(bone)) {
+                serializer.write_i32(append_transform.parent_index); // parent index
+                serializer.write_f32(append_transform.ratio); // ratio
+            }
+
+            if let Some(axis_limit) = bone.axis_limit {
+                serializer.write_vector(axis_limit); // axis
+            }
+
+            if let Some(ik) = &bone.ik {
+                serializer.write_i32(ik.target); // target
+                serializer.write_i32(ik.iteration); // iteration
+                serializer.write_f32(ik.rotation_constraint); // rotation constraint
+                serializer.write_u32(ik.links.len() as u32); // link count
+
+                for link in &ik.links {
+                    serializer.write_i32(link.target); // target
+                    serializer.write_u8(link.limits.is_some() as u8); // has limits
+                    serializer.write_padding(3);
+
+                    if let Some(limits) = &link.limits {
+                        serializer.write_vector(limits.minimum_angle); // minimum angle
+                        serializer.write_vector(limits.maximum_angle); // maximum angle
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_morphs(&self, serializer: &mut MetadataSerializer) -> Result<(), LoaderError> {
+        serializer.write_u32(self.morphs.len() as u32); // morph count
+
+        for (i, morph) in self.morphs.iter().enumerate() {
+            match morph {
+                MorphMetadata::Bone(morph) => {
+                    let count = morph.indices.len();
+                    if morph.positions.len() != count || morph.rotations.len() != count {
+                        return Err(LoaderError::MorphElementCountMismatch(i));
+                    }
+
+                    serializer.write_u8(MorphKind::BoneMorph as u8); // kind
+                    serializer.write_padding(3);
+                    serializer.write_u32(count as u32); // bone count
+                    for index in &morph.indices {
+                        serializer.write_i32(*index); // indices
+                    }
+                    for position in &morph.positions {
+                        serializer.write_vector(*position); // positions
+                    }
+                    for rotation in &morph.rotations {
+                        serializer.write_quaternion(*rotation); // rotations
+                    }
+                }
+                MorphMetadata::Group(morph) => {
+                    let count = morph.indices.len();
+                    if morph.ratios.len() != count {
+                        return Err(LoaderError::MorphElementCountMismatch(i));
+                    }
+
+                    serializer.write_u8(MorphKind::GroupMorph as u8); // kind
+                    serializer.write_padding(3);
+                    serializer.write_u32(count as u32); // index count
+                    for index in &morph.indices {
+                        serializer.write_i32(*index); // indices
+                    }
+                    for ratio in &morph.ratios {
+                        serializer.write_f32(*ratio); // ratios
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_physics(&self, serializer: &mut MetadataSerializer) {
+        match &self.physics {
+            PhysicsMetadata::NoPhysics => {
+                serializer.write_u8(PhysicsInfoKind::NoPhysics as u8); // physics info kind
+                serializer.write_padding(3);
+            }
+            PhysicsMetadata::StripedRigidbodies(rigid_bodies) => {
+                serializer.write_u8(PhysicsInfoKind::StripedRigidbodies as u8); // physics info kind
+                serializer.write_padding(3);
+
+                serializer.write_u32(rigid_bodies.len() as u32); // rigid body count
+                for rigid_body in rigid_bodies {
+                    serializer.write_i32(rigid_body.bone_index); // bone index
+                    serializer.write_u8(rigid_body.physics_mode); // physics mode
+                    serializer.write_padding(3);
+                }
+            }
+            #[cfg(feature = "physics")]
+            PhysicsMetadata::FullPhysics(physics) => {
+                serializer.write_u8(PhysicsInfoKind::FullPhysics as u8); // physics info kind
+                serializer.write_padding(3);
+
+                serializer.write_u32(physics.physics_world_id); // physics world id
+                serializer.write_u32(physics.kinematic_shared_physics_world_ids.len() as u32); // kinematic shared physics world id count
+                for id in &physics.kinematic_shared_physics_world_ids {
+                    serializer.write_u32(*id); // kinematic shared physics world ids
+                }
+                serializer.write_matrix(&physics.model_initial_world_matrix); // model initial world matrix
+                serializer.write_u8(physics.disable_offset_for_constraint_frame as u8); // disable offset for constraint frame
+                serializer.write_padding(3);
+
+                serializer.write_u32(physics.rigid_bodies.len() as u32); // rigid body count
+                for rigid_body in &physics.rigid_bodies {
+                    serializer.write_i32(rigid_body.bone_index); // bone index
+                    serializer.write_u8(rigid_body.collision_group); // collision group
+                    serializer.write_u8(rigid_body.shape_type); // shape type
+                    serializer.write_u16(rigid_body.collision_mask); // collision mask
+                    for component in rigid_body.shape_size.to_array() {
+                        serializer.write_f32(component); // shape size
+                    }
+                    serializer.write_vector(rigid_body.shape_position); // shape position
+                    serializer.write_vector(rigid_body.shape_rotation); // shape rotation
+                    serializer.write_f32(rigid_body.mass); // mass
+                    serializer.write_f32(rigid_body.linear_damping); // linear damping
+                    serializer.write_f32(rigid_body.angular_damping); // angular damping
+                    serializer.write_f32(rigid_body.repulsion); // repulsion
+                    serializer.write_f32(rigid_body.friction); // friction
+                    serializer.write_u8(rigid_body.physics_mode); // physics mode
+                    serializer.write_padding(3);
+                }
+
+                serializer.write_u32(physics.joints.len() as u32); // joint count
+                for joint in &physics.joints {
+                    serializer.write_u8(joint.kind); // kind
+                    serializer.write_padding(3);
+                    serializer.write_i32(joint.rigidbody_index_a); // rigidbody index a
+                    serializer.write_i32(joint.rigidbody_index_b); // rigidbody index b
+                    serializer.write_vector(joint.position); // position
+                    serializer.write_vector(joint.rotation); // rotation
+                    serializer.write_vector(joint.position_min); // position min
+                    serializer.write_vector(joint.position_max); // position max
+                    serializer.write_vector(joint.rotation_min); // rotation min
+                    serializer.write_vector(joint.rotation_max); // rotation max
+                    serializer.write_vector(joint.spring_position); // spring position
+                    serializer.write_vector(joint.spring_rotation); // spring rotation
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    fn write_ragdoll(serializer: &mut MetadataSerializer, ragdoll: &RagdollMetadata) {
+        serializer.write_u32(ExtensionChunkTag::Ragdoll as u32); // tag
+        serializer.write_u32(4 + 4 * 3 * 2 + 4 + 4); // size
+        serializer.write_f32(ragdoll.default_mass); // default mass
+        serializer.write_vector(ragdoll.angular_lower_limit); // angular lower limit
+        serializer.write_vector(ragdoll.angular_upper_limit); // angular upper limit
+        serializer.write_f32(ragdoll.radius_scale); // radius scale
+        serializer.write_f32(ragdoll.min_bone_length); // min bone length
+    }
+
+    #[cfg(feature = "physics")]
+    fn write_ccd(serializer: &mut MetadataSerializer, ccd: &CcdMetadata) {
+        serializer.write_u32(ExtensionChunkTag::Ccd as u32); // tag
+        serializer.write_u32(4 * 2); // size
+        serializer.write_f32(ccd.motion_threshold_scale); // motion threshold scale
+        serializer.write_f32(ccd.swept_sphere_radius_scale); // swept sphere radius scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Quat, Vec3A};
+    #[cfg(feature = "physics")]
+    use glam::Vec4;
+
+    #[cfg(feature = "physics")]
+    use crate::mmd_model_metadata::RigidBodyShapeType;
+    use crate::mmd_model_metadata::{AppendTransformMetadata, BoneMetadataReader, BoneMorphMetadata, GroupMorphMetadata, IkChainAngleLimits, IkLinkMetadata, IkMetadata, MetadataBuffer, MetadataError};
+
+    use super::*;
+
+    fn bone(parent_bone_index: i32, flag: u16) -> BoneMetadata {
+        BoneMetadata {
+            rest_position: Vec3A::new(0.0, 1.0, parent_bone_index as f32),
+            absolute_inverse_bind_matrix: Mat4::from_translation(glam::Vec3::new(0.0, -1.0, 0.5)),
+            parent_bone_index,
+            transform_order: 0,
+            flag,
+            append_transform: None,
+            axis_limit: None,
+            ik: None,
+        }
+    }
+
+    #[cfg(not(feature = "physics"))]
+    fn physics() -> PhysicsMetadata {
+        PhysicsMetadata::StripedRigidbodies(vec![
+            RigidBodyMetadata {
+                bone_index: 1,
+                physics_mode: 0,
+            },
+            RigidBodyMetadata {
+                bone_index: -1,
+                physics_mode: 2,
+            },
+        ])
+    }
+
+    #[cfg(feature = "physics")]
+    fn physics() -> PhysicsMetadata {
+        let rigid_body = |bone_index: i32, physics_mode: u8| RigidBodyMetadata {
+            bone_index,
+            collision_group: 3,
+            collision_mask: 0x00FF,
+            shape_type: RigidBodyShapeType::Box as u8,
+            shape_size: Vec4::new(1.0, 2.0, 3.0, 0.0),
+            shape_position: Vec3A::new(0.5, 1.5, 2.5),
+            shape_rotation: Vec3A::new(0.1, 0.2, 0.3),
+            mass: 2.0,
+            linear_damping: 0.25,
+            angular_damping: 0.75,
+            repulsion: 0.125,
+            friction: 0.5,
+            physics_mode,
+        };
+        let joint = || JointMetadata {
+            kind: 0,
+            rigidbody_index_a: 0,
+            rigidbody_index_b: 1,
+            position: Vec3A::new(1.0, 2.0, 3.0),
+            rotation: Vec3A::new(0.1, 0.2, 0.3),
+            position_min: Vec3A::splat(-1.0),
+            position_max: Vec3A::splat(1.0),
+            rotation_min: Vec3A::splat(-0.5),
+            rotation_max: Vec3A::splat(0.5),
+            spring_position: Vec3A::splat(10.0),
+            spring_rotation: Vec3A::splat(20.0),
+        };
+
+        PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+            physics_world_id: 7,
+            kinematic_shared_physics_world_ids: vec![1, 2],
+            model_initial_world_matrix: Mat4::from_translation(glam::Vec3::new(1.0, 2.0, 3.0)),
+            disable_offset_for_constraint_frame: true,
+            rigid_bodies: vec![rigid_body(1, 0), rigid_body(-1, 2)],
+            joints: vec![
+                joint(),
+                joint(),
+            ],
+            ragdoll: None,
+            ccd: None,
+        })
+    }
+
+    fn add_bones(builder: &mut MetadataBuilder) {
+        builder.add_bone(bone(-1, BoneFlag::IsRotatable as u16 | BoneFlag::IsMovable as u16));
+        // flags of optional fields are wrong on purpose, they are derived from the fields
+        let mut ik_bone = bone(0, BoneFlag::IsRotatable as u16 | BoneFlag::HasAxisLimit as u16);
+        ik_bone.transform_order = 1;
+        ik_bone.ik = Some(Box::new(IkMetadata {
+            target: 2,
+            iteration: 40,
+            rotation_constraint: 2.0,
+            links: vec![
+                IkLinkMetadata {
+                    target: 2,
+                    limits: Some(IkChainAngleLimits {
+                        minimum_angle: Vec3A::new(-3.0, 0.0, 0.0),
+                        maximum_angle: Vec3A::new(-0.01, 0.0, 0.0),
+                    }),
+                },
+                IkLinkMetadata {
+                    target: 0,
+                    limits: None,
+                },
+            ],
+        }));
+        builder.add_bone(ik_bone);
+        let mut append_bone = bone(1, BoneFlag::HasAppendRotate as u16 | BoneFlag::HasAppendMove as u16);
+        append_bone.append_transform = Some(AppendTransformMetadata {
+            parent_index: 0,
+            ratio: -0.5,
+        });
+        append_bone.axis_limit = Some(Vec3A::Z);
+        builder.add_bone(append_bone);
+        // append flag without data is cleared
+        builder.add_bone(bone(2, BoneFlag::HasAppendRotate as u16));
+    }
+
+    fn add_morphs(builder: &mut MetadataBuilder) {
+        builder.add_morph(MorphMetadata::Bone(BoneMorphMetadata {
+            indices: vec![0, 2],
+            positions: vec![Vec3A::X, Vec3A::Y],
+            rotations: vec![Quat::IDENTITY, Quat::from_rotation_z(1.0)],
+        }));
+        builder.add_morph(MorphMetadata::Group(GroupMorphMetadata {
+            indices: vec![0, -1],
+            ratios: vec![0.5, 1.0],
+        }));
+        builder.add_morph(MorphMetadata::Group(GroupMorphMetadata {
+            indices: Vec::new(),
+            ratios: Vec::new(),
+        }));
+    }
+
+    /// Read every section back into a builder
+    fn read_back(bytes: &[u8]) -> Result<MetadataBuilder, MetadataError> {
+        let mut builder = MetadataBuilder::new();
+
+        let reader = BoneMetadataReader::new(MetadataBuffer::new(bytes))?;
+        let reader = reader.enumerate(|_, bone| builder.add_bone(bone))?;
+        #[cfg_attr(not(feature = "physics"), allow(unused_mut))]
+        let (morphs, mut reader) = reader.read()?;
+        for morph in morphs {
+            builder.add_morph(morph);
+        }
+
+        let mut rigid_bodies = Vec::new();
+        reader.enumerate(|_, rigid_body| rigid_bodies.push(rigid_body))?;
+
+        #[cfg(not(feature = "physics"))]
+        builder.set_physics(PhysicsMetadata::StripedRigidbodies(rigid_bodies));
+
+        #[cfg(feature = "physics")]
+        {
+            let ragdoll = reader.ragdoll()?;
+            let ccd = reader.ccd()?;
+            let mut joints = Vec::new();
+            let physics_world_id = reader.physics_world_id();
+            let kinematic_shared_physics_world_ids = reader.take_kinematic_shared_physics_world_ids();
+            let model_initial_world_matrix = *reader.model_initial_world_matrix();
+            let disable_offset_for_constraint_frame = reader.disable_offset_for_constraint_frame();
+            if let Some(mut joint_reader) = reader.next()? {
+                joint_reader.enumerate(|_, joint| joints.push(joint))?;
+            }
+            builder.set_physics(PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+                physics_world_id,
+                kinematic_shared_physics_world_ids,
+                model_initial_world_matrix,
+                disable_offset_for_constraint_frame,
+                rigid_bodies,
+                joints,
+                ragdoll,
+                ccd,
+            }));
+        }
+
+        Ok(builder)
+    }
+
+    fn round_trip(builder: &MetadataBuilder) -> MetadataBuilder {
+        read_back(&builder.build().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn read_back_metadata_is_rebuilt_identically() {
+        let mut builder = MetadataBuilder::new();
+        add_bones(&mut builder);
+        add_morphs(&mut builder);
+        builder.set_physics(physics());
+
+        let bytes = builder.build().unwrap();
+        assert_eq!(round_trip(&builder).build().unwrap(), bytes);
+    }
+
+    #[test]
+    fn bones_round_trip() {
+        let mut builder = MetadataBuilder::new();
+        add_bones(&mut builder);
+
+        let bones = round_trip(&builder).bones;
+        assert_eq!(bones.len(), 4);
+
+        assert_eq!(bones[0].parent_bone_index, -1);
+        assert_eq!(bones[0].flag, BoneFlag::IsRotatable as u16 | BoneFlag::IsMovable as u16);
+        assert_eq!(bones[1].rest_position, Vec3A::new(0.0, 1.0, 0.0));
+        assert_eq!(bones[1].absolute_inverse_bind_matrix, Mat4::from_translation(glam::Vec3::new(0.0, -1.0, 0.5)));
+        assert_eq!(bones[1].transform_order, 1);
+
+        assert_eq!(bones[1].flag, BoneFlag::IsRotatable as u16 | BoneFlag::IsIkEnabled as u16);
+        assert!(bones[1].axis_limit.is_none());
+        let ik = bones[1].ik.as_ref().expect("ik is lost");
+        assert_eq!((ik.target, ik.iteration, ik.rotation_constraint), (2, 40, 2.0));
+        assert_eq!(ik.links.len(), 2);
+        let limits = ik.links[0].limits.as_ref().expect("ik link limits are lost");
+        assert_eq!(limits.minimum_angle, Vec3A::new(-3.0, 0.0, 0.0));
+        assert_eq!(limits.maximum_angle, Vec3A::new(-0.01, 0.0, 0.0));
+        assert_eq!(ik.links[1].target, 0);
+        assert!(ik.links[1].limits.is_none());
+
+        let append_transform = bones[2].append_transform.as_ref().expect("append transform is lost");
+        assert_eq!((append_transform.parent_index, append_transform.ratio), (0, -0.5));
+        assert_eq!(bones[2].axis_limit, Some(Vec3A::Z));
+        assert_ne!(bones[2].flag & BoneFlag::HasAxisLimit as u16, 0);
+
+        assert!(bones[3].append_transform.is_none());
+        assert_eq!(bones[3].flag, 0);
+    }
+
+    #[test]
+    fn morphs_round_trip() {
+        let mut builder = MetadataBuilder::new();
+        add_morphs(&mut builder);
+
+        let morphs = round_trip(&builder).morphs;
+        assert_eq!(morphs.len(), 3);
+
+        let MorphMetadata::Bone(bone_morph) = &morphs[0] else {
+            panic!("bone morph is lost");
+        };
+        assert_eq!(bone_morph.indices, vec![0, 2]);
+        assert_eq!(bone_morph.positions, vec![Vec3A::X, Vec3A::Y]);
+        assert_eq!(bone_morph.rotations, vec![Quat::IDENTITY, Quat::from_rotation_z(1.0)]);
+
+        let MorphMetadata::Group(group_morph) = &morphs[1] else {
+            panic!("group morph is lost");
+        };
+        assert_eq!(group_morph.indices, vec![0, -1]);
+        assert_eq!(group_morph.ratios, vec![0.5, 1.0]);
+
+        assert!(matches!(&morphs[2], MorphMetadata::Group(morph) if morph.indices.is_empty() && morph.ratios.is_empty()));
+    }
+
+    #[test]
+    fn rigid_bodies_round_trip() {
+        let mut builder = MetadataBuilder::new();
+        builder.set_physics(physics());
+
+        let physics = round_trip(&builder).physics;
+
+        #[cfg(not(feature = "physics"))]
+        {
+            let PhysicsMetadata::StripedRigidbodies(rigid_bodies) = physics else {
+                panic!("rigid bodies are lost");
+            };
+            let fields = rigid_bodies.iter().map(|rigid_body| (rigid_body.bone_index, rigid_body.physics_mode)).collect::<Vec<_>>();
+            assert_eq!(fields, vec![(1, 0), (-1, 2)]);
+        }
+
+        #[cfg(feature = "physics")]
+        {
+            let PhysicsMetadata::FullPhysics(physics) = physics else {
+                panic!("physics is lost");
+            };
+            assert_eq!(physics.physics_world_id, 7);
+            assert_eq!(physics.kinematic_shared_physics_world_ids, vec![1, 2]);
+            assert_eq!(physics.model_initial_world_matrix, Mat4::from_translation(glam::Vec3::new(1.0, 2.0, 3.0)));
+            assert!(physics.disable_offset_for_constraint_frame);
+
+            assert_eq!(physics.rigid_bodies.len(), 2);
+            let rigid_body = &physics.rigid_bodies[1];
+            assert_eq!((rigid_body.bone_index, rigid_body.physics_mode), (-1, 2));
+            assert_eq!((rigid_body.collision_group, rigid_body.collision_mask), (3, 0x00FF));
+            assert_eq!(rigid_body.shape_type, RigidBodyShapeType::Box as u8);
+            assert_eq!(rigid_body.shape_size, Vec4::new(1.0, 2.0, 3.0, 0.0));
+            assert_eq!(rigid_body.shape_position, Vec3A::new(0.5, 1.5, 2.5));
+            assert_eq!(rigid_body.shape_rotation, Vec3A::new(0.1, 0.2, 0.3));
+            assert_eq!(
+                (rigid_body.mass, rigid_body.linear_damping, rigid_body.angular_damping, rigid_body.repulsion, rigid_body.friction),
+                (2.0, 0.25, 0.75, 0.125, 0.5)
+            );
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    #[test]
+    fn joints_round_trip() {
+        let mut builder = MetadataBuilder::new();
+        builder.set_physics(physics());
+
+        let PhysicsMetadata::FullPhysics(physics) = round_trip(&builder).physics else {
+            panic!("physics is lost");
+        };
+
+        assert_eq!(physics.joints.len(), 2);
+        let joint = &physics.joints[1];
+        assert_eq!((joint.kind, joint.rigidbody_index_a, joint.rigidbody_index_b), (0, 0, 1));
+        assert_eq!(joint.position, Vec3A::new(1.0, 2.0, 3.0));
+        assert_eq!(joint.rotation, Vec3A::new(0.1, 0.2, 0.3));
+        assert_eq!((joint.position_min, joint.position_max), (Vec3A::splat(-1.0), Vec3A::splat(1.0)));
+        assert_eq!((joint.rotation_min, joint.rotation_max), (Vec3A::splat(-0.5), Vec3A::splat(0.5)));
+        assert_eq!((joint.spring_position, joint.spring_rotation), (Vec3A::splat(10.0), Vec3A::splat(20.0)));
+    }
+
+    #[cfg(feature = "physics")]
+    #[test]
+    fn extension_chunks_round_trip() {
+        let PhysicsMetadata::FullPhysics(mut physics) = physics() else {
+            unreachable!();
+        };
+        physics.ragdoll = Some(RagdollMetadata {
+            default_mass: 1.5,
+            angular_lower_limit: Vec3A::splat(-1.0),
+            angular_upper_limit: Vec3A::splat(1.0),
+            radius_scale: 0.3,
+            min_bone_length: 0.5,
+        });
+        physics.ccd = Some(CcdMetadata {
+            motion_threshold_scale: 0.5,
+            swept_sphere_radius_scale: 0.25,
+        });
+        let mut builder = MetadataBuilder::new();
+        builder.set_physics(PhysicsMetadata::FullPhysics(physics));
+
+        let PhysicsMetadata::FullPhysics(physics) = round_trip(&builder).physics else {
+            panic!("physics is lost");
+        };
+
+        let ragdoll = physics.ragdoll.expect("ragdoll is lost");
+        assert_eq!(ragdoll.default_mass, 1.5);
+        assert_eq!((ragdoll.angular_lower_limit, ragdoll.angular_upper_limit), (Vec3A::splat(-1.0), Vec3A::splat(1.0)));
+        assert_eq!((ragdoll.radius_scale, ragdoll.min_bone_length), (0.3, 0.5));
+
+        let ccd = physics.ccd.expect("ccd is lost");
+        assert_eq!((ccd.motion_threshold_scale, ccd.swept_sphere_radius_scale), (0.5, 0.25));
+    }
+
+    #[test]
+    fn mismatched_morph_arrays_are_rejected() {
+        let mut builder = MetadataBuilder::new();
+        builder.add_morph(MorphMetadata::Group(GroupMorphMetadata {
+            indices: vec![0],
+            ratios: vec![1.0],
+        }));
+        builder.add_morph(MorphMetadata::Bone(BoneMorphMetadata {
+            indices: vec![0, 1],
+            positions: vec![Vec3A::X, Vec3A::Y],
+            rotations: vec![Quat::IDENTITY],
+        }));
+        assert!(matches!(builder.build(), Err(LoaderError::MorphElementCountMismatch(1))));
+
+        let mut builder = MetadataBuilder::new();
+        builder.add_morph(MorphMetadata::Group(GroupMorphMetadata {
+            indices: vec![0, 1],
+            ratios: vec![1.0],
+        }));
+        assert!(matches!(builder.build(), Err(LoaderError::MorphElementCountMismatch(0))));
+    }
+}
@@ -0,0 +1,84 @@
+use glam::{Mat4, Quat, Vec3A};
+
+use crate::mmd_model_metadata::{METADATA_MAGIC, METADATA_VERSION, MetadataSectionKind};
+
+/// Serializer for the format read by `MetadataBuffer`
+///
+/// Header and section table are written by `finish`
+pub(crate) struct MetadataSerializer {
+    bytes: Vec<u8>,
+    sections: Vec<(MetadataSectionKind, usize)>,
+}
+
+impl MetadataSerializer {
+    pub(crate) fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// Start a new section, the previous section ends at the current offset
+    pub(crate) fn begin_section(&mut self, kind: MetadataSectionKind) {
+        self.sections.push((kind, self.bytes.len()));
+    }
+
+    pub(crate) fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn write_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_i32(&mut self, value: i32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_padding(&mut self, size: usize) {
+        self.bytes.resize(self.bytes.len() + size, 0);
+    }
+
+    pub(crate) fn write_vector(&mut self, value: Vec3A) {
+        for component in value.to_array() {
+            self.write_f32(component);
+        }
+    }
+
+    pub(crate) fn write_quaternion(&mut self, value: Quat) {
+        for component in value.to_array() {
+            self.write_f32(component);
+        }
+    }
+
+    pub(crate) fn write_matrix(&mut self, value: &Mat4) {
+        for component in value.to_cols_array() {
+            self.write_f32(component);
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        let header_size = 4 + 4 + 4 + (4 + 4 + 4) * self.sections.len();
+
+        let mut bytes = Vec::with_capacity(header_size + self.bytes.len());
+        bytes.extend_from_slice(&METADATA_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&METADATA_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.sections.len() as u32).to_le_bytes());
+        for (i, (kind, start)) in self.sections.iter().enumerate() {
+            let end = self.sections.get(i + 1).map_or(self.bytes.len(), |(_, next_start)| *next_start);
+            bytes.extend_from_slice(&(*kind as u32).to_le_bytes()); // kind
+            bytes.extend_from_slice(&((header_size + start) as u32).to_le_bytes()); // offset
+            bytes.extend_from_slice(&((end - start) as u32).to_le_bytes()); // size
+        }
+        bytes.extend_from_slice(&self.bytes);
+        bytes
+    }
+}
@@ -0,0 +1,44 @@
+pub(crate) mod bpmx_reader;
+mod data_deserializer;
+pub(crate) mod metadata_builder;
+mod metadata_serializer;
+pub(crate) mod pmd_reader;
+pub(crate) mod pmx_metadata_encoder;
+pub(crate) mod pmx_object;
+pub(crate) mod pmx_reader;
+
+#[derive(Debug)]
+pub(crate) enum LoaderError {
+    UnexpectedEnd {
+        offset: usize,
+        size: usize,
+    },
+    InvalidSignature,
+    UnsupportedVersion([u8; 3]),
+    UnsupportedPmxVersion(f32),
+    InvalidGlobalsCount(u8),
+    InvalidIndexSize(u8),
+    InvalidTextLength(i32),
+    InvalidBoneWeightType(u8),
+    InvalidMorphKind(u8),
+    InvalidDisplayFrameKind(u8),
+    MorphElementCountMismatch(usize),
+}
+
+impl std::fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::UnexpectedEnd { offset, size } => write!(f, "Unexpected end of data: tried to read {} bytes at offset {}", size, offset),
+            LoaderError::InvalidSignature => write!(f, "Invalid file signature"),
+            LoaderError::UnsupportedVersion(version) => write!(f, "Unsupported version: {}.{}.{}", version[0], version[1], version[2]),
+            LoaderError::UnsupportedPmxVersion(version) => write!(f, "Unsupported PMX version: {}", version),
+            LoaderError::InvalidGlobalsCount(count) => write!(f, "Invalid globals count: {}", count),
+            LoaderError::InvalidIndexSize(size) => write!(f, "Invalid index size: {}", size),
+            LoaderError::InvalidTextLength(length) => write!(f, "Invalid text length: {}", length),
+            LoaderError::InvalidBoneWeightType(kind) => write!(f, "Invalid bone weight type: {}", kind),
+            LoaderError::InvalidMorphKind(kind) => write!(f, "Invalid morph kind: {}", kind),
+            LoaderError::InvalidDisplayFrameKind(kind) => write!(f, "Invalid display frame kind: {}", kind),
+            LoaderError::MorphElementCountMismatch(index) => write!(f, "Element arrays of morph {} have different lengths", index),
+        }
+    }
+}
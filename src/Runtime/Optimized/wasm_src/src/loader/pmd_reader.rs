@@ -0,0 +1,923 @@
+use glam::Vec3A;
+
+use crate::mmd_model_metadata::BoneFlag;
+
+use super::LoaderError;
+use super::data_deserializer::DataDeserializer;
+use super::pmx_object::{PmxAppendTransform, PmxBone, PmxIk, PmxIkLink, PmxJoint, PmxMorph, PmxObject, PmxRigidBody};
+
+enum PmdBoneType {
+    // Rotate = 0,
+    RotateMove = 1,
+    Ik = 2,
+    // Unknown = 3,
+    // IkLink = 4,
+    RotateEffect = 5,
+    // IkTo = 6,
+    // Invisible = 7,
+    Twist = 8,
+    // RotateRatio = 9,
+}
+
+struct PmdBone<'a> {
+    name: &'a [u8],
+    parent_bone_index: i32,
+    tail_index: i32,
+    kind: u8,
+    ik_index: i32,
+    position: Vec3A,
+}
+
+struct PmdIk {
+    bone_index: i32,
+    target_index: i32,
+    iteration: i32,
+    rotation_constraint: f32,
+    links: Vec<i32>,
+}
+
+/// from pmx editor IK制限角.txt, names are encoded in Shift-JIS
+///
+/// format: name, minX, maxX, minY, maxY, minZ, maxZ
+const IK_ANGLE_LIMIT_TABLE: [(&[u8], [f32; 6]); 2] = [
+    (b"\x8d\xb6\x82\xd0\x82\xb4", [-180.0, -0.5, 0.0, 0.0, 0.0, 0.0]), // 左ひざ
+    (b"\x89\x45\x82\xd0\x82\xb4", [-180.0, -0.5, 0.0, 0.0, 0.0, 0.0]), // 右ひざ
+];
+
+pub(crate) struct PmdReader<'a> {
+    deserializer: DataDeserializer<'a>,
+}
+
+impl<'a> PmdReader<'a> {
+    /// Parse PMD file and convert it to `PmxObject` with the same semantics as `PmdReader.ParseAsync`
+    pub(crate) fn read(bytes: &'a [u8]) -> Result<PmxObject, LoaderError> {
+        let mut reader = Self {
+            deserializer: DataDeserializer::new(bytes),
+        };
+
+        reader.read_header()?;
+        reader.skip_vertices()?;
+        reader.skip_indices()?;
+        reader.skip_materials()?;
+        let bones = reader.read_bones()?;
+        let iks = reader.read_iks()?;
+        let morph_count = reader.skip_morphs()?;
+        let bone_frame_count = reader.skip_display_frames()?;
+
+        let mut rigid_bodies = Vec::new();
+        let mut joints = Vec::new();
+        if reader.deserializer.bytes_available() != 0 {
+            let has_english_names = reader.deserializer.read::<u8>()? != 0;
+            if has_english_names {
+                reader.deserializer.skip(
+                    20 + // english model name
+                    256 + // english comment
+                    20 * bones.len() +
+                    20 * morph_count +
+                    50 * bone_frame_count
+                )?;
+            }
+            reader.deserializer.skip(100 * 10)?; // toon textures
+
+            if reader.deserializer.bytes_available() != 0 {
+                rigid_bodies = reader.read_rigid_bodies()?;
+                joints = reader.read_joints()?;
+            }
+        }
+
+        let pmx_bones = Self::convert_bones(&bones, &iks, &mut rigid_bodies);
+        Self::normalize_rigid_body_positions(&mut rigid_bodies, &pmx_bones);
+
+        Ok(PmxObject {
+            bones: pmx_bones,
+            // pmd only has vertex morphs
+            morphs: (0..morph_count).map(|_| PmxMorph::Other).collect(),
+            rigid_bodies,
+            joints,
+        })
+    }
+
+    fn read_name(&mut self, size: usize) -> Result<&'a [u8], LoaderError> {
+        let bytes = self.deserializer.read_bytes(size)?;
+        let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+        Ok(&bytes[..end])
+    }
+
+    /// Shift-JIS names are only compared with each other, so each byte is mapped to a char losslessly
+    fn name_to_string(name: &[u8]) -> String {
+        name.iter().map(|&byte| byte as char).collect()
+    }
+
+    fn read_count(&mut self, element_size: usize) -> Result<usize, LoaderError> {
+        let count = self.deserializer.read::<u32>()? as usize;
+        self.deserializer.check_array(element_size, count)?;
+        Ok(count)
+    }
+
+    fn read_header(&mut self) -> Result<(), LoaderError> {
+        let signature = self.deserializer.read_bytes(3)?;
+        if signature != b"Pmd" {
+            return Err(LoaderError::InvalidSignature);
+        }
+        self.deserializer.skip(
+            4 + // version (f32)
+            20 + // model name
+            256 // comment
+        )
+    }
+
+    fn skip_vertices(&mut self) -> Result<(), LoaderError> {
+        let vertex_size =
+            4 * 3 + // position
+            4 * 3 + // normal
+            4 * 2 + // uv
+            2 * 2 + // bone indices
+            1 + // bone weight
+            1; // edge flag
+        let count = self.read_count(vertex_size)?;
+        self.deserializer.skip(vertex_size * count)
+    }
+
+    fn skip_indices(&mut self) -> Result<(), LoaderError> {
+        let count = self.read_count(2)?;
+        self.deserializer.skip(2 * count)
+    }
+
+    fn skip_materials(&mut self) -> Result<(), LoaderError> {
+        let material_size =
+            4 * 4 + // diffuse
+            4 + // shininess
+            4 * 3 + // specular
+            4 * 3 + // ambient
+            1 + // toon texture index
+            1 + // edge flag
+            4 + // index count
+            20; // texture path
+        let count = self.read_count(material_size)?;
+        self.deserializer.skip(material_size * count)
+    }
+
+    fn read_bones(&mut self) -> Result<Vec<PmdBone<'a>>, LoaderError> {
+        let count = self.deserializer.read::<u16>()? as usize;
+        self.deserializer.check_array(20 + 2 + 2 + 1 + 2 + 4 * 3, count)?;
+
+        let mut bones = Vec::with_capacity(count);
+        for _ in 0..count {
+            bones.push(PmdBone {
+                name: self.read_name(20)?,
+                parent_bone_index: self.deserializer.read::<i16>()? as i32,
+                tail_index: self.deserializer.read::<i16>()? as i32,
+                kind: self.deserializer.read::<u8>()?,
+                ik_index: self.deserializer.read::<i16>()? as i32,
+                position: self.deserializer.read_vector()?,
+            });
+        }
+        Ok(bones)
+    }
+
+    fn read_iks(&mut self) -> Result<Vec<PmdIk>, LoaderError> {
+        let count = self.deserializer.read::<u16>()? as usize;
+        self.deserializer.check_array(2 + 2 + 1 + 2 + 4, count)?;
+
+        let mut iks = Vec::with_capacity(count);
+        for _ in 0..count {
+            let bone_index = self.deserializer.read::<u16>()? as i32;
+            let target_index = self.deserializer.read::<u16>()? as i32;
+            let link_count = self.deserializer.read::<u8>()?;
+            let iteration = self.deserializer.read::<u16>()? as i32;
+            let rotation_constraint = self.deserializer.read::<f32>()?;
+            let mut links = Vec::with_capacity(link_count as usize);
+            for _ in 0..link_count {
+                links.push(self.deserializer.read::<u16>()? as i32);
+            }
+            iks.push(PmdIk {
+                bone_index,
+                target_index,
+                iteration,
+                rotation_constraint,
+                links,
+            });
+        }
+        Ok(iks)
+    }
+
+    /// Returns morph count excluding the base morph
+    fn skip_morphs(&mut self) -> Result<usize, LoaderError> {
+        let count = self.deserializer.read::<u16>()? as usize;
+        for _ in 0..count {
+            self.deserializer.skip(20)?; // name
+            let offset_count = self.deserializer.read::<u32>()? as usize;
+            self.deserializer.skip(1)?; // category
+            self.deserializer.check_array(4 + 4 * 3, offset_count)?;
+            self.deserializer.skip((4 + 4 * 3) * offset_count)?;
+        }
+        Ok(count.saturating_sub(1))
+    }
+
+    /// Returns bone display frame count
+    fn skip_display_frames(&mut self) -> Result<usize, LoaderError> {
+        let morph_frame_count = self.deserializer.read::<u8>()? as usize;
+        self.deserializer.skip(2 * morph_frame_count)?;
+
+        let bone_frame_count = self.deserializer.read::<u8>()? as usize;
+        self.deserializer.skip(50 * bone_frame_count)?;
+
+        let frame_bone_count = self.read_count(2 + 1)?;
+        self.deserializer.skip((2 + 1) * frame_bone_count)?;
+
+        Ok(bone_frame_count)
+    }
+
+    fn read_rigid_bodies(&mut self) -> Result<Vec<PmxRigidBody>, LoaderError> {
+        let count = self.read_count(20 + 2 + 1 + 2 + 1 + 4 * 3 * 3 + 4 * 5 + 1)?;
+
+        let mut rigid_bodies = Vec::with_capacity(count);
+        for _ in 0..count {
+            let name = Self::name_to_string(self.read_name(20)?);
+            rigid_bodies.push(PmxRigidBody {
+                name,
+                bone_index: self.deserializer.read::<i16>()? as i32,
+                collision_group: self.deserializer.read::<u8>()?,
+                collision_mask: self.deserializer.read::<u16>()?,
+                shape_type: self.deserializer.read::<u8>()?,
+                shape_size: self.deserializer.read_vector()?,
+                shape_position: self.deserializer.read_vector()?,
+                shape_rotation: self.deserializer.read_vector()?,
+                mass: self.deserializer.read::<f32>()?,
+                linear_damping: self.deserializer.read::<f32>()?,
+                angular_damping: self.deserializer.read::<f32>()?,
+                repulsion: self.deserializer.read::<f32>()?,
+                friction: self.deserializer.read::<f32>()?,
+                physics_mode: self.deserializer.read::<u8>()?,
+            });
+        }
+        Ok(rigid_bodies)
+    }
+
+    fn read_joints(&mut self) -> Result<Vec<PmxJoint>, LoaderError> {
+        let count = self.read_count(20 + 4 + 4 + 4 * 3 * 8)?;
+
+        let mut joints = Vec::with_capacity(count);
+        for _ in 0..count {
+            self.deserializer.skip(20)?; // name
+            joints.push(PmxJoint {
+                kind: 0, // spring 6dof
+                rigidbody_index_a: self.deserializer.read::<i32>()?,
+                rigidbody_index_b: self.deserializer.read::<i32>()?,
+                position: self.deserializer.read_vector()?,
+                rotation: self.deserializer.read_vector()?,
+                position_min: self.deserializer.read_vector()?,
+                position_max: self.deserializer.read_vector()?,
+                rotation_min: self.deserializer.read_vector()?,
+                rotation_max: self.deserializer.read_vector()?,
+                spring_position: self.deserializer.read_vector()?,
+                spring_rotation: self.deserializer.read_vector()?,
+            });
+        }
+        Ok(joints)
+    }
+
+    fn convert_bones(bones: &[PmdBone], iks: &[PmdIk], rigid_bodies: &mut [PmxRigidBody]) -> Vec<PmxBone> {
+        let is_pmd_bone_index = |index: i32| 0 <= index && index < bones.len() as i32;
+
+        let mut final_bones = Vec::with_capacity(bones.len());
+        for (i, bone) in bones.iter().enumerate() {
+            let mut flag = BoneFlag::UseBoneIndexAsTailPosition as u16 |
+                BoneFlag::IsRotatable as u16 |
+                BoneFlag::IsVisible as u16 |
+                BoneFlag::IsControllable as u16;
+            let mut transform_order = 0;
+            let mut append_transform = None;
+
+            let mut is_ik_bone = iks.iter().any(|ik| ik.bone_index == i as i32);
+
+            if bone.kind == PmdBoneType::RotateMove as u8 {
+                flag |= BoneFlag::IsMovable as u16;
+            } else if bone.kind == PmdBoneType::Ik as u8 {
+                is_ik_bone = true;
+            } else if bone.kind == PmdBoneType::RotateEffect as u8 {
+                flag |= BoneFlag::HasAppendRotate as u16;
+                flag &= !(BoneFlag::UseBoneIndexAsTailPosition as u16 | BoneFlag::IsVisible as u16);
+                append_transform = Some(PmxAppendTransform {
+                    parent_index: bone.tail_index,
+                    ratio: bone.ik_index as f32 * 0.01,
+                });
+            }
+
+            if is_ik_bone {
+                flag |= BoneFlag::IsMovable as u16 | BoneFlag::IsIkEnabled as u16;
+                transform_order = 1;
+            }
+
+            final_bones.push(PmxBone {
+                name: Self::name_to_string(bone.name),
+                position: bone.position,
+                parent_bone_index: bone.parent_bone_index,
+                transform_order,
+                flag,
+                append_transform,
+                axis_limit: None,
+                ik: None,
+            });
+        }
+
+        // normalize twist bones
+        for (bone, pmx_bone) in bones.iter().zip(final_bones.iter_mut()) {
+            if bone.kind == PmdBoneType::Twist as u8 {
+                let tail_bone = if is_pmd_bone_index(bone.tail_index) {
+                    &bones[bone.tail_index as usize]
+                } else {
+                    &bones[0]
+                };
+                pmx_bone.axis_limit = Some((tail_bone.position - bone.position).normalize_or_zero());
+                pmx_bone.flag &= !(BoneFlag::UseBoneIndexAsTailPosition as u16);
+            }
+        }
+
+        // normalize ik bones
+        let mut ik_chain_bones = Vec::new();
+        for (bone_index, pmx_bone) in final_bones.iter_mut().enumerate() {
+            if pmx_bone.flag & BoneFlag::IsIkEnabled as u16 == 0 {
+                continue;
+            }
+
+            let mut is_first = true;
+            for ik in iks.iter().filter(|ik| ik.bone_index == bone_index as i32) {
+                let links = ik.links
+                    .iter()
+                    .filter(|&&link| is_pmd_bone_index(link))
+                    .map(|&link| {
+                        let limitation = IK_ANGLE_LIMIT_TABLE
+                            .iter()
+                            .find(|(name, _)| *name == bones[link as usize].name)
+                            .map(|(_, limitation)| (
+                                Vec3A::new(limitation[0], limitation[2], limitation[4]),
+                                Vec3A::new(limitation[1], limitation[3], limitation[5]),
+                            ));
+                        PmxIkLink {
+                            target: link,
+                            limitation,
+                        }
+                    })
+                    .collect();
+                let pmx_ik = PmxIk {
+                    target: ik.target_index,
+                    iteration: ik.iteration,
+                    rotation_constraint: ik.rotation_constraint * 4.0,
+                    links,
+                };
+
+                if is_first {
+                    pmx_bone.ik = Some(pmx_ik);
+                    is_first = false;
+                } else {
+                    // additional ik of the same bone is solved by a hidden child bone
+                    ik_chain_bones.push(PmxBone {
+                        name: format!("{}+", pmx_bone.name),
+                        position: pmx_bone.position,
+                        parent_bone_index: bone_index as i32,
+                        transform_order: pmx_bone.transform_order,
+                        flag: pmx_bone.flag & !(BoneFlag::IsVisible as u16 | BoneFlag::UseBoneIndexAsTailPosition as u16),
+                        append_transform: pmx_bone.append_transform.as_ref().map(|append_transform| PmxAppendTransform {
+                            parent_index: append_transform.parent_index,
+                            ratio: append_transform.ratio,
+                        }),
+                        axis_limit: pmx_bone.axis_limit,
+                        ik: Some(pmx_ik),
+                    });
+                }
+            }
+        }
+        final_bones.extend(ik_chain_bones);
+
+        Self::normalize_ik_order(&mut final_bones, bones.len(), iks, rigid_bodies);
+        Self::normalize_transform_order(&mut final_bones);
+
+        final_bones
+    }
+
+    /// PMD evaluates iks in the order of the ik list, PMX in the order of the bones
+    ///
+    /// Bones are moved to keep the PMD ik order, same as `PmdReader._ConvertBones`
+    fn normalize_ik_order(final_bones: &mut Vec<PmxBone>, pmd_bone_count: usize, iks: &[PmdIk], rigid_bodies: &mut [PmxRigidBody]) {
+        // (pmx bone index, pmd ik index)
+        let mut ik_index_map = Vec::new();
+        for (i, pmx_bone) in final_bones.iter().enumerate().take(pmd_bone_count) {
+            if pmx_bone.flag & BoneFlag::IsIkEnabled as u16 == 0 {
+                continue;
+            }
+            if let Some(j) = iks.iter().position(|ik| ik.bone_index == i as i32) {
+                ik_index_map.push((i, j));
+            }
+        }
+
+        if ik_index_map.windows(2).all(|pair| pair[0].1 <= pair[1].1) {
+            return;
+        }
+        ik_index_map.sort_by_key(|&(_, ik_index)| ik_index);
+
+        // bones are identified by their index before reordering
+        let mut invalid_order_bone_map = vec![None; ik_index_map.len()];
+        for i in 1..ik_index_map.len() {
+            if ik_index_map[i].0 < ik_index_map[i - 1].0 || invalid_order_bone_map[i - 1].is_some() {
+                invalid_order_bone_map[i] = Some(ik_index_map[i - 1].0);
+            }
+        }
+        if invalid_order_bone_map.iter().all(Option::is_none) {
+            return;
+        }
+        let is_invalid_order_anchor = |bone: usize| invalid_order_bone_map.contains(&Some(bone));
+
+        let mut order = (0..final_bones.len()).collect::<Vec<_>>();
+        for _ in 0..ik_index_map.len() + 2 {
+            for j in 1..ik_index_map.len() {
+                let Some(anchor) = invalid_order_bone_map[j] else {
+                    continue;
+                };
+                let bone = ik_index_map[j].0;
+                if is_invalid_order_anchor(bone) {
+                    continue;
+                }
+                let remove_index = order.iter().position(|&b| b == bone).unwrap();
+                order.remove(remove_index);
+                let insert_index = order.iter().position(|&b| b == anchor).unwrap() + 1;
+                order.insert(insert_index, bone);
+            }
+        }
+
+        let mut new_index_map = vec![0; order.len()];
+        for (new_index, &old_index) in order.iter().enumerate() {
+            new_index_map[old_index] = new_index as i32;
+        }
+        let remap = |index: &mut i32| {
+            if 0 <= *index && (*index as usize) < new_index_map.len() {
+                *index = new_index_map[*index as usize];
+            }
+        };
+
+        let mut old_bones = std::mem::take(final_bones).into_iter().map(Some).collect::<Vec<_>>();
+        for &old_index in &order {
+            final_bones.push(old_bones[old_index].take().unwrap());
+        }
+        for pmx_bone in final_bones.iter_mut() {
+            remap(&mut pmx_bone.parent_bone_index);
+            if let Some(append_transform) = &mut pmx_bone.append_transform {
+                remap(&mut append_transform.parent_index);
+            }
+            if let Some(ik) = &mut pmx_bone.ik {
+                remap(&mut ik.target);
+                for link in &mut ik.links {
+                    remap(&mut link.target);
+                }
+            }
+        }
+        for rigid_body in rigid_bodies.iter_mut() {
+            remap(&mut rigid_body.bone_index);
+        }
+    }
+
+    /// Child bones must not be transformed before their ancestors
+    fn normalize_transform_order(final_bones: &mut [PmxBone]) {
+        let bone_count = final_bones.len();
+        for _ in 0..bone_count {
+            let mut order_updated = false;
+            for i in 0..bone_count {
+                let mut transform_order = final_bones[i].transform_order;
+                let mut ancestor_index = final_bones[i].parent_bone_index;
+                // bounded walk, looped parenting is handled by the metadata encoder
+                for _ in 0..bone_count {
+                    if ancestor_index < 0 || bone_count as i32 <= ancestor_index || ancestor_index == i as i32 {
+                        break;
+                    }
+                    let ancestor = &final_bones[ancestor_index as usize];
+                    if transform_order < ancestor.transform_order {
+                        transform_order = ancestor.transform_order;
+                        order_updated = true;
+                    }
+                    ancestor_index = ancestor.parent_bone_index;
+                }
+                final_bones[i].transform_order = transform_order;
+            }
+            if !order_updated {
+                break;
+            }
+        }
+    }
+
+    /// PMD rigid body position is relative to the bone
+    fn normalize_rigid_body_positions(rigid_bodies: &mut [PmxRigidBody], bones: &[PmxBone]) {
+        for rigid_body in rigid_bodies {
+            let bone_index = rigid_body.bone_index.max(0) as usize;
+            if let Some(bone) = bones.get(bone_index) {
+                rigid_body.shape_position += bone.position;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct PmdWriter {
+        bytes: Vec<u8>,
+    }
+
+    impl PmdWriter {
+        fn u8(&mut self, value: u8) {
+            self.bytes.push(value);
+        }
+
+        fn u16(&mut self, value: u16) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn i16(&mut self, value: i16) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn u32(&mut self, value: u32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn i32(&mut self, value: i32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn f32(&mut self, value: f32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn vector(&mut self, value: Vec3A) {
+            for component in value.to_array() {
+                self.f32(component);
+            }
+        }
+
+        fn zeros(&mut self, size: usize) {
+            self.bytes.resize(self.bytes.len() + size, 0);
+        }
+
+        /// Fixed size Shift-JIS text padded with zeros
+        fn name(&mut self, name: &[u8], size: usize) {
+            self.bytes.extend_from_slice(name);
+            self.zeros(size - name.len());
+        }
+    }
+
+    struct PmdBoneFixture {
+        name: &'static [u8], // Shift-JIS
+        parent_bone_index: i16,
+        tail_index: i16,
+        kind: u8,
+        ik_index: i16,
+        position: Vec3A,
+    }
+
+    struct PmdIkFixture {
+        bone_index: u16,
+        target_index: u16,
+        iteration: u16,
+        rotation_constraint: f32,
+        links: Vec<u16>,
+    }
+
+    /// PMD model, rigid body positions are relative to the bone as written in the file
+    struct PmdModel {
+        bones: Vec<PmdBoneFixture>,
+        iks: Vec<PmdIkFixture>,
+        has_english_names: bool,
+        has_physics: bool,
+        rigid_bodies: Vec<PmxRigidBody>,
+        joints: Vec<PmxJoint>,
+    }
+
+    const KNEE_NAME: &[u8] = b"\x8d\xb6\x82\xd0\x82\xb4"; // 左ひざ
+
+    /// Leg with knee ik and each bone kind that is converted by the reader
+    fn sample_pmd_model() -> PmdModel {
+        let bone = |name: &'static [u8], parent_bone_index: i16, tail_index: i16, kind: u8, position: Vec3A| PmdBoneFixture {
+            name,
+            parent_bone_index,
+            tail_index,
+            kind,
+            ik_index: 0,
+            position,
+        };
+
+        let mut bones = vec![
+            bone(b"\x83\x5a\x83\x93\x83\x5e\x81\x5b", -1, 1, 1, Vec3A::new(0.0, 8.0, 0.0)), // センター, rotate move
+            bone(b"\x8d\xb6\x91\xab", 0, 2, 0, Vec3A::new(1.0, 10.0, 0.0)), // 左足
+            bone(KNEE_NAME, 1, 3, 0, Vec3A::new(1.0, 5.0, 0.2)),
+            bone(b"\x8d\xb6\x91\xab\x8e\xf1", 2, -1, 0, Vec3A::new(1.0, 1.0, 0.0)), // 左足首
+            bone(b"\x8d\xb6\x91\xab\x82\x68\x82\x6a", 0, 3, 2, Vec3A::new(1.0, 1.0, 0.0)), // 左足ＩＫ, ik
+            bone(b"twist", 1, 2, 8, Vec3A::new(1.0, 8.0, 0.0)),
+            bone(b"effect", 1, 2, 5, Vec3A::new(1.5, 5.0, 0.0)),
+        ];
+        bones[6].ik_index = 50; // append transform ratio in percent
+
+        let rigid_body = |name: &str, bone_index: i32| PmxRigidBody {
+            name: name.to_owned(),
+            bone_index,
+            collision_group: 1,
+            collision_mask: 0xFFFE,
+            shape_type: 2,
+            shape_size: Vec3A::new(0.5, 4.0, 0.0),
+            shape_position: Vec3A::new(0.0, -2.5, 0.0),
+            shape_rotation: Vec3A::new(0.0, 0.0, 0.1),
+            mass: 1.0,
+            linear_damping: 0.5,
+            angular_damping: 0.5,
+            repulsion: 0.0,
+            friction: 0.5,
+            physics_mode: 1,
+        };
+
+        PmdModel {
+            bones,
+            iks: vec![PmdIkFixture {
+                bone_index: 4,
+                target_index: 3,
+                iteration: 40,
+                rotation_constraint: 0.5,
+                links: vec![2, 1],
+            }],
+            has_english_names: true,
+            has_physics: true,
+            rigid_bodies: vec![rigid_body("leg", 1), rigid_body("root", -1)],
+            joints: vec![PmxJoint {
+                kind: 0,
+                rigidbody_index_a: 0,
+                rigidbody_index_b: 1,
+                position: Vec3A::new(1.0, 5.0, 0.0),
+                rotation: Vec3A::ZERO,
+                position_min: Vec3A::ZERO,
+                position_max: Vec3A::ZERO,
+                rotation_min: Vec3A::splat(-0.5),
+                rotation_max: Vec3A::splat(0.5),
+                spring_position: Vec3A::ZERO,
+                spring_rotation: Vec3A::splat(10.0),
+            }],
+        }
+    }
+
+    /// Write the model as PMD 1.0 with two vertex morphs (base and one offset morph)
+    fn write_pmd(pmd: &PmdModel) -> Vec<u8> {
+        let mut w = PmdWriter::default();
+
+        // header
+        w.bytes.extend_from_slice(b"Pmd");
+        w.f32(1.0);
+        w.name(b"test", 20);
+        w.name(b"", 256);
+
+        // vertices
+        w.u32(3);
+        w.zeros((4 * 3 + 4 * 3 + 4 * 2 + 2 * 2 + 1 + 1) * 3);
+
+        // indices
+        w.u32(3);
+        for i in 0..3 {
+            w.u16(i);
+        }
+
+        // materials
+        w.u32(1);
+        w.zeros(4 * 4 + 4 + 4 * 3 + 4 * 3 + 1 + 1);
+        w.u32(3); // index count
+        w.name(b"tex.bmp", 20);
+
+        // bones
+        w.u16(pmd.bones.len() as u16);
+        for bone in &pmd.bones {
+            w.name(bone.name, 20);
+            w.i16(bone.parent_bone_index);
+            w.i16(bone.tail_index);
+            w.u8(bone.kind);
+            w.i16(bone.ik_index);
+            w.vector(bone.position);
+        }
+
+        // iks
+        w.u16(pmd.iks.len() as u16);
+        for ik in &pmd.iks {
+            w.u16(ik.bone_index);
+            w.u16(ik.target_index);
+            w.u8(ik.links.len() as u8);
+            w.u16(ik.iteration);
+            w.f32(ik.rotation_constraint);
+            for link in &ik.links {
+                w.u16(*link);
+            }
+        }
+
+        // morphs, base morph and one offset morph
+        w.u16(2);
+        for (name, category) in [(b"base".as_slice(), 0), (b"morph".as_slice(), 1)] {
+            w.name(name, 20);
+            w.u32(2); // offset count
+            w.u8(category);
+            for i in 0..2 {
+                w.u32(i);
+                w.vector(Vec3A::ONE);
+            }
+        }
+
+        // display frames
+        w.u8(1); // morph frame count
+        w.u16(1);
+        w.u8(1); // bone frame count
+        w.name(b"frame", 50);
+        w.u32(1); // frame bone count
+        w.u16(1);
+        w.u8(1);
+
+        if !pmd.has_english_names && !pmd.has_physics {
+            return w.bytes;
+        }
+
+        w.u8(pmd.has_english_names as u8);
+        if pmd.has_english_names {
+            w.name(b"test", 20);
+            w.name(b"", 256);
+            for _ in &pmd.bones {
+                w.name(b"bone", 20);
+            }
+            w.name(b"morph", 20); // base morph has no english name
+            w.name(b"frame", 50);
+        }
+        w.zeros(100 * 10); // toon textures
+
+        if !pmd.has_physics {
+            return w.bytes;
+        }
+
+        w.u32(pmd.rigid_bodies.len() as u32);
+        for rigid_body in &pmd.rigid_bodies {
+            w.name(rigid_body.name.as_bytes(), 20);
+            w.i16(rigid_body.bone_index as i16);
+            w.u8(rigid_body.collision_group);
+            w.u16(rigid_body.collision_mask);
+            w.u8(rigid_body.shape_type);
+            w.vector(rigid_body.shape_size);
+            w.vector(rigid_body.shape_position);
+            w.vector(rigid_body.shape_rotation);
+            w.f32(rigid_body.mass);
+            w.f32(rigid_body.linear_damping);
+            w.f32(rigid_body.angular_damping);
+            w.f32(rigid_body.repulsion);
+            w.f32(rigid_body.friction);
+            w.u8(rigid_body.physics_mode);
+        }
+
+        w.u32(pmd.joints.len() as u32);
+        for joint in &pmd.joints {
+            w.name(b"joint", 20);
+            w.i32(joint.rigidbody_index_a);
+            w.i32(joint.rigidbody_index_b);
+            for vector in [
+                joint.position,
+                joint.rotation,
+                joint.position_min,
+                joint.position_max,
+                joint.rotation_min,
+                joint.rotation_max,
+                joint.spring_position,
+                joint.spring_rotation,
+            ] {
+                w.vector(vector);
+            }
+        }
+
+        w.bytes
+    }
+
+    fn read(bytes: &[u8]) -> PmxObject {
+        PmdReader::read(bytes).unwrap()
+    }
+
+    #[test]
+    fn knee_gets_ik_angle_limit() {
+        let pmx = read(&write_pmd(&sample_pmd_model()));
+
+        let ik = pmx.bones[4].ik.as_ref().expect("ik is not converted");
+        assert_eq!(ik.target, 3);
+        assert_eq!(ik.iteration, 40);
+        assert_eq!(ik.rotation_constraint, 2.0); // pmd stores a quarter of the limit
+        assert_eq!(ik.links.len(), 2);
+
+        assert_eq!(pmx.bones[ik.links[0].target as usize].name, PmdReader::name_to_string(KNEE_NAME));
+        let (minimum_angle, maximum_angle) = ik.links[0].limitation.expect("knee has no limitation");
+        assert_eq!(minimum_angle, Vec3A::new(-180.0, 0.0, 0.0));
+        assert_eq!(maximum_angle, Vec3A::new(-0.5, 0.0, 0.0));
+        assert!(ik.links[1].limitation.is_none());
+    }
+
+    #[test]
+    fn bone_kinds_are_converted() {
+        let pmx = read(&write_pmd(&sample_pmd_model()));
+        let bones = &pmx.bones;
+        assert_eq!(bones.len(), 7);
+
+        assert_ne!(bones[0].flag & BoneFlag::IsMovable as u16, 0);
+        assert_eq!(bones[1].flag & BoneFlag::IsMovable as u16, 0);
+
+        let ik_flag = BoneFlag::IsMovable as u16 | BoneFlag::IsIkEnabled as u16;
+        assert_eq!(bones[4].flag & ik_flag, ik_flag);
+        assert_eq!(bones[4].transform_order, 1);
+
+        let axis_limit = bones[5].axis_limit.expect("twist bone has no axis limit");
+        assert!(axis_limit.abs_diff_eq((bones[2].position - bones[5].position).normalize(), 1e-6));
+
+        let append_transform = bones[6].append_transform.as_ref().expect("effect bone has no append transform");
+        assert_eq!(append_transform.parent_index, 2);
+        assert_eq!(append_transform.ratio, 0.5);
+        assert_ne!(bones[6].flag & BoneFlag::HasAppendRotate as u16, 0);
+        assert_eq!(bones[6].flag & (BoneFlag::IsVisible as u16 | BoneFlag::UseBoneIndexAsTailPosition as u16), 0);
+
+        // pmd has only vertex morphs and the base morph is not a morph
+        assert_eq!(pmx.morphs.len(), 1);
+        assert!(matches!(pmx.morphs[0], PmxMorph::Other));
+    }
+
+    #[test]
+    fn rigid_body_position_is_converted_to_model_space() {
+        let pmx = read(&write_pmd(&sample_pmd_model()));
+        assert_eq!(pmx.rigid_bodies.len(), 2);
+        assert_eq!(pmx.rigid_bodies[0].shape_position, Vec3A::new(1.0, 7.5, 0.0));
+        // rigid body without bone is relative to the first bone
+        assert_eq!(pmx.rigid_bodies[1].bone_index, -1);
+        assert_eq!(pmx.rigid_bodies[1].shape_position, Vec3A::new(0.0, 5.5, 0.0));
+        assert_eq!(pmx.joints.len(), 1);
+    }
+
+    #[test]
+    fn ik_order_is_kept() {
+        let mut model = sample_pmd_model();
+        model.bones.truncate(5);
+        // second ik bone which is solved before the first one
+        model.bones.push(PmdBoneFixture {
+            name: b"ik2",
+            parent_bone_index: 0,
+            tail_index: 1,
+            kind: 2,
+            ik_index: 0,
+            position: Vec3A::new(1.0, 5.0, 0.0),
+        });
+        model.iks.insert(0, PmdIkFixture {
+            bone_index: 5,
+            target_index: 2,
+            iteration: 10,
+            rotation_constraint: 0.25,
+            links: vec![1],
+        });
+        model.rigid_bodies[0].bone_index = 4;
+
+        let pmx = read(&write_pmd(&model));
+        assert_eq!(pmx.bones[4].name, "ik2");
+        assert_eq!(pmx.bones[4].ik.as_ref().map(|ik| ik.target), Some(2));
+        assert_eq!(pmx.bones[5].ik.as_ref().map(|ik| ik.target), Some(3));
+        assert_eq!(pmx.rigid_bodies[0].bone_index, 5);
+    }
+
+    #[test]
+    fn optional_blocks_can_be_omitted() {
+        let mut model = sample_pmd_model();
+        model.has_english_names = false;
+        let pmx = read(&write_pmd(&model));
+        assert_eq!(pmx.rigid_bodies.len(), 2);
+
+        model.has_physics = false;
+        let pmx = read(&write_pmd(&model));
+        assert!(pmx.rigid_bodies.is_empty() && pmx.joints.is_empty());
+        assert_eq!(pmx.bones.len(), 7);
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let mut model = sample_pmd_model();
+        model.has_english_names = false;
+        let bytes = write_pmd(&model);
+
+        model.has_physics = false;
+        let required_length = write_pmd(&model).len();
+        let toon_end = required_length + 1 + 100 * 10;
+
+        // file can end after the display frames or after the toon textures
+        for length in (0..required_length).chain(required_length + 1..toon_end).chain(toon_end + 1..bytes.len()) {
+            assert!(
+                matches!(PmdReader::read(&bytes[..length]), Err(LoaderError::UnexpectedEnd { .. })),
+                "truncated at {} of {} bytes was accepted", length, bytes.len()
+            );
+        }
+        assert!(PmdReader::read(&bytes[..toon_end]).is_ok());
+    }
+
+    #[test]
+    fn invalid_signature_is_rejected() {
+        let mut bytes = write_pmd(&sample_pmd_model());
+        bytes[0..3].copy_from_slice(b"PMX");
+        assert!(matches!(PmdReader::read(&bytes), Err(LoaderError::InvalidSignature)));
+    }
+}
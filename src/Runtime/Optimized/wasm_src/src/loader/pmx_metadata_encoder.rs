@@ -0,0 +1,361 @@
+use glam::Mat4;
+#[cfg(feature = "physics")]
+use glam::Vec4;
+
+use crate::diagnostic::DiagnosticWriter;
+#[cfg(feature = "physics")]
+use crate::mmd_model_metadata::JointMetadata;
+use crate::mmd_model_metadata::{AppendTransformMetadata, BoneMetadata, BoneMetadataReader, BoneMorphMetadata, GroupMorphMetadata, IkChainAngleLimits, IkLinkMetadata, IkMetadata, MetadataBuffer, MetadataError, MorphMetadata, PhysicsInfoKind, RigidBodyMetadata};
+
+#[cfg(feature = "physics")]
+use super::metadata_builder::FullPhysicsMetadata;
+use super::LoaderError;
+use super::metadata_builder::{MetadataBuilder, PhysicsMetadata};
+use super::pmx_object::{PmxMorph, PmxObject};
+
+pub(crate) struct PmxMetadataEncodeOptions {
+    /// Physics creation options, rigid bodies and joints are replaced by the ones of the model
+    pub(crate) physics: PhysicsMetadata,
+}
+
+impl PmxMetadataEncodeOptions {
+    /// Read the options from the metadata which has no bones, morphs, rigid bodies and joints
+    ///
+    /// The metadata is encoded by `MmdMetadataEncoder.encodePhysicsOptions` of the TS side
+    pub(crate) fn read(buffer: MetadataBuffer) -> Result<Self, MetadataError> {
+        let reader = BoneMetadataReader::new(buffer)?;
+        let reader = reader.enumerate(|_, _| { })?;
+        #[cfg_attr(not(feature = "physics"), allow(unused_mut))]
+        let (_, mut reader) = reader.read()?;
+
+        let physics = match reader.physics_info_kind() {
+            PhysicsInfoKind::NoPhysics => PhysicsMetadata::NoPhysics,
+            PhysicsInfoKind::StripedRigidbodies => PhysicsMetadata::StripedRigidbodies(Vec::new()),
+            #[cfg(feature = "physics")]
+            PhysicsInfoKind::FullPhysics => PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+                physics_world_id: reader.physics_world_id(),
+                kinematic_shared_physics_world_ids: reader.take_kinematic_shared_physics_world_ids(),
+                model_initial_world_matrix: *reader.model_initial_world_matrix(),
+                disable_offset_for_constraint_frame: reader.disable_offset_for_constraint_frame(),
+                rigid_bodies: Vec::new(),
+                joints: Vec::new(),
+                ragdoll: reader.ragdoll()?,
+                ccd: reader.ccd()?,
+            }),
+            // MmdModel of this build only takes physics modes of the rigid bodies
+            #[cfg(not(feature = "physics"))]
+            PhysicsInfoKind::FullPhysics => PhysicsMetadata::StripedRigidbodies(Vec::new()),
+        };
+
+        Ok(Self {
+            physics,
+        })
+    }
+
+    /// Options of the physics world 0 without any optional physics features
+    #[cfg(test)]
+    pub(crate) fn default_physics() -> Self {
+        #[cfg(not(feature = "physics"))]
+        let physics = PhysicsMetadata::StripedRigidbodies(Vec::new());
+        #[cfg(feature = "physics")]
+        let physics = PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+            physics_world_id: 0,
+            kinematic_shared_physics_world_ids: Vec::new(),
+            model_initial_world_matrix: Mat4::IDENTITY,
+            disable_offset_for_constraint_frame: false,
+            rigid_bodies: Vec::new(),
+            joints: Vec::new(),
+            ragdoll: None,
+            ccd: None,
+        });
+
+        Self {
+            physics,
+        }
+    }
+}
+
+/// Native counterpart of `MmdMetadataEncoder` which encodes `PmxObject` into the runtime metadata
+pub(crate) struct PmxMetadataEncoder;
+
+impl PmxMetadataEncoder {
+    pub(crate) fn encode(pmx: &PmxObject, options: PmxMetadataEncodeOptions, diagnostic: &mut DiagnosticWriter) -> Result<Vec<u8>, LoaderError> {
+        let mut builder = MetadataBuilder::new();
+
+        Self::encode_bones(&mut builder, pmx, diagnostic);
+        Self::encode_morphs(&mut builder, pmx);
+        builder.set_physics(Self::encode_physics(pmx, options.physics));
+
+        builder.build()
+    }
+
+    fn encode_bones(builder: &mut MetadataBuilder, pmx: &PmxObject, diagnostic: &mut DiagnosticWriter) {
+        let bones = &pmx.bones;
+        let is_valid_index = |index: i32| 0 <= index && index < bones.len() as i32;
+
+        for (i, bone) in bones.iter().enumerate() {
+            // same as the skeleton construction of MmdModelLoader, looped parenting is ignored
+            let mut parent_bone_index = bone.parent_bone_index;
+            if is_valid_index(parent_bone_index) {
+                let mut ancestor_index = parent_bone_index;
+                for _ in 0..bones.len() {
+                    if ancestor_index == i as i32 {
+                        diagnostic.warning(format!("Bone loop detected. Ignore Parenting. Bone index: {}", i));
+                        parent_bone_index = -1;
+                        break;
+                    }
+                    if !is_valid_index(ancestor_index) {
+                        break;
+                    }
+                    ancestor_index = bones[ancestor_index as usize].parent_bone_index;
+                }
+            } else if parent_bone_index != -1 {
+                diagnostic.error(format!("Parent bone index is out of range. Bone index: {} Parent bone index: {}", i, parent_bone_index));
+            }
+
+            let rest_position = if is_valid_index(parent_bone_index) {
+                bone.position - bones[parent_bone_index as usize].position
+            } else {
+                bone.position
+            };
+
+            // flags of optional fields are normalized by the builder
+            builder.add_bone(BoneMetadata {
+                rest_position,
+                absolute_inverse_bind_matrix: Mat4::from_translation((-bone.position).into()),
+                parent_bone_index,
+                transform_order: bone.transform_order,
+                flag: bone.flag,
+                append_transform: bone.append_transform.as_ref().map(|append_transform| AppendTransformMetadata {
+                    parent_index: append_transform.parent_index,
+                    ratio: append_transform.ratio,
+                }),
+                axis_limit: bone.axis_limit,
+                ik: bone.ik.as_ref().map(|ik| Box::new(IkMetadata {
+                    target: ik.target,
+                    iteration: ik.iteration,
+                    rotation_constraint: ik.rotation_constraint,
+                    links: ik.links.iter().map(|link| IkLinkMetadata {
+                        target: link.target,
+                        limits: link.limitation.map(|(minimum_angle, maximum_angle)| IkChainAngleLimits {
+                            minimum_angle,
+                            maximum_angle,
+                        }),
+                    }).collect(),
+                })),
+            });
+        }
+    }
+
+    /// Index of each morph in the runtime, -1 for morphs which are not evaluated by the runtime
+    ///
+    /// Same as the wasm morph index map returned by `MmdMetadataEncoder.encode` of the TS side
+    pub(crate) fn runtime_morph_map(morphs: &[PmxMorph]) -> Vec<i32> {
+        let mut runtime_morph_map = vec![-1; morphs.len()];
+        let mut morph_count = 0;
+        for (i, morph) in morphs.iter().enumerate() {
+            if !matches!(morph, PmxMorph::Other) {
+                runtime_morph_map[i] = morph_count;
+                morph_count += 1;
+            }
+        }
+        runtime_morph_map
+    }
+
+    fn encode_morphs(builder: &mut MetadataBuilder, pmx: &PmxObject) {
+        let morphs = &pmx.morphs;
+
+        // only bone and group morphs are evaluated by the runtime, so group morph indices are remapped
+        let runtime_morph_map = Self::runtime_morph_map(morphs);
+
+        for morph in morphs {
+            match morph {
+                PmxMorph::Bone { indices, positions, rotations } => {
+                    builder.add_morph(MorphMetadata::Bone(BoneMorphMetadata {
+                        indices: indices.clone(),
+                        positions: positions.clone(),
+                        rotations: rotations.clone(),
+                    }));
+                }
+                PmxMorph::Group { indices, ratios } => {
+                    let remapped_indices = indices.iter().map(|index| {
+                        if 0 <= *index && (*index as usize) < runtime_morph_map.len() {
+                            runtime_morph_map[*index as usize]
+                        } else {
+                            -1
+                        }
+                    }).collect();
+                    builder.add_morph(MorphMetadata::Group(GroupMorphMetadata {
+                        indices: remapped_indices,
+                        ratios: ratios.clone(),
+                    }));
+                }
+                PmxMorph::Other => { }
+            }
+        }
+    }
+
+    fn rigid_body_bone_indices(pmx: &PmxObject) -> Vec<i32> {
+        let bones = &pmx.bones;
+        pmx.rigid_bodies
+            .iter()
+            .map(|rigid_body| {
+                if 0 <= rigid_body.bone_index && rigid_body.bone_index < bones.len() as i32 {
+                    rigid_body.bone_index
+                } else {
+                    // fallback to name, last bone wins like the name map of the TS encoder
+                    bones.iter().rposition(|bone| bone.name == rigid_body.name).map_or(-1, |index| index as i32)
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "physics"))]
+    fn encode_physics(pmx: &PmxObject, options: PhysicsMetadata) -> PhysicsMetadata {
+        if let PhysicsMetadata::NoPhysics = options {
+            return PhysicsMetadata::NoPhysics;
+        }
+
+        let bone_indices = Self::rigid_body_bone_indices(pmx);
+        let rigid_bodies = pmx.rigid_bodies.iter().zip(bone_indices).map(|(rigid_body, bone_index)| RigidBodyMetadata {
+            bone_index,
+            physics_mode: rigid_body.physics_mode,
+        }).collect();
+
+        PhysicsMetadata::StripedRigidbodies(rigid_bodies)
+    }
+
+    #[cfg(feature = "physics")]
+    fn encode_physics(pmx: &PmxObject, options: PhysicsMetadata) -> PhysicsMetadata {
+        if let PhysicsMetadata::NoPhysics = options {
+            return PhysicsMetadata::NoPhysics;
+        }
+
+        let bone_indices = Self::rigid_body_bone_indices(pmx);
+        let rigid_bodies = pmx.rigid_bodies.iter().zip(bone_indices).map(|(rigid_body, bone_index)| RigidBodyMetadata {
+            bone_index,
+            collision_group: rigid_body.collision_group,
+            collision_mask: rigid_body.collision_mask,
+            shape_type: rigid_body.shape_type,
+            shape_size: Vec4::from((rigid_body.shape_size, 0.0)),
+            shape_position: rigid_body.shape_position,
+            shape_rotation: rigid_body.shape_rotation,
+            mass: rigid_body.mass,
+            linear_damping: rigid_body.linear_damping,
+            angular_damping: rigid_body.angular_damping,
+            repulsion: rigid_body.repulsion,
+            friction: rigid_body.friction,
+            physics_mode: rigid_body.physics_mode,
+        }).collect();
+
+        let PhysicsMetadata::FullPhysics(options) = options else {
+            return PhysicsMetadata::StripedRigidbodies(rigid_bodies);
+        };
+
+        let joints = pmx.joints.iter().map(|joint| JointMetadata {
+            kind: joint.kind,
+            rigidbody_index_a: joint.rigidbody_index_a,
+            rigidbody_index_b: joint.rigidbody_index_b,
+            position: joint.position,
+            rotation: joint.rotation,
+            position_min: joint.position_min,
+            position_max: joint.position_max,
+            rotation_min: joint.rotation_min,
+            rotation_max: joint.rotation_max,
+            spring_position: joint.spring_position,
+            spring_rotation: joint.spring_rotation,
+        }).collect();
+
+        PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+            rigid_bodies,
+            joints,
+            ..options
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostic::Diagnostic;
+    use crate::mmd_model_metadata::{BoneMetadataReader, MetadataBuffer};
+
+    use super::*;
+
+    fn empty_model() -> PmxObject {
+        PmxObject {
+            bones: Vec::new(),
+            morphs: vec![PmxMorph::Other, PmxMorph::Group {
+                indices: vec![0],
+                ratios: vec![1.0],
+            }],
+            rigid_bodies: Vec::new(),
+            joints: Vec::new(),
+        }
+    }
+
+    fn read_options(physics: PhysicsMetadata) -> PmxMetadataEncodeOptions {
+        let mut builder = MetadataBuilder::new();
+        builder.set_physics(physics);
+        PmxMetadataEncodeOptions::read(MetadataBuffer::new(&builder.build().unwrap())).unwrap()
+    }
+
+    #[test]
+    fn runtime_morph_map_skips_other_morphs() {
+        assert_eq!(PmxMetadataEncoder::runtime_morph_map(&empty_model().morphs), vec![-1, 0]);
+    }
+
+    #[test]
+    fn no_physics_option_encodes_no_physics() {
+        let options = read_options(PhysicsMetadata::NoPhysics);
+        assert!(matches!(options.physics, PhysicsMetadata::NoPhysics));
+
+        let mut diagnostic = Diagnostic::new();
+        let bytes = PmxMetadataEncoder::encode(&empty_model(), options, &mut diagnostic.writer()).unwrap();
+        let reader = BoneMetadataReader::new(MetadataBuffer::new(&bytes)).unwrap();
+        let (_, reader) = reader.enumerate(|_, _| { }).unwrap().read().unwrap();
+        assert!(matches!(reader.physics_info_kind(), PhysicsInfoKind::NoPhysics));
+    }
+
+    #[cfg(feature = "physics")]
+    #[test]
+    fn physics_options_are_passed_through() {
+        use glam::Vec3A;
+
+        use crate::mmd_model_metadata::{CcdMetadata, RagdollMetadata};
+
+        let options = read_options(PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+            physics_world_id: 3,
+            kinematic_shared_physics_world_ids: vec![1, 2],
+            model_initial_world_matrix: Mat4::from_translation(glam::Vec3::new(1.0, 2.0, 3.0)),
+            disable_offset_for_constraint_frame: true,
+            rigid_bodies: Vec::new(),
+            joints: Vec::new(),
+            ragdoll: Some(RagdollMetadata {
+                default_mass: 2.0,
+                angular_lower_limit: Vec3A::splat(-0.5),
+                angular_upper_limit: Vec3A::splat(0.5),
+                radius_scale: 0.3,
+                min_bone_length: 0.25,
+            }),
+            ccd: Some(CcdMetadata {
+                motion_threshold_scale: 0.5,
+                swept_sphere_radius_scale: 0.25,
+            }),
+        }));
+
+        let mut diagnostic = Diagnostic::new();
+        let bytes = PmxMetadataEncoder::encode(&empty_model(), options, &mut diagnostic.writer()).unwrap();
+        let reader = BoneMetadataReader::new(MetadataBuffer::new(&bytes)).unwrap();
+        let (_, mut reader) = reader.enumerate(|_, _| { }).unwrap().read().unwrap();
+
+        assert!(matches!(reader.physics_info_kind(), PhysicsInfoKind::FullPhysics));
+        assert_eq!(reader.physics_world_id(), 3);
+        assert_eq!(reader.take_kinematic_shared_physics_world_ids(), vec![1, 2]);
+        assert_eq!(*reader.model_initial_world_matrix(), Mat4::from_translation(glam::Vec3::new(1.0, 2.0, 3.0)));
+        assert!(reader.disable_offset_for_constraint_frame());
+        let ragdoll = reader.ragdoll().unwrap().expect("ragdoll is lost");
+        assert_eq!((ragdoll.default_mass, ragdoll.radius_scale, ragdoll.min_bone_length), (2.0, 0.3, 0.25));
+        let ccd = reader.ccd().unwrap().expect("ccd is lost");
+        assert_eq!((ccd.motion_threshold_scale, ccd.swept_sphere_radius_scale), (0.5, 0.25));
+    }
+}
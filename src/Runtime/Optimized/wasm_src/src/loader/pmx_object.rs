@@ -0,0 +1,102 @@
+use glam::{Quat, Vec3A};
+
+/// Parsed PMX model
+///
+/// Only the data consumed by the runtime is kept,
+/// vertices, materials, textures and display frames are validated and skipped
+pub(crate) struct PmxObject {
+    pub(crate) bones: Vec<PmxBone>,
+    pub(crate) morphs: Vec<PmxMorph>,
+    pub(crate) rigid_bodies: Vec<PmxRigidBody>,
+    #[cfg_attr(not(feature = "physics"), allow(dead_code))] // only used in physics build
+    pub(crate) joints: Vec<PmxJoint>,
+}
+
+pub(crate) struct PmxBone {
+    pub(crate) name: String,
+    pub(crate) position: Vec3A,
+    pub(crate) parent_bone_index: i32,
+    pub(crate) transform_order: i32,
+    pub(crate) flag: u16,
+    pub(crate) append_transform: Option<PmxAppendTransform>,
+    pub(crate) axis_limit: Option<Vec3A>,
+    pub(crate) ik: Option<PmxIk>,
+}
+
+pub(crate) struct PmxAppendTransform {
+    pub(crate) parent_index: i32,
+    pub(crate) ratio: f32,
+}
+
+pub(crate) struct PmxIk {
+    pub(crate) target: i32,
+    pub(crate) iteration: i32,
+    pub(crate) rotation_constraint: f32,
+    pub(crate) links: Vec<PmxIkLink>,
+}
+
+pub(crate) struct PmxIkLink {
+    pub(crate) target: i32,
+    pub(crate) limitation: Option<(Vec3A, Vec3A)>, // minimum angle, maximum angle
+}
+
+pub(crate) enum PmxMorphKind {
+    GroupMorph = 0,
+    VertexMorph = 1,
+    BoneMorph = 2,
+    UvMorph = 3,
+    AdditionalUvMorph1 = 4,
+    AdditionalUvMorph2 = 5,
+    AdditionalUvMorph3 = 6,
+    AdditionalUvMorph4 = 7,
+    MaterialMorph = 8,
+    FlipMorph = 9, // pmx 2.1 spec
+    ImpulseMorph = 10, // pmx 2.1 spec
+}
+
+pub(crate) enum PmxMorph {
+    Group {
+        indices: Vec<i32>,
+        ratios: Vec<f32>,
+    },
+    Bone {
+        indices: Vec<i32>,
+        positions: Vec<Vec3A>,
+        rotations: Vec<Quat>,
+    },
+    // morphs which are not evaluated by the runtime
+    Other,
+}
+
+#[cfg_attr(not(feature = "physics"), allow(dead_code))] // only fully used in physics build
+pub(crate) struct PmxRigidBody {
+    pub(crate) name: String,
+    pub(crate) bone_index: i32,
+    pub(crate) collision_group: u8,
+    pub(crate) collision_mask: u16,
+    pub(crate) shape_type: u8,
+    pub(crate) shape_size: Vec3A,
+    pub(crate) shape_position: Vec3A,
+    pub(crate) shape_rotation: Vec3A,
+    pub(crate) mass: f32,
+    pub(crate) linear_damping: f32,
+    pub(crate) angular_damping: f32,
+    pub(crate) repulsion: f32,
+    pub(crate) friction: f32,
+    pub(crate) physics_mode: u8,
+}
+
+#[cfg_attr(not(feature = "physics"), allow(dead_code))] // only used in physics build
+pub(crate) struct PmxJoint {
+    pub(crate) kind: u8,
+    pub(crate) rigidbody_index_a: i32,
+    pub(crate) rigidbody_index_b: i32,
+    pub(crate) position: Vec3A,
+    pub(crate) rotation: Vec3A,
+    pub(crate) position_min: Vec3A,
+    pub(crate) position_max: Vec3A,
+    pub(crate) rotation_min: Vec3A,
+    pub(crate) rotation_max: Vec3A,
+    pub(crate) spring_position: Vec3A,
+    pub(crate) spring_rotation: Vec3A,
+}
@@ -0,0 +1,1040 @@
+use crate::mmd_model_metadata::BoneFlag;
+
+use super::LoaderError;
+use super::data_deserializer::DataDeserializer;
+use super::pmx_object::{PmxAppendTransform, PmxBone, PmxIk, PmxIkLink, PmxJoint, PmxMorph, PmxMorphKind, PmxObject, PmxRigidBody};
+
+enum PmxBoneWeightType {
+    Bdef1 = 0,
+    Bdef2 = 1,
+    Bdef4 = 2,
+    Sdef = 3,
+    Qdef = 4, // pmx 2.1 spec
+}
+
+enum PmxDisplayFrameKind {
+    Bone = 0,
+    Morph = 1,
+}
+
+pub(crate) struct PmxReader<'a> {
+    deserializer: DataDeserializer<'a>,
+    is_utf8: bool,
+    additional_vec4_count: u8,
+    vertex_index_size: u8,
+    texture_index_size: u8,
+    material_index_size: u8,
+    bone_index_size: u8,
+    morph_index_size: u8,
+    rigid_body_index_size: u8,
+}
+
+impl<'a> PmxReader<'a> {
+    /// Parse PMX 2.0 / 2.1 file
+    ///
+    /// Soft bodies are not read since they are not supported by the runtime
+    pub(crate) fn read(bytes: &'a [u8]) -> Result<PmxObject, LoaderError> {
+        let mut reader = Self::read_header(DataDeserializer::new(bytes))?;
+
+        reader.skip_vertices()?;
+        reader.skip_indices()?;
+        reader.skip_textures()?;
+        reader.skip_materials()?;
+        let bones = reader.read_bones()?;
+        let morphs = reader.read_morphs()?;
+        reader.skip_display_frames()?;
+        let rigid_bodies = reader.read_rigid_bodies()?;
+        let joints = reader.read_joints()?;
+
+        Ok(PmxObject {
+            bones,
+            morphs,
+            rigid_bodies,
+            joints,
+        })
+    }
+
+    fn read_header(mut deserializer: DataDeserializer<'a>) -> Result<Self, LoaderError> {
+        let signature = deserializer.read_bytes(4)?;
+        if &signature[0..3] != b"PMX" {
+            return Err(LoaderError::InvalidSignature);
+        }
+        let version = deserializer.read::<f32>()?;
+        if version != 2.0 && version != 2.1 {
+            return Err(LoaderError::UnsupportedPmxVersion(version));
+        }
+
+        let globals_count = deserializer.read::<u8>()?;
+        if globals_count < 8 {
+            return Err(LoaderError::InvalidGlobalsCount(globals_count));
+        }
+
+        let encoding = deserializer.read::<u8>()?;
+        let additional_vec4_count = deserializer.read::<u8>()?;
+        let mut index_sizes = [0u8; 6];
+        for index_size in &mut index_sizes {
+            *index_size = deserializer.read::<u8>()?;
+            if !matches!(*index_size, 1 | 2 | 4) {
+                return Err(LoaderError::InvalidIndexSize(*index_size));
+            }
+        }
+        // globals added by higher version are ignored
+        deserializer.skip(globals_count as usize - 8)?;
+
+        let mut reader = Self {
+            deserializer,
+            is_utf8: encoding == 1,
+            additional_vec4_count,
+            vertex_index_size: index_sizes[0],
+            texture_index_size: index_sizes[1],
+            material_index_size: index_sizes[2],
+            bone_index_size: index_sizes[3],
+            morph_index_size: index_sizes[4],
+            rigid_body_index_size: index_sizes[5],
+        };
+
+        reader.skip_text()?; // model name
+        reader.skip_text()?; // english model name
+        reader.skip_text()?; // comment
+        reader.skip_text()?; // english comment
+
+        Ok(reader)
+    }
+
+    fn read_count(&mut self, min_element_size: usize) -> Result<usize, LoaderError> {
+        let count = self.deserializer.read::<i32>()?;
+        let count = count.max(0) as usize;
+        // prevents huge allocation from broken data
+        self.deserializer.check_array(min_element_size, count)?;
+        Ok(count)
+    }
+
+    fn read_text_bytes(&mut self) -> Result<&'a [u8], LoaderError> {
+        let length = self.deserializer.read::<i32>()?;
+        if length < 0 {
+            return Err(LoaderError::InvalidTextLength(length));
+        }
+        self.deserializer.read_bytes(length as usize)
+    }
+
+    fn read_text(&mut self) -> Result<String, LoaderError> {
+        let bytes = self.read_text_bytes()?;
+        let text = if self.is_utf8 {
+            String::from_utf8_lossy(bytes).into_owned()
+        } else {
+            let units = bytes
+                .chunks_exact(2)
+                .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+                .collect::<Vec<_>>();
+            String::from_utf16_lossy(&units)
+        };
+        Ok(text)
+    }
+
+    fn skip_text(&mut self) -> Result<(), LoaderError> {
+        self.read_text_bytes().map(|_| ())
+    }
+
+    fn read_index(&mut self, index_size: u8) -> Result<i32, LoaderError> {
+        match index_size {
+            1 => Ok(self.deserializer.read::<i8>()? as i32),
+            2 => Ok(self.deserializer.read::<i16>()? as i32),
+            _ => self.deserializer.read::<i32>(),
+        }
+    }
+
+    fn read_bone_index(&mut self) -> Result<i32, LoaderError> {
+        self.read_index(self.bone_index_size)
+    }
+
+    fn read_morph_index(&mut self) -> Result<i32, LoaderError> {
+        self.read_index(self.morph_index_size)
+    }
+
+    fn read_rigid_body_index(&mut self) -> Result<i32, LoaderError> {
+        self.read_index(self.rigid_body_index_size)
+    }
+
+    fn skip_vertices(&mut self) -> Result<(), LoaderError> {
+        let bone_index_size = self.bone_index_size as usize;
+        let count = self.read_count(
+            4 * 3 + // position
+            4 * 3 + // normal
+            4 * 2 + // uv
+            1 + // weight type
+            4 // edge scale
+        )?;
+
+        for _ in 0..count {
+            self.deserializer.skip(
+                4 * 3 + // position
+                4 * 3 + // normal
+                4 * 2 + // uv
+                4 * 4 * self.additional_vec4_count as usize
+            )?;
+
+            let weight_type = self.deserializer.read::<u8>()?;
+            let weight_size = if weight_type == PmxBoneWeightType::Bdef1 as u8 {
+                bone_index_size
+            } else if weight_type == PmxBoneWeightType::Bdef2 as u8 {
+                bone_index_size * 2 + 4
+            } else if weight_type == PmxBoneWeightType::Bdef4 as u8 || weight_type == PmxBoneWeightType::Qdef as u8 {
+                bone_index_size * 4 + 4 * 4
+            } else if weight_type == PmxBoneWeightType::Sdef as u8 {
+                bone_index_size * 2 + 4 + 4 * 3 * 3 // indices, weight, c, r0, r1
+            } else {
+                return Err(LoaderError::InvalidBoneWeightType(weight_type));
+            };
+            self.deserializer.skip(weight_size + 4)?; // weights, edge scale
+        }
+
+        Ok(())
+    }
+
+    fn skip_indices(&mut self) -> Result<(), LoaderError> {
+        let count = self.read_count(self.vertex_index_size as usize)?;
+        self.deserializer.skip(count * self.vertex_index_size as usize)
+    }
+
+    fn skip_textures(&mut self) -> Result<(), LoaderError> {
+        let count = self.read_count(4)?;
+        for _ in 0..count {
+            self.skip_text()?;
+        }
+        Ok(())
+    }
+
+    fn skip_materials(&mut self) -> Result<(), LoaderError> {
+        let count = self.read_count(4 * 2)?;
+        for _ in 0..count {
+            self.skip_text()?; // name
+            self.skip_text()?; // english name
+            self.deserializer.skip(
+                4 * 4 + // diffuse
+                4 * 3 + // specular
+                4 + // shininess
+                4 * 3 + // ambient
+                1 + // flag
+                4 * 4 + // edge color
+                4 + // edge size
+                self.texture_index_size as usize * 2 + // texture index, sphere texture index
+                1 // sphere texture mode
+            )?;
+            let is_shared_toon_texture = self.deserializer.read::<u8>()? == 1;
+            self.deserializer.skip(if is_shared_toon_texture { 1 } else { self.texture_index_size as usize })?;
+            self.skip_text()?; // comment
+            self.deserializer.skip(4)?; // index count
+        }
+        Ok(())
+    }
+
+    fn read_bones(&mut self) -> Result<Vec<PmxBone>, LoaderError> {
+        let count = self.read_count(4 * 2 + 4 * 3 + 4 + 2)?;
+        let mut bones = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let name = self.read_text()?;
+            self.skip_text()?; // english name
+            let position = self.deserializer.read_vector()?;
+            let parent_bone_index = self.read_bone_index()?;
+            let transform_order = self.deserializer.read::<i32>()?;
+            let flag = self.deserializer.read::<u16>()?;
+
+            // tail position
+            if flag & BoneFlag::UseBoneIndexAsTailPosition as u16 != 0 {
+                self.read_bone_index()?;
+            } else {
+                self.deserializer.skip(4 * 3)?;
+            }
+
+            let append_transform = if flag & BoneFlag::HasAppendMove as u16 != 0 || flag & BoneFlag::HasAppendRotate as u16 != 0 {
+                Some(PmxAppendTransform {
+                    parent_index: self.read_bone_index()?,
+                    ratio: self.deserializer.read::<f32>()?,
+                })
+            } else {
+                None
+            };
+
+            let axis_limit = if flag & BoneFlag::HasAxisLimit as u16 != 0 {
+                Some(self.deserializer.read_vector()?)
+            } else {
+                None
+            };
+
+            if flag & BoneFlag::HasLocalVector as u16 != 0 {
+                self.deserializer.skip(4 * 3 * 2)?; // x axis, z axis
+            }
+
+            if flag & BoneFlag::IsExternalParentTransformed as u16 != 0 {
+                self.deserializer.skip(4)?; // external parent key
+            }
+
+            let ik = if flag & BoneFlag::IsIkEnabled as u16 != 0 {
+                let target = self.read_bone_index()?;
+                let iteration = self.deserializer.read::<i32>()?;
+                let rotation_constraint = self.deserializer.read::<f32>()?;
+                let link_count = self.read_count(self.bone_index_size as usize + 1)?;
+                let mut links = Vec::with_capacity(link_count);
+                for _ in 0..link_count {
+                    let target = self.read_bone_index()?;
+                    let has_limitation = self.deserializer.read::<u8>()? == 1;
+                    let limitation = if has_limitation {
+                        Some((self.deserializer.read_vector()?, self.deserializer.read_vector()?))
+                    } else {
+                        None
+                    };
+                    links.push(PmxIkLink {
+                        target,
+                        limitation,
+                    });
+                }
+                Some(PmxIk {
+                    target,
+                    iteration,
+                    rotation_constraint,
+                    links,
+                })
+            } else {
+                None
+            };
+
+            bones.push(PmxBone {
+                name,
+                position,
+                parent_bone_index,
+                transform_order,
+                flag,
+                append_transform,
+                axis_limit,
+                ik,
+            });
+        }
+
+        Ok(bones)
+    }
+
+    fn read_morphs(&mut self) -> Result<Vec<PmxMorph>, LoaderError> {
+        let count = self.read_count(4 * 2 + 1 + 1 + 4)?;
+        let mut morphs = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            self.skip_text()?; // name
+            self.skip_text()?; // english name
+            self.deserializer.skip(1)?; // category
+            let kind = self.deserializer.read::<u8>()?;
+
+            let morph = if kind == PmxMorphKind::GroupMorph as u8 {
+                let element_count = self.read_count(self.morph_index_size as usize + 4)?;
+                let mut indices = Vec::with_capacity(element_count);
+                let mut ratios = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    indices.push(self.read_morph_index()?);
+                    ratios.push(self.deserializer.read::<f32>()?);
+                }
+                PmxMorph::Group {
+                    indices,
+                    ratios,
+                }
+            } else if kind == PmxMorphKind::BoneMorph as u8 {
+                let element_count = self.read_count(self.bone_index_size as usize + 4 * 3 + 4 * 4)?;
+                let mut indices = Vec::with_capacity(element_count);
+                let mut positions = Vec::with_capacity(element_count);
+                let mut rotations = Vec::with_capacity(element_count);
+                for _ in 0..element_count {
+                    indices.push(self.read_bone_index()?);
+                    positions.push(self.deserializer.read_vector()?);
+                    rotations.push(self.deserializer.read_quaternion()?);
+                }
+                PmxMorph::Bone {
+                    indices,
+                    positions,
+                    rotations,
+                }
+            } else {
+                let element_size = if kind == PmxMorphKind::VertexMorph as u8 {
+                    self.vertex_index_size as usize + 4 * 3
+                } else if kind == PmxMorphKind::UvMorph as u8 ||
+                    kind == PmxMorphKind::AdditionalUvMorph1 as u8 ||
+                    kind == PmxMorphKind::AdditionalUvMorph2 as u8 ||
+                    kind == PmxMorphKind::AdditionalUvMorph3 as u8 ||
+                    kind == PmxMorphKind::AdditionalUvMorph4 as u8
+                {
+                    self.vertex_index_size as usize + 4 * 4
+                } else if kind == PmxMorphKind::MaterialMorph as u8 {
+                    self.material_index_size as usize +
+                        1 + // operation type
+                        4 * 4 + // diffuse
+                        4 * 3 + // specular
+                        4 + // shininess
+                        4 * 3 + // ambient
+                        4 * 4 + // edge color
+                        4 + // edge size
+                        4 * 4 + // texture color
+                        4 * 4 + // sphere texture color
+                        4 * 4 // toon texture color
+                } else if kind == PmxMorphKind::FlipMorph as u8 {
+                    self.morph_index_size as usize + 4
+                } else if kind == PmxMorphKind::ImpulseMorph as u8 {
+                    self.rigid_body_index_size as usize +
+                        1 + // is local
+                        4 * 3 + // velocity
+                        4 * 3 // torque
+                } else {
+                    return Err(LoaderError::InvalidMorphKind(kind));
+                };
+                let element_count = self.read_count(element_size)?;
+                self.deserializer.skip(element_size * element_count)?;
+                PmxMorph::Other
+            };
+
+            morphs.push(morph);
+        }
+
+        Ok(morphs)
+    }
+
+    fn skip_display_frames(&mut self) -> Result<(), LoaderError> {
+        let count = self.read_count(4 * 2 + 1 + 4)?;
+        for _ in 0..count {
+            self.skip_text()?; // name
+            self.skip_text()?; // english name
+            self.deserializer.skip(1)?; // is special frame
+
+            let frame_count = self.read_count(1 + 1)?;
+            for _ in 0..frame_count {
+                let kind = self.deserializer.read::<u8>()?;
+                if kind == PmxDisplayFrameKind::Bone as u8 {
+                    self.read_bone_index()?;
+                } else if kind == PmxDisplayFrameKind::Morph as u8 {
+                    self.read_morph_index()?;
+                } else {
+                    return Err(LoaderError::InvalidDisplayFrameKind(kind));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_rigid_bodies(&mut self) -> Result<Vec<PmxRigidBody>, LoaderError> {
+        let count = self.read_count(4 * 2 + 1 + 2 + 1 + 4 * 3 * 3 + 4 * 5 + 1)?;
+        let mut rigid_bodies = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let name = self.read_text()?;
+            self.skip_text()?; // english name
+            let bone_index = self.read_bone_index()?;
+            let collision_group = self.deserializer.read::<u8>()?;
+            let collision_mask = self.deserializer.read::<u16>()?;
+            let shape_type = self.deserializer.read::<u8>()?;
+            let shape_size = self.deserializer.read_vector()?;
+            let shape_position = self.deserializer.read_vector()?;
+            let shape_rotation = self.deserializer.read_vector()?;
+            let mass = self.deserializer.read::<f32>()?;
+            let linear_damping = self.deserializer.read::<f32>()?;
+            let angular_damping = self.deserializer.read::<f32>()?;
+            let repulsion = self.deserializer.read::<f32>()?;
+            let friction = self.deserializer.read::<f32>()?;
+            let physics_mode = self.deserializer.read::<u8>()?;
+
+            rigid_bodies.push(PmxRigidBody {
+                name,
+                bone_index,
+                collision_group,
+                collision_mask,
+                shape_type,
+                shape_size,
+                shape_position,
+                shape_rotation,
+                mass,
+                linear_damping,
+                angular_damping,
+                repulsion,
+                friction,
+                physics_mode,
+            });
+        }
+
+        Ok(rigid_bodies)
+    }
+
+    fn read_joints(&mut self) -> Result<Vec<PmxJoint>, LoaderError> {
+        let count = self.read_count(4 * 2 + 1 + 4 * 3 * 8)?;
+        let mut joints = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            self.skip_text()?; // name
+            self.skip_text()?; // english name
+            let kind = self.deserializer.read::<u8>()?;
+            let rigidbody_index_a = self.read_rigid_body_index()?;
+            let rigidbody_index_b = self.read_rigid_body_index()?;
+
+            joints.push(PmxJoint {
+                kind,
+                rigidbody_index_a,
+                rigidbody_index_b,
+                position: self.deserializer.read_vector()?,
+                rotation: self.deserializer.read_vector()?,
+                position_min: self.deserializer.read_vector()?,
+                position_max: self.deserializer.read_vector()?,
+                rotation_min: self.deserializer.read_vector()?,
+                rotation_max: self.deserializer.read_vector()?,
+                spring_position: self.deserializer.read_vector()?,
+                spring_rotation: self.deserializer.read_vector()?,
+            });
+        }
+
+        Ok(joints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Quat, Vec3A};
+
+    use crate::diagnostic::Diagnostic;
+    use crate::mmd_model_metadata::{BoneMetadataReader, MetadataBuffer, MorphMetadata};
+
+    use super::super::pmx_metadata_encoder::{PmxMetadataEncodeOptions, PmxMetadataEncoder};
+    use super::*;
+
+    #[derive(Default)]
+    struct ByteWriter {
+        bytes: Vec<u8>,
+    }
+
+    impl ByteWriter {
+        fn u8(&mut self, value: u8) {
+            self.bytes.push(value);
+        }
+
+        fn u16(&mut self, value: u16) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn i16(&mut self, value: i16) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn u32(&mut self, value: u32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn i32(&mut self, value: i32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn f32(&mut self, value: f32) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn vector(&mut self, value: Vec3A) {
+            for component in value.to_array() {
+                self.f32(component);
+            }
+        }
+
+        fn quaternion(&mut self, value: Quat) {
+            for component in value.to_array() {
+                self.f32(component);
+            }
+        }
+
+        fn zeros(&mut self, size: usize) {
+            self.bytes.resize(self.bytes.len() + size, 0);
+        }
+    }
+
+    /// Model which uses every field read by the loaders
+    ///
+    /// Indices fit in 1 byte index so it can be written with any index size
+    fn sample_model() -> PmxObject {
+        let common_flag = BoneFlag::UseBoneIndexAsTailPosition as u16 | BoneFlag::IsRotatable as u16 | BoneFlag::IsVisible as u16 | BoneFlag::IsControllable as u16;
+        let bone = |name: &str, position: Vec3A, parent_bone_index: i32, flag: u16| PmxBone {
+            name: name.to_owned(),
+            position,
+            parent_bone_index,
+            transform_order: 0,
+            flag,
+            append_transform: None,
+            axis_limit: None,
+            ik: None,
+        };
+
+        let mut bones = vec![
+            bone("センター", Vec3A::new(0.0, 8.0, 0.0), -1, common_flag | BoneFlag::IsMovable as u16),
+            bone("左足", Vec3A::new(1.0, 10.0, 0.0), 0, common_flag),
+            bone("左ひざ", Vec3A::new(1.0, 5.0, 0.2), 1, common_flag),
+            bone("左足首", Vec3A::new(1.0, 1.0, 0.0), 2, common_flag | BoneFlag::HasLocalVector as u16 | BoneFlag::IsExternalParentTransformed as u16),
+            bone("左足ＩＫ", Vec3A::new(1.0, 1.0, 0.0), 0, common_flag | BoneFlag::IsMovable as u16 | BoneFlag::IsIkEnabled as u16),
+            bone("左ダミー", Vec3A::new(1.5, 5.0, 0.0), 1, (common_flag & !(BoneFlag::UseBoneIndexAsTailPosition as u16)) | BoneFlag::HasAppendRotate as u16 | BoneFlag::HasAxisLimit as u16),
+        ];
+        bones[4].transform_order = 1;
+        bones[4].ik = Some(PmxIk {
+            target: 3,
+            iteration: 40,
+            rotation_constraint: 2.0,
+            links: vec![
+                PmxIkLink {
+                    target: 2,
+                    limitation: Some((Vec3A::new(-std::f32::consts::PI, 0.0, 0.0), Vec3A::new(-0.008, 0.0, 0.0))),
+                },
+                PmxIkLink {
+                    target: 1,
+                    limitation: None,
+                },
+            ],
+        });
+        bones[5].append_transform = Some(PmxAppendTransform {
+            parent_index: 2,
+            ratio: 0.5,
+        });
+        bones[5].axis_limit = Some(Vec3A::X);
+
+        let rigid_body = |name: &str, bone_index: i32, physics_mode: u8| PmxRigidBody {
+            name: name.to_owned(),
+            bone_index,
+            collision_group: 1,
+            collision_mask: 0xFFFE,
+            shape_type: 2,
+            shape_size: Vec3A::new(0.5, 4.0, 0.0),
+            shape_position: Vec3A::new(1.0, 7.5, 0.0),
+            shape_rotation: Vec3A::new(0.0, 0.0, 0.1),
+            mass: 1.0,
+            linear_damping: 0.5,
+            angular_damping: 0.5,
+            repulsion: 0.0,
+            friction: 0.5,
+            physics_mode,
+        };
+
+        PmxObject {
+            bones,
+            morphs: vec![
+                PmxMorph::Other,
+                PmxMorph::Bone {
+                    indices: vec![1, 2],
+                    positions: vec![Vec3A::Y, Vec3A::ZERO],
+                    rotations: vec![Quat::IDENTITY, Quat::from_rotation_x(0.5)],
+                },
+                PmxMorph::Other,
+                PmxMorph::Group {
+                    indices: vec![1, 0, 7], // vertex morph and out of range index are remapped to -1
+                    ratios: vec![1.0, 0.5, 0.25],
+                },
+            ],
+            rigid_bodies: vec![
+                rigid_body("左足", 1, 0),
+                rigid_body("左ダミー", -1, 1), // bone is found by name
+            ],
+            joints: vec![PmxJoint {
+                kind: 0,
+                rigidbody_index_a: 0,
+                rigidbody_index_b: 1,
+                position: Vec3A::new(1.0, 5.0, 0.0),
+                rotation: Vec3A::ZERO,
+                position_min: Vec3A::ZERO,
+                position_max: Vec3A::ZERO,
+                rotation_min: Vec3A::splat(-0.5),
+                rotation_max: Vec3A::splat(0.5),
+                spring_position: Vec3A::ZERO,
+                spring_rotation: Vec3A::splat(10.0),
+            }],
+        }
+    }
+
+    /// Encode the model with the native metadata encoder
+    ///
+    /// Used to compare the result of each reader, diagnostics are discarded
+    fn encode(pmx: &PmxObject) -> Vec<u8> {
+        let mut diagnostic = Diagnostic::new();
+        PmxMetadataEncoder::encode(pmx, PmxMetadataEncodeOptions::default_physics(), &mut diagnostic.writer()).unwrap()
+    }
+
+    struct PmxWriteOptions {
+        is_utf8: bool,
+        index_size: u8,
+    }
+
+    struct PmxWriter {
+        writer: ByteWriter,
+        options: PmxWriteOptions,
+    }
+
+    impl PmxWriter {
+        fn text(&mut self, text: &str) {
+            if self.options.is_utf8 {
+                self.writer.i32(text.len() as i32);
+                self.writer.bytes.extend_from_slice(text.as_bytes());
+            } else {
+                let units = text.encode_utf16().collect::<Vec<_>>();
+                self.writer.i32(units.len() as i32 * 2);
+                for unit in units {
+                    self.writer.u16(unit);
+                }
+            }
+        }
+
+        /// Signed index of 1, 2 or 4 bytes
+        fn index(&mut self, value: i32) {
+            match self.options.index_size {
+                1 => self.writer.u8(value as i8 as u8),
+                2 => self.writer.i16(value as i16),
+                _ => self.writer.i32(value),
+            }
+        }
+    }
+
+    /// Write the model as PMX 2.0 with one index size for every index kind
+    ///
+    /// `PmxMorph::Other` is written as a vertex morph
+    fn write_pmx(pmx: &PmxObject, options: PmxWriteOptions) -> Vec<u8> {
+        let index_size = options.index_size;
+        let mut w = PmxWriter {
+            writer: ByteWriter::default(),
+            options,
+        };
+
+        // header
+        w.writer.bytes.extend_from_slice(b"PMX ");
+        w.writer.f32(2.0);
+        w.writer.u8(8); // globals count
+        w.writer.u8(if w.options.is_utf8 { 1 } else { 0 });
+        w.writer.u8(1); // additional vec4 count
+        for _ in 0..6 {
+            w.writer.u8(index_size);
+        }
+        w.text("テストモデル");
+        w.text("test model");
+        w.text("");
+        w.text("");
+
+        // vertices, one for each weight type
+        let weight_types: [(u8, usize); 4] = [(0, 1), (1, 2), (2, 4), (3, 2)];
+        w.writer.i32(weight_types.len() as i32);
+        for (weight_type, bone_count) in weight_types {
+            w.writer.zeros(4 * 3 + 4 * 3 + 4 * 2 + 4 * 4); // position, normal, uv, additional vec4
+            w.writer.u8(weight_type);
+            for i in 0..bone_count {
+                w.index(i as i32);
+            }
+            match weight_type {
+                0 => { },
+                1 => w.writer.f32(1.0),
+                2 => w.writer.zeros(4 * 4),
+                _ => w.writer.zeros(4 + 4 * 3 * 3),
+            }
+            w.writer.f32(1.0); // edge scale
+        }
+
+        // indices, vertex index is unsigned
+        w.writer.i32(3);
+        for i in 0..3 {
+            match index_size {
+                1 => w.writer.u8(i),
+                2 => w.writer.u16(i as u16),
+                _ => w.writer.u32(i as u32),
+            }
+        }
+
+        // textures
+        w.writer.i32(1);
+        w.text("tex.png");
+
+        // materials
+        w.writer.i32(1);
+        w.text("材質");
+        w.text("material");
+        w.writer.zeros(4 * 4 + 4 * 3 + 4 + 4 * 3 + 1 + 4 * 4 + 4); // colors, flag, edge
+        w.index(0); // texture index
+        w.index(-1); // sphere texture index
+        w.writer.u8(0); // sphere texture mode
+        w.writer.u8(0); // is shared toon texture
+        w.index(-1); // toon texture index
+        w.text("");
+        w.writer.i32(3); // index count
+
+        // bones
+        w.writer.i32(pmx.bones.len() as i32);
+        for bone in &pmx.bones {
+            w.text(&bone.name);
+            w.text("");
+            w.writer.vector(bone.position);
+            w.index(bone.parent_bone_index);
+            w.writer.i32(bone.transform_order);
+            w.writer.u16(bone.flag);
+            if bone.flag & BoneFlag::UseBoneIndexAsTailPosition as u16 != 0 {
+                w.index(-1);
+            } else {
+                w.writer.vector(Vec3A::Y);
+            }
+            if let Some(append_transform) = &bone.append_transform {
+                w.index(append_transform.parent_index);
+                w.writer.f32(append_transform.ratio);
+            }
+            if let Some(axis_limit) = bone.axis_limit {
+                w.writer.vector(axis_limit);
+            }
+            if bone.flag & BoneFlag::HasLocalVector as u16 != 0 {
+                w.writer.vector(Vec3A::X);
+                w.writer.vector(Vec3A::Z);
+            }
+            if bone.flag & BoneFlag::IsExternalParentTransformed as u16 != 0 {
+                w.writer.i32(0);
+            }
+            if let Some(ik) = &bone.ik {
+                w.index(ik.target);
+                w.writer.i32(ik.iteration);
+                w.writer.f32(ik.rotation_constraint);
+                w.writer.i32(ik.links.len() as i32);
+                for link in &ik.links {
+                    w.index(link.target);
+                    if let Some((minimum_angle, maximum_angle)) = link.limitation {
+                        w.writer.u8(1);
+                        w.writer.vector(minimum_angle);
+                        w.writer.vector(maximum_angle);
+                    } else {
+                        w.writer.u8(0);
+                    }
+                }
+            }
+        }
+
+        // morphs
+        w.writer.i32(pmx.morphs.len() as i32);
+        for morph in &pmx.morphs {
+            w.text("モーフ");
+            w.text("");
+            w.writer.u8(1); // category
+            match morph {
+                PmxMorph::Group { indices, ratios } => {
+                    w.writer.u8(PmxMorphKind::GroupMorph as u8);
+                    w.writer.i32(indices.len() as i32);
+                    for (index, ratio) in indices.iter().zip(ratios) {
+                        w.index(*index);
+                        w.writer.f32(*ratio);
+                    }
+                }
+                PmxMorph::Bone { indices, positions, rotations } => {
+                    w.writer.u8(PmxMorphKind::BoneMorph as u8);
+                    w.writer.i32(indices.len() as i32);
+                    for ((index, position), rotation) in indices.iter().zip(positions).zip(rotations) {
+                        w.index(*index);
+                        w.writer.vector(*position);
+                        w.writer.quaternion(*rotation);
+                    }
+                }
+                PmxMorph::Other => {
+                    w.writer.u8(PmxMorphKind::VertexMorph as u8);
+                    w.writer.i32(2);
+                    for i in 0..2 {
+                        w.index(i);
+                        w.writer.vector(Vec3A::ONE);
+                    }
+                }
+            }
+        }
+
+        // display frames
+        w.writer.i32(1);
+        w.text("Root");
+        w.text("Root");
+        w.writer.u8(1); // is special frame
+        w.writer.i32(2);
+        w.writer.u8(0); // bone
+        w.index(0);
+        w.writer.u8(1); // morph
+        w.index(0);
+
+        // rigid bodies
+        w.writer.i32(pmx.rigid_bodies.len() as i32);
+        for rigid_body in &pmx.rigid_bodies {
+            w.text(&rigid_body.name);
+            w.text("");
+            w.index(rigid_body.bone_index);
+            w.writer.u8(rigid_body.collision_group);
+            w.writer.u16(rigid_body.collision_mask);
+            w.writer.u8(rigid_body.shape_type);
+            w.writer.vector(rigid_body.shape_size);
+            w.writer.vector(rigid_body.shape_position);
+            w.writer.vector(rigid_body.shape_rotation);
+            w.writer.f32(rigid_body.mass);
+            w.writer.f32(rigid_body.linear_damping);
+            w.writer.f32(rigid_body.angular_damping);
+            w.writer.f32(rigid_body.repulsion);
+            w.writer.f32(rigid_body.friction);
+            w.writer.u8(rigid_body.physics_mode);
+        }
+
+        // joints
+        w.writer.i32(pmx.joints.len() as i32);
+        for joint in &pmx.joints {
+            w.text("ジョイント");
+            w.text("");
+            w.writer.u8(joint.kind);
+            w.index(joint.rigidbody_index_a);
+            w.index(joint.rigidbody_index_b);
+            for vector in [
+                joint.position,
+                joint.rotation,
+                joint.position_min,
+                joint.position_max,
+                joint.rotation_min,
+                joint.rotation_max,
+                joint.spring_position,
+                joint.spring_rotation,
+            ] {
+                w.writer.vector(vector);
+            }
+        }
+
+        w.writer.bytes
+    }
+
+    fn read(bytes: &[u8]) -> PmxObject {
+        PmxReader::read(bytes).unwrap()
+    }
+
+    #[test]
+    fn every_encoding_and_index_size_is_readable() {
+        let model = sample_model();
+        let expected = encode(&model);
+
+        for is_utf8 in [true, false] {
+            for index_size in [1, 2, 4] {
+                let pmx = read(&write_pmx(&model, PmxWriteOptions {
+                    is_utf8,
+                    index_size,
+                }));
+
+                let names = pmx.bones.iter().map(|bone| bone.name.as_str()).collect::<Vec<_>>();
+                assert_eq!(names, model.bones.iter().map(|bone| bone.name.as_str()).collect::<Vec<_>>(), "utf8: {}, index size: {}", is_utf8, index_size);
+                assert_eq!(pmx.rigid_bodies[1].name, "左ダミー");
+                assert_eq!(encode(&pmx), expected, "utf8: {}, index size: {}", is_utf8, index_size);
+            }
+        }
+    }
+
+    /// Same conversions as `MmdMetadataEncoder` of the TS side
+    #[test]
+    fn metadata_follows_ts_encoder() {
+        let pmx = read(&write_pmx(&sample_model(), PmxWriteOptions {
+            is_utf8: false,
+            index_size: 1,
+        }));
+        let bytes = encode(&pmx);
+
+        let mut bones = Vec::new();
+        let reader = BoneMetadataReader::new(MetadataBuffer::new(&bytes)).unwrap();
+        let reader = reader.enumerate(|_, bone| bones.push(bone)).unwrap();
+        let (morphs, mut reader) = reader.read().unwrap();
+        let mut rigid_body_bone_indices = Vec::new();
+        reader.enumerate(|_, rigid_body| rigid_body_bone_indices.push(rigid_body.bone_index)).unwrap();
+
+        // rest position is relative to the parent, bind matrix is absolute
+        assert_eq!(bones[2].rest_position, Vec3A::new(0.0, -5.0, 0.2));
+        assert_eq!(bones[2].absolute_inverse_bind_matrix.w_axis.truncate(), glam::Vec3::new(-1.0, -5.0, -0.2));
+        assert_eq!(bones[4].ik.as_ref().map(|ik| ik.links.len()), Some(2));
+        assert!(bones[5].append_transform.is_some() && bones[5].axis_limit.is_some());
+
+        // only bone and group morphs are kept and group morph indices are remapped to them
+        assert_eq!(morphs.len(), 2);
+        let MorphMetadata::Group(group_morph) = &morphs[1] else {
+            panic!("group morph is not encoded");
+        };
+        assert_eq!(group_morph.indices, vec![0, -1, -1]);
+
+        // rigid body without bone index is bound by name
+        assert_eq!(rigid_body_bone_indices, vec![1, 5]);
+    }
+
+    #[test]
+    fn wide_index_is_not_truncated() {
+        let mut model = sample_model();
+        // chain longer than 1 byte index range
+        for i in 0..300 {
+            let mut bone = sample_model().bones.swap_remove(1);
+            bone.name = format!("bone{}", i);
+            bone.position = Vec3A::new(0.0, i as f32, 0.0);
+            bone.parent_bone_index = model.bones.len() as i32 - 1;
+            model.bones.push(bone);
+        }
+        model.rigid_bodies[0].bone_index = model.bones.len() as i32 - 1;
+
+        for index_size in [2, 4] {
+            let pmx = read(&write_pmx(&model, PmxWriteOptions {
+                is_utf8: false,
+                index_size,
+            }));
+            let last = pmx.bones.last().unwrap();
+            assert_eq!(last.parent_bone_index, pmx.bones.len() as i32 - 2);
+            assert_eq!(pmx.rigid_bodies[0].bone_index, pmx.bones.len() as i32 - 1);
+            assert_eq!(encode(&pmx), encode(&model));
+        }
+    }
+
+    #[test]
+    fn fixture_files_are_readable() {
+        let fixtures: [(&str, &[u8]); 6] = [
+            ("bone_flag_test.pmx", include_bytes!("../../../../../../res/model/bone_flag_test.pmx")),
+            ("bone_hierarchy_test.pmx", include_bytes!("../../../../../../res/model/bone_hierarchy_test.pmx")),
+            ("bone_hierarchy_test2.pmx", include_bytes!("../../../../../../res/model/bone_hierarchy_test2.pmx")),
+            ("constraint_test.pmx", include_bytes!("../../../../../../res/model/constraint_test.pmx")),
+            ("uv_morph_test.pmx", include_bytes!("../../../../../../res/model/uv_morph_test.pmx")),
+            ("matcap_sample.pmx", include_bytes!("../../../../../../res/model/matcap_sample.pmx")),
+        ];
+
+        for (name, bytes) in fixtures {
+            let pmx = PmxReader::read(bytes).unwrap_or_else(|err| panic!("{}: {}", name, err));
+            assert!(!pmx.bones.is_empty(), "{} has no bones", name);
+        }
+
+        let constraint_test = read(include_bytes!("../../../../../../res/model/constraint_test.pmx"));
+        assert!(!constraint_test.rigid_bodies.is_empty());
+        assert!(!constraint_test.joints.is_empty());
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let bytes = write_pmx(&sample_model(), PmxWriteOptions {
+            is_utf8: false,
+            index_size: 2,
+        });
+        for length in 0..bytes.len() {
+            assert!(
+                matches!(PmxReader::read(&bytes[..length]), Err(LoaderError::UnexpectedEnd { .. })),
+                "truncated at {} of {} bytes was accepted", length, bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_header_is_rejected() {
+        let bytes = write_pmx(&sample_model(), PmxWriteOptions {
+            is_utf8: true,
+            index_size: 1,
+        });
+
+        let mut invalid_signature = bytes.clone();
+        invalid_signature[0..3].copy_from_slice(b"Pmd");
+        assert!(matches!(PmxReader::read(&invalid_signature), Err(LoaderError::InvalidSignature)));
+
+        let mut pmx_2_1 = bytes.clone();
+        pmx_2_1[4..8].copy_from_slice(&2.1f32.to_le_bytes());
+        assert!(PmxReader::read(&pmx_2_1).is_ok());
+
+        for version in [1.0f32, 2.2, 3.0] {
+            let mut unsupported_version = bytes.clone();
+            unsupported_version[4..8].copy_from_slice(&version.to_le_bytes());
+            assert!(matches!(PmxReader::read(&unsupported_version), Err(LoaderError::UnsupportedPmxVersion(v)) if v == version));
+        }
+
+        let mut invalid_globals_count = bytes.clone();
+        invalid_globals_count[8] = 7;
+        assert!(matches!(PmxReader::read(&invalid_globals_count), Err(LoaderError::InvalidGlobalsCount(7))));
+
+        let mut invalid_index_size = bytes.clone();
+        invalid_index_size[12] = 3; // bone index size
+        assert!(matches!(PmxReader::read(&invalid_index_size), Err(LoaderError::InvalidIndexSize(3))));
+    }
+}
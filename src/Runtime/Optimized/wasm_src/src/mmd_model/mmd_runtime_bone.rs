@@ -1,5 +1,6 @@
 use glam::{Vec3, Vec3A, Mat4, Quat};
 
+use crate::profiler::StageTimer;
 use crate::unchecked_slice::{UncheckedSlice, UncheckedSliceMut};
 
 use super::{animation_arena::AnimationArena, ik_chain_info::IkChainInfo, MmdModel};
@@ -125,6 +126,18 @@ impl MmdRuntimeBone {
         self.parent_bone
     }
 
+    #[inline]
+    #[allow(dead_code)]
+    pub(crate) fn child_bones(&self) -> &[u32] {
+        &self.child_bones
+    }
+
+    #[inline]
+    #[allow(dead_code)]
+    pub(crate) fn has_ik_or_append_transform(&self) -> bool {
+        self.ik_solver.is_some() || self.append_transform_solver.is_some()
+    }
+
     #[inline]
     #[allow(dead_code)]
     pub(crate) fn rigidbody_indices(&self) -> &[u32] {
@@ -233,7 +246,12 @@ impl MmdModel {
             if let Some(ik_solver_index) = bone.ik_solver {
                 let ik_solver = &self.ik_solver_arena.arena()[ik_solver_index];
                 if !(use_physics && ik_solver.can_skip_when_physics_enabled()) {
+                    let mut timer = StageTimer::new(self.profile.is_some());
                     self.solve_ik(ik_solver_index, use_physics);
+                    let ik_time = timer.lap();
+                    if let Some(profile) = self.profile.as_mut() {
+                        profile.ik += ik_time;
+                    }
                 }
             }
         }
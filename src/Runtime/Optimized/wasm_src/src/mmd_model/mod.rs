@@ -14,7 +14,7 @@ use mmd_morph_controller::MmdMorphController;
 use mmd_runtime_bone::{MmdRuntimeBone, MmdRuntimeBoneArena};
 
 use crate::diagnostic::Diagnostic;
-use crate::mmd_model_metadata::{BoneFlag, BoneMetadataReader, MetadataBuffer, RigidBodyPhysicsMode};
+use crate::mmd_model_metadata::{BoneFlag, BoneMetadataReader, MetadataBuffer, MetadataError, RigidBodyPhysicsMode};
 
 use crate::mmd_model_metadata::PhysicsInfoKind;
 #[cfg(feature = "physics")]
@@ -25,6 +25,7 @@ use crate::{
 
 use crate::animation::mmd_runtime_animation::MmdRuntimeAnimation;
 
+use crate::profiler::{ModelProfile, StageTimer};
 use crate::unchecked_slice::{UncheckedSlice, UncheckedSliceMut};
 
 pub(crate) struct MmdModel {
@@ -37,6 +38,7 @@ pub(crate) struct MmdModel {
     sorted_runtime_bones: Box<[u32]>,
     bone_stack: Option<Vec<u32>>,
     external_physics: bool,
+    profile: Option<ModelProfile>,
 
     #[cfg(feature = "physics")]
     physics_model_context: Option<PhysicsModelContext>,
@@ -50,8 +52,8 @@ impl MmdModel {
         physics_runtime: &mut MmdPhysicsRuntime,
         
         diagnostic: &mut Diagnostic
-    ) -> Self {
-        let reader = BoneMetadataReader::new(buffer);
+    ) -> Result<Self, MetadataError> {
+        let reader = BoneMetadataReader::new(buffer)?;
 
         let mut bone_arena: Vec<MmdRuntimeBone> = Vec::with_capacity(reader.bone_count() as usize);
         for i in 0..reader.bone_count() {
@@ -124,9 +126,9 @@ impl MmdModel {
                     diagnostic.error(format!("Invalid IK target bone index: {}", ik.target));
                 }
             }
-        });
+        })?;
     
-        let (morphs, mut reader) = reader.read();
+        let (morphs, mut reader) = reader.read()?;
         
         #[cfg(feature = "physics")]
         let build_physics = matches!(reader.physics_info_kind(), PhysicsInfoKind::FullPhysics);
@@ -151,7 +153,7 @@ impl MmdModel {
                 if 0 <= metadata.bone_index && metadata.bone_index < bone_arena.len() as i32 {
                     bone_to_rigidbodies_index_map[metadata.bone_index as usize].push(i);
                 }
-            });
+            })?;
 
             for (i, bone) in bone_arena.iter_mut().enumerate() {
                 bone.rigidbody_indices = std::mem::take(&mut bone_to_rigidbodies_index_map[i]).into_boxed_slice();
@@ -164,7 +166,7 @@ impl MmdModel {
             if metadata.physics_mode != RigidBodyPhysicsMode::FollowBone as u8 && 0 <= metadata.bone_index && metadata.bone_index < bone_arena.len() as i32 {
                 is_physics_bone[metadata.bone_index as usize] = true;
             }
-        });
+        })?;
 
         #[cfg(feature = "physics")]
         let mut physics_model_context = None;
@@ -179,7 +181,7 @@ impl MmdModel {
         {
             if build_physics {
                 physics_model_context = Some(
-                    physics_runtime.create_physics_context(&bone_arena, reader, diagnostic)
+                    physics_runtime.create_physics_context(&bone_arena, reader, diagnostic)?
                 );
             }
         }
@@ -214,7 +216,7 @@ impl MmdModel {
             }
         }
 
-        MmdModel {
+        Ok(MmdModel {
             runtime_animation: None,
             animation_arena,
             bone_arena: MmdRuntimeBoneArena::new(bone_arena),
@@ -224,10 +226,11 @@ impl MmdModel {
             sorted_runtime_bones: sorted_runtime_bones.into_boxed_slice(),
             bone_stack: Some(Vec::with_capacity(bone_max_depth as usize)),
             external_physics: false,
+            profile: None,
 
             #[cfg(feature = "physics")]
             physics_model_context,
-        }
+        })
     }
 
     #[inline]
@@ -258,6 +261,24 @@ impl MmdModel {
         &mut self.bone_arena
     }
 
+    pub(crate) fn set_profiling_enabled(&mut self, enabled: bool) {
+        if enabled == self.profile.is_some() {
+            return;
+        }
+        self.profile = if enabled { Some(ModelProfile::default()) } else { None };
+    }
+
+    #[inline]
+    pub(crate) fn profile(&self) -> Option<&ModelProfile> {
+        self.profile.as_ref()
+    }
+
+    #[inline]
+    #[cfg(feature = "physics")]
+    pub(crate) fn profile_mut(&mut self) -> Option<&mut ModelProfile> {
+        self.profile.as_mut()
+    }
+
     #[inline]
     pub(crate) fn use_external_physics(&mut self, rigidbody_state_size: u32) {
         self.external_physics = true;
@@ -277,6 +298,11 @@ impl MmdModel {
     }
 
     pub(crate) fn before_physics(&mut self, frame_time: Option<f32>) {
+        let mut timer = StageTimer::new(self.profile.is_some());
+        if let Some(profile) = self.profile.as_mut() {
+            *profile = ModelProfile::default();
+        }
+
         if let Some(frame_time) = frame_time {
             if let Some(runtime_animation) = self.runtime_animation {
                 let runtime_animation: &mut MmdRuntimeAnimation = unsafe {
@@ -300,7 +326,10 @@ impl MmdModel {
             }
         }
 
+        let animation_time = timer.lap();
+
         self.morph_controller.update(&mut self.bone_arena, self.animation_arena.morph_arena());
+        let morph_time = timer.lap();
         
         self.bone_arena.reset_world_matrices();
         for i in 0..self.sorted_runtime_bones.len() {
@@ -310,10 +339,25 @@ impl MmdModel {
         }
         self.append_transform_solver_arena.reset_state();
         self.update(false);
+        let bone_update_time = timer.lap();
+
+        if let Some(profile) = self.profile.as_mut() {
+            profile.animation = animation_time;
+            profile.morph = morph_time;
+            profile.bone_update = bone_update_time - profile.ik;
+        }
     }
 
     pub(crate) fn after_physics(&mut self) {
+        let mut timer = StageTimer::new(self.profile.is_some());
+        let ik_time = self.profile.as_ref().map_or(0.0, |profile| profile.ik);
+
         self.update(true);
+
+        let bone_update_time = timer.lap();
+        if let Some(profile) = self.profile.as_mut() {
+            profile.bone_update += bone_update_time - (profile.ik - ik_time);
+        }
     }
 
     fn update(&mut self, after_physics_stage: bool) {
@@ -357,4 +401,16 @@ impl MmdModel {
             physics_model_context.commit_body_states(&*self.animation_arena.rigidbody_state_arena());
         }
     }
+
+    pub(crate) fn store_ragdoll_follower_bones(&mut self) {
+        if let Some(physics_model_context) = self.physics_model_context.as_mut() {
+            physics_model_context.store_ragdoll_follower_bones(&self.bone_arena.world_matrices());
+        }
+    }
+
+    pub(crate) fn restore_ragdoll_follower_bones(&mut self) {
+        if let Some(physics_model_context) = self.physics_model_context.as_ref() {
+            physics_model_context.restore_ragdoll_follower_bones(&mut self.bone_arena.world_matrices_mut());
+        }
+    }
 }
@@ -2,6 +2,45 @@ use byte_slice_cast::{AsSliceOf, FromByteSlice};
 use glam::{Mat4, Quat, Vec3A, Vec4};
 use num_traits::FromBytes;
 
+pub(crate) const METADATA_MAGIC: u32 = u32::from_le_bytes(*b"MMDM");
+pub(crate) const METADATA_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum MetadataSectionKind {
+    Bones = 0,
+    Morphs = 1,
+    Physics = 2,
+    Extensions = 3,
+}
+
+#[derive(Debug)]
+pub(crate) enum MetadataError {
+    UnexpectedEnd {
+        offset: usize,
+        size: usize,
+    },
+    InvalidMagic(u32),
+    UnsupportedVersion(u32),
+    InvalidSection(u32),
+    MissingSection(MetadataSectionKind),
+    InvalidMorphKind(u8),
+    InvalidPhysicsInfoKind(u8),
+}
+
+impl std::fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataError::UnexpectedEnd { offset, size } => write!(f, "Unexpected end of metadata: tried to read {} bytes at offset {}", size, offset),
+            MetadataError::InvalidMagic(magic) => write!(f, "Invalid metadata magic: 0x{:08x}", magic),
+            MetadataError::UnsupportedVersion(version) => write!(f, "Unsupported metadata version: {} (expected {})", version, METADATA_VERSION),
+            MetadataError::InvalidSection(index) => write!(f, "Invalid metadata section table entry: {}", index),
+            MetadataError::MissingSection(kind) => write!(f, "Missing metadata section: {}", *kind as u32),
+            MetadataError::InvalidMorphKind(kind) => write!(f, "Invalid morph kind: {}", kind),
+            MetadataError::InvalidPhysicsInfoKind(kind) => write!(f, "Invalid physics info kind: {}", kind),
+        }
+    }
+}
+
 pub(crate) struct MetadataBuffer<'a> {
     bytes: &'a [u8],
     offset: usize,
@@ -15,97 +54,214 @@ impl<'a> MetadataBuffer<'a> {
         }
     }
 
-    fn read<'b, T>(&'b mut self) -> T
+    #[inline]
+    fn check(&self, size: usize) -> Result<std::ops::Range<usize>, MetadataError> {
+        match self.offset.checked_add(size) {
+            Some(end) if end <= self.bytes.len() => Ok(self.offset..end),
+            _ => Err(MetadataError::UnexpectedEnd {
+                offset: self.offset,
+                size,
+            }),
+        }
+    }
+
+    #[inline]
+    fn check_array(&self, element_size: usize, n: usize) -> Result<std::ops::Range<usize>, MetadataError> {
+        match element_size.checked_mul(n) {
+            Some(size) => self.check(size),
+            None => Err(MetadataError::UnexpectedEnd {
+                offset: self.offset,
+                size: usize::MAX,
+            }),
+        }
+    }
+
+    fn skip(&mut self, size: usize) -> Result<(), MetadataError> {
+        let range = self.check(size)?;
+        self.offset = range.end;
+        Ok(())
+    }
+
+    fn read<'b, T>(&'b mut self) -> Result<T, MetadataError>
     where
         T: FromBytes,
         <T as FromBytes>::Bytes: 'b,
         &'b [u8]: TryInto<&'b <T as FromBytes>::Bytes>,
         <&'b [u8] as TryInto<&'b <T as FromBytes>::Bytes>>::Error: std::fmt::Debug,
     {
-        let value = T::from_le_bytes(self.bytes[self.offset..self.offset + std::mem::size_of::<T>()].as_ref().try_into().unwrap());
-        self.offset += std::mem::size_of::<T>();
-        value
+        let range = self.check(std::mem::size_of::<T>())?;
+        self.offset = range.end;
+        let value = T::from_le_bytes(self.bytes[range].as_ref().try_into().unwrap());
+        Ok(value)
     }
 
-    fn read_array<'b, T>(&'b mut self, n: usize) -> Vec<T>
+    fn read_array<'b, T>(&'b mut self, n: usize) -> Result<Vec<T>, MetadataError>
     where
         T: FromByteSlice + Clone + num_traits::FromBytes,
         <T as FromBytes>::Bytes: 'b,
         &'b [u8]: TryInto<&'b <T as FromBytes>::Bytes>,
         <&'b [u8] as TryInto<&'b <T as FromBytes>::Bytes>>::Error: std::fmt::Debug,
     {
-        let vec = match self.bytes[self.offset..self.offset + std::mem::size_of::<T>() * n].as_ref().as_slice_of() {
+        let range = self.check_array(std::mem::size_of::<T>(), n)?;
+        self.offset = range.end;
+        let bytes = &self.bytes[range];
+        let vec = match bytes.as_slice_of() {
             Ok(slice) => slice.to_vec(),
             Err(_) => {
                 let mut vec = Vec::with_capacity(n);
-                for _ in 0..n {
-                    vec.push(T::from_le_bytes(self.bytes[self.offset..self.offset + std::mem::size_of::<T>()].as_ref().try_into().unwrap()));
+                for chunk in bytes.chunks_exact(std::mem::size_of::<T>()) {
+                    vec.push(T::from_le_bytes(chunk.try_into().unwrap()));
                 }
                 vec
             }
         };
-        self.offset += std::mem::size_of::<T>() * n;
-        vec
+        Ok(vec)
     }
 
-    fn read_vector(&mut self) -> Vec3A {
-        let value = match self.bytes[self.offset..self.offset + std::mem::size_of::<f32>() * 3].as_ref().as_slice_of() {
+    fn read_vector(&mut self) -> Result<Vec3A, MetadataError> {
+        let range = self.check(std::mem::size_of::<f32>() * 3)?;
+        let value = match self.bytes[range.clone()].as_slice_of() {
             Ok(slice) => {
-                self.offset += std::mem::size_of::<f32>() * 3;
+                self.offset = range.end;
                 Vec3A::from_slice(slice)
             },
             Err(_) => {
                 Vec3A::new(
-                    self.read::<f32>(),
-                    self.read::<f32>(),
-                    self.read::<f32>(),
+                    self.read::<f32>()?,
+                    self.read::<f32>()?,
+                    self.read::<f32>()?,
                 )
             }
         };
-        value
+        Ok(value)
     }
 
-    fn read_vector_array(&mut self, n: usize) -> Vec<Vec3A> {
+    fn read_vector_array(&mut self, n: usize) -> Result<Vec<Vec3A>, MetadataError> {
+        let range = self.check_array(std::mem::size_of::<f32>() * 3, n)?;
         let mut values = Vec::with_capacity(n);
-        match self.bytes[self.offset..self.offset + std::mem::size_of::<f32>() * 3 * n].as_ref().as_slice_of() {
+        match self.bytes[range.clone()].as_slice_of() {
             Ok(slice) => {
                 for i in 0..n {
                     values.push(Vec3A::from_slice(&slice[i * 3..i * 3 + 3]));
                 }
-                self.offset += std::mem::size_of::<f32>() * 3 * n;
+                self.offset = range.end;
             },
             Err(_) => {
                 for _ in 0..n {
-                    values.push(self.read_vector());
+                    values.push(self.read_vector()?);
                 }
             }
         };
-        values
+        Ok(values)
     }
 
-    fn read_quaternion_array(&mut self, n: usize) -> Vec<Quat> {
+    fn read_quaternion_array(&mut self, n: usize) -> Result<Vec<Quat>, MetadataError> {
+        let range = self.check_array(std::mem::size_of::<f32>() * 4, n)?;
         let mut values = Vec::with_capacity(n);
-        match self.bytes[self.offset..self.offset + std::mem::size_of::<f32>() * 4 * n].as_ref().as_slice_of() {
+        match self.bytes[range.clone()].as_slice_of() {
             Ok(slice) => {
                 for i in 0..n {
                     values.push(Quat::from_slice(&slice[i * 4..i * 4 + 4]));
                 }
-                self.offset += std::mem::size_of::<f32>() * 4 * n;
+                self.offset = range.end;
             },
             Err(_) => {
                 for _ in 0..n {
                     values.push(
                         Quat::from_xyzw(
-                            self.read::<f32>(),
-                            self.read::<f32>(),
-                            self.read::<f32>(),
-                            self.read::<f32>(),
+                            self.read::<f32>()?,
+                            self.read::<f32>()?,
+                            self.read::<f32>()?,
+                            self.read::<f32>()?,
                         )
                     );
                 }
             }
         };
-        values
+        Ok(values)
+    }
+
+    fn read_matrix(&mut self) -> Result<Mat4, MetadataError> {
+        Ok(Mat4::from_cols(
+            Vec4::new(self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?),
+            Vec4::new(self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?),
+            Vec4::new(self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?),
+            Vec4::new(self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?, self.read::<f32>()?),
+        ))
+    }
+}
+
+/// Sections of the serialized metadata
+///
+/// Metadata starts with a header (magic, version, section table) and
+/// each section is addressed by its offset and size from the start of the buffer
+pub(crate) struct MetadataSections<'a> {
+    morphs: MetadataBuffer<'a>,
+    physics: MetadataBuffer<'a>,
+    extensions: ExtensionChunkTable<'a>,
+}
+
+impl<'a> MetadataSections<'a> {
+    fn read(mut buffer: MetadataBuffer<'a>) -> Result<(MetadataBuffer<'a>, Self), MetadataError> {
+        let magic = buffer.read::<u32>()?;
+        if magic != METADATA_MAGIC {
+            return Err(MetadataError::InvalidMagic(magic));
+        }
+        let version = buffer.read::<u32>()?;
+        if version != METADATA_VERSION {
+            return Err(MetadataError::UnsupportedVersion(version));
+        }
+
+        let bytes = buffer.bytes;
+        let mut bones = None;
+        let mut morphs = None;
+        let mut physics = None;
+        let mut extensions = None;
+
+        let section_count = buffer.read::<u32>()?;
+        for i in 0..section_count {
+            let kind = buffer.read::<u32>()?;
+            let offset = buffer.read::<u32>()? as usize;
+            let size = buffer.read::<u32>()? as usize;
+
+            let section_bytes = match offset.checked_add(size) {
+                Some(end) if end <= bytes.len() => &bytes[offset..end],
+                _ => return Err(MetadataError::InvalidSection(i)),
+            };
+
+            let section = if kind == MetadataSectionKind::Bones as u32 {
+                &mut bones
+            } else if kind == MetadataSectionKind::Morphs as u32 {
+                &mut morphs
+            } else if kind == MetadataSectionKind::Physics as u32 {
+                &mut physics
+            } else if kind == MetadataSectionKind::Extensions as u32 {
+                &mut extensions
+            } else {
+                // unknown sections are ignored for forward compatibility
+                continue;
+            };
+            if section.is_some() {
+                return Err(MetadataError::InvalidSection(i));
+            }
+            *section = Some(MetadataBuffer::new(section_bytes));
+        }
+
+        let bones = bones.ok_or(MetadataError::MissingSection(MetadataSectionKind::Bones))?;
+        let morphs = morphs.ok_or(MetadataError::MissingSection(MetadataSectionKind::Morphs))?;
+        let physics = physics.ok_or(MetadataError::MissingSection(MetadataSectionKind::Physics))?;
+
+        // extensions section is optional
+        let extensions = match extensions {
+            Some(extensions) => ExtensionChunkTable::read(extensions)?,
+            None => ExtensionChunkTable::empty(),
+        };
+
+        Ok((bones, Self {
+            morphs,
+            physics,
+            extensions,
+        }))
     }
 }
 
@@ -143,42 +299,93 @@ pub(crate) struct IkChainAngleLimits {
 }
 
 pub(crate) enum BoneFlag {
-    // UseBoneIndexAsTailPosition = 0x0001,
+    UseBoneIndexAsTailPosition = 0x0001,
 
-    // IsRotatable = 0x0002,
-    // IsMovable = 0x0004,
-    // IsVisible = 0x0008,
-    // IsControllable = 0x0010,
+    IsRotatable = 0x0002,
+    IsMovable = 0x0004,
+    IsVisible = 0x0008,
+    IsControllable = 0x0010,
     IsIkEnabled = 0x0020,
 
     LocalAppendTransform = 0x0080,
     HasAppendRotate = 0x0100,
     HasAppendMove = 0x0200,
     HasAxisLimit = 0x0400,
-    // HasLocalVector = 0x0800,
+    HasLocalVector = 0x0800,
     TransformAfterPhysics = 0x1000,
-    // IsExternalParentTransformed = 0x2000,
+    IsExternalParentTransformed = 0x2000,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)] // only used in physics build
+pub(crate) enum ExtensionChunkTag {
+    Ragdoll = 2,
+    Ccd = 3,
+}
+
+/// Tagged, length-prefixed extension chunks of the extensions section
+///
+/// Each chunk is `tag: u32, size: u32, payload: u8[size]` and padded to 4 bytes.
+/// Chunk headers are validated once when the metadata is opened,
+/// chunks with unknown tag are never read so older runtimes can read newer metadata
+pub(crate) struct ExtensionChunkTable<'a> {
+    chunks: Vec<(u32, &'a [u8])>,
+}
+
+impl<'a> ExtensionChunkTable<'a> {
+    fn read(mut buffer: MetadataBuffer<'a>) -> Result<Self, MetadataError> {
+        let mut chunks = Vec::new();
+        while buffer.offset < buffer.bytes.len() {
+            let tag = buffer.read::<u32>()?;
+            let size = buffer.read::<u32>()? as usize;
+            let range = buffer.check(size)?;
+            chunks.push((tag, &buffer.bytes[range.clone()]));
+            buffer.offset = range.end + (4 - size % 4) % 4; // padding
+        }
+        Ok(Self {
+            chunks,
+        })
+    }
+
+    fn empty() -> Self {
+        Self {
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Find the first chunk with the given tag and return its payload
+    #[allow(dead_code)] // only used in physics build
+    pub(crate) fn find(&self, tag: ExtensionChunkTag) -> Option<MetadataBuffer<'a>> {
+        self.chunks
+            .iter()
+            .find(|(chunk_tag, _)| *chunk_tag == tag as u32)
+            .map(|(_, payload)| MetadataBuffer::new(payload))
+    }
 }
 
 pub(crate) struct BoneMetadataReader<'a> {
     buffer: MetadataBuffer<'a>,
+    sections: MetadataSections<'a>,
     bone_count: u32,
     append_transform_count: u32,
     ik_count: u32,
 }
 
 impl<'a> BoneMetadataReader<'a> {
-    pub(crate) fn new(mut buffer: MetadataBuffer<'a>) -> Self {
-        let bone_count = buffer.read::<u32>();
-        let append_transform_count = buffer.read::<u32>();
-        let ik_count = buffer.read::<u32>();
+    pub(crate) fn new(buffer: MetadataBuffer<'a>) -> Result<Self, MetadataError> {
+        let (mut buffer, sections) = MetadataSections::read(buffer)?;
 
-        Self {
+        let bone_count = buffer.read::<u32>()?;
+        let append_transform_count = buffer.read::<u32>()?;
+        let ik_count = buffer.read::<u32>()?;
+
+        Ok(Self {
             buffer,
+            sections,
             bone_count,
             append_transform_count,
             ik_count,
-        }
+        })
     }
 
     pub(crate) fn bone_count(&self) -> u32 {
@@ -193,78 +400,81 @@ impl<'a> BoneMetadataReader<'a> {
         self.ik_count
     }
 
-    pub(crate) fn enumerate(mut self, mut f: impl FnMut(u32, BoneMetadata)) -> MorphMetadataReader<'a> {
+    fn read_bone(&mut self) -> Result<BoneMetadata, MetadataError> {
+        let rest_position = self.buffer.read_vector()?;
+        let absolute_inverse_bind_matrix = self.buffer.read_matrix()?;
+        let parent_bone_index = self.buffer.read::<i32>()?;
+        let transform_order = self.buffer.read::<i32>()?;
+        let flag = self.buffer.read::<u16>()?;
+        self.buffer.skip(2)?; // padding
+        let append_transform = if flag & BoneFlag::HasAppendMove as u16 != 0 || flag & BoneFlag::HasAppendRotate as u16 != 0 {
+            Some(AppendTransformMetadata {
+                parent_index: self.buffer.read::<i32>()?,
+                ratio: self.buffer.read::<f32>()?,
+            })
+        } else {
+            None
+        };
+        let axis_limit = if flag & BoneFlag::HasAxisLimit as u16 != 0 {
+            Some(self.buffer.read_vector()?)
+        } else {
+            None
+        };
+        let ik = if flag & BoneFlag::IsIkEnabled as u16 != 0 {
+            Some(Box::new(IkMetadata {
+                target: self.buffer.read::<i32>()?,
+                iteration: self.buffer.read::<i32>()?,
+                rotation_constraint: self.buffer.read::<f32>()?,
+                links: {
+                    let link_count = self.buffer.read::<u32>()?;
+                    // each link is at least 8 bytes, so this also prevents huge allocation from broken data
+                    self.buffer.check_array(8, link_count as usize)?;
+                    let mut links = Vec::with_capacity(link_count as usize);
+                    for _ in 0..link_count {
+                        let target = self.buffer.read::<i32>()?;
+                        let has_limits = self.buffer.read::<u8>()? != 0;
+                        self.buffer.skip(3)?; // padding
+
+                        let limits = if has_limits {
+                            Some(IkChainAngleLimits {
+                                minimum_angle: self.buffer.read_vector()?,
+                                maximum_angle: self.buffer.read_vector()?,
+                            })
+                        } else {
+                            None
+                        };
+
+                        links.push(IkLinkMetadata {
+                            target,
+                            limits,
+                        });
+                    }
+                    links
+                },
+            }))
+        } else {
+            None
+        };
+
+        Ok(BoneMetadata {
+            rest_position,
+            absolute_inverse_bind_matrix,
+            parent_bone_index,
+            transform_order,
+            flag,
+            append_transform,
+            axis_limit,
+            ik,
+        })
+    }
+
+    pub(crate) fn enumerate(mut self, mut f: impl FnMut(u32, BoneMetadata)) -> Result<MorphMetadataReader<'a>, MetadataError> {
         for i in 0..self.bone_count {
-            let rest_position = self.buffer.read_vector();
-            let absolute_inverse_bind_matrix = Mat4::from_cols(
-                Vec4::new(self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>()),
-                Vec4::new(self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>()),
-                Vec4::new(self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>()),
-                Vec4::new(self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>(), self.buffer.read::<f32>()),
-            );
-            let parent_bone_index = self.buffer.read::<i32>();
-            let transform_order = self.buffer.read::<i32>();
-            let flag = self.buffer.read::<u16>();
-            self.buffer.offset += 2; // padding
-            let append_transform = if flag & BoneFlag::HasAppendMove as u16 != 0 || flag & BoneFlag::HasAppendRotate as u16 != 0 {
-                Some(AppendTransformMetadata {
-                    parent_index: self.buffer.read::<i32>(),
-                    ratio: self.buffer.read::<f32>(),
-                })
-            } else {
-                None
-            };
-            let axis_limit = if flag & BoneFlag::HasAxisLimit as u16 != 0 {
-                Some(self.buffer.read_vector())
-            } else {
-                None
-            };
-            let ik = if flag & BoneFlag::IsIkEnabled as u16 != 0 {
-                Some(Box::new(IkMetadata {
-                    target: self.buffer.read::<i32>(),
-                    iteration: self.buffer.read::<i32>(),
-                    rotation_constraint: self.buffer.read::<f32>(),
-                    links: {
-                        let link_count = self.buffer.read::<u32>();
-                        let mut links = Vec::with_capacity(link_count as usize);
-                        for _ in 0..link_count {
-                            let target = self.buffer.read::<i32>();
-                            let has_limits = self.buffer.read::<u8>() != 0;
-                            self.buffer.offset += 3; // padding
-
-                            let limits = if has_limits {
-                                Some(IkChainAngleLimits {
-                                    minimum_angle: self.buffer.read_vector(),
-                                    maximum_angle: self.buffer.read_vector(),
-                                })
-                            } else {
-                                None
-                            };
-
-                            links.push(IkLinkMetadata {
-                                target,
-                                limits,
-                            });
-                        }
-                        links
-                    },
-                }))
-            } else {
-                None
-            };
-            f(i, BoneMetadata {
-                rest_position,
-                absolute_inverse_bind_matrix,
-                parent_bone_index,
-                transform_order,
-                flag,
-                append_transform,
-                axis_limit,
-                ik,
-            });
+            let metadata = self.read_bone()?;
+            f(i, metadata);
         }
 
-        MorphMetadataReader::new(self.buffer)
+        MorphMetadataReader::new(self.sections)
     }
 }
 
@@ -284,61 +494,68 @@ pub(crate) struct GroupMorphMetadata {
     pub(crate) ratios: Vec<f32>,
 }
 
-enum MorphKind {
+pub(crate) enum MorphKind {
     GroupMorph = 0,
     BoneMorph = 2,
 }
 
 pub(crate) struct MorphMetadataReader<'a> {
     buffer: MetadataBuffer<'a>,
+    physics_buffer: MetadataBuffer<'a>,
+    extensions: ExtensionChunkTable<'a>,
     count: u32,
 }
 
 impl<'a> MorphMetadataReader<'a> {
-    fn new(mut buffer: MetadataBuffer<'a>) -> Self {
-        let count = buffer.read::<u32>();
+    fn new(sections: MetadataSections<'a>) -> Result<Self, MetadataError> {
+        let mut buffer = sections.morphs;
+        let count = buffer.read::<u32>()?;
 
-        Self {
+        Ok(Self {
             buffer,
+            physics_buffer: sections.physics,
+            extensions: sections.extensions,
             count,
-        }
+        })
     }
 
     // pub(crate) fn count(&self) -> u32 {
     //     self.count
     // }
 
-    pub(crate) fn read(mut self) -> (Vec<MorphMetadata>, RigidBodyMetadataReader<'a>) {
+    pub(crate) fn read(mut self) -> Result<(Vec<MorphMetadata>, RigidBodyMetadataReader<'a>), MetadataError> {
+        // each morph is at least 8 bytes, so this also prevents huge allocation from broken data
+        self.buffer.check_array(8, self.count as usize)?;
         let mut morphs = Vec::with_capacity(self.count as usize);
 
         for _ in 0..self.count {
-            let kind = self.buffer.read::<u8>();
-            self.buffer.offset += 3; // padding
+            let kind = self.buffer.read::<u8>()?;
+            self.buffer.skip(3)?; // padding
 
             if kind == MorphKind::BoneMorph as u8 {
-                let morph_count = self.buffer.read::<i32>();
-                let indices = self.buffer.read_array::<i32>(morph_count as usize);
-                let positions = self.buffer.read_vector_array(morph_count as usize);
-                let rotations = self.buffer.read_quaternion_array(morph_count as usize);
+                let morph_count = self.buffer.read::<u32>()?;
+                let indices = self.buffer.read_array::<i32>(morph_count as usize)?;
+                let positions = self.buffer.read_vector_array(morph_count as usize)?;
+                let rotations = self.buffer.read_quaternion_array(morph_count as usize)?;
                 morphs.push(MorphMetadata::Bone(BoneMorphMetadata {
                     indices,
                     positions,
                     rotations,
                 }));
             } else if kind == MorphKind::GroupMorph as u8 {
-                let morph_count = self.buffer.read::<i32>();
-                let indices = self.buffer.read_array::<i32>(morph_count as usize);
-                let ratios = self.buffer.read_array::<f32>(morph_count as usize);
+                let morph_count = self.buffer.read::<u32>()?;
+                let indices = self.buffer.read_array::<i32>(morph_count as usize)?;
+                let ratios = self.buffer.read_array::<f32>(morph_count as usize)?;
                 morphs.push(MorphMetadata::Group(GroupMorphMetadata {
                     indices,
                     ratios,
                 }));
             } else {
-                unreachable!()
+                return Err(MetadataError::InvalidMorphKind(kind));
             }
         }
 
-        (morphs, RigidBodyMetadataReader::new(self.buffer))
+        Ok((morphs, RigidBodyMetadataReader::new(self.physics_buffer, self.extensions)?))
     }
 }
 
@@ -349,6 +566,27 @@ pub(crate) enum PhysicsInfoKind {
     FullPhysics = 2,
 }
 
+const STRIPED_RIGIDBODY_SIZE: usize =
+    4 + // bone_index
+    1 + // physics_mode
+    3; // padding
+
+const FULL_RIGIDBODY_SIZE: usize =
+    4 + // bone_index
+    1 + // collision_group
+    1 + // shape_type
+    2 + // collision_mask
+    4 * 4 + // shape_size
+    3 * 4 + // shape_position
+    3 * 4 + // shape_rotation
+    4 + // mass
+    4 + // linear_damping
+    4 + // angular_damping
+    4 + // repulsion
+    4 + // friction
+    1 + // physics_mode
+    3; // padding
+
 // for non physics build
 
 #[cfg(not(feature = "physics"))]
@@ -366,15 +604,16 @@ pub(crate) struct RigidBodyMetadata {
 #[cfg(not(feature = "physics"))]
 pub(crate) struct RigidBodyMetadataReader<'a> {
     buffer: MetadataBuffer<'a>,
+    buffer_start_offset: usize,
     physics_info_kind: PhysicsInfoKind,
     count: u32,
 }
 
 #[cfg(not(feature = "physics"))]
 impl<'a> RigidBodyMetadataReader<'a> {
-    fn new(mut buffer: MetadataBuffer<'a>) -> Self {
-        let physics_info_kind = buffer.read::<u8>();
-        buffer.offset += 3; // padding
+    fn new(mut buffer: MetadataBuffer<'a>, _extensions: ExtensionChunkTable<'a>) -> Result<Self, MetadataError> {
+        let physics_info_kind = buffer.read::<u8>()?;
+        buffer.skip(3)?; // padding
 
         let kind: PhysicsInfoKind;
         let count: u32;
@@ -384,25 +623,32 @@ impl<'a> RigidBodyMetadataReader<'a> {
             count = 0;
         } else if physics_info_kind == PhysicsInfoKind::StripedRigidbodies as u8 {
             kind = PhysicsInfoKind::StripedRigidbodies;
-            count = buffer.read::<u32>();
+            count = buffer.read::<u32>()?;
+            buffer.check_array(STRIPED_RIGIDBODY_SIZE, count as usize)?;
         } else if physics_info_kind == PhysicsInfoKind::FullPhysics as u8 {
             // skip physics world info
-            buffer.offset += 4; // physics world id (u32)
-            let kinematic_shared_physics_world_id_count = buffer.read::<u32>();
-            buffer.offset += 4 * kinematic_shared_physics_world_id_count as usize;
-            buffer.offset += 16 * 4; // model initial world matrix
-            
+            buffer.skip(4)?; // physics world id (u32)
+            let kinematic_shared_physics_world_id_count = buffer.read::<u32>()?;
+            buffer.check_array(4, kinematic_shared_physics_world_id_count as usize)?;
+            buffer.skip(4 * kinematic_shared_physics_world_id_count as usize)?;
+            buffer.skip(16 * 4)?; // model initial world matrix
+            buffer.skip(4)?; // disable offset for constraint frame + padding
+
             kind = PhysicsInfoKind::FullPhysics;
-            count = buffer.read::<u32>();
+            count = buffer.read::<u32>()?;
+            buffer.check_array(FULL_RIGIDBODY_SIZE, count as usize)?;
         } else {
-            panic!("Invalid physics info kind");
+            return Err(MetadataError::InvalidPhysicsInfoKind(physics_info_kind));
         }
-        
-        Self {
+
+        let buffer_start_offset = buffer.offset;
+
+        Ok(Self {
             buffer,
+            buffer_start_offset,
             physics_info_kind: kind,
             count,
-        }
+        })
     }
 
     pub(crate) fn physics_info_kind(&self) -> PhysicsInfoKind {
@@ -413,14 +659,16 @@ impl<'a> RigidBodyMetadataReader<'a> {
     //     self.count
     // }
 
-    pub(crate) fn enumerate(&mut self, mut f: impl FnMut(u32, RigidBodyMetadata)) {
+    pub(crate) fn enumerate(&mut self, mut f: impl FnMut(u32, RigidBodyMetadata)) -> Result<(), MetadataError> {
+        self.buffer.offset = self.buffer_start_offset;
+
         match self.physics_info_kind {
             PhysicsInfoKind::NoPhysics => {},
             PhysicsInfoKind::StripedRigidbodies => {
                 for i in 0..self.count {
-                    let bone_index = self.buffer.read::<i32>();
-                    let physics_mode = self.buffer.read::<u8>();
-                    self.buffer.offset += 3; // padding
+                    let bone_index = self.buffer.read::<i32>()?;
+                    let physics_mode = self.buffer.read::<u8>()?;
+                    self.buffer.skip(3)?; // padding
                     f(i, RigidBodyMetadata {
                         bone_index,
                         physics_mode,
@@ -429,8 +677,8 @@ impl<'a> RigidBodyMetadataReader<'a> {
             },
             PhysicsInfoKind::FullPhysics => {
                 for i in 0..self.count {
-                    let bone_index = self.buffer.read::<i32>();
-                    self.buffer.offset +=
+                    let bone_index = self.buffer.read::<i32>()?;
+                    self.buffer.skip(
                         1 + // collision_group
                         1 + // shape_type
                         2 + // collision_mask
@@ -441,9 +689,10 @@ impl<'a> RigidBodyMetadataReader<'a> {
                         4 + // linear_damping
                         4 + // angular_damping
                         4 + // repulsion
-                        4; // friction
-                    let physics_mode = self.buffer.read::<u8>();
-                    self.buffer.offset += 3; // padding
+                        4 // friction
+                    )?;
+                    let physics_mode = self.buffer.read::<u8>()?;
+                    self.buffer.skip(3)?; // padding
                     f(i, RigidBodyMetadata {
                         bone_index,
                         physics_mode,
@@ -451,6 +700,8 @@ impl<'a> RigidBodyMetadataReader<'a> {
                 }
             },
         }
+
+        Ok(())
     }
 }
 
@@ -473,12 +724,39 @@ pub(crate) struct RigidBodyMetadata {
     pub(crate) physics_mode: u8,
 }
 
+/// Presence of this data makes the model able to switch into ragdoll mode
+///
+/// Ragdoll bodies are derived from the follow bone bodies and the bone hierarchy when the model is created
+#[cfg(feature = "physics")]
+pub(crate) struct RagdollMetadata {
+    /// Mass of follow bone bodies that have zero mass and of generated bodies
+    pub(crate) default_mass: f32,
+    /// Angular limits of the joints generated between ragdoll bodies
+    pub(crate) angular_lower_limit: Vec3A,
+    pub(crate) angular_upper_limit: Vec3A,
+    /// Radius of generated capsules relative to the bone length
+    pub(crate) radius_scale: f32,
+    /// Bones shorter than this get no generated body and follow their parent body
+    pub(crate) min_bone_length: f32,
+}
+
+/// Continuous collision detection parameters relative to the size of each body
+#[cfg(feature = "physics")]
+pub(crate) struct CcdMetadata {
+    /// Zero disables CCD
+    pub(crate) motion_threshold_scale: f32,
+    pub(crate) swept_sphere_radius_scale: f32,
+}
+
 #[cfg(feature = "physics")]
 pub(crate) enum RigidBodyShapeType {
     Sphere = 0,
     Box = 1,
     Capsule = 2,
+    Cylinder = 3,
+    Cone = 4,
     StaticPlane = 5,
+    // convex hull and compound shapes need more data than shape size, so they are only available from the physics runtime api
 }
 
 #[cfg(feature = "physics")]
@@ -500,13 +778,14 @@ pub(crate) struct RigidBodyMetadataReader<'a> {
     model_initial_world_matrix: Mat4,
     disable_offset_for_constraint_frame: bool,
     count: u32,
+    extensions: ExtensionChunkTable<'a>,
 }
 
 #[cfg(feature = "physics")]
 impl<'a> RigidBodyMetadataReader<'a> {
-    fn new(mut buffer: MetadataBuffer<'a>) -> Self {
-        let physics_info_kind = buffer.read::<u8>();
-        buffer.offset += 3; // padding
+    fn new(mut buffer: MetadataBuffer<'a>, extensions: ExtensionChunkTable<'a>) -> Result<Self, MetadataError> {
+        let physics_info_kind = buffer.read::<u8>()?;
+        buffer.skip(3)?; // padding
 
         let kind: PhysicsInfoKind;
         let mut physics_world_id = 0;
@@ -520,29 +799,25 @@ impl<'a> RigidBodyMetadataReader<'a> {
             count = 0;
         } else if physics_info_kind == PhysicsInfoKind::StripedRigidbodies as u8 {
             kind = PhysicsInfoKind::StripedRigidbodies;
-            count = buffer.read::<u32>();
+            count = buffer.read::<u32>()?;
+            buffer.check_array(STRIPED_RIGIDBODY_SIZE, count as usize)?;
         } else if physics_info_kind == PhysicsInfoKind::FullPhysics as u8 {
-            // skip physics world info
-            physics_world_id = buffer.read::<u32>();
-            let kinematic_shared_physics_world_id_count = buffer.read::<u32>();
-            kinematic_shared_physics_world_ids = buffer.read_array::<u32>(kinematic_shared_physics_world_id_count as usize);
-            model_initial_world_matrix = Mat4::from_cols(
-                Vec4::new(buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>()),
-                Vec4::new(buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>()),
-                Vec4::new(buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>()),
-                Vec4::new(buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>(), buffer.read::<f32>()),
-            );
-            disable_offset_for_constraint_frame = buffer.read::<u8>() != 0;
-            buffer.offset += 3; // padding
+            physics_world_id = buffer.read::<u32>()?;
+            let kinematic_shared_physics_world_id_count = buffer.read::<u32>()?;
+            kinematic_shared_physics_world_ids = buffer.read_array::<u32>(kinematic_shared_physics_world_id_count as usize)?;
+            model_initial_world_matrix = buffer.read_matrix()?;
+            disable_offset_for_constraint_frame = buffer.read::<u8>()? != 0;
+            buffer.skip(3)?; // padding
             kind = PhysicsInfoKind::FullPhysics;
-            count = buffer.read::<u32>();
+            count = buffer.read::<u32>()?;
+            buffer.check_array(FULL_RIGIDBODY_SIZE, count as usize)?;
         } else {
-            panic!("Invalid physics info kind");
+            return Err(MetadataError::InvalidPhysicsInfoKind(physics_info_kind));
         }
 
         let buffer_start_offset = buffer.offset;
-        
-        Self {
+
+        Ok(Self {
             buffer,
             buffer_start_offset,
             physics_info_kind: kind,
@@ -551,7 +826,8 @@ impl<'a> RigidBodyMetadataReader<'a> {
             model_initial_world_matrix,
             disable_offset_for_constraint_frame,
             count,
-        }
+            extensions,
+        })
     }
 
     pub(crate) fn physics_info_kind(&self) -> PhysicsInfoKind {
@@ -578,16 +854,45 @@ impl<'a> RigidBodyMetadataReader<'a> {
         self.count
     }
 
-    pub(crate) fn enumerate(&mut self, mut f: impl FnMut(u32, RigidBodyMetadata)) {
+    pub(crate) fn ragdoll(&self) -> Result<Option<RagdollMetadata>, MetadataError> {
+        let mut chunk = if let Some(chunk) = self.extensions.find(ExtensionChunkTag::Ragdoll) {
+            chunk
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(RagdollMetadata {
+            default_mass: chunk.read::<f32>()?,
+            angular_lower_limit: chunk.read_vector()?,
+            angular_upper_limit: chunk.read_vector()?,
+            radius_scale: chunk.read::<f32>()?,
+            min_bone_length: chunk.read::<f32>()?,
+        }))
+    }
+
+    pub(crate) fn ccd(&self) -> Result<Option<CcdMetadata>, MetadataError> {
+        let mut chunk = if let Some(chunk) = self.extensions.find(ExtensionChunkTag::Ccd) {
+            chunk
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(CcdMetadata {
+            motion_threshold_scale: chunk.read::<f32>()?,
+            swept_sphere_radius_scale: chunk.read::<f32>()?,
+        }))
+    }
+
+    pub(crate) fn enumerate(&mut self, mut f: impl FnMut(u32, RigidBodyMetadata)) -> Result<(), MetadataError> {
         self.buffer.offset = self.buffer_start_offset;
 
         match self.physics_info_kind {
             PhysicsInfoKind::NoPhysics => { },
             PhysicsInfoKind::StripedRigidbodies => {
                 for i in 0..self.count {
-                    let bone_index = self.buffer.read::<i32>();
-                    let physics_mode = self.buffer.read::<u8>();
-                    self.buffer.offset += 3; // padding
+                    let bone_index = self.buffer.read::<i32>()?;
+                    let physics_mode = self.buffer.read::<u8>()?;
+                    self.buffer.skip(3)?; // padding
                     f(i, RigidBodyMetadata {
                         bone_index,
                         collision_group: 0,
@@ -607,25 +912,25 @@ impl<'a> RigidBodyMetadataReader<'a> {
             },
             PhysicsInfoKind::FullPhysics => {
                 for i in 0..self.count {
-                    let bone_index = self.buffer.read::<i32>();
-                    let collision_group = self.buffer.read::<u8>();
-                    let shape_type = self.buffer.read::<u8>();
-                    let collision_mask = self.buffer.read::<u16>();
+                    let bone_index = self.buffer.read::<i32>()?;
+                    let collision_group = self.buffer.read::<u8>()?;
+                    let shape_type = self.buffer.read::<u8>()?;
+                    let collision_mask = self.buffer.read::<u16>()?;
                     let shape_size = Vec4::new(
-                        self.buffer.read::<f32>(), 
-                        self.buffer.read::<f32>(), 
-                        self.buffer.read::<f32>(), 
-                        self.buffer.read::<f32>()
+                        self.buffer.read::<f32>()?,
+                        self.buffer.read::<f32>()?,
+                        self.buffer.read::<f32>()?,
+                        self.buffer.read::<f32>()?
                     );
-                    let shape_position = self.buffer.read_vector();
-                    let shape_rotation = self.buffer.read_vector();
-                    let mass = self.buffer.read::<f32>();
-                    let linear_damping = self.buffer.read::<f32>();
-                    let angular_damping = self.buffer.read::<f32>();
-                    let repulsion = self.buffer.read::<f32>();
-                    let friction = self.buffer.read::<f32>();
-                    let physics_mode = self.buffer.read::<u8>();
-                    self.buffer.offset += 3; // padding
+                    let shape_position = self.buffer.read_vector()?;
+                    let shape_rotation = self.buffer.read_vector()?;
+                    let mass = self.buffer.read::<f32>()?;
+                    let linear_damping = self.buffer.read::<f32>()?;
+                    let angular_damping = self.buffer.read::<f32>()?;
+                    let repulsion = self.buffer.read::<f32>()?;
+                    let friction = self.buffer.read::<f32>()?;
+                    let physics_mode = self.buffer.read::<u8>()?;
+                    self.buffer.skip(3)?; // padding
                     f(i, RigidBodyMetadata {
                         bone_index,
                         collision_group,
@@ -644,14 +949,18 @@ impl<'a> RigidBodyMetadataReader<'a> {
                 }
             },
         }
+
+        Ok(())
     }
 
-    pub(crate) fn next(self) -> Option<JointMetadataReader<'a>> {
+    pub(crate) fn next(mut self) -> Result<Option<JointMetadataReader<'a>>, MetadataError> {
         match self.physics_info_kind {
-            PhysicsInfoKind::NoPhysics => None,
-            PhysicsInfoKind::StripedRigidbodies => None,
+            PhysicsInfoKind::NoPhysics => Ok(None),
+            PhysicsInfoKind::StripedRigidbodies => Ok(None),
             PhysicsInfoKind::FullPhysics => {
-                Some(JointMetadataReader::new(self.buffer))
+                // joint section starts right after the rigidbody records which are validated at new
+                self.buffer.offset = self.buffer_start_offset + FULL_RIGIDBODY_SIZE * self.count as usize;
+                Ok(Some(JointMetadataReader::new(self.buffer)?))
             },
         }
     }
@@ -675,13 +984,21 @@ pub(crate) struct JointMetadata {
 #[cfg(feature = "physics")]
 pub(crate) enum JointKind {
     Spring6Dof = 0,
-    // SixDof = 1,
-    // P2p = 2,
-    // ConeTwist = 3,
-    // Slider = 4,
-    // Hinge = 5,
+    SixDof = 1, // pmx 2.1 spec
+    P2p = 2, // pmx 2.1 spec
+    ConeTwist = 3, // pmx 2.1 spec
+    Slider = 4, // pmx 2.1 spec
+    Hinge = 5, // pmx 2.1 spec
 }
 
+#[cfg(feature = "physics")]
+const JOINT_SIZE: usize =
+    1 + // kind
+    3 + // padding
+    4 + // rigidbody_index_a
+    4 + // rigidbody_index_b
+    3 * 4 * 8; // position, rotation, position_min, position_max, rotation_min, rotation_max, spring_position, spring_rotation
+
 #[cfg(feature = "physics")]
 pub(crate) struct JointMetadataReader<'a> {
     buffer: MetadataBuffer<'a>,
@@ -690,13 +1007,14 @@ pub(crate) struct JointMetadataReader<'a> {
 
 #[cfg(feature = "physics")]
 impl<'a> JointMetadataReader<'a> {
-    fn new(mut buffer: MetadataBuffer<'a>) -> Self {
-        let count = buffer.read::<u32>();
+    fn new(mut buffer: MetadataBuffer<'a>) -> Result<Self, MetadataError> {
+        let count = buffer.read::<u32>()?;
+        buffer.check_array(JOINT_SIZE, count as usize)?;
 
-        Self {
+        Ok(Self {
             buffer,
             count,
-        }
+        })
     }
 
     #[inline]
@@ -704,20 +1022,20 @@ impl<'a> JointMetadataReader<'a> {
         self.count
     }
 
-    pub(crate) fn enumerate(&mut self, mut f: impl FnMut(u32, JointMetadata)) {
+    pub(crate) fn enumerate(&mut self, mut f: impl FnMut(u32, JointMetadata)) -> Result<(), MetadataError> {
         for i in 0..self.count {
-            let kind = self.buffer.read::<u8>();
-            self.buffer.offset += 3; // padding
-            let rigidbody_index_a = self.buffer.read::<i32>();
-            let rigidbody_index_b = self.buffer.read::<i32>();
-            let position = self.buffer.read_vector();
-            let rotation = self.buffer.read_vector();
-            let position_min = self.buffer.read_vector();
-            let position_max = self.buffer.read_vector();
-            let rotation_min = self.buffer.read_vector();
-            let rotation_max = self.buffer.read_vector();
-            let spring_position = self.buffer.read_vector();
-            let spring_rotation = self.buffer.read_vector();
+            let kind = self.buffer.read::<u8>()?;
+            self.buffer.skip(3)?; // padding
+            let rigidbody_index_a = self.buffer.read::<i32>()?;
+            let rigidbody_index_b = self.buffer.read::<i32>()?;
+            let position = self.buffer.read_vector()?;
+            let rotation = self.buffer.read_vector()?;
+            let position_min = self.buffer.read_vector()?;
+            let position_max = self.buffer.read_vector()?;
+            let rotation_min = self.buffer.read_vector()?;
+            let rotation_max = self.buffer.read_vector()?;
+            let spring_position = self.buffer.read_vector()?;
+            let spring_rotation = self.buffer.read_vector()?;
             f(i, JointMetadata {
                 kind,
                 rigidbody_index_a,
@@ -732,5 +1050,315 @@ impl<'a> JointMetadataReader<'a> {
                 spring_rotation,
             });
         }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Quat, Vec3A};
+    #[cfg(feature = "physics")]
+    use glam::Vec4;
+
+    use crate::loader::metadata_builder::{MetadataBuilder, PhysicsMetadata};
+    #[cfg(feature = "physics")]
+    use crate::loader::metadata_builder::FullPhysicsMetadata;
+
+    use super::*;
+
+    #[cfg(not(feature = "physics"))]
+    fn rigid_body(bone_index: i32) -> RigidBodyMetadata {
+        RigidBodyMetadata {
+            bone_index,
+            physics_mode: 1,
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    fn rigid_body(bone_index: i32) -> RigidBodyMetadata {
+        RigidBodyMetadata {
+            bone_index,
+            collision_group: 0,
+            collision_mask: 0xFFFF,
+            shape_type: RigidBodyShapeType::Capsule as u8,
+            shape_size: Vec4::new(0.5, 1.0, 0.0, 0.0),
+            shape_position: Vec3A::ZERO,
+            shape_rotation: Vec3A::ZERO,
+            mass: 1.0,
+            linear_damping: 0.5,
+            angular_damping: 0.5,
+            repulsion: 0.0,
+            friction: 0.5,
+            physics_mode: RigidBodyPhysicsMode::Physics as u8,
+        }
+    }
+
+    #[cfg(not(feature = "physics"))]
+    fn physics() -> PhysicsMetadata {
+        PhysicsMetadata::StripedRigidbodies(vec![rigid_body(0), rigid_body(1)])
+    }
+
+    #[cfg(feature = "physics")]
+    fn physics() -> PhysicsMetadata {
+        PhysicsMetadata::FullPhysics(FullPhysicsMetadata {
+            physics_world_id: 0,
+            kinematic_shared_physics_world_ids: vec![1],
+            model_initial_world_matrix: Mat4::IDENTITY,
+            disable_offset_for_constraint_frame: false,
+            rigid_bodies: vec![rigid_body(0), rigid_body(1)],
+            joints: vec![JointMetadata {
+                kind: JointKind::Spring6Dof as u8,
+                rigidbody_index_a: 0,
+                rigidbody_index_b: 1,
+                position: Vec3A::ZERO,
+                rotation: Vec3A::ZERO,
+                position_min: Vec3A::ZERO,
+                position_max: Vec3A::ZERO,
+                rotation_min: Vec3A::splat(-1.0),
+                rotation_max: Vec3A::splat(1.0),
+                spring_position: Vec3A::ZERO,
+                spring_rotation: Vec3A::ZERO,
+            }],
+            ragdoll: None,
+            ccd: Some(CcdMetadata {
+                motion_threshold_scale: 1.0,
+                swept_sphere_radius_scale: 0.5,
+            }),
+        })
+    }
+
+    /// Model that uses every optional field of the layout
+    fn sample_metadata() -> Vec<u8> {
+        let mut builder = MetadataBuilder::new();
+        builder.add_bone(BoneMetadata {
+            rest_position: Vec3A::ZERO,
+            absolute_inverse_bind_matrix: Mat4::IDENTITY,
+            parent_bone_index: -1,
+            transform_order: 0,
+            flag: BoneFlag::IsRotatable as u16 | BoneFlag::IsMovable as u16,
+            append_transform: None,
+            axis_limit: None,
+            ik: None,
+        });
+        builder.add_bone(BoneMetadata {
+            rest_position: Vec3A::Y,
+            absolute_inverse_bind_matrix: Mat4::from_translation(-glam::Vec3::Y),
+            parent_bone_index: 0,
+            transform_order: 0,
+            flag: BoneFlag::IsRotatable as u16 | BoneFlag::HasAppendRotate as u16,
+            append_transform: Some(AppendTransformMetadata {
+                parent_index: 0,
+                ratio: 0.5,
+            }),
+            axis_limit: Some(Vec3A::X),
+            ik: Some(Box::new(IkMetadata {
+                target: 0,
+                iteration: 10,
+                rotation_constraint: 1.0,
+                links: vec![IkLinkMetadata {
+                    target: 0,
+                    limits: Some(IkChainAngleLimits {
+                        minimum_angle: Vec3A::splat(-1.0),
+                        maximum_angle: Vec3A::splat(1.0),
+                    }),
+                }],
+            })),
+        });
+        builder.add_morph(MorphMetadata::Bone(BoneMorphMetadata {
+            indices: vec![0, 1],
+            positions: vec![Vec3A::X, Vec3A::Y],
+            rotations: vec![Quat::IDENTITY, Quat::IDENTITY],
+        }));
+        builder.add_morph(MorphMetadata::Group(GroupMorphMetadata {
+            indices: vec![0],
+            ratios: vec![1.0],
+        }));
+        builder.set_physics(physics());
+        builder.build().unwrap()
+    }
+
+    /// Run every reader over the metadata like `MmdModel::new`
+    fn read_all(bytes: &[u8]) -> Result<(), MetadataError> {
+        let reader = BoneMetadataReader::new(MetadataBuffer::new(bytes))?;
+        let reader = reader.enumerate(|_, _| {})?;
+        #[cfg_attr(not(feature = "physics"), allow(unused_mut))]
+        let (_, mut reader) = reader.read()?;
+        reader.enumerate(|_, _| {})?;
+
+        #[cfg(feature = "physics")]
+        {
+            reader.ragdoll()?;
+            reader.ccd()?;
+            if let Some(mut joint_reader) = reader.next()? {
+                joint_reader.enumerate(|_, _| {})?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn section_offset(bytes: &[u8], kind: MetadataSectionKind) -> usize {
+        let section_count = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        for i in 0..section_count {
+            let entry = 12 + i * 12;
+            if u32::from_le_bytes(bytes[entry..entry + 4].try_into().unwrap()) == kind as u32 {
+                return u32::from_le_bytes(bytes[entry + 4..entry + 8].try_into().unwrap()) as usize;
+            }
+        }
+        panic!("section {} not found", kind as u32);
+    }
+
+    #[test]
+    fn sample_metadata_is_readable() {
+        assert!(read_all(&sample_metadata()).is_ok());
+    }
+
+    #[test]
+    fn truncated_metadata_is_rejected() {
+        let bytes = sample_metadata();
+        for length in 0..bytes.len() {
+            assert!(read_all(&bytes[..length]).is_err(), "truncated at {} of {} bytes was accepted", length, bytes.len());
+        }
+    }
+
+    #[test]
+    fn truncated_header_reports_unexpected_end() {
+        let bytes = sample_metadata();
+        assert!(matches!(read_all(&bytes[..6]), Err(MetadataError::UnexpectedEnd { offset: 4, size: 4 })));
+    }
+
+    #[test]
+    fn section_out_of_buffer_is_rejected() {
+        let bytes = sample_metadata();
+        let last_section_end = bytes.len() - 1;
+        assert!(matches!(read_all(&bytes[..last_section_end]), Err(MetadataError::InvalidSection(_))));
+    }
+
+    #[test]
+    fn invalid_magic_is_rejected() {
+        let mut bytes = sample_metadata();
+        bytes[0..4].copy_from_slice(b"PMX ");
+        assert!(matches!(read_all(&bytes), Err(MetadataError::InvalidMagic(magic)) if magic == u32::from_le_bytes(*b"PMX ")));
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut bytes = sample_metadata();
+        bytes[4..8].copy_from_slice(&(METADATA_VERSION + 1).to_le_bytes());
+        assert!(matches!(read_all(&bytes), Err(MetadataError::UnsupportedVersion(version)) if version == METADATA_VERSION + 1));
+    }
+
+    #[test]
+    fn missing_section_is_rejected() {
+        let mut bytes = sample_metadata();
+        // turn the physics section entry into an unknown section
+        let entry = 12 + 2 * 12;
+        bytes[entry..entry + 4].copy_from_slice(&100u32.to_le_bytes());
+        assert!(matches!(read_all(&bytes), Err(MetadataError::MissingSection(MetadataSectionKind::Physics))));
+    }
+
+    #[test]
+    fn unknown_physics_kind_is_rejected() {
+        let mut bytes = sample_metadata();
+        let offset = section_offset(&bytes, MetadataSectionKind::Physics);
+        bytes[offset] = 7;
+        assert!(matches!(read_all(&bytes), Err(MetadataError::InvalidPhysicsInfoKind(7))));
+    }
+
+    #[test]
+    fn unknown_morph_kind_is_rejected() {
+        let mut bytes = sample_metadata();
+        let offset = section_offset(&bytes, MetadataSectionKind::Morphs);
+        bytes[offset + 4] = 1; // vertex morph is never encoded
+        assert!(matches!(read_all(&bytes), Err(MetadataError::InvalidMorphKind(1))));
+    }
+
+    #[test]
+    fn padding_past_the_section_end_is_rejected() {
+        let mut bytes = sample_metadata();
+        // physics section ends right after the physics info kind, before its padding
+        let entry = 12 + 2 * 12;
+        bytes[entry + 8..entry + 12].copy_from_slice(&1u32.to_le_bytes());
+        assert!(matches!(read_all(&bytes), Err(MetadataError::UnexpectedEnd { .. })));
+    }
+
+    #[test]
+    fn huge_counts_are_rejected_before_allocation() {
+        let bytes = sample_metadata();
+
+        let mut bone_count_broken = bytes.clone();
+        let offset = section_offset(&bytes, MetadataSectionKind::Bones);
+        bone_count_broken[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_all(&bone_count_broken), Err(MetadataError::UnexpectedEnd { .. })));
+
+        let mut morph_count_broken = bytes.clone();
+        let offset = section_offset(&bytes, MetadataSectionKind::Morphs);
+        morph_count_broken[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_all(&morph_count_broken), Err(MetadataError::UnexpectedEnd { .. })));
+
+        let mut rigid_body_count_broken = bytes.clone();
+        let offset = section_offset(&bytes, MetadataSectionKind::Physics);
+        #[cfg(not(feature = "physics"))]
+        let count_offset = offset + 4;
+        #[cfg(feature = "physics")]
+        let count_offset = offset + 4 + 4 + 4 + 4 + 16 * 4 + 4; // kind, world id, shared world ids, matrix, flag
+        rigid_body_count_broken[count_offset..count_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_all(&rigid_body_count_broken), Err(MetadataError::UnexpectedEnd { .. })));
+    }
+
+    fn chunk(tag: u32, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(payload);
+        bytes.resize(bytes.len().next_multiple_of(4), 0);
+        bytes
+    }
+
+    #[cfg(feature = "physics")]
+    #[test]
+    fn extension_chunks_reach_the_rigid_body_reader() {
+        let bytes = sample_metadata();
+        let reader = BoneMetadataReader::new(MetadataBuffer::new(&bytes)).unwrap();
+        let (_, reader) = reader.enumerate(|_, _| {}).unwrap().read().unwrap();
+
+        let ccd = reader.ccd().unwrap().expect("ccd chunk is lost");
+        assert_eq!((ccd.motion_threshold_scale, ccd.swept_sphere_radius_scale), (1.0, 0.5));
+        assert!(reader.ragdoll().unwrap().is_none());
+    }
+
+    #[test]
+    fn unknown_extension_chunk_is_skipped() {
+        let mut bytes = chunk(100, &[1, 2, 3]); // unknown chunk is skipped with its padding
+        bytes.extend(chunk(ExtensionChunkTag::Ccd as u32, &2.0f32.to_le_bytes()));
+
+        let table = ExtensionChunkTable::read(MetadataBuffer::new(&bytes)).unwrap();
+        let mut payload = table.find(ExtensionChunkTag::Ccd).expect("chunk after unknown chunk is lost");
+        assert_eq!(payload.read::<f32>().unwrap(), 2.0);
+        assert!(table.find(ExtensionChunkTag::Ragdoll).is_none());
+    }
+
+    #[test]
+    fn broken_extension_chunk_is_rejected_on_open() {
+        let mut bytes = chunk(ExtensionChunkTag::Ccd as u32, &[0; 8]);
+        bytes[4..8].copy_from_slice(&64u32.to_le_bytes()); // payload size beyond the section
+        assert!(matches!(ExtensionChunkTable::read(MetadataBuffer::new(&bytes)), Err(MetadataError::UnexpectedEnd { .. })));
+
+        let bytes = [0u8; 6]; // truncated chunk header
+        assert!(matches!(ExtensionChunkTable::read(MetadataBuffer::new(&bytes)), Err(MetadataError::UnexpectedEnd { .. })));
+    }
+
+    #[test]
+    fn corrupted_bytes_never_panic() {
+        let bytes = sample_metadata();
+        for i in 0..bytes.len() {
+            for value in [0x00, 0x7F, 0x80, 0xFF] {
+                let mut corrupted = bytes.clone();
+                corrupted[i] = value;
+                // result can be either ok or err, but reading must not panic
+                let _ = read_all(&corrupted);
+            }
+        }
     }
 }
@@ -0,0 +1,187 @@
+use crate::mmd_model_metadata::{BoneFlag, BoneMetadataReader, MetadataBuffer, MetadataError};
+
+#[cfg(feature = "physics")]
+use crate::mmd_model_metadata::{PhysicsInfoKind, RigidBodyPhysicsMode};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValidationIssueKind {
+    CyclicParentChain = 0,
+    CyclicAppendTransform = 1,
+    IkChainContainsTarget = 2,
+    #[cfg(feature = "physics")]
+    ZeroMassDynamicBody = 3,
+    #[cfg(feature = "physics")]
+    SelfJoint = 4,
+    TransformOrderInversion = 5,
+}
+
+/// Single validation issue
+///
+/// `subject_index` and `related_index` meaning depends on `kind`:
+/// - CyclicParentChain: bone index that closes the cycle, its parent bone index
+/// - CyclicAppendTransform: bone index that closes the cycle, its append transform parent bone index
+/// - IkChainContainsTarget: ik bone index, ik link bone index
+/// - ZeroMassDynamicBody: rigidbody index, linked bone index
+/// - SelfJoint: joint index, rigidbody index
+/// - TransformOrderInversion: bone index, parent bone index which is transformed after the bone
+#[repr(C)]
+pub(crate) struct ValidationIssue {
+    kind: u8,
+    _padding: [u8; 3],
+    subject_index: i32,
+    related_index: i32,
+}
+
+impl ValidationIssue {
+    fn new(kind: ValidationIssueKind, subject_index: i32, related_index: i32) -> Self {
+        Self {
+            kind: kind as u8,
+            _padding: [0; 3],
+            subject_index,
+            related_index,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct ValidationResult {
+    ptr: *const ValidationIssue, // ptr must be 4 bytes
+    len: usize,
+}
+
+unsafe impl Send for ValidationResult {}
+
+pub(crate) struct ModelValidationReport {
+    issues: Vec<ValidationIssue>,
+    result: ValidationResult,
+}
+
+struct BoneNode {
+    parent_index: i32,
+    append_parent_index: i32,
+    transform_order: i32,
+    transform_after_physics: bool,
+}
+
+impl ModelValidationReport {
+    pub(crate) fn new() -> Self {
+        Self {
+            issues: Vec::new(),
+            result: ValidationResult {
+                ptr: std::ptr::null(),
+                len: 0,
+            },
+        }
+    }
+
+    pub(crate) fn validate(&mut self, buffer: MetadataBuffer) -> Result<&ValidationResult, MetadataError> {
+        self.issues.clear();
+
+        let reader = BoneMetadataReader::new(buffer)?;
+        let mut bones = Vec::with_capacity(reader.bone_count() as usize);
+
+        let issues = &mut self.issues;
+        #[cfg_attr(not(feature = "physics"), allow(unused_variables))]
+        let reader = reader.enumerate(|i, metadata| {
+            if let Some(ik) = &metadata.ik {
+                for link in &ik.links {
+                    if link.target == ik.target || link.target == i as i32 {
+                        issues.push(ValidationIssue::new(ValidationIssueKind::IkChainContainsTarget, i as i32, link.target));
+                    }
+                }
+            }
+
+            bones.push(BoneNode {
+                parent_index: metadata.parent_bone_index,
+                append_parent_index: metadata.append_transform.as_ref().map_or(-1, |append_transform| append_transform.parent_index),
+                transform_order: metadata.transform_order,
+                transform_after_physics: metadata.flag & BoneFlag::TransformAfterPhysics as u16 != 0,
+            });
+        })?;
+
+        Self::find_cycles(&bones, |bone| bone.parent_index, ValidationIssueKind::CyclicParentChain, issues);
+        Self::find_cycles(&bones, |bone| bone.append_parent_index, ValidationIssueKind::CyclicAppendTransform, issues);
+
+        // sorted_runtime_bones is a stable sort by transform order in each stage,
+        // so parent must come first in (stage, transform order, index) order
+        for (i, bone) in bones.iter().enumerate() {
+            if bone.parent_index < 0 || bones.len() as i32 <= bone.parent_index || bone.parent_index == i as i32 {
+                continue;
+            }
+            let parent = &bones[bone.parent_index as usize];
+            let bone_key = (bone.transform_after_physics, bone.transform_order, i as i32);
+            let parent_key = (parent.transform_after_physics, parent.transform_order, bone.parent_index);
+            if bone_key < parent_key {
+                issues.push(ValidationIssue::new(ValidationIssueKind::TransformOrderInversion, i as i32, bone.parent_index));
+            }
+        }
+
+        #[cfg(feature = "physics")]
+        {
+            let (_, mut reader) = reader.read()?;
+            if matches!(reader.physics_info_kind(), PhysicsInfoKind::FullPhysics) {
+                reader.enumerate(|i, metadata| {
+                    let is_dynamic = metadata.physics_mode == RigidBodyPhysicsMode::Physics as u8 ||
+                        metadata.physics_mode == RigidBodyPhysicsMode::PhysicsWithBone as u8;
+                    if is_dynamic && metadata.mass <= 0.0 {
+                        issues.push(ValidationIssue::new(ValidationIssueKind::ZeroMassDynamicBody, i as i32, metadata.bone_index));
+                    }
+                })?;
+
+                if let Some(mut joint_reader) = reader.next()? {
+                    joint_reader.enumerate(|i, metadata| {
+                        if metadata.rigidbody_index_a == metadata.rigidbody_index_b {
+                            issues.push(ValidationIssue::new(ValidationIssueKind::SelfJoint, i as i32, metadata.rigidbody_index_a));
+                        }
+                    })?;
+                }
+            }
+        }
+
+        self.result.ptr = self.issues.as_ptr();
+        self.result.len = self.issues.len();
+        Ok(&self.result)
+    }
+
+    fn find_cycles(
+        bones: &[BoneNode],
+        next: impl Fn(&BoneNode) -> i32,
+        kind: ValidationIssueKind,
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        // 0: unvisited, 1: in current path, 2: done
+        let mut state = vec![0u8; bones.len()];
+
+        for start in 0..bones.len() {
+            if state[start] != 0 {
+                continue;
+            }
+
+            let mut path = Vec::new();
+            let mut current = start as i32;
+            loop {
+                if current < 0 || bones.len() as i32 <= current {
+                    break;
+                }
+                match state[current as usize] {
+                    0 => {
+                        state[current as usize] = 1;
+                        path.push(current);
+                        current = next(&bones[current as usize]);
+                    }
+                    1 => {
+                        // last node in path closes the cycle
+                        let last = *path.last().unwrap();
+                        issues.push(ValidationIssue::new(kind, last, current));
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+
+            for index in path {
+                state[index as usize] = 2;
+            }
+        }
+    }
+}
@@ -5,8 +5,15 @@ use wasm_bindgen::prelude::*;
 
 use crate::animation::mmd_runtime_animation::MmdRuntimeAnimation;
 use crate::diagnostic::{Diagnostic, DiagnosticResult};
+use crate::loader::bpmx_reader::BpmxReader;
+use crate::loader::pmd_reader::PmdReader;
+use crate::loader::pmx_metadata_encoder::{PmxMetadataEncodeOptions, PmxMetadataEncoder};
+use crate::loader::pmx_object::PmxObject;
+use crate::loader::pmx_reader::PmxReader;
 use crate::mmd_model::MmdModel;
 use crate::mmd_model_metadata::MetadataBuffer;
+use crate::mmd_model_validation::{ModelValidationReport, ValidationResult};
+use crate::profiler::{ModelProfile, RuntimeProfile, StageTimer};
 
 #[cfg(feature = "physics")]
 use crate::physics::mmd::MmdPhysicsRuntime;
@@ -23,6 +30,8 @@ pub struct MmdRuntime {
     mmd_models: Vec<Box<MmdModel>>,
     lock: atomic::AtomicU8,
     diagnostic: Diagnostic,
+    validation_report: ModelValidationReport,
+    profile: Option<RuntimeProfile>,
 }
 
 #[wasm_bindgen]
@@ -35,6 +44,8 @@ impl MmdRuntime {
             mmd_models: Vec::new(),
             lock: atomic::AtomicU8::new(0),
             diagnostic: Diagnostic::new(),
+            validation_report: ModelValidationReport::new(),
+            profile: None,
         }
     }
 
@@ -43,23 +54,176 @@ impl MmdRuntime {
         let serialized_metadata = unsafe {
             std::slice::from_raw_parts(serialized_metadata_ptr, serialized_metadata_size)
         };
-        let metadata_buffer = MetadataBuffer::new(serialized_metadata);
+        self.add_mmd_model(MetadataBuffer::new(serialized_metadata))
+    }
+
+    /// Create mmd model directly from PMX 2.0 / 2.1 file bytes
+    ///
+    /// Physics creation options are read from `physics_options`, the metadata which has no bones, morphs, rigid bodies and joints.
+    /// Runtime index of each morph of the file is written to `morph_index_map` whose length must be the morph count of the file,
+    /// it is not touched if the file has no morphs
+    ///
+    /// null is returned if the file is malformed or the morph count is different, the error is written to the diagnostic
+    #[wasm_bindgen(js_name = "createMmdModelFromPmx")]
+    pub fn create_mmd_model_from_pmx(
+        &mut self,
+        pmx_ptr: *const u8,
+        pmx_size: usize,
+        physics_options_ptr: *const u8,
+        physics_options_size: usize,
+        morph_index_map_ptr: *mut i32,
+        morph_count: usize,
+    ) -> *mut usize {
+        let pmx_bytes = unsafe {
+            std::slice::from_raw_parts(pmx_ptr, pmx_size)
+        };
 
-        let mmd_model = Box::new(
-            MmdModel::new(
-                metadata_buffer,
+        match PmxReader::read(pmx_bytes) {
+            Ok(pmx_object) => self.add_mmd_model_from_pmx_object(pmx_object, physics_options_ptr, physics_options_size, morph_index_map_ptr, morph_count),
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to read pmx: {}", err));
+                std::ptr::null_mut()
+            }
+        }
+    }
 
-                #[cfg(feature = "physics")]
-                &mut self.physics_runtime,
+    /// Create mmd model directly from PMD file bytes
+    ///
+    /// PMD is converted to PMX semantics first, so the result is same as loading the converted PMX.
+    /// Arguments are same as `create_mmd_model_from_pmx`
+    #[wasm_bindgen(js_name = "createMmdModelFromPmd")]
+    pub fn create_mmd_model_from_pmd(
+        &mut self,
+        pmd_ptr: *const u8,
+        pmd_size: usize,
+        physics_options_ptr: *const u8,
+        physics_options_size: usize,
+        morph_index_map_ptr: *mut i32,
+        morph_count: usize,
+    ) -> *mut usize {
+        let pmd_bytes = unsafe {
+            std::slice::from_raw_parts(pmd_ptr, pmd_size)
+        };
 
-                &mut self.diagnostic
-            )
-        );
+        match PmdReader::read(pmd_bytes) {
+            Ok(pmx_object) => self.add_mmd_model_from_pmx_object(pmx_object, physics_options_ptr, physics_options_size, morph_index_map_ptr, morph_count),
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to read pmd: {}", err));
+                std::ptr::null_mut()
+            }
+        }
+    }
+
+    /// Create mmd model directly from BPMX file bytes
+    ///
+    /// Arguments are same as `create_mmd_model_from_pmx`
+    #[wasm_bindgen(js_name = "createMmdModelFromBpmx")]
+    pub fn create_mmd_model_from_bpmx(
+        &mut self,
+        bpmx_ptr: *const u8,
+        bpmx_size: usize,
+        physics_options_ptr: *const u8,
+        physics_options_size: usize,
+        morph_index_map_ptr: *mut i32,
+        morph_count: usize,
+    ) -> *mut usize {
+        let bpmx_bytes = unsafe {
+            std::slice::from_raw_parts(bpmx_ptr, bpmx_size)
+        };
+
+        match BpmxReader::read(bpmx_bytes) {
+            Ok(pmx_object) => self.add_mmd_model_from_pmx_object(pmx_object, physics_options_ptr, physics_options_size, morph_index_map_ptr, morph_count),
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to read bpmx: {}", err));
+                std::ptr::null_mut()
+            }
+        }
+    }
+
+    fn add_mmd_model_from_pmx_object(
+        &mut self,
+        pmx_object: PmxObject,
+        physics_options_ptr: *const u8,
+        physics_options_size: usize,
+        morph_index_map_ptr: *mut i32,
+        morph_count: usize,
+    ) -> *mut usize {
+        // morphs of the mesh are bound by index, so the file must have the same morphs
+        if pmx_object.morphs.len() != morph_count {
+            self.diagnostic.writer().error(format!("Morph count of the file {} is different from the mesh {}", pmx_object.morphs.len(), morph_count));
+            return std::ptr::null_mut();
+        }
+        if morph_count != 0 {
+            let morph_index_map = unsafe {
+                std::slice::from_raw_parts_mut(morph_index_map_ptr, morph_count)
+            };
+            morph_index_map.copy_from_slice(&PmxMetadataEncoder::runtime_morph_map(&pmx_object.morphs));
+        }
+
+        let physics_options = unsafe {
+            std::slice::from_raw_parts(physics_options_ptr, physics_options_size)
+        };
+        let options = match PmxMetadataEncodeOptions::read(MetadataBuffer::new(physics_options)) {
+            Ok(options) => options,
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to read physics options: {}", err));
+                return std::ptr::null_mut();
+            }
+        };
+
+        let serialized_metadata = PmxMetadataEncoder::encode(&pmx_object, options, &mut self.diagnostic.writer());
+        match serialized_metadata {
+            Ok(serialized_metadata) => self.add_mmd_model(MetadataBuffer::new(&serialized_metadata)),
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to encode metadata: {}", err));
+                std::ptr::null_mut()
+            }
+        }
+    }
+
+    fn add_mmd_model(&mut self, metadata_buffer: MetadataBuffer) -> *mut usize {
+        let mmd_model = match MmdModel::new(
+            metadata_buffer,
+
+            #[cfg(feature = "physics")]
+            &mut self.physics_runtime,
+
+            &mut self.diagnostic
+        ) {
+            Ok(mut mmd_model) => {
+                mmd_model.set_profiling_enabled(self.profile.is_some());
+                Box::new(mmd_model)
+            }
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to create mmd model: {}", err));
+                return std::ptr::null_mut();
+            }
+        };
         let ptr = &*mmd_model as *const MmdModel as *mut usize;
         self.mmd_models.push(mmd_model);
         ptr
     }
 
+    /// Validate serialized metadata without creating a model
+    ///
+    /// Returned result is valid until the next call of this function,
+    /// null is returned if the metadata is malformed
+    #[wasm_bindgen(js_name = "validateMmdModelMetadata")]
+    pub fn validate_mmd_model_metadata(&mut self, serialized_metadata_ptr: *const u8, serialized_metadata_size: usize) -> *const usize {
+        let serialized_metadata = unsafe {
+            std::slice::from_raw_parts(serialized_metadata_ptr, serialized_metadata_size)
+        };
+        let metadata_buffer = MetadataBuffer::new(serialized_metadata);
+
+        match self.validation_report.validate(metadata_buffer) {
+            Ok(result) => result as *const ValidationResult as *const usize,
+            Err(err) => {
+                self.diagnostic.writer().error(format!("Failed to validate mmd model metadata: {}", err));
+                std::ptr::null()
+            }
+        }
+    }
+
     #[wasm_bindgen(js_name = "destroyMmdModel")]
     pub fn destroy_mmd_model(&mut self, ptr: *mut usize) {
         let ptr = ptr as *mut MmdModel;
@@ -175,6 +339,8 @@ impl MmdRuntime {
         #[cfg(feature = "physics")]
         time_step: Option<f32>,
     ) {
+        let mut timer = StageTimer::new(self.profile.is_some());
+
         #[cfg(feature = "parallel")]
         {
             if 1 < self.mmd_models.len() {
@@ -200,8 +366,47 @@ impl MmdRuntime {
             mmd_model.before_physics(frame_time);
         }
 
+        let models_time = timer.lap();
+
         #[cfg(feature = "physics")]
         self.physics_runtime.step_simulation(time_step.unwrap_or(1.0 / 60.0), &mut self.mmd_models);
+
+        let physics_time = timer.lap();
+        if let Some(profile) = self.profile.as_mut() {
+            profile.models = models_time;
+            profile.physics = physics_time;
+        }
+    }
+
+    /// Collects stage timings of the runtime and each model, also physics world stats if physics is enabled
+    ///
+    /// Each timing costs a call to `performance.now`, so keep it disabled in production
+    #[wasm_bindgen(js_name = "setProfilingEnabled")]
+    pub fn set_profiling_enabled(&mut self, enabled: bool) {
+        self.profile = if enabled { Some(self.profile.unwrap_or_default()) } else { None };
+        for mmd_model in &mut self.mmd_models {
+            mmd_model.set_profiling_enabled(enabled);
+        }
+
+        #[cfg(feature = "physics")]
+        self.physics_runtime.set_stats_enabled(enabled);
+    }
+
+    /// Models and physics wall time in milliseconds as f32, null if profiling is disabled
+    ///
+    /// Valid until profiling is disabled, values are updated on each `beforePhysics`
+    #[wasm_bindgen(js_name = "getProfilePtr")]
+    pub fn get_profile_ptr(&self) -> *const f32 {
+        self.profile.as_ref().map_or(std::ptr::null(), |profile| profile as *const RuntimeProfile as *const f32)
+    }
+
+    /// Animation, morph, bone update, ik, sync bodies and sync bones time in milliseconds as f32
+    ///
+    /// Null if profiling is disabled
+    #[wasm_bindgen(js_name = "getMmdModelProfilePtr")]
+    pub fn get_mmd_model_profile_ptr(&self, ptr: *const usize) -> *const f32 {
+        let mmd_model = unsafe { &*(ptr as *const MmdModel) };
+        mmd_model.profile().map_or(std::ptr::null(), |profile| profile as *const ModelProfile as *const f32)
     }
 
     #[cfg(feature = "physics")]
@@ -359,7 +564,11 @@ impl MmdRuntime {
 }
 
 #[cfg(feature = "physics")]
-use glam::Mat4;
+use glam::{Mat4, Vec3};
+#[cfg(feature = "physics")]
+use crate::physics::mmd::physics_model_context::{CcdOptions, JointEdit, PhysicsBlendOptions, RigidBodyEdit, SleepingOptions};
+#[cfg(feature = "physics")]
+use crate::mmd_model_metadata::RigidBodyPhysicsMode;
 
 #[cfg(feature = "physics")]
 #[wasm_bindgen]
@@ -393,12 +602,277 @@ impl MmdRuntime {
     fn apply_mmd_models_physics_parameters(&mut self) {
         for mmd_model in &mut self.mmd_models {
             if let Some(context) = mmd_model.physics_model_context_mut() {
-                context.apply_world_matrix();
-                context.apply_need_init();
+                context.apply_buffers();
             }
         }
     }
 
+    /// Let dynamic bodies of the model sleep when their velocities stay below the thresholds
+    ///
+    /// Sleeping islands wake up when a bone driven body joined to them moves
+    #[wasm_bindgen(js_name = "setMmdModelPhysicsSleeping")]
+    pub fn set_mmd_model_physics_sleeping(&mut self, ptr: *mut usize, enabled: bool, linear_threshold: f32, angular_threshold: f32) {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.set_sleeping(SleepingOptions {
+                enabled,
+                linear_threshold,
+                angular_threshold,
+            });
+        }
+    }
+
+    /// Enable continuous collision detection for fast moving bodies of the model
+    ///
+    /// Parameters are relative to the inner radius of each body, zero motion threshold scale disables ccd
+    #[wasm_bindgen(js_name = "setMmdModelPhysicsCcd")]
+    pub fn set_mmd_model_physics_ccd(&mut self, ptr: *mut usize, motion_threshold_scale: f32, swept_sphere_radius_scale: f32) {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.set_ccd(CcdOptions {
+                motion_threshold_scale,
+                swept_sphere_radius_scale,
+            });
+        }
+    }
+
+    /// Show dynamic bodies of the model at the frame time when the frame rate is higher than the physics rate
+    ///
+    /// Bodies are shown between the previous and the last fixed step by the time that is not simulated yet
+    #[wasm_bindgen(js_name = "setMmdModelPhysicsInterpolation")]
+    pub fn set_mmd_model_physics_interpolation(&mut self, ptr: *mut usize, enabled: bool) {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.set_interpolation(enabled);
+        }
+    }
+
+    fn edit_mmd_model_rigidbody(ptr: *mut usize, rigidbody_index: u32, edit: RigidBodyEdit) -> bool {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.edit_rigidbody(rigidbody_index, edit)
+        } else {
+            false
+        }
+    }
+
+    /// Rigid body edits are applied before the next physics step
+    ///
+    /// Returns false if the body is failed to create or the body is not dynamic
+    #[wasm_bindgen(js_name = "setMmdModelRigidBodyMass")]
+    pub fn set_mmd_model_rigidbody_mass(&mut self, ptr: *mut usize, rigidbody_index: u32, mass: f32) -> bool {
+        Self::edit_mmd_model_rigidbody(ptr, rigidbody_index, RigidBodyEdit::Mass(mass))
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyMass")]
+    pub fn get_mmd_model_rigidbody_mass(&self, ptr: *const usize, rigidbody_index: u32) -> Option<f32> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_mass(rigidbody_index)
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelRigidBodyDamping")]
+    pub fn set_mmd_model_rigidbody_damping(&mut self, ptr: *mut usize, rigidbody_index: u32, linear_damping: f32, angular_damping: f32) -> bool {
+        Self::edit_mmd_model_rigidbody(ptr, rigidbody_index, RigidBodyEdit::Damping(linear_damping, angular_damping))
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyLinearDamping")]
+    pub fn get_mmd_model_rigidbody_linear_damping(&self, ptr: *const usize, rigidbody_index: u32) -> Option<f32> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_damping(rigidbody_index).map(|(linear_damping, _)| linear_damping)
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyAngularDamping")]
+    pub fn get_mmd_model_rigidbody_angular_damping(&self, ptr: *const usize, rigidbody_index: u32) -> Option<f32> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_damping(rigidbody_index).map(|(_, angular_damping)| angular_damping)
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelRigidBodyFriction")]
+    pub fn set_mmd_model_rigidbody_friction(&mut self, ptr: *mut usize, rigidbody_index: u32, friction: f32) -> bool {
+        Self::edit_mmd_model_rigidbody(ptr, rigidbody_index, RigidBodyEdit::Friction(friction))
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyFriction")]
+    pub fn get_mmd_model_rigidbody_friction(&self, ptr: *const usize, rigidbody_index: u32) -> Option<f32> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_friction(rigidbody_index)
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelRigidBodyRestitution")]
+    pub fn set_mmd_model_rigidbody_restitution(&mut self, ptr: *mut usize, rigidbody_index: u32, restitution: f32) -> bool {
+        Self::edit_mmd_model_rigidbody(ptr, rigidbody_index, RigidBodyEdit::Restitution(restitution))
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyRestitution")]
+    pub fn get_mmd_model_rigidbody_restitution(&self, ptr: *const usize, rigidbody_index: u32) -> Option<f32> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_restitution(rigidbody_index)
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelRigidBodyCollisionFilter")]
+    pub fn set_mmd_model_rigidbody_collision_filter(&mut self, ptr: *mut usize, rigidbody_index: u32, group: u16, mask: u16) -> bool {
+        Self::edit_mmd_model_rigidbody(ptr, rigidbody_index, RigidBodyEdit::CollisionFilter(group, mask))
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyCollisionGroup")]
+    pub fn get_mmd_model_rigidbody_collision_group(&self, ptr: *const usize, rigidbody_index: u32) -> Option<u16> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_collision_filter(rigidbody_index).map(|(group, _)| group)
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyCollisionMask")]
+    pub fn get_mmd_model_rigidbody_collision_mask(&self, ptr: *const usize, rigidbody_index: u32) -> Option<u16> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_collision_filter(rigidbody_index).map(|(_, mask)| mask)
+    }
+
+    /// Bodies created as dynamic can switch between follow bone, physics and physics with bone
+    ///
+    /// Bodies created as follow bone or static can not change their physics mode
+    #[wasm_bindgen(js_name = "setMmdModelRigidBodyPhysicsMode")]
+    pub fn set_mmd_model_rigidbody_physics_mode(&mut self, ptr: *mut usize, rigidbody_index: u32, physics_mode: u8) -> bool {
+        let physics_mode = if physics_mode == RigidBodyPhysicsMode::FollowBone as u8 {
+            RigidBodyPhysicsMode::FollowBone
+        } else if physics_mode == RigidBodyPhysicsMode::Physics as u8 {
+            RigidBodyPhysicsMode::Physics
+        } else if physics_mode == RigidBodyPhysicsMode::PhysicsWithBone as u8 {
+            RigidBodyPhysicsMode::PhysicsWithBone
+        } else if physics_mode == RigidBodyPhysicsMode::Static as u8 {
+            RigidBodyPhysicsMode::Static
+        } else {
+            return false;
+        };
+        Self::edit_mmd_model_rigidbody(ptr, rigidbody_index, RigidBodyEdit::PhysicsMode(physics_mode))
+    }
+
+    #[wasm_bindgen(js_name = "getMmdModelRigidBodyPhysicsMode")]
+    pub fn get_mmd_model_rigidbody_physics_mode(&self, ptr: *const usize, rigidbody_index: u32) -> Option<u8> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.rigidbody_physics_mode(rigidbody_index).map(|physics_mode| physics_mode as u8)
+    }
+
+    fn edit_mmd_model_joint(ptr: *mut usize, joint_index: u32, edit: JointEdit) -> bool {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.edit_joint(joint_index, edit)
+        } else {
+            false
+        }
+    }
+
+    /// Joint edits are applied before the next physics step
+    ///
+    /// Returns false if the joint is failed to create or the joint kind does not support the parameter
+    #[wasm_bindgen(js_name = "setMmdModelJointLinearLimit")]
+    pub fn set_mmd_model_joint_linear_limit(&mut self, ptr: *mut usize, joint_index: u32, lower: *const f32, upper: *const f32) -> bool {
+        let lower = Vec3::from_slice(unsafe { std::slice::from_raw_parts(lower, 3) });
+        let upper = Vec3::from_slice(unsafe { std::slice::from_raw_parts(upper, 3) });
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::LinearLimit(lower, upper))
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelJointAngularLimit")]
+    pub fn set_mmd_model_joint_angular_limit(&mut self, ptr: *mut usize, joint_index: u32, lower: *const f32, upper: *const f32) -> bool {
+        let lower = Vec3::from_slice(unsafe { std::slice::from_raw_parts(lower, 3) });
+        let upper = Vec3::from_slice(unsafe { std::slice::from_raw_parts(upper, 3) });
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::AngularLimit(lower, upper))
+    }
+
+    /// Linear spring of the axis is disabled if its stiffness is zero
+    #[wasm_bindgen(js_name = "setMmdModelJointLinearStiffness")]
+    pub fn set_mmd_model_joint_linear_stiffness(&mut self, ptr: *mut usize, joint_index: u32, x: f32, y: f32, z: f32) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::LinearStiffness(Vec3::new(x, y, z)))
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelJointAngularStiffness")]
+    pub fn set_mmd_model_joint_angular_stiffness(&mut self, ptr: *mut usize, joint_index: u32, x: f32, y: f32, z: f32) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::AngularStiffness(Vec3::new(x, y, z)))
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelJointLinearDamping")]
+    pub fn set_mmd_model_joint_linear_damping(&mut self, ptr: *mut usize, joint_index: u32, x: f32, y: f32, z: f32) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::LinearDamping(Vec3::new(x, y, z)))
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelJointAngularDamping")]
+    pub fn set_mmd_model_joint_angular_damping(&mut self, ptr: *mut usize, joint_index: u32, x: f32, y: f32, z: f32) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::AngularDamping(Vec3::new(x, y, z)))
+    }
+
+    /// Applied to all 6 axes, the default stop ERP of MMD joints is 0.475
+    #[wasm_bindgen(js_name = "setMmdModelJointStopErp")]
+    pub fn set_mmd_model_joint_stop_erp(&mut self, ptr: *mut usize, joint_index: u32, value: f32) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::StopErp(value))
+    }
+
+    /// Applied to all 6 axes
+    #[wasm_bindgen(js_name = "setMmdModelJointStopCfm")]
+    pub fn set_mmd_model_joint_stop_cfm(&mut self, ptr: *mut usize, joint_index: u32, value: f32) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::StopCfm(value))
+    }
+
+    #[wasm_bindgen(js_name = "setMmdModelJointEnabled")]
+    pub fn set_mmd_model_joint_enabled(&mut self, ptr: *mut usize, joint_index: u32, enabled: bool) -> bool {
+        Self::edit_mmd_model_joint(ptr, joint_index, JointEdit::Enabled(enabled))
+    }
+
+    #[wasm_bindgen(js_name = "isMmdModelJointEnabled")]
+    pub fn is_mmd_model_joint_enabled(&self, ptr: *const usize, joint_index: u32) -> Option<bool> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.joint_enabled(joint_index)
+    }
+
+    /// Blend bodies between animation and physics over `transition_time` seconds when physics toggle of the bone changes
+    ///
+    /// Gains scale the velocity that drives bodies toward their bones while animation has control, defaults are 30
+    #[wasm_bindgen(js_name = "setMmdModelPhysicsBlend")]
+    pub fn set_mmd_model_physics_blend(&mut self, ptr: *mut usize, transition_time: f32, force_gain: f32, torque_gain: f32) {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.set_blend(PhysicsBlendOptions {
+                transition_time,
+                force_gain,
+                torque_gain,
+            });
+        }
+    }
+
+    /// Switch the model into ragdoll, every follow bone body and bodies generated from bones become dynamic
+    ///
+    /// Returning to animation blends over the transition time of `setMmdModelPhysicsBlend`.
+    /// Returns false if the model was created without the ragdoll option
+    #[wasm_bindgen(js_name = "setMmdModelRagdoll")]
+    pub fn set_mmd_model_ragdoll(&mut self, ptr: *mut usize, enabled: bool) -> bool {
+        let ptr = ptr as *mut MmdModel;
+        if let Some(context) = unsafe { &mut *ptr }.physics_model_context_mut() {
+            context.set_ragdoll(enabled)
+        } else {
+            false
+        }
+    }
+
+    #[wasm_bindgen(js_name = "isMmdModelRagdollEnabled")]
+    pub fn is_mmd_model_ragdoll_enabled(&self, ptr: *const usize) -> Option<bool> {
+        let ptr = ptr as *const MmdModel;
+        unsafe { &*ptr }.physics_model_context().as_ref()?.ragdoll_enabled()
+    }
+
+    /// Runs physics of the model for `step_count` fixed steps while its animation is held at the current frame
+    ///
+    /// Other models are not advanced, so this can be called after seek without disturbing them.
+    /// Must not be called while buffered evaluation is running
+    #[wasm_bindgen(js_name = "preRollMmdModelPhysics")]
+    pub fn pre_roll_mmd_model_physics(&mut self, ptr: *mut usize, step_count: u32, initial_damping: f32) {
+        let mmd_model = unsafe { &mut *(ptr as *mut MmdModel) };
+        // other models keep their pending parameters until the next update
+        if let Some(context) = mmd_model.physics_model_context_mut() {
+            context.apply_buffers();
+        }
+        mmd_model.commit_physics_body_states();
+        mmd_model.before_physics(None);
+        self.physics_runtime.pre_roll(mmd_model, step_count, initial_damping);
+        mmd_model.after_physics();
+    }
+
     #[wasm_bindgen(js_name = "markMmdModelPhysicsAsNeedInit")]
     pub fn mark_mmd_model_physics_as_need_init(&mut self, ptr: *mut usize) {
         let ptr = ptr as *mut MmdModel;
@@ -408,6 +882,28 @@ impl MmdRuntime {
     }
 }
 
+#[cfg(feature = "deterministic")]
+#[wasm_bindgen]
+impl MmdRuntime {
+    /// Hash of bone world matrices of all models and the physics state
+    ///
+    /// Two runs with the same inputs produce the same hash on every frame, compare it to detect desync
+    #[wasm_bindgen(js_name = "getStateHash")]
+    pub fn get_state_hash(&self) -> u64 {
+        let mut hasher = crate::state_hash::StateHasher::new();
+        for mmd_model in &self.mmd_models {
+            for world_matrix in mmd_model.bone_arena().world_matrices().iter() {
+                hasher.write_f32_slice(&world_matrix.to_cols_array());
+            }
+        }
+
+        #[cfg(feature = "physics")]
+        hasher.write(&self.physics_runtime.world().write_snapshot());
+
+        hasher.finish()
+    }
+}
+
 impl Default for MmdRuntime {
     fn default() -> Self {
         Self::new()
@@ -1,4 +1,4 @@
-use glam::Vec3;
+use glam::{Mat4, Vec3};
 
 #[link(name = "bullet")]
 unsafe extern "C" {
@@ -17,6 +17,36 @@ unsafe extern "C" {
     fn bw_create_staticplaneshape(normal_x: f32, normal_y: f32, normal_z: f32, plane_constant: f32) -> *mut std::ffi::c_void;
 
     fn bw_destroy_staticplaneshape(shape: *mut std::ffi::c_void);
+
+    fn bw_create_cylindershape(x: f32, y: f32, z: f32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_cylindershape(shape: *mut std::ffi::c_void);
+
+    fn bw_create_coneshape(radius: f32, height: f32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_coneshape(shape: *mut std::ffi::c_void);
+
+    fn bw_create_convexhullshape(points: *const f32, point_count: i32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_convexhullshape(shape: *mut std::ffi::c_void);
+
+    fn bw_create_compoundshape(initial_child_capacity: i32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_compoundshape(shape: *mut std::ffi::c_void);
+
+    fn bw_compoundshape_add_child_shape(shape: *mut std::ffi::c_void, local_transform: *const f32, child_shape: *const std::ffi::c_void);
+
+    fn bw_create_bvhtrianglemeshshape(vertices: *const f32, vertex_count: i32, indices: *const i32, triangle_count: i32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_bvhtrianglemeshshape(shape: *mut std::ffi::c_void);
+
+    fn bw_create_scaledbvhtrianglemeshshape(child_shape: *const std::ffi::c_void, x: f32, y: f32, z: f32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_scaledbvhtrianglemeshshape(shape: *mut std::ffi::c_void);
+
+    fn bw_create_heightfieldterrainshape(width: i32, length: i32, heights: *const f32, min_height: f32, max_height: f32, scale_x: f32, scale_y: f32, scale_z: f32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_heightfieldterrainshape(shape: *mut std::ffi::c_void);
 }
 
 pub(crate) struct BoxShape {
@@ -146,3 +176,270 @@ impl Drop for StaticPlaneShape {
         }
     }
 }
+
+pub(crate) struct CylinderShape {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl CylinderShape {
+    pub(crate) fn new(half_extents: Vec3) -> Self {
+        Self {
+            ptr: unsafe { bw_create_cylindershape(half_extents.x, half_extents.y, half_extents.z) },
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for CylinderShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("CylinderShape already dropped");
+        }
+
+        unsafe { bw_destroy_cylindershape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct ConeShape {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl ConeShape {
+    pub(crate) fn new(radius: f32, height: f32) -> Self {
+        Self {
+            ptr: unsafe { bw_create_coneshape(radius, height) },
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for ConeShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("ConeShape already dropped");
+        }
+
+        unsafe { bw_destroy_coneshape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct ConvexHullShape {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl ConvexHullShape {
+    /// Points are copied, so the buffer can be freed after construction
+    pub(crate) fn new(points: &[Vec3]) -> Self {
+        let points = points.iter().flat_map(|point| point.to_array()).collect::<Vec<f32>>();
+        Self {
+            ptr: unsafe { bw_create_convexhullshape(points.as_ptr(), (points.len() / 3) as i32) },
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for ConvexHullShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("ConvexHullShape already dropped");
+        }
+
+        unsafe { bw_destroy_convexhullshape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct CompoundShape {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl CompoundShape {
+    pub(crate) fn new(initial_child_capacity: usize) -> Self {
+        Self {
+            ptr: unsafe { bw_create_compoundshape(initial_child_capacity as i32) },
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+
+    /// Child shape is not owned by the compound shape, it must outlive this shape
+    pub(crate) fn add_child_shape(&mut self, local_transform: &Mat4, child_shape: *const std::ffi::c_void) {
+        let local_transform = local_transform.as_ref();
+        unsafe { bw_compoundshape_add_child_shape(self.ptr, local_transform.as_ptr(), child_shape) };
+    }
+}
+
+impl Drop for CompoundShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("CompoundShape already dropped");
+        }
+
+        unsafe { bw_destroy_compoundshape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct BvhTriangleMeshShape {
+    ptr: *mut std::ffi::c_void,
+    // referenced by the bullet mesh interface
+    _vertices: Box<[f32]>,
+    _indices: Box<[i32]>,
+}
+
+impl BvhTriangleMeshShape {
+    /// Build the mesh bvh, `vertices` is xyz array and `indices` is triangle list
+    ///
+    /// Caller must guarantee that all indices are in range
+    pub(crate) fn new(vertices: Box<[f32]>, indices: Box<[i32]>) -> Self {
+        let ptr = unsafe {
+            bw_create_bvhtrianglemeshshape(
+                vertices.as_ptr(),
+                (vertices.len() / 3) as i32,
+                indices.as_ptr(),
+                (indices.len() / 3) as i32
+            )
+        };
+        Self {
+            ptr,
+            _vertices: vertices,
+            _indices: indices,
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for BvhTriangleMeshShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("BvhTriangleMeshShape already dropped");
+        }
+
+        unsafe { bw_destroy_bvhtrianglemeshshape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct ScaledBvhTriangleMeshShape {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl ScaledBvhTriangleMeshShape {
+    /// Child shape is not owned by the scaled shape, it must outlive this shape
+    pub(crate) fn new(child_shape: &BvhTriangleMeshShape, scaling: Vec3) -> Self {
+        Self {
+            ptr: unsafe { bw_create_scaledbvhtrianglemeshshape(child_shape.ptr(), scaling.x, scaling.y, scaling.z) },
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for ScaledBvhTriangleMeshShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("ScaledBvhTriangleMeshShape already dropped");
+        }
+
+        unsafe { bw_destroy_scaledbvhtrianglemeshshape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct HeightfieldTerrainShape {
+    ptr: *mut std::ffi::c_void,
+    // referenced by the bullet shape
+    _heights: Box<[f32]>,
+}
+
+impl HeightfieldTerrainShape {
+    /// `heights` is row major `width * length` array
+    ///
+    /// Caller must guarantee the length of `heights`
+    pub(crate) fn new(width: u32, length: u32, heights: Box<[f32]>, min_height: f32, max_height: f32, scaling: Vec3) -> Self {
+        let ptr = unsafe {
+            bw_create_heightfieldterrainshape(
+                width as i32,
+                length as i32,
+                heights.as_ptr(),
+                min_height,
+                max_height,
+                scaling.x,
+                scaling.y,
+                scaling.z
+            )
+        };
+        Self {
+            ptr,
+            _heights: heights,
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for HeightfieldTerrainShape {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("HeightfieldTerrainShape already dropped");
+        }
+
+        unsafe { bw_destroy_heightfieldterrainshape(self.ptr); }
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
@@ -29,6 +29,77 @@ unsafe extern "C" {
     fn bw_generic6dofspringconstraint_set_stiffness(constraint: *mut std::ffi::c_void, index: u8, stiffness: f32);
 
     fn bw_generic6dofspringconstraint_set_damping(constraint: *mut std::ffi::c_void, index: u8, damping: f32);
+
+    fn bw_create_point2pointconstraint(body_a: *const std::ffi::c_void, body_b: *const std::ffi::c_void, pivot_in_a: *const f32, pivot_in_b: *const f32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_point2pointconstraint(constraint: *mut std::ffi::c_void);
+
+    fn bw_create_conetwistconstraint(body_a: *const std::ffi::c_void, body_b: *const std::ffi::c_void, frame_a: *const f32, frame_b: *const f32) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_conetwistconstraint(constraint: *mut std::ffi::c_void);
+
+    fn bw_conetwistconstraint_set_limit(constraint: *mut std::ffi::c_void, swing_span1: f32, swing_span2: f32, twist_span: f32, softness: f32, bias_factor: f32, relaxation_factor: f32);
+
+    fn bw_conetwistconstraint_set_damping(constraint: *mut std::ffi::c_void, damping: f32);
+
+    fn bw_conetwistconstraint_set_fix_thresh(constraint: *mut std::ffi::c_void, fix_thresh: f32);
+
+    fn bw_create_sliderconstraint(body_a: *const std::ffi::c_void, body_b: *const std::ffi::c_void, frame_a: *const f32, frame_b: *const f32, use_linear_reference_frame_a: u8) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_sliderconstraint(constraint: *mut std::ffi::c_void);
+
+    fn bw_sliderconstraint_set_linear_limit(constraint: *mut std::ffi::c_void, lower: f32, upper: f32);
+
+    fn bw_sliderconstraint_set_angular_limit(constraint: *mut std::ffi::c_void, lower: f32, upper: f32);
+
+    fn bw_sliderconstraint_set_linear_motor(constraint: *mut std::ffi::c_void, on_off: u8, target_velocity: f32, max_force: f32);
+
+    fn bw_sliderconstraint_set_angular_motor(constraint: *mut std::ffi::c_void, on_off: u8, target_velocity: f32, max_force: f32);
+
+    fn bw_create_hingeconstraint(body_a: *const std::ffi::c_void, body_b: *const std::ffi::c_void, frame_a: *const f32, frame_b: *const f32, use_reference_frame_a: u8) -> *mut std::ffi::c_void;
+
+    fn bw_destroy_hingeconstraint(constraint: *mut std::ffi::c_void);
+
+    fn bw_hingeconstraint_set_limit(constraint: *mut std::ffi::c_void, low: f32, high: f32, softness: f32, bias_factor: f32, relaxation_factor: f32);
+
+    fn bw_hingeconstraint_enable_angular_motor(constraint: *mut std::ffi::c_void, enable_motor: u8, target_velocity: f32, max_motor_impulse: f32);
+
+    fn bw_constraint_set_enabled(constraint: *mut std::ffi::c_void, enabled: u8);
+
+    fn bw_constraint_is_enabled(constraint: *const std::ffi::c_void) -> u8;
+
+    fn bw_constraint_get_state(constraint: *mut std::ffi::c_void, out: *mut std::ffi::c_void);
+
+    fn bw_constraint_set_state(constraint: *mut std::ffi::c_void, state: *const std::ffi::c_void);
+}
+
+/// Layout must match `bwConstraintState`
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ConstraintState {
+    applied_impulse: f32,
+    // warm start impulses of generic 6 DOF, zero for other types
+    linear_accumulated_impulse: [f32; 3],
+    angular_accumulated_impulse: [f32; 3],
+}
+
+/// Works for any constraint pointer created by this module
+pub(crate) fn get_constraint_state(constraint: *mut std::ffi::c_void) -> ConstraintState {
+    let mut state = ConstraintState::default();
+    unsafe { bw_constraint_get_state(constraint, &mut state as *mut ConstraintState as *mut std::ffi::c_void) };
+    state
+}
+
+pub(crate) fn set_constraint_enabled(constraint: *mut std::ffi::c_void, enabled: bool) {
+    unsafe { bw_constraint_set_enabled(constraint, enabled as u8) };
+}
+
+pub(crate) fn is_constraint_enabled(constraint: *const std::ffi::c_void) -> bool {
+    unsafe { bw_constraint_is_enabled(constraint) != 0 }
+}
+
+pub(crate) fn set_constraint_state(constraint: *mut std::ffi::c_void, state: &ConstraintState) {
+    unsafe { bw_constraint_set_state(constraint, state as *const ConstraintState as *const std::ffi::c_void) };
 }
 
 pub(crate) struct Generic6DofConstraint {
@@ -160,3 +231,179 @@ impl Drop for Generic6DofSpringConstraint {
         }
     }
 }
+
+pub(crate) struct Point2PointConstraint {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl Point2PointConstraint {
+    pub(crate) fn new(body_a: &RigidBody, body_b: &RigidBody, pivot_in_a: Vec3, pivot_in_b: Vec3) -> Self {
+        let pivot_in_a = pivot_in_a.to_array();
+        let pivot_in_b = pivot_in_b.to_array();
+
+        Self {
+            ptr: unsafe { bw_create_point2pointconstraint(body_a.ptr(), body_b.ptr(), pivot_in_a.as_ptr(), pivot_in_b.as_ptr()) },
+        }
+    }
+
+    pub(crate) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.ptr
+    }
+}
+
+impl Drop for Point2PointConstraint {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("Point2PointConstraint already dropped");
+        }
+
+        unsafe { bw_destroy_point2pointconstraint(self.ptr) };
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct ConeTwistConstraint {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl ConeTwistConstraint {
+    pub(crate) fn new(body_a: &RigidBody, body_b: &RigidBody, frame_a: &Mat4, frame_b: &Mat4) -> Self {
+        let frame_a = frame_a.as_ref();
+        let frame_b = frame_b.as_ref();
+        
+        Self {
+            ptr: unsafe { bw_create_conetwistconstraint(body_a.ptr(), body_b.ptr(), frame_a.as_ptr(), frame_b.as_ptr()) },
+        }
+    }
+
+    pub(crate) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.ptr
+    }
+
+    pub(crate) fn set_limit(&mut self, swing_span1: f32, swing_span2: f32, twist_span: f32, softness: f32, bias_factor: f32, relaxation_factor: f32) {
+        unsafe { bw_conetwistconstraint_set_limit(self.ptr, swing_span1, swing_span2, twist_span, softness, bias_factor, relaxation_factor) };
+    }
+
+    pub(crate) fn set_damping(&mut self, damping: f32) {
+        unsafe { bw_conetwistconstraint_set_damping(self.ptr, damping) };
+    }
+
+    pub(crate) fn set_fix_thresh(&mut self, fix_thresh: f32) {
+        unsafe { bw_conetwistconstraint_set_fix_thresh(self.ptr, fix_thresh) };
+    }
+}
+
+impl Drop for ConeTwistConstraint {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("ConeTwistConstraint already dropped");
+        }
+
+        unsafe { bw_destroy_conetwistconstraint(self.ptr) };
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct SliderConstraint {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl SliderConstraint {
+    pub(crate) fn new(body_a: &RigidBody, body_b: &RigidBody, frame_a: &Mat4, frame_b: &Mat4, use_linear_reference_frame_a: bool) -> Self {
+        let frame_a = frame_a.as_ref();
+        let frame_b = frame_b.as_ref();
+        
+        Self {
+            ptr: unsafe { bw_create_sliderconstraint(body_a.ptr(), body_b.ptr(), frame_a.as_ptr(), frame_b.as_ptr(), use_linear_reference_frame_a as u8) },
+        }
+    }
+
+    pub(crate) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.ptr
+    }
+
+    pub(crate) fn set_linear_limit(&mut self, lower: f32, upper: f32) {
+        unsafe { bw_sliderconstraint_set_linear_limit(self.ptr, lower, upper) };
+    }
+
+    pub(crate) fn set_angular_limit(&mut self, lower: f32, upper: f32) {
+        unsafe { bw_sliderconstraint_set_angular_limit(self.ptr, lower, upper) };
+    }
+
+    pub(crate) fn set_linear_motor(&mut self, on_off: bool, target_velocity: f32, max_force: f32) {
+        unsafe { bw_sliderconstraint_set_linear_motor(self.ptr, on_off as u8, target_velocity, max_force) };
+    }
+
+    pub(crate) fn set_angular_motor(&mut self, on_off: bool, target_velocity: f32, max_force: f32) {
+        unsafe { bw_sliderconstraint_set_angular_motor(self.ptr, on_off as u8, target_velocity, max_force) };
+    }
+}
+
+impl Drop for SliderConstraint {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("SliderConstraint already dropped");
+        }
+
+        unsafe { bw_destroy_sliderconstraint(self.ptr) };
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+pub(crate) struct HingeConstraint {
+    ptr: *mut std::ffi::c_void,
+}
+
+impl HingeConstraint {
+    pub(crate) fn new(body_a: &RigidBody, body_b: &RigidBody, frame_a: &Mat4, frame_b: &Mat4, use_reference_frame_a: bool) -> Self {
+        let frame_a = frame_a.as_ref();
+        let frame_b = frame_b.as_ref();
+        
+        Self {
+            ptr: unsafe { bw_create_hingeconstraint(body_a.ptr(), body_b.ptr(), frame_a.as_ptr(), frame_b.as_ptr(), use_reference_frame_a as u8) },
+        }
+    }
+
+    pub(crate) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.ptr
+    }
+
+    pub(crate) fn set_limit(&mut self, low: f32, high: f32, softness: f32, bias_factor: f32, relaxation_factor: f32) {
+        unsafe { bw_hingeconstraint_set_limit(self.ptr, low, high, softness, bias_factor, relaxation_factor) };
+    }
+
+    pub(crate) fn enable_angular_motor(&mut self, enable_motor: bool, target_velocity: f32, max_motor_impulse: f32) {
+        unsafe { bw_hingeconstraint_enable_angular_motor(self.ptr, enable_motor as u8, target_velocity, max_motor_impulse) };
+    }
+}
+
+impl Drop for HingeConstraint {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.ptr.is_null() {
+            panic!("HingeConstraint already dropped");
+        }
+
+        unsafe { bw_destroy_hingeconstraint(self.ptr) };
+
+        #[cfg(debug_assertions)]
+        {
+            self.ptr = std::ptr::null_mut();
+        }
+    }
+}
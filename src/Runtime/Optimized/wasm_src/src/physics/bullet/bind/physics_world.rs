@@ -1,3 +1,5 @@
+use glam::Vec3;
+
 use super::rigidbody::{RigidBody, RigidBodyShadow};
 
 #[link(name = "bullet")]
@@ -25,6 +27,79 @@ unsafe extern "C" {
     fn bw_world_set_body_temporal_kinematic(world: *mut std::ffi::c_void, rigidbody: *mut std::ffi::c_void, value: u8);
 
     fn bw_world_set_body_kinematic_toggle(world: *mut std::ffi::c_void, rigidbody: *mut std::ffi::c_void, value: u8);
+
+    fn bw_world_ray_test(world: *mut std::ffi::c_void, from_x: f32, from_y: f32, from_z: f32, to_x: f32, to_y: f32, to_z: f32, group: u16, mask: u16, out: *mut std::ffi::c_void) -> u8;
+
+    fn bw_world_sphere_sweep_test(world: *mut std::ffi::c_void, from_x: f32, from_y: f32, from_z: f32, to_x: f32, to_y: f32, to_z: f32, radius: f32, group: u16, mask: u16, out: *mut std::ffi::c_void) -> u8;
+
+    fn bw_world_set_contact_collection_enabled(world: *mut std::ffi::c_void, enabled: u8);
+
+    fn bw_world_clear_contacts(world: *mut std::ffi::c_void);
+
+    fn bw_world_collect_contacts(world: *mut std::ffi::c_void, out: *mut std::ffi::c_void, capacity: i32) -> i32;
+
+    fn bw_world_sphere_contact_test(world: *mut std::ffi::c_void, x: f32, y: f32, z: f32, radius: f32, group: u16, mask: u16, out: *mut std::ffi::c_void, capacity: i32) -> i32;
+
+    fn bw_world_set_debug_draw_categories(world: *mut std::ffi::c_void, categories: u32);
+
+    fn bw_world_debug_draw(world: *mut std::ffi::c_void);
+
+    fn bw_world_get_debug_lines(world: *mut std::ffi::c_void, out: *mut *const f32) -> i32;
+
+    fn bw_world_get_debug_points(world: *mut std::ffi::c_void, out: *mut *const f32) -> i32;
+
+    fn bw_world_set_stats_enabled(world: *mut std::ffi::c_void, enabled: u8);
+
+    fn bw_world_get_stats(world: *mut std::ffi::c_void, out: *mut std::ffi::c_void);
+}
+
+/// Layout must match `bwQueryHit`
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct QueryHit {
+    // bwRigidBody pointer, shadows are reported as their source body
+    pub(crate) body: *const std::ffi::c_void,
+    // hit fraction for ray and sweep, signed distance for contact
+    pub(crate) fraction: f32,
+    pub(crate) point: [f32; 3],
+    pub(crate) normal: [f32; 3],
+}
+
+impl QueryHit {
+    fn empty() -> Self {
+        Self {
+            body: std::ptr::null(),
+            fraction: 1.0,
+            point: [0.0; 3],
+            normal: [0.0; 3],
+        }
+    }
+}
+
+/// Layout must match `bwContactPoint`
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct ContactPoint {
+    pub(crate) body0: *const std::ffi::c_void,
+    pub(crate) body1: *const std::ffi::c_void,
+    // deepest point on body1, normal points from body1 to body0
+    pub(crate) point: [f32; 3],
+    pub(crate) normal: [f32; 3],
+    pub(crate) impulse: f32,
+}
+
+/// Layout must match `bwPhysicsWorldStats`, read from js
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct PhysicsWorldStats {
+    object_count: i32,
+    constraint_count: i32,
+    overlapping_pair_count: i32,
+    manifold_count: i32,
+    island_count: i32,
+    // accumulated over the steps since the previous read
+    sub_step_count: i32,
+    step_time: f32,
 }
 
 pub(crate) struct PhysicsWorld {
@@ -77,6 +152,116 @@ impl PhysicsWorld {
     pub(crate) fn set_body_kinematic_toggle(&mut self, rigidbody: &mut RigidBody, value: bool) {
         unsafe { bw_world_set_body_kinematic_toggle(self.ptr, rigidbody.ptr_mut(), value as u8) };
     }
+
+    /// Collects contacts from an internal tick callback after every sub step while enabled
+    pub(crate) fn set_contact_collection_enabled(&mut self, enabled: bool) {
+        unsafe { bw_world_set_contact_collection_enabled(self.ptr, enabled as u8) };
+    }
+
+    pub(crate) fn clear_contacts(&mut self) {
+        unsafe { bw_world_clear_contacts(self.ptr) };
+    }
+
+    /// One contact per touching manifold of each sub step since the last `clear_contacts`
+    pub(crate) fn collect_contacts(&mut self, out: &mut Vec<ContactPoint>) {
+        out.clear();
+        loop {
+            let capacity = out.capacity();
+            let count = unsafe {
+                bw_world_collect_contacts(self.ptr, out.as_mut_ptr() as *mut std::ffi::c_void, capacity as i32)
+            } as usize;
+
+            if count <= capacity {
+                unsafe { out.set_len(count) };
+                return;
+            }
+            // collected contacts are not changed between calls, so the second try always fits
+            out.reserve(count);
+        }
+    }
+
+    /// Closest hit, queries see the body transforms of the last step
+    pub(crate) fn ray_test(&self, from: Vec3, to: Vec3, group: u16, mask: u16) -> Option<QueryHit> {
+        let mut hit = QueryHit::empty();
+        let has_hit = unsafe {
+            bw_world_ray_test(
+                self.ptr,
+                from.x, from.y, from.z,
+                to.x, to.y, to.z,
+                group,
+                mask,
+                &mut hit as *mut QueryHit as *mut std::ffi::c_void
+            )
+        };
+        if has_hit != 0 { Some(hit) } else { None }
+    }
+
+    pub(crate) fn sphere_sweep_test(&self, from: Vec3, to: Vec3, radius: f32, group: u16, mask: u16) -> Option<QueryHit> {
+        let mut hit = QueryHit::empty();
+        let has_hit = unsafe {
+            bw_world_sphere_sweep_test(
+                self.ptr,
+                from.x, from.y, from.z,
+                to.x, to.y, to.z,
+                radius,
+                group,
+                mask,
+                &mut hit as *mut QueryHit as *mut std::ffi::c_void
+            )
+        };
+        if has_hit != 0 { Some(hit) } else { None }
+    }
+
+    /// Appends contact points up to the spare capacity of `out`
+    pub(crate) fn sphere_contact_test(&mut self, center: Vec3, radius: f32, group: u16, mask: u16, out: &mut Vec<QueryHit>) {
+        let spare = out.spare_capacity_mut();
+        let count = unsafe {
+            bw_world_sphere_contact_test(
+                self.ptr,
+                center.x, center.y, center.z,
+                radius,
+                group,
+                mask,
+                spare.as_mut_ptr() as *mut std::ffi::c_void,
+                spare.len() as i32
+            )
+        };
+        unsafe { out.set_len(out.len() + count as usize) };
+    }
+
+    /// Step time is measured only while enabled
+    pub(crate) fn set_stats_enabled(&mut self, enabled: bool) {
+        unsafe { bw_world_set_stats_enabled(self.ptr, enabled as u8) };
+    }
+
+    /// Also resets the step time and sub step count accumulated in the world
+    pub(crate) fn get_stats(&mut self, out: &mut PhysicsWorldStats) {
+        unsafe { bw_world_get_stats(self.ptr, out as *mut PhysicsWorldStats as *mut std::ffi::c_void) };
+    }
+
+    /// Bit flags of `bwDebugDrawCategory`, 0 disables debug draw
+    pub(crate) fn set_debug_draw_categories(&mut self, categories: u32) {
+        unsafe { bw_world_set_debug_draw_categories(self.ptr, categories) };
+    }
+
+    /// Refills the debug buffers from the current world state
+    pub(crate) fn debug_draw(&mut self) {
+        unsafe { bw_world_debug_draw(self.ptr) };
+    }
+
+    /// Vertex pairs of position xyz and color rgb, valid until the next `debug_draw`
+    pub(crate) fn debug_lines(&self) -> &[f32] {
+        let mut ptr = std::ptr::null();
+        let len = unsafe { bw_world_get_debug_lines(self.ptr, &mut ptr) } as usize;
+        if ptr.is_null() { &[] } else { unsafe { std::slice::from_raw_parts(ptr, len) } }
+    }
+
+    /// Points of position xyz and color rgb, valid until the next `debug_draw`
+    pub(crate) fn debug_points(&self) -> &[f32] {
+        let mut ptr = std::ptr::null();
+        let len = unsafe { bw_world_get_debug_points(self.ptr, &mut ptr) } as usize;
+        if ptr.is_null() { &[] } else { unsafe { std::slice::from_raw_parts(ptr, len) } }
+    }
 }
 
 impl Drop for PhysicsWorld {
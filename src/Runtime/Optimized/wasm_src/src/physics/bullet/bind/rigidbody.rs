@@ -1,4 +1,4 @@
-use glam::Vec3;
+use glam::{Mat4, Vec3};
 
 use super::motion_state::MotionState;
 use super::super::runtime;
@@ -15,12 +15,34 @@ unsafe extern "C" {
 
     fn bw_rigidbody_get_angular_damping(body: *const std::ffi::c_void) -> f32;
 
+    fn bw_rigidbody_set_ccd(body: *mut std::ffi::c_void, motion_threshold: f32, swept_sphere_radius: f32);
+
+    fn bw_rigidbody_get_ccd_motion_threshold(body: *const std::ffi::c_void) -> f32;
+
+    fn bw_rigidbody_get_ccd_swept_sphere_radius(body: *const std::ffi::c_void) -> f32;
+
     fn bw_rigidbody_set_mass_props(body: *mut std::ffi::c_void, mass: f32, local_inertia: *const f32);
 
     fn bw_rigidbody_get_mass(body: *const std::ffi::c_void) -> f32;
 
     fn bw_rigidbody_get_local_inertia(body: *const std::ffi::c_void, local_inertia: *mut f32);
 
+    fn bw_rigidbody_set_mass_with_inertia(body: *mut std::ffi::c_void, mass: f32);
+
+    fn bw_rigidbody_set_friction(body: *mut std::ffi::c_void, friction: f32);
+
+    fn bw_rigidbody_get_friction(body: *const std::ffi::c_void) -> f32;
+
+    fn bw_rigidbody_set_restitution(body: *mut std::ffi::c_void, restitution: f32);
+
+    fn bw_rigidbody_get_restitution(body: *const std::ffi::c_void) -> f32;
+
+    fn bw_rigidbody_set_collision_filter(body: *mut std::ffi::c_void, group: u16, mask: u16);
+
+    fn bw_rigidbody_get_collision_group(body: *const std::ffi::c_void) -> u16;
+
+    fn bw_rigidbody_get_collision_mask(body: *const std::ffi::c_void) -> u16;
+
     fn bw_rigidbody_get_total_force(body: *const std::ffi::c_void, force: *mut f32);
 
     fn bw_rigidbody_get_total_torque(body: *const std::ffi::c_void, torque: *mut f32);
@@ -61,6 +83,12 @@ unsafe extern "C" {
 
     fn bw_rigidbody_set_angular_velocity(body: *mut std::ffi::c_void, velocity: *const f32);
 
+    fn bw_rigidbody_set_sleeping(body: *mut std::ffi::c_void, enabled: u8, linear_threshold: f32, angular_threshold: f32);
+
+    fn bw_rigidbody_is_sleeping(body: *const std::ffi::c_void) -> u8;
+
+    fn bw_rigidbody_wake_up(body: *mut std::ffi::c_void);
+
     fn bw_rigidbody_get_velocity_in_local_point(body: *const std::ffi::c_void, relative_position: *const f32, velocity: *mut f32);
 
     fn bw_rigidbody_get_push_velocity_in_local_point(body: *const std::ffi::c_void, relative_position: *const f32, velocity: *mut f32);
@@ -73,6 +101,12 @@ unsafe extern "C" {
 
     fn bw_rigidbody_get_motion_type(body: *mut std::ffi::c_void) -> u8;
 
+    fn bw_rigidbody_get_state(body: *const std::ffi::c_void, out: *mut std::ffi::c_void);
+
+    fn bw_rigidbody_set_state(body: *mut std::ffi::c_void, state: *const std::ffi::c_void);
+
+    fn bw_rigidbody_get_step_transform(body: *const std::ffi::c_void, out: *mut f32);
+
     fn bw_create_rigidbody_shadow(body: *mut std::ffi::c_void, motion_state: *mut std::ffi::c_void) -> *mut std::ffi::c_void;
 
     fn bw_destroy_rigidbody_shadow(shadow: *mut std::ffi::c_void);
@@ -122,6 +156,8 @@ pub(crate) struct RigidBodyConstructionInfo {
     // additional_angular_damping_factor: f32,
     no_contact_response: u8,
     disable_deactivation: u8,
+    ccd_motion_threshold: f32,
+    ccd_swept_sphere_radius: f32,
 }
 
 impl RigidBodyConstructionInfo {
@@ -129,6 +165,10 @@ impl RigidBodyConstructionInfo {
         info: &runtime::rigidbody_construction_info::RigidBodyConstructionInfo,
         motion_state: *const std::ffi::c_void,
     ) -> Self {
+        if info.shape.is_concave() && info.motion_type == MotionType::Dynamic as u8 {
+            panic!("Concave shape can not be used for dynamic rigid body");
+        }
+
         Self {
             shape: info.shape.ptr(),
             motion_state,
@@ -154,6 +194,8 @@ impl RigidBodyConstructionInfo {
             // additional_angular_damping_factor: 0.0,
             no_contact_response: info.no_contact_response,
             disable_deactivation: info.disable_deactivation,
+            ccd_motion_threshold: info.ccd_motion_threshold,
+            ccd_swept_sphere_radius: info.ccd_swept_sphere_radius,
         }
     }
     
@@ -174,6 +216,17 @@ impl RigidBodyConstructionInfo {
     }
 }
 
+/// Layout must match `bwRigidBodyState`
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct RigidBodyState {
+    transform: [f32; 16],
+    linear_velocity: [f32; 3],
+    angular_velocity: [f32; 3],
+    activation_state: i32,
+    deactivation_time: f32,
+}
+
 pub(crate) struct RigidBody {
     ptr: *mut std::ffi::c_void,
 }
@@ -185,7 +238,7 @@ impl RigidBody {
         }
     }
 
-    pub(super) fn ptr(&self) -> *const std::ffi::c_void {
+    pub(crate) fn ptr(&self) -> *const std::ffi::c_void {
         self.ptr
     }
 
@@ -205,6 +258,18 @@ impl RigidBody {
         unsafe { bw_rigidbody_get_angular_damping(self.ptr) }
     }
 
+    pub(crate) fn set_ccd(&mut self, motion_threshold: f32, swept_sphere_radius: f32) {
+        unsafe { bw_rigidbody_set_ccd(self.ptr, motion_threshold, swept_sphere_radius) };
+    }
+
+    pub(crate) fn get_ccd_motion_threshold(&self) -> f32 {
+        unsafe { bw_rigidbody_get_ccd_motion_threshold(self.ptr) }
+    }
+
+    pub(crate) fn get_ccd_swept_sphere_radius(&self) -> f32 {
+        unsafe { bw_rigidbody_get_ccd_swept_sphere_radius(self.ptr) }
+    }
+
     pub(crate) fn set_mass_props(&mut self, mass: f32, local_inertia: Vec3) {
         unsafe { bw_rigidbody_set_mass_props(self.ptr, mass, local_inertia.as_ref().as_ptr()) };
     }
@@ -219,6 +284,39 @@ impl RigidBody {
         local_inertia
     }
 
+    /// Local inertia is recalculated from the shape, body must be dynamic
+    pub(crate) fn set_mass_with_inertia(&mut self, mass: f32) {
+        unsafe { bw_rigidbody_set_mass_with_inertia(self.ptr, mass) };
+    }
+
+    pub(crate) fn set_friction(&mut self, friction: f32) {
+        unsafe { bw_rigidbody_set_friction(self.ptr, friction) };
+    }
+
+    pub(crate) fn get_friction(&self) -> f32 {
+        unsafe { bw_rigidbody_get_friction(self.ptr) }
+    }
+
+    pub(crate) fn set_restitution(&mut self, restitution: f32) {
+        unsafe { bw_rigidbody_set_restitution(self.ptr, restitution) };
+    }
+
+    pub(crate) fn get_restitution(&self) -> f32 {
+        unsafe { bw_rigidbody_get_restitution(self.ptr) }
+    }
+
+    pub(crate) fn set_collision_filter(&mut self, group: u16, mask: u16) {
+        unsafe { bw_rigidbody_set_collision_filter(self.ptr, group, mask) };
+    }
+
+    pub(crate) fn get_collision_group(&self) -> u16 {
+        unsafe { bw_rigidbody_get_collision_group(self.ptr) }
+    }
+
+    pub(crate) fn get_collision_mask(&self) -> u16 {
+        unsafe { bw_rigidbody_get_collision_mask(self.ptr) }
+    }
+
     pub(crate) fn get_total_force(&self) -> Vec3 {
         let mut force = Vec3::ZERO;
         unsafe { bw_rigidbody_get_total_force(self.ptr, force.as_mut().as_mut_ptr()) };
@@ -311,6 +409,19 @@ impl RigidBody {
         unsafe { bw_rigidbody_set_angular_velocity(self.ptr, velocity.as_ref().as_ptr()) };
     }
 
+    /// Only for dynamic bodies
+    pub(crate) fn set_sleeping(&mut self, enabled: bool, linear_threshold: f32, angular_threshold: f32) {
+        unsafe { bw_rigidbody_set_sleeping(self.ptr, enabled as u8, linear_threshold, angular_threshold) };
+    }
+
+    pub(crate) fn is_sleeping(&self) -> bool {
+        unsafe { bw_rigidbody_is_sleeping(self.ptr) != 0 }
+    }
+
+    pub(crate) fn wake_up(&mut self) {
+        unsafe { bw_rigidbody_wake_up(self.ptr) };
+    }
+
     pub(crate) fn get_velocity_in_local_point(&self, relative_position: Vec3) -> Vec3 {
         let mut velocity = Vec3::ZERO;
         unsafe { bw_rigidbody_get_velocity_in_local_point(self.ptr, relative_position.as_ref().as_ptr(), velocity.as_mut().as_mut_ptr()) };
@@ -331,6 +442,24 @@ impl RigidBody {
         unsafe { bw_rigidbody_set_shape(self.ptr, shape) };
     }
 
+    pub(crate) fn get_state(&self) -> RigidBodyState {
+        let mut state = RigidBodyState::default();
+        unsafe { bw_rigidbody_get_state(self.ptr, &mut state as *mut RigidBodyState as *mut std::ffi::c_void) };
+        state
+    }
+
+    /// Also writes the motion state of dynamic body, so the restored transform is visible before the next step
+    pub(crate) fn set_state(&mut self, state: &RigidBodyState) {
+        unsafe { bw_rigidbody_set_state(self.ptr, state as *const RigidBodyState as *const std::ffi::c_void) };
+    }
+
+    /// Transform at the end of the last fixed step
+    pub(crate) fn get_step_transform(&self) -> Mat4 {
+        let mut transform = Mat4::IDENTITY;
+        unsafe { bw_rigidbody_get_step_transform(self.ptr, transform.as_mut().as_mut_ptr()) };
+        transform
+    }
+
     pub(crate) fn get_world_transform_ptr_mut(&mut self) -> *mut std::ffi::c_void {
         unsafe { bw_rigidbody_get_world_transform_ptr(self.ptr) }
     }
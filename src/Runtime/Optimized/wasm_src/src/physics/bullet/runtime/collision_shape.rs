@@ -1,4 +1,4 @@
-use glam::Vec3;
+use glam::{Mat4, Vec3};
 use wasm_bindgen::prelude::*;
 
 use super::super::bind;
@@ -107,11 +107,240 @@ impl Drop for StaticPlaneShape {
     }
 }
 
+pub(crate) struct CylinderShape {
+    inner: bind::collision_shape::CylinderShape,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl CylinderShape {
+    pub(crate) fn new(half_extents: Vec3) -> Self {
+        let inner = bind::collision_shape::CylinderShape::new(half_extents);
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for CylinderShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("CylinderShape still has references");
+        }
+    }
+}
+
+pub(crate) struct ConeShape {
+    inner: bind::collision_shape::ConeShape,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl ConeShape {
+    pub(crate) fn new(radius: f32, height: f32) -> Self {
+        let inner = bind::collision_shape::ConeShape::new(radius, height);
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for ConeShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("ConeShape still has references");
+        }
+    }
+}
+
+pub(crate) struct ConvexHullShape {
+    inner: bind::collision_shape::ConvexHullShape,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl ConvexHullShape {
+    pub(crate) fn new(points: &[Vec3]) -> Self {
+        let inner = bind::collision_shape::ConvexHullShape::new(points);
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for ConvexHullShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("ConvexHullShape still has references");
+        }
+    }
+}
+
+pub(crate) struct CompoundShape {
+    inner: bind::collision_shape::CompoundShape,
+    // keeps child shapes referenced while the compound shape is alive
+    #[allow(dead_code)]
+    children: Vec<CollisionShapeHandle>,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl CompoundShape {
+    pub(crate) fn new(children: Vec<(CollisionShapeHandle, Mat4)>) -> Self {
+        let mut inner = bind::collision_shape::CompoundShape::new(children.len());
+        let children = children
+            .into_iter()
+            .map(|(child, local_transform)| {
+                inner.add_child_shape(&local_transform, child.get().ptr());
+                child
+            })
+            .collect();
+        Self {
+            inner,
+            children,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for CompoundShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("CompoundShape still has references");
+        }
+    }
+}
+
+pub(crate) struct BvhTriangleMeshShape {
+    inner: bind::collision_shape::BvhTriangleMeshShape,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl BvhTriangleMeshShape {
+    /// `vertices` is xyz array and `indices` is triangle list
+    pub(crate) fn new(vertices: &[f32], indices: &[u32]) -> Result<Self, &'static str> {
+        if !vertices.len().is_multiple_of(3) || !indices.len().is_multiple_of(3) || indices.is_empty() {
+            return Err("Triangle mesh must have xyz vertices and at least one triangle");
+        }
+        let vertex_count = vertices.len() / 3;
+        // bullet reads the vertex buffer without bounds check
+        if indices.iter().any(|index| vertex_count <= *index as usize) {
+            return Err("Triangle mesh index out of range");
+        }
+
+        let inner = bind::collision_shape::BvhTriangleMeshShape::new(
+            vertices.into(),
+            indices.iter().map(|index| *index as i32).collect()
+        );
+        Ok(Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        })
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for BvhTriangleMeshShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("BvhTriangleMeshShape still has references");
+        }
+    }
+}
+
+/// Shares the bvh of the child shape, so the same stage mesh can be placed with different scales
+pub(crate) struct ScaledBvhTriangleMeshShape {
+    inner: bind::collision_shape::ScaledBvhTriangleMeshShape,
+    // keeps the child shape referenced while the scaled shape is alive
+    #[allow(dead_code)]
+    child: CollisionShapeHandle,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl ScaledBvhTriangleMeshShape {
+    pub(crate) fn new(child: CollisionShapeHandle, scaling: Vec3) -> Result<Self, &'static str> {
+        let inner = match child.get() {
+            CollisionShape::BvhTriangleMesh(shape) => bind::collision_shape::ScaledBvhTriangleMeshShape::new(&shape.inner, scaling),
+            _ => return Err("Scaled triangle mesh shape requires triangle mesh shape as child"),
+        };
+        Ok(Self {
+            inner,
+            child,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        })
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for ScaledBvhTriangleMeshShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("ScaledBvhTriangleMeshShape still has references");
+        }
+    }
+}
+
+pub(crate) struct HeightfieldTerrainShape {
+    inner: bind::collision_shape::HeightfieldTerrainShape,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+}
+
+impl HeightfieldTerrainShape {
+    /// `heights` is row major `width * length` array, `scaling` is grid spacing for x, z and height scale for y
+    pub(crate) fn new(width: u32, length: u32, heights: &[f32], min_height: f32, max_height: f32, scaling: Vec3) -> Result<Self, &'static str> {
+        if width < 2 || length < 2 || heights.len() != width as usize * length as usize {
+            return Err("Heightfield must have at least 2x2 heights and match width * length");
+        }
+        if min_height > max_height {
+            return Err("Heightfield min height must not be greater than max height");
+        }
+
+        let inner = bind::collision_shape::HeightfieldTerrainShape::new(width, length, heights.into(), min_height, max_height, scaling);
+        Ok(Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+        })
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for HeightfieldTerrainShape {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("HeightfieldTerrainShape still has references");
+        }
+    }
+}
+
 pub(crate) enum CollisionShape {
     Box(BoxShape),
     Sphere(SphereShape),
     Capsule(CapsuleShape),
     StaticPlane(StaticPlaneShape),
+    Cylinder(CylinderShape),
+    Cone(ConeShape),
+    ConvexHull(ConvexHullShape),
+    Compound(CompoundShape),
+    BvhTriangleMesh(BvhTriangleMeshShape),
+    ScaledBvhTriangleMesh(ScaledBvhTriangleMeshShape),
+    HeightfieldTerrain(HeightfieldTerrainShape),
 }
 
 impl CollisionShape {
@@ -121,6 +350,13 @@ impl CollisionShape {
             CollisionShape::Sphere(shape) => shape.inner.ptr(),
             CollisionShape::Capsule(shape) => shape.inner.ptr(),
             CollisionShape::StaticPlane(shape) => shape.inner.ptr(),
+            CollisionShape::Cylinder(shape) => shape.inner.ptr(),
+            CollisionShape::Cone(shape) => shape.inner.ptr(),
+            CollisionShape::ConvexHull(shape) => shape.inner.ptr(),
+            CollisionShape::Compound(shape) => shape.inner.ptr(),
+            CollisionShape::BvhTriangleMesh(shape) => shape.inner.ptr(),
+            CollisionShape::ScaledBvhTriangleMesh(shape) => shape.inner.ptr(),
+            CollisionShape::HeightfieldTerrain(shape) => shape.inner.ptr(),
         }
     }
 
@@ -131,9 +367,27 @@ impl CollisionShape {
             CollisionShape::Sphere(shape) => &mut shape.ref_count,
             CollisionShape::Capsule(shape) => &mut shape.ref_count,
             CollisionShape::StaticPlane(shape) => &mut shape.ref_count,
+            CollisionShape::Cylinder(shape) => &mut shape.ref_count,
+            CollisionShape::Cone(shape) => &mut shape.ref_count,
+            CollisionShape::ConvexHull(shape) => &mut shape.ref_count,
+            CollisionShape::Compound(shape) => &mut shape.ref_count,
+            CollisionShape::BvhTriangleMesh(shape) => &mut shape.ref_count,
+            CollisionShape::ScaledBvhTriangleMesh(shape) => &mut shape.ref_count,
+            CollisionShape::HeightfieldTerrain(shape) => &mut shape.ref_count,
         }
     }
 
+    /// Concave shapes can not be simulated dynamically, they are used for static colliders like stage geometry
+    pub(crate) fn is_concave(&self) -> bool {
+        matches!(
+            self,
+            CollisionShape::StaticPlane(_) |
+            CollisionShape::BvhTriangleMesh(_) |
+            CollisionShape::ScaledBvhTriangleMesh(_) |
+            CollisionShape::HeightfieldTerrain(_)
+        )
+    }
+
     pub(crate) fn create_handle(&mut self) -> CollisionShapeHandle {
         CollisionShapeHandle::new(self)
     }
@@ -215,6 +469,90 @@ pub fn create_staticplaneshape(normal_x: f32, normal_y: f32, normal_z: f32, plan
     Box::into_raw(Box::new(plane_shape)) as *mut usize
 }
 
+#[wasm_bindgen(js_name = "createCylinderShape")]
+pub fn create_cylindershape(half_extent_x: f32, half_extent_y: f32, half_extent_z: f32) -> *mut usize {
+    let cylinder_shape = CylinderShape::new(Vec3::new(half_extent_x, half_extent_y, half_extent_z));
+    let cylinder_shape = CollisionShape::Cylinder(cylinder_shape);
+    Box::into_raw(Box::new(cylinder_shape)) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createConeShape")]
+pub fn create_coneshape(radius: f32, height: f32) -> *mut usize {
+    let cone_shape = ConeShape::new(radius, height);
+    let cone_shape = CollisionShape::Cone(cone_shape);
+    Box::into_raw(Box::new(cone_shape)) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createConvexHullShape")]
+pub fn create_convexhullshape(points: *const f32, point_count: u32) -> *mut usize {
+    let points = unsafe { std::slice::from_raw_parts(points, point_count as usize * 3) };
+    let points = points.chunks_exact(3).map(Vec3::from_slice).collect::<Vec<Vec3>>();
+
+    let convex_hull_shape = ConvexHullShape::new(&points);
+    let convex_hull_shape = CollisionShape::ConvexHull(convex_hull_shape);
+    Box::into_raw(Box::new(convex_hull_shape)) as *mut usize
+}
+
+/// Child shapes must be destroyed after the compound shape
+#[wasm_bindgen(js_name = "createCompoundShape")]
+pub fn create_compoundshape(child_shapes: *const usize, local_transforms: *const f32, child_count: u32) -> *mut usize {
+    let child_shapes = unsafe { std::slice::from_raw_parts(child_shapes, child_count as usize) };
+    let local_transforms = unsafe { std::slice::from_raw_parts(local_transforms, child_count as usize * 16) };
+
+    let children = child_shapes
+        .iter()
+        .zip(local_transforms.chunks_exact(16))
+        .map(|(child_shape, local_transform)| {
+            let child_shape = unsafe { &mut *(*child_shape as *mut CollisionShape) };
+            (child_shape.create_handle(), Mat4::from_cols_slice(local_transform))
+        })
+        .collect();
+
+    let compound_shape = CompoundShape::new(children);
+    let compound_shape = CollisionShape::Compound(compound_shape);
+    Box::into_raw(Box::new(compound_shape)) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createTriangleMeshShape")]
+pub fn create_trianglemeshshape(vertices: *const f32, vertex_count: u32, indices: *const u32, index_count: u32) -> Result<*mut usize, JsError> {
+    let vertices = unsafe { std::slice::from_raw_parts(vertices, vertex_count as usize * 3) };
+    let indices = unsafe { std::slice::from_raw_parts(indices, index_count as usize) };
+
+    let triangle_mesh_shape = BvhTriangleMeshShape::new(vertices, indices).map_err(JsError::new)?;
+    let triangle_mesh_shape = CollisionShape::BvhTriangleMesh(triangle_mesh_shape);
+    Ok(Box::into_raw(Box::new(triangle_mesh_shape)) as *mut usize)
+}
+
+/// Child shape must be destroyed after the scaled shape
+#[wasm_bindgen(js_name = "createScaledTriangleMeshShape")]
+pub fn create_scaledtrianglemeshshape(child_shape: *mut usize, scaling_x: f32, scaling_y: f32, scaling_z: f32) -> Result<*mut usize, JsError> {
+    let child_shape = unsafe { &mut *(child_shape as *mut CollisionShape) };
+
+    let scaled_shape = ScaledBvhTriangleMeshShape::new(child_shape.create_handle(), Vec3::new(scaling_x, scaling_y, scaling_z)).map_err(JsError::new)?;
+    let scaled_shape = CollisionShape::ScaledBvhTriangleMesh(scaled_shape);
+    Ok(Box::into_raw(Box::new(scaled_shape)) as *mut usize)
+}
+
+#[wasm_bindgen(js_name = "createHeightfieldShape")]
+#[allow(clippy::too_many_arguments)]
+pub fn create_heightfieldshape(
+    width: u32,
+    length: u32,
+    heights: *const f32,
+    min_height: f32,
+    max_height: f32,
+    scaling_x: f32,
+    scaling_y: f32,
+    scaling_z: f32,
+) -> Result<*mut usize, JsError> {
+    let heights = unsafe { std::slice::from_raw_parts(heights, width as usize * length as usize) };
+
+    let heightfield_shape = HeightfieldTerrainShape::new(width, length, heights, min_height, max_height, Vec3::new(scaling_x, scaling_y, scaling_z))
+        .map_err(JsError::new)?;
+    let heightfield_shape = CollisionShape::HeightfieldTerrain(heightfield_shape);
+    Ok(Box::into_raw(Box::new(heightfield_shape)) as *mut usize)
+}
+
 #[wasm_bindgen(js_name = "destroyShape")]
 pub fn destroy_shape(ptr: *mut usize) {
     unsafe {
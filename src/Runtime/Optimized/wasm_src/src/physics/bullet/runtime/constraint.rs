@@ -256,9 +256,369 @@ impl Drop for Generic6DofSpringConstraint {
     }
 }
 
+pub(crate) struct Point2PointConstraint {
+    inner: bind::constraint::Point2PointConstraint,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+    #[allow(dead_code)]
+    #[cfg(debug_assertions)]
+    body_handle: ConstraintRigidBodyHandleInfo,
+}
+
+impl Point2PointConstraint {
+    fn new_raw(
+        body_a: &bind::rigidbody::RigidBody,
+        body_b: &bind::rigidbody::RigidBody,
+        #[cfg(debug_assertions)]
+        body_handle: ConstraintRigidBodyHandleInfo,
+        pivot_in_a: Vec3,
+        pivot_in_b: Vec3,
+    ) -> Self {
+        let inner = bind::constraint::Point2PointConstraint::new(
+            body_a,
+            body_b,
+            pivot_in_a,
+            pivot_in_b
+        );
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+            #[cfg(debug_assertions)]
+            body_handle,
+        }
+    }
+
+    pub(crate) fn new(mut body_a: RigidBodyHandle, mut body_b: RigidBodyHandle, pivot_in_a: Vec3, pivot_in_b: Vec3) -> Self {
+        let body_a_binding: RigidBodyHandle = body_a.clone();
+        let body_inner_a = body_a_binding.get().get_inner();
+
+        let body_b_binding: RigidBodyHandle = body_b.clone();
+        let body_inner_b = body_b_binding.get().get_inner();
+
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBody((body_a, body_b)),
+            pivot_in_a,
+            pivot_in_b
+        )
+    }
+
+    pub(crate) fn from_bundle(mut body_bundle: RigidBodyBundleHandle, body_a_index: u32, body_b_index: u32, pivot_in_a: Vec3, pivot_in_b: Vec3) -> Self {
+        let cloned_handle = body_bundle.clone();
+        let body_inner_a = &cloned_handle.get().bodies()[body_a_index as usize];
+        let body_inner_b = &cloned_handle.get().bodies()[body_b_index as usize];
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBodyBundle(body_bundle),
+            pivot_in_a,
+            pivot_in_b
+        )
+    }
+
+    pub(super) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.inner.ptr_mut()
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for Point2PointConstraint {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("Point2PointConstraint still has references");
+        }
+    }
+}
+
+pub(crate) struct ConeTwistConstraint {
+    inner: bind::constraint::ConeTwistConstraint,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+    #[allow(dead_code)]
+    #[cfg(debug_assertions)]
+    body_handle: ConstraintRigidBodyHandleInfo,
+}
+
+impl ConeTwistConstraint {
+    fn new_raw(
+        body_a: &bind::rigidbody::RigidBody,
+        body_b: &bind::rigidbody::RigidBody,
+        #[cfg(debug_assertions)]
+        body_handle: ConstraintRigidBodyHandleInfo,
+        frame_a: &Mat4,
+        frame_b: &Mat4,
+    ) -> Self {
+        let inner = bind::constraint::ConeTwistConstraint::new(
+            body_a,
+            body_b,
+            frame_a,
+            frame_b
+        );
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+            #[cfg(debug_assertions)]
+            body_handle,
+        }
+    }
+
+    pub(crate) fn new(mut body_a: RigidBodyHandle, mut body_b: RigidBodyHandle, frame_a: &Mat4, frame_b: &Mat4) -> Self {
+        let body_a_binding: RigidBodyHandle = body_a.clone();
+        let body_inner_a = body_a_binding.get().get_inner();
+
+        let body_b_binding: RigidBodyHandle = body_b.clone();
+        let body_inner_b = body_b_binding.get().get_inner();
+
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBody((body_a, body_b)),
+            frame_a,
+            frame_b
+        )
+    }
+
+    pub(crate) fn from_bundle(mut body_bundle: RigidBodyBundleHandle, body_a_index: u32, body_b_index: u32, frame_a: &Mat4, frame_b: &Mat4) -> Self {
+        let cloned_handle = body_bundle.clone();
+        let body_inner_a = &cloned_handle.get().bodies()[body_a_index as usize];
+        let body_inner_b = &cloned_handle.get().bodies()[body_b_index as usize];
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBodyBundle(body_bundle),
+            frame_a,
+            frame_b
+        )
+    }
+
+    pub(super) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.inner.ptr_mut()
+    }
+
+    pub(crate) fn set_limit(&mut self, swing_span1: f32, swing_span2: f32, twist_span: f32, softness: f32, bias_factor: f32, relaxation_factor: f32) {
+        self.inner.set_limit(swing_span1, swing_span2, twist_span, softness, bias_factor, relaxation_factor);
+    }
+
+    pub(crate) fn set_damping(&mut self, damping: f32) {
+        self.inner.set_damping(damping);
+    }
+
+    pub(crate) fn set_fix_thresh(&mut self, fix_thresh: f32) {
+        self.inner.set_fix_thresh(fix_thresh);
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for ConeTwistConstraint {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("ConeTwistConstraint still has references");
+        }
+    }
+}
+
+pub(crate) struct SliderConstraint {
+    inner: bind::constraint::SliderConstraint,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+    #[allow(dead_code)]
+    #[cfg(debug_assertions)]
+    body_handle: ConstraintRigidBodyHandleInfo,
+}
+
+impl SliderConstraint {
+    fn new_raw(
+        body_a: &bind::rigidbody::RigidBody,
+        body_b: &bind::rigidbody::RigidBody,
+        #[cfg(debug_assertions)]
+        body_handle: ConstraintRigidBodyHandleInfo,
+        frame_a: &Mat4,
+        frame_b: &Mat4,
+        use_linear_reference_frame_a: bool,
+    ) -> Self {
+        let inner = bind::constraint::SliderConstraint::new(
+            body_a,
+            body_b,
+            frame_a,
+            frame_b,
+            use_linear_reference_frame_a
+        );
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+            #[cfg(debug_assertions)]
+            body_handle,
+        }
+    }
+
+    pub(crate) fn new(mut body_a: RigidBodyHandle, mut body_b: RigidBodyHandle, frame_a: &Mat4, frame_b: &Mat4, use_linear_reference_frame_a: bool) -> Self {
+        let body_a_binding: RigidBodyHandle = body_a.clone();
+        let body_inner_a = body_a_binding.get().get_inner();
+
+        let body_b_binding: RigidBodyHandle = body_b.clone();
+        let body_inner_b = body_b_binding.get().get_inner();
+
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBody((body_a, body_b)),
+            frame_a,
+            frame_b,
+            use_linear_reference_frame_a
+        )
+    }
+
+    pub(crate) fn from_bundle(mut body_bundle: RigidBodyBundleHandle, body_a_index: u32, body_b_index: u32, frame_a: &Mat4, frame_b: &Mat4, use_linear_reference_frame_a: bool) -> Self {
+        let cloned_handle = body_bundle.clone();
+        let body_inner_a = &cloned_handle.get().bodies()[body_a_index as usize];
+        let body_inner_b = &cloned_handle.get().bodies()[body_b_index as usize];
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBodyBundle(body_bundle),
+            frame_a,
+            frame_b,
+            use_linear_reference_frame_a
+        )
+    }
+
+    pub(super) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.inner.ptr_mut()
+    }
+
+    pub(crate) fn set_linear_limit(&mut self, lower: f32, upper: f32) {
+        self.inner.set_linear_limit(lower, upper);
+    }
+
+    pub(crate) fn set_angular_limit(&mut self, lower: f32, upper: f32) {
+        self.inner.set_angular_limit(lower, upper);
+    }
+
+    pub(crate) fn set_linear_motor(&mut self, on_off: bool, target_velocity: f32, max_force: f32) {
+        self.inner.set_linear_motor(on_off, target_velocity, max_force);
+    }
+
+    pub(crate) fn set_angular_motor(&mut self, on_off: bool, target_velocity: f32, max_force: f32) {
+        self.inner.set_angular_motor(on_off, target_velocity, max_force);
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for SliderConstraint {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("SliderConstraint still has references");
+        }
+    }
+}
+
+pub(crate) struct HingeConstraint {
+    inner: bind::constraint::HingeConstraint,
+    #[cfg(debug_assertions)]
+    ref_count: u32,
+    #[allow(dead_code)]
+    #[cfg(debug_assertions)]
+    body_handle: ConstraintRigidBodyHandleInfo,
+}
+
+impl HingeConstraint {
+    fn new_raw(
+        body_a: &bind::rigidbody::RigidBody,
+        body_b: &bind::rigidbody::RigidBody,
+        #[cfg(debug_assertions)]
+        body_handle: ConstraintRigidBodyHandleInfo,
+        frame_a: &Mat4,
+        frame_b: &Mat4,
+        use_reference_frame_a: bool,
+    ) -> Self {
+        let inner = bind::constraint::HingeConstraint::new(
+            body_a,
+            body_b,
+            frame_a,
+            frame_b,
+            use_reference_frame_a
+        );
+        Self {
+            inner,
+            #[cfg(debug_assertions)]
+            ref_count: 0,
+            #[cfg(debug_assertions)]
+            body_handle,
+        }
+    }
+
+    pub(crate) fn new(mut body_a: RigidBodyHandle, mut body_b: RigidBodyHandle, frame_a: &Mat4, frame_b: &Mat4, use_reference_frame_a: bool) -> Self {
+        let body_a_binding: RigidBodyHandle = body_a.clone();
+        let body_inner_a = body_a_binding.get().get_inner();
+
+        let body_b_binding: RigidBodyHandle = body_b.clone();
+        let body_inner_b = body_b_binding.get().get_inner();
+
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBody((body_a, body_b)),
+            frame_a,
+            frame_b,
+            use_reference_frame_a
+        )
+    }
+
+    pub(crate) fn from_bundle(mut body_bundle: RigidBodyBundleHandle, body_a_index: u32, body_b_index: u32, frame_a: &Mat4, frame_b: &Mat4, use_reference_frame_a: bool) -> Self {
+        let cloned_handle = body_bundle.clone();
+        let body_inner_a = &cloned_handle.get().bodies()[body_a_index as usize];
+        let body_inner_b = &cloned_handle.get().bodies()[body_b_index as usize];
+        Self::new_raw(
+            body_inner_a,
+            body_inner_b,
+            #[cfg(debug_assertions)]
+            ConstraintRigidBodyHandleInfo::RigidBodyBundle(body_bundle),
+            frame_a,
+            frame_b,
+            use_reference_frame_a
+        )
+    }
+
+    pub(super) fn ptr_mut(&self) -> *mut std::ffi::c_void {
+        self.inner.ptr_mut()
+    }
+
+    pub(crate) fn set_limit(&mut self, low: f32, high: f32, softness: f32, bias_factor: f32, relaxation_factor: f32) {
+        self.inner.set_limit(low, high, softness, bias_factor, relaxation_factor);
+    }
+
+    pub(crate) fn enable_angular_motor(&mut self, enable_motor: bool, target_velocity: f32, max_motor_impulse: f32) {
+        self.inner.enable_angular_motor(enable_motor, target_velocity, max_motor_impulse);
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for HingeConstraint {
+    fn drop(&mut self) {
+        if 0 < self.ref_count {
+            panic!("HingeConstraint still has references");
+        }
+    }
+}
+
 pub(crate) enum Constraint {
     Generic6Dof(Generic6DofConstraint),
     Generic6DofSpring(Generic6DofSpringConstraint),
+    Point2Point(Point2PointConstraint),
+    ConeTwist(ConeTwistConstraint),
+    Slider(SliderConstraint),
+    Hinge(HingeConstraint),
     #[allow(dead_code)]
     Unknown,
 }
@@ -268,6 +628,10 @@ impl Constraint {
         match self {
             Constraint::Generic6Dof(constraint) => constraint.ptr_mut(),
             Constraint::Generic6DofSpring(constraint) => constraint.ptr_mut(),
+            Constraint::Point2Point(constraint) => constraint.ptr_mut(),
+            Constraint::ConeTwist(constraint) => constraint.ptr_mut(),
+            Constraint::Slider(constraint) => constraint.ptr_mut(),
+            Constraint::Hinge(constraint) => constraint.ptr_mut(),
             Constraint::Unknown => panic!("Unknown constraint"),
         }
     }
@@ -277,6 +641,10 @@ impl Constraint {
         match self {
             Constraint::Generic6Dof(constraint) => &mut constraint.ref_count,
             Constraint::Generic6DofSpring(constraint) => &mut constraint.ref_count,
+            Constraint::Point2Point(constraint) => &mut constraint.ref_count,
+            Constraint::ConeTwist(constraint) => &mut constraint.ref_count,
+            Constraint::Slider(constraint) => &mut constraint.ref_count,
+            Constraint::Hinge(constraint) => &mut constraint.ref_count,
             Constraint::Unknown => panic!("Unknown constraint"),
         }
     }
@@ -284,6 +652,15 @@ impl Constraint {
     pub(crate) fn create_handle(&mut self) -> ConstraintHandle {
         ConstraintHandle::new(self)
     }
+
+    /// Disabled constraints are skipped by the solver but stay in the world
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        bind::constraint::set_constraint_enabled(self.ptr_mut(), enabled);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        bind::constraint::is_constraint_enabled(self.ptr_mut())
+    }
 }
 
 pub(crate) struct ConstraintHandle {
@@ -306,17 +683,17 @@ impl ConstraintHandle {
         }
     }
 
-    // pub(crate) fn get(&self) -> &Constraint {
-    //     self.constraint
-    // }
+    pub(crate) fn get(&self) -> &Constraint {
+        self.constraint
+    }
 
     pub(crate) fn get_mut(&mut self) -> &mut Constraint {
         self.constraint
     }
 
-    // pub(crate) fn clone(&mut self) -> Self {
-    //     Self::new(self.constraint)
-    // }
+    pub(crate) fn clone(&mut self) -> Self {
+        Self::new(self.constraint)
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -418,6 +795,170 @@ pub fn create_generic6dof_spring_constraint_from_bundle(
     Box::into_raw(constraint) as *mut usize
 }
 
+#[wasm_bindgen(js_name = "createPoint2PointConstraint")]
+pub fn create_point2point_constraint(
+    body_a: *mut usize,
+    body_b: *mut usize,
+    pivot_in_a: *const f32,
+    pivot_in_b: *const f32,
+) -> *mut usize {
+    let body_a = unsafe { &mut *(body_a as *mut RigidBody) };
+    let body_b = unsafe { &mut *(body_b as *mut RigidBody) };
+
+    let pivot_in_a = unsafe { std::slice::from_raw_parts(pivot_in_a, 3) };
+    let pivot_in_b = unsafe { std::slice::from_raw_parts(pivot_in_b, 3) };
+    let pivot_in_a = Vec3::from_slice(pivot_in_a);
+    let pivot_in_b = Vec3::from_slice(pivot_in_b);
+
+    let constraint = Point2PointConstraint::new(body_a.create_handle(), body_b.create_handle(), pivot_in_a, pivot_in_b);
+    let constraint = Box::new(Constraint::Point2Point(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createPoint2PointConstraintFromBundle")]
+pub fn create_point2point_constraint_from_bundle(
+    body_bundle: *mut usize,
+    body_a_index: u32,
+    body_b_index: u32,
+    pivot_in_a: *const f32,
+    pivot_in_b: *const f32,
+) -> *mut usize {
+    let body_bundle = unsafe { &mut *(body_bundle as *mut RigidBodyBundle) };
+
+    let pivot_in_a = unsafe { std::slice::from_raw_parts(pivot_in_a, 3) };
+    let pivot_in_b = unsafe { std::slice::from_raw_parts(pivot_in_b, 3) };
+    let pivot_in_a = Vec3::from_slice(pivot_in_a);
+    let pivot_in_b = Vec3::from_slice(pivot_in_b);
+
+    let constraint = Point2PointConstraint::from_bundle(body_bundle.create_handle(), body_a_index, body_b_index, pivot_in_a, pivot_in_b);
+    let constraint = Box::new(Constraint::Point2Point(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createConeTwistConstraint")]
+pub fn create_cone_twist_constraint(
+    body_a: *mut usize,
+    body_b: *mut usize,
+    frame_a: *const f32,
+    frame_b: *const f32,
+) -> *mut usize {
+    let body_a = unsafe { &mut *(body_a as *mut RigidBody) };
+    let body_b = unsafe { &mut *(body_b as *mut RigidBody) };
+
+    let frame_a = unsafe { std::slice::from_raw_parts(frame_a, 16) };
+    let frame_b = unsafe { std::slice::from_raw_parts(frame_b, 16) };
+    let frame_a = Mat4::from_cols_slice(frame_a);
+    let frame_b = Mat4::from_cols_slice(frame_b);
+
+    let constraint = ConeTwistConstraint::new(body_a.create_handle(), body_b.create_handle(), &frame_a, &frame_b);
+    let constraint = Box::new(Constraint::ConeTwist(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createConeTwistConstraintFromBundle")]
+pub fn create_cone_twist_constraint_from_bundle(
+    body_bundle: *mut usize,
+    body_a_index: u32,
+    body_b_index: u32,
+    frame_a: *const f32,
+    frame_b: *const f32,
+) -> *mut usize {
+    let body_bundle = unsafe { &mut *(body_bundle as *mut RigidBodyBundle) };
+
+    let frame_a = unsafe { std::slice::from_raw_parts(frame_a, 16) };
+    let frame_b = unsafe { std::slice::from_raw_parts(frame_b, 16) };
+    let frame_a = Mat4::from_cols_slice(frame_a);
+    let frame_b = Mat4::from_cols_slice(frame_b);
+
+    let constraint = ConeTwistConstraint::from_bundle(body_bundle.create_handle(), body_a_index, body_b_index, &frame_a, &frame_b);
+    let constraint = Box::new(Constraint::ConeTwist(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createSliderConstraint")]
+pub fn create_slider_constraint(
+    body_a: *mut usize,
+    body_b: *mut usize,
+    frame_a: *const f32,
+    frame_b: *const f32,
+    use_linear_reference_frame_a: bool,
+) -> *mut usize {
+    let body_a = unsafe { &mut *(body_a as *mut RigidBody) };
+    let body_b = unsafe { &mut *(body_b as *mut RigidBody) };
+
+    let frame_a = unsafe { std::slice::from_raw_parts(frame_a, 16) };
+    let frame_b = unsafe { std::slice::from_raw_parts(frame_b, 16) };
+    let frame_a = Mat4::from_cols_slice(frame_a);
+    let frame_b = Mat4::from_cols_slice(frame_b);
+
+    let constraint = SliderConstraint::new(body_a.create_handle(), body_b.create_handle(), &frame_a, &frame_b, use_linear_reference_frame_a);
+    let constraint = Box::new(Constraint::Slider(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createSliderConstraintFromBundle")]
+pub fn create_slider_constraint_from_bundle(
+    body_bundle: *mut usize,
+    body_a_index: u32,
+    body_b_index: u32,
+    frame_a: *const f32,
+    frame_b: *const f32,
+    use_linear_reference_frame_a: bool,
+) -> *mut usize {
+    let body_bundle = unsafe { &mut *(body_bundle as *mut RigidBodyBundle) };
+
+    let frame_a = unsafe { std::slice::from_raw_parts(frame_a, 16) };
+    let frame_b = unsafe { std::slice::from_raw_parts(frame_b, 16) };
+    let frame_a = Mat4::from_cols_slice(frame_a);
+    let frame_b = Mat4::from_cols_slice(frame_b);
+
+    let constraint = SliderConstraint::from_bundle(body_bundle.create_handle(), body_a_index, body_b_index, &frame_a, &frame_b, use_linear_reference_frame_a);
+    let constraint = Box::new(Constraint::Slider(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createHingeConstraint")]
+pub fn create_hinge_constraint(
+    body_a: *mut usize,
+    body_b: *mut usize,
+    frame_a: *const f32,
+    frame_b: *const f32,
+    use_reference_frame_a: bool,
+) -> *mut usize {
+    let body_a = unsafe { &mut *(body_a as *mut RigidBody) };
+    let body_b = unsafe { &mut *(body_b as *mut RigidBody) };
+
+    let frame_a = unsafe { std::slice::from_raw_parts(frame_a, 16) };
+    let frame_b = unsafe { std::slice::from_raw_parts(frame_b, 16) };
+    let frame_a = Mat4::from_cols_slice(frame_a);
+    let frame_b = Mat4::from_cols_slice(frame_b);
+
+    let constraint = HingeConstraint::new(body_a.create_handle(), body_b.create_handle(), &frame_a, &frame_b, use_reference_frame_a);
+    let constraint = Box::new(Constraint::Hinge(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
+#[wasm_bindgen(js_name = "createHingeConstraintFromBundle")]
+pub fn create_hinge_constraint_from_bundle(
+    body_bundle: *mut usize,
+    body_a_index: u32,
+    body_b_index: u32,
+    frame_a: *const f32,
+    frame_b: *const f32,
+    use_reference_frame_a: bool,
+) -> *mut usize {
+    let body_bundle = unsafe { &mut *(body_bundle as *mut RigidBodyBundle) };
+
+    let frame_a = unsafe { std::slice::from_raw_parts(frame_a, 16) };
+    let frame_b = unsafe { std::slice::from_raw_parts(frame_b, 16) };
+    let frame_a = Mat4::from_cols_slice(frame_a);
+    let frame_b = Mat4::from_cols_slice(frame_b);
+
+    let constraint = HingeConstraint::from_bundle(body_bundle.create_handle(), body_a_index, body_b_index, &frame_a, &frame_b, use_reference_frame_a);
+    let constraint = Box::new(Constraint::Hinge(constraint));
+    Box::into_raw(constraint) as *mut usize
+}
+
 #[wasm_bindgen(js_name = "destroyConstraint")]
 pub fn destroy_constraint(ptr: *mut usize) {
     unsafe {
@@ -568,3 +1109,15 @@ pub fn constraint_set_damping(ptr: *mut usize, index: u8, damping: f32) {
         }
     }
 }
+
+#[wasm_bindgen(js_name = "constraintSetEnabled")]
+pub fn constraint_set_enabled(ptr: *mut usize, enabled: bool) {
+    let constraint = unsafe { &mut *(ptr as *mut Constraint) };
+    constraint.set_enabled(enabled);
+}
+
+#[wasm_bindgen(js_name = "constraintIsEnabled")]
+pub fn constraint_is_enabled(ptr: *const usize) -> bool {
+    let constraint = unsafe { &*(ptr as *const Constraint) };
+    constraint.is_enabled()
+}
@@ -0,0 +1,125 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::super::bind;
+
+use super::physics_query::QueryBody;
+
+pub(crate) enum ContactEventState {
+    Begin = 0,
+    Persist = 1,
+    End = 2,
+}
+
+/// Layout read from js
+///
+/// Body pair is ordered by owner pointer and body index so the same pair always has the same order
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct ContactEvent {
+    owner_a: *const usize,
+    body_index_a: i32,
+    owner_b: *const usize,
+    body_index_b: i32,
+    // deepest point on body b, normal points from body b to body a
+    point: [f32; 3],
+    normal: [f32; 3],
+    impulse: f32,
+    state: u32,
+}
+
+type ContactPairKey = (usize, i32, usize, i32);
+
+impl ContactEvent {
+    fn key(&self) -> ContactPairKey {
+        (self.owner_a as usize, self.body_index_a, self.owner_b as usize, self.body_index_b)
+    }
+}
+
+/// Contact events of a world, rebuilt on every step from the contacts of all its sub steps
+pub(crate) struct ContactEventBuffer {
+    raw_contacts: Vec<bind::physics_world::ContactPoint>,
+    events: Vec<ContactEvent>,
+    // touching pairs of the previous step, used to derive begin and end
+    previous: Vec<ContactEvent>,
+    previous_keys: FxHashSet<ContactPairKey>,
+}
+
+impl ContactEventBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            raw_contacts: Vec::new(),
+            events: Vec::new(),
+            previous: Vec::new(),
+            previous_keys: FxHashSet::default(),
+        }
+    }
+
+    pub(super) fn update(&mut self, world: &mut bind::physics_world::PhysicsWorld, bodies: &FxHashMap<*const std::ffi::c_void, QueryBody>) {
+        world.collect_contacts(&mut self.raw_contacts);
+
+        let mut current: Vec<ContactEvent> = Vec::with_capacity(self.raw_contacts.len());
+        let mut current_indices: FxHashMap<ContactPairKey, usize> = FxHashMap::default();
+
+        for contact in self.raw_contacts.iter() {
+            let (Some(body0), Some(body1)) = (bodies.get(&contact.body0), bodies.get(&contact.body1)) else {
+                continue;
+            };
+
+            let swap = (body1.owner as usize, body1.body_index) < (body0.owner as usize, body0.body_index);
+            let (a, b) = if swap { (body1, body0) } else { (body0, body1) };
+            let normal = if swap { contact.normal.map(|v| -v) } else { contact.normal };
+
+            let event = ContactEvent {
+                owner_a: a.owner,
+                body_index_a: a.body_index,
+                owner_b: b.owner,
+                body_index_b: b.body_index,
+                point: contact.point,
+                normal,
+                impulse: contact.impulse,
+                state: ContactEventState::Begin as u32,
+            };
+
+            // compound shapes and sub steps can produce multiple manifolds for the same pair,
+            // impulses are summed and the point of the latest sub step is kept
+            match current_indices.get(&event.key()) {
+                Some(&index) => {
+                    let merged = &mut current[index];
+                    merged.point = event.point;
+                    merged.normal = event.normal;
+                    merged.impulse += event.impulse;
+                }
+                None => {
+                    current_indices.insert(event.key(), current.len());
+                    current.push(event);
+                }
+            }
+        }
+
+        self.events.clear();
+        for event in current.iter_mut() {
+            if self.previous_keys.contains(&event.key()) {
+                event.state = ContactEventState::Persist as u32;
+            }
+            self.events.push(*event);
+        }
+        // owner of the end event may already be removed from the world
+        for event in self.previous.iter() {
+            if !current_indices.contains_key(&event.key()) {
+                self.events.push(ContactEvent {
+                    impulse: 0.0,
+                    state: ContactEventState::End as u32,
+                    ..*event
+                });
+            }
+        }
+
+        self.previous_keys.clear();
+        self.previous_keys.extend(current_indices.into_keys());
+        self.previous = current;
+    }
+
+    pub(crate) fn events(&self) -> &[ContactEvent] {
+        &self.events
+    }
+}
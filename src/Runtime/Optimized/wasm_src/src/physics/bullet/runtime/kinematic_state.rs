@@ -13,7 +13,6 @@ pub(crate) enum TemporalKinematicState {
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub(crate) enum KinematicToggleState {
     Disabled = 0,
-    #[allow(dead_code)]
     Enabled = 1,
 }
 
@@ -29,6 +28,20 @@ impl KinematicState {
         state
     }
 
+    pub(crate) fn to_raw(&self) -> u8 {
+        self.nibble_pair
+    }
+
+    /// None if either nibble is not a valid state
+    pub(crate) fn from_raw(value: u8) -> Option<Self> {
+        let temporal_state = value & 0xF0;
+        let toggle_state = value & 0x0F;
+        if (TemporalKinematicState::WaitForRestore as u8) < temporal_state || (KinematicToggleState::Enabled as u8) < toggle_state {
+            return None;
+        }
+        Some(KinematicState { nibble_pair: value })
+    }
+
     #[inline]
     pub(crate) fn set_temporal_state(&mut self, state: TemporalKinematicState) {
         self.nibble_pair = (self.nibble_pair & 0x0F) | (state as u8);
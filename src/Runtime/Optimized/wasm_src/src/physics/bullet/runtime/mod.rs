@@ -1,11 +1,14 @@
 pub(crate) mod collision_shape;
 pub(crate) mod constraint;
+pub(crate) mod contact_event;
 pub(crate) mod kinematic_state;
 pub(crate) mod motion_type;
 pub(crate) mod multi_physics_runtime;
 pub(crate) mod multi_physics_world;
+pub(crate) mod physics_query;
 pub(crate) mod physics_runtime;
 pub(crate) mod physics_world;
 pub(crate) mod rigidbody_bundle;
 pub(crate) mod rigidbody_construction_info;
 pub(crate) mod rigidbody;
+pub(crate) mod world_snapshot;
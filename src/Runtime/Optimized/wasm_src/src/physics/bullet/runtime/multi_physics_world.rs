@@ -1,17 +1,29 @@
 use glam::Vec3;
+#[cfg(not(feature = "deterministic"))]
 use rustc_hash::FxHashMap;
 use wasm_bindgen::prelude::*;
 
+use super::super::bind::physics_world::PhysicsWorldStats;
+
 use super::constraint::{Constraint, ConstraintHandle};
+use super::contact_event::ContactEventBuffer;
+use super::physics_query::{QueryHit, QueryHitWriter};
 use super::physics_world::PhysicsWorld;
 use super::rigidbody::{RigidBody, RigidBodyHandle};
 use super::rigidbody_bundle::{RigidBodyBundle, RigidBodyBundleHandle};
+use super::world_snapshot;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 pub(crate) type PhysicsWorldId = u32;
 
+// deterministic build iterates worlds in id order
+#[cfg(not(feature = "deterministic"))]
+type WorldMap = FxHashMap<PhysicsWorldId, PhysicsWorld>;
+#[cfg(feature = "deterministic")]
+type WorldMap = std::collections::BTreeMap<PhysicsWorldId, PhysicsWorld>;
+
 #[cfg(debug_assertions)]
 struct MultiPhysicsWorldHandleInfo {
     bodies: Vec<RigidBodyHandle>,
@@ -19,7 +31,7 @@ struct MultiPhysicsWorldHandleInfo {
 }
 
 pub(crate) struct MultiPhysicsWorld {
-    worlds: FxHashMap<PhysicsWorldId, PhysicsWorld>,
+    worlds: WorldMap,
     gravity: Vec3,
 
     #[cfg(debug_assertions)]
@@ -37,12 +49,15 @@ pub(crate) struct MultiPhysicsWorld {
 
     allow_dynamic_shadow: bool,
     use_motion_state_buffer: bool,
+    contact_events_enabled: bool,
+    debug_draw_categories: u32,
+    stats_enabled: bool,
 }
 
 impl MultiPhysicsWorld {
     pub(crate) fn new(allow_dynamic_shadow: bool) -> Self {
         Self {
-            worlds: FxHashMap::default(),
+            worlds: WorldMap::default(),
             gravity: Vec3::new(0.0, -10.0, 0.0),
             
             #[cfg(debug_assertions)]
@@ -62,6 +77,9 @@ impl MultiPhysicsWorld {
 
             allow_dynamic_shadow,
             use_motion_state_buffer: false,
+            contact_events_enabled: false,
+            debug_draw_categories: 0,
+            stats_enabled: false,
         }
     }
 
@@ -69,6 +87,9 @@ impl MultiPhysicsWorld {
         self.worlds.entry(id).or_insert_with(|| {
             let mut world = PhysicsWorld::new(self.use_motion_state_buffer);
             world.set_gravity(self.gravity);
+            world.set_contact_events_enabled(self.contact_events_enabled);
+            world.set_debug_draw_categories(self.debug_draw_categories);
+            world.set_stats_enabled(self.stats_enabled);
 
             for body in self.global_bodies.iter_mut() {
                 world.add_rigidbody_shadow(body.clone(), true);
@@ -123,23 +144,44 @@ impl MultiPhysicsWorld {
         }
     }
 
+    /// Worlds only share bodies through shadows, kinematic shadows read motion states that are written before the step
+    /// and dynamic shadows read the motion state buffer, so such worlds can be stepped concurrently
+    ///
+    /// Without the buffer a dynamic shadow reads the motion state its source world is writing
+    #[cfg(feature = "parallel")]
+    fn worlds_independent(&self) -> bool {
+        !self.allow_dynamic_shadow || self.use_motion_state_buffer
+    }
+
+    /// Motion state buffer must be synced before this call if it is used
     pub(crate) fn step_simulation(&mut self, time_step: f32, max_sub_steps: i32, fixed_time_step: f32) {
         #[cfg(feature = "parallel")]
         {
-            if 1 < self.worlds.len() {
+            if 1 < self.worlds.len() && self.worlds_independent() {
                 self.worlds.par_iter_mut().for_each(|(_, world)| {
                     world.step_simulation(time_step, max_sub_steps, fixed_time_step);
                 });
-            } else if !self.worlds.is_empty() {
-                self.worlds.values_mut().next().unwrap().step_simulation(time_step, max_sub_steps, fixed_time_step);
+                return;
             }
         }
 
-        #[cfg(not(feature = "parallel"))]
-        {
-            for (_, world) in self.worlds.iter_mut() {
-                world.step_simulation(time_step, max_sub_steps, fixed_time_step);
-            }
+        for (_, world) in self.worlds.iter_mut() {
+            world.step_simulation(time_step, max_sub_steps, fixed_time_step);
+        }
+    }
+
+    /// Steps only the given world, bodies other than `bundle` are restored after stepping
+    pub(crate) fn step_world_isolated(
+        &mut self,
+        world_id: PhysicsWorldId,
+        bundle: &RigidBodyBundleHandle,
+        bundle_constraints: &[ConstraintHandle],
+        step_count: u32,
+        fixed_time_step: f32,
+        before_step: impl FnMut(u32),
+    ) {
+        if let Some(world) = self.get_world(world_id) {
+            world.step_isolated(bundle, bundle_constraints, step_count, fixed_time_step, before_step);
         }
     }
 
@@ -440,6 +482,99 @@ impl MultiPhysicsWorld {
         self.use_motion_state_buffer = use_buffer;
     }
 
+    /// Worlds ordered by id so the snapshot layout does not depend on the hash map order
+    ///
+    /// Global and orphan bodies only exist as shadows, their state is driven by the source and not captured
+    fn sorted_worlds(&self) -> Vec<(PhysicsWorldId, &PhysicsWorld)> {
+        let mut worlds: Vec<_> = self.worlds.iter().map(|(id, world)| (*id, world)).collect();
+        worlds.sort_unstable_by_key(|(id, _)| *id);
+        worlds
+    }
+
+    pub(crate) fn snapshot_size(&self) -> usize {
+        world_snapshot::snapshot_size(&self.sorted_worlds())
+    }
+
+    pub(crate) fn write_snapshot(&self) -> Vec<u8> {
+        world_snapshot::write_snapshot(&self.sorted_worlds())
+    }
+
+    pub(crate) fn restore_snapshot(&mut self, snapshot: &[u8]) -> bool {
+        let mut worlds: Vec<_> = self.worlds.iter_mut().map(|(id, world)| (*id, world)).collect();
+        worlds.sort_unstable_by_key(|(id, _)| *id);
+        world_snapshot::restore_snapshot(&mut worlds, snapshot)
+    }
+
+    /// Each world has own contact event buffer, worlds created later follow this setting
+    pub(crate) fn set_contact_events_enabled(&mut self, enabled: bool) {
+        self.contact_events_enabled = enabled;
+        for world in self.worlds.values_mut() {
+            world.set_contact_events_enabled(enabled);
+        }
+    }
+
+    pub(crate) fn contact_events(&self, world_id: PhysicsWorldId) -> Option<&ContactEventBuffer> {
+        self.worlds.get(&world_id).and_then(|world| world.contact_events())
+    }
+
+    /// Applied to all worlds, worlds created later follow this setting
+    pub(crate) fn set_debug_draw_categories(&mut self, categories: u32) {
+        self.debug_draw_categories = categories;
+        for world in self.worlds.values_mut() {
+            world.set_debug_draw_categories(categories);
+        }
+    }
+
+    /// Applied to all worlds, worlds created later follow this setting
+    pub(crate) fn set_stats_enabled(&mut self, enabled: bool) {
+        self.stats_enabled = enabled;
+        for world in self.worlds.values_mut() {
+            world.set_stats_enabled(enabled);
+        }
+    }
+
+    pub(crate) fn stats(&self, world_id: PhysicsWorldId) -> Option<&PhysicsWorldStats> {
+        self.worlds.get(&world_id).and_then(|world| world.stats())
+    }
+
+    pub(crate) fn debug_lines(&self, world_id: PhysicsWorldId) -> &[f32] {
+        self.worlds.get(&world_id).map_or(&[], |world| world.debug_lines())
+    }
+
+    pub(crate) fn debug_points(&self, world_id: PhysicsWorldId) -> &[f32] {
+        self.worlds.get(&world_id).map_or(&[], |world| world.debug_points())
+    }
+
+    /// Query the world of `world_id` or all worlds if `None`, closest hit among worlds wins
+    pub(crate) fn ray_test(&self, world_id: Option<PhysicsWorldId>, from: Vec3, to: Vec3, group: u16, mask: u16) -> Option<QueryHit> {
+        match world_id {
+            Some(id) => self.worlds.get(&id).and_then(|world| world.ray_test(from, to, group, mask)),
+            None => self.worlds.values()
+                .filter_map(|world| world.ray_test(from, to, group, mask))
+                .min_by(|a, b| a.fraction.total_cmp(&b.fraction)),
+        }
+    }
+
+    pub(crate) fn sphere_sweep_test(&self, world_id: Option<PhysicsWorldId>, from: Vec3, to: Vec3, radius: f32, group: u16, mask: u16) -> Option<QueryHit> {
+        match world_id {
+            Some(id) => self.worlds.get(&id).and_then(|world| world.sphere_sweep_test(from, to, radius, group, mask)),
+            None => self.worlds.values()
+                .filter_map(|world| world.sphere_sweep_test(from, to, radius, group, mask))
+                .min_by(|a, b| a.fraction.total_cmp(&b.fraction)),
+        }
+    }
+
+    pub(crate) fn sphere_contact_test(&mut self, world_id: Option<PhysicsWorldId>, center: Vec3, radius: f32, group: u16, mask: u16, contacts: &mut Vec<QueryHit>) {
+        match world_id {
+            Some(id) => if let Some(world) = self.worlds.get_mut(&id) {
+                world.sphere_contact_test(center, radius, group, mask, contacts);
+            },
+            None => for world in self.worlds.values_mut() {
+                world.sphere_contact_test(center, radius, group, mask, contacts);
+            },
+        }
+    }
+
     pub(crate) fn create_handle(&mut self) -> MultiPhysicsWorldHandle {
         MultiPhysicsWorldHandle::new(self)
     }
@@ -635,3 +770,169 @@ pub fn multi_physics_world_use_motion_state_buffer(world: *mut usize, use_buffer
     let world = unsafe { &mut *(world as *mut MultiPhysicsWorld) };
     world.use_motion_state_buffer(use_buffer);
 }
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldSetContactEventsEnabled")]
+pub fn multi_physics_world_set_contact_events_enabled(world: *mut usize, enabled: bool) {
+    let world = unsafe { &mut *(world as *mut MultiPhysicsWorld) };
+    world.set_contact_events_enabled(enabled);
+}
+
+/// Pointer is valid until the next step, null if the world does not exist or contact events are disabled
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetContactEventsPtr")]
+pub fn multi_physics_world_get_contact_events_ptr(world: *const usize, world_id: PhysicsWorldId) -> *const usize {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    world.contact_events(world_id).map_or(std::ptr::null(), |buffer| buffer.events().as_ptr() as *const usize)
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetContactEventCount")]
+pub fn multi_physics_world_get_contact_event_count(world: *const usize, world_id: PhysicsWorldId) -> u32 {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    world.contact_events(world_id).map_or(0, |buffer| buffer.events().len() as u32)
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldSetStatsEnabled")]
+pub fn multi_physics_world_set_stats_enabled(world: *mut usize, enabled: bool) {
+    let world = unsafe { &mut *(world as *mut MultiPhysicsWorld) };
+    world.set_stats_enabled(enabled);
+}
+
+/// See `physicsWorldGetStatsPtr`, null if the world does not exist or stats are disabled
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetStatsPtr")]
+pub fn multi_physics_world_get_stats_ptr(world: *const usize, world_id: PhysicsWorldId) -> *const usize {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    world.stats(world_id).map_or(std::ptr::null(), |stats| stats as *const PhysicsWorldStats as *const usize)
+}
+
+/// See `physicsWorldSetDebugDrawCategories`
+#[wasm_bindgen(js_name = "multiPhysicsWorldSetDebugDrawCategories")]
+pub fn multi_physics_world_set_debug_draw_categories(world: *mut usize, categories: u32) {
+    let world = unsafe { &mut *(world as *mut MultiPhysicsWorld) };
+    world.set_debug_draw_categories(categories);
+}
+
+/// Shadow bodies are drawn in every world they are added to
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetDebugLinesPtr")]
+pub fn multi_physics_world_get_debug_lines_ptr(world: *const usize, world_id: PhysicsWorldId) -> *const f32 {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    world.debug_lines(world_id).as_ptr()
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetDebugLineCount")]
+pub fn multi_physics_world_get_debug_line_count(world: *const usize, world_id: PhysicsWorldId) -> u32 {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    (world.debug_lines(world_id).len() / 12) as u32
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetDebugPointsPtr")]
+pub fn multi_physics_world_get_debug_points_ptr(world: *const usize, world_id: PhysicsWorldId) -> *const f32 {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    world.debug_points(world_id).as_ptr()
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetDebugPointCount")]
+pub fn multi_physics_world_get_debug_point_count(world: *const usize, world_id: PhysicsWorldId) -> u32 {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    (world.debug_points(world_id).len() / 6) as u32
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldGetSnapshotSize")]
+pub fn multi_physics_world_get_snapshot_size(world: *const usize) -> usize {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    world.snapshot_size()
+}
+
+/// Same as `physicsWorldWriteSnapshot` for all worlds
+#[wasm_bindgen(js_name = "multiPhysicsWorldWriteSnapshot")]
+pub fn multi_physics_world_write_snapshot(world: *const usize, out: *mut u8, size: usize) -> bool {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    let snapshot = world.write_snapshot();
+    if snapshot.len() != size {
+        return false;
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(out, size) };
+    out.copy_from_slice(&snapshot);
+    true
+}
+
+/// Fails if worlds were created or removed since the snapshot was taken
+#[wasm_bindgen(js_name = "multiPhysicsWorldRestoreSnapshot")]
+pub fn multi_physics_world_restore_snapshot(world: *mut usize, snapshot: *const u8, size: usize) -> bool {
+    let world = unsafe { &mut *(world as *mut MultiPhysicsWorld) };
+    let snapshot = unsafe { std::slice::from_raw_parts(snapshot, size) };
+    world.restore_snapshot(snapshot)
+}
+
+/// Same as `physicsWorldRayTestBatch`, `world_id` of undefined queries all worlds
+#[wasm_bindgen(js_name = "multiPhysicsWorldRayTestBatch")]
+#[allow(clippy::too_many_arguments)]
+pub fn multi_physics_world_ray_test_batch(
+    world: *const usize,
+    world_id: Option<PhysicsWorldId>,
+    rays: *const f32,
+    count: u32,
+    group: u16,
+    mask: u16,
+    out_bodies: *mut usize,
+    out_body_indices: *mut i32,
+    out_hits: *mut f32,
+) {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    let rays = unsafe { std::slice::from_raw_parts(rays, count as usize * 6) };
+    let mut writer = unsafe { QueryHitWriter::from_raw(out_bodies, out_body_indices, out_hits, count as usize) };
+
+    for (i, ray) in rays.chunks_exact(6).enumerate() {
+        let hit = world.ray_test(world_id, Vec3::from_slice(&ray[0..3]), Vec3::from_slice(&ray[3..6]), group, mask);
+        writer.write(i, hit.as_ref());
+    }
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldSphereSweepTestBatch")]
+#[allow(clippy::too_many_arguments)]
+pub fn multi_physics_world_sphere_sweep_test_batch(
+    world: *const usize,
+    world_id: Option<PhysicsWorldId>,
+    sweeps: *const f32,
+    count: u32,
+    group: u16,
+    mask: u16,
+    out_bodies: *mut usize,
+    out_body_indices: *mut i32,
+    out_hits: *mut f32,
+) {
+    let world = unsafe { &*(world as *const MultiPhysicsWorld) };
+    let sweeps = unsafe { std::slice::from_raw_parts(sweeps, count as usize * 7) };
+    let mut writer = unsafe { QueryHitWriter::from_raw(out_bodies, out_body_indices, out_hits, count as usize) };
+
+    for (i, sweep) in sweeps.chunks_exact(7).enumerate() {
+        let hit = world.sphere_sweep_test(world_id, Vec3::from_slice(&sweep[0..3]), Vec3::from_slice(&sweep[3..6]), sweep[6], group, mask);
+        writer.write(i, hit.as_ref());
+    }
+}
+
+#[wasm_bindgen(js_name = "multiPhysicsWorldSphereContactTest")]
+#[allow(clippy::too_many_arguments)]
+pub fn multi_physics_world_sphere_contact_test(
+    world: *mut usize,
+    world_id: Option<PhysicsWorldId>,
+    x: f32,
+    y: f32,
+    z: f32,
+    radius: f32,
+    group: u16,
+    mask: u16,
+    out_bodies: *mut usize,
+    out_body_indices: *mut i32,
+    out_hits: *mut f32,
+    capacity: u32,
+) -> u32 {
+    let world = unsafe { &mut *(world as *mut MultiPhysicsWorld) };
+    let mut contacts = Vec::new();
+    world.sphere_contact_test(world_id, Vec3::new(x, y, z), radius, group, mask, &mut contacts);
+
+    let count = contacts.len().min(capacity as usize);
+    let mut writer = unsafe { QueryHitWriter::from_raw(out_bodies, out_body_indices, out_hits, count) };
+    for (i, contact) in contacts[..count].iter().enumerate() {
+        writer.write(i, Some(contact));
+    }
+    count as u32
+}
@@ -0,0 +1,93 @@
+use glam::Vec3;
+
+use super::super::bind;
+
+/// Rigid body reported by a query
+///
+/// `owner` is the `RigidBody` or `RigidBodyBundle` pointer held by the caller,
+/// `body_index` is the index in the bundle or -1 for `RigidBody`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct QueryBody {
+    pub(crate) owner: *const usize,
+    pub(crate) body_index: i32,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct QueryHit {
+    pub(crate) body: QueryBody,
+    // hit fraction for ray and sweep, signed distance for contact
+    pub(crate) fraction: f32,
+    pub(crate) point: Vec3,
+    pub(crate) normal: Vec3,
+}
+
+impl QueryHit {
+    pub(super) fn from_raw(body: QueryBody, hit: &bind::physics_world::QueryHit) -> Self {
+        Self {
+            body,
+            fraction: hit.fraction,
+            point: Vec3::from_array(hit.point),
+            normal: Vec3::from_array(hit.normal),
+        }
+    }
+
+    /// Keep only the deepest contact per body
+    ///
+    /// Bodies shadowed into multiple worlds are reported once as well
+    pub(super) fn merge_contact(contacts: &mut Vec<QueryHit>, contact: QueryHit) {
+        if let Some(existing) = contacts.iter_mut().find(|c| c.body == contact.body) {
+            if contact.fraction < existing.fraction {
+                *existing = contact;
+            }
+        } else {
+            contacts.push(contact);
+        }
+    }
+}
+
+/// Raw contact points collected from a single world per contact query
+pub(super) const MAX_CONTACT_POINTS: usize = 1024;
+
+/// Output buffers of query exports
+///
+/// Each hit is written as owner pointer (0 if no hit), body index and
+/// 7 floats of fraction, point xyz and normal xyz
+pub(super) struct QueryHitWriter<'a> {
+    bodies: &'a mut [usize],
+    body_indices: &'a mut [i32],
+    hits: &'a mut [f32],
+}
+
+impl<'a> QueryHitWriter<'a> {
+    /// # Safety
+    ///
+    /// Buffers must be valid for `count` hits
+    pub(super) unsafe fn from_raw(bodies: *mut usize, body_indices: *mut i32, hits: *mut f32, count: usize) -> Self {
+        unsafe {
+            Self {
+                bodies: std::slice::from_raw_parts_mut(bodies, count),
+                body_indices: std::slice::from_raw_parts_mut(body_indices, count),
+                hits: std::slice::from_raw_parts_mut(hits, count * 7),
+            }
+        }
+    }
+
+    pub(super) fn write(&mut self, index: usize, hit: Option<&QueryHit>) {
+        let out = &mut self.hits[index * 7..index * 7 + 7];
+        match hit {
+            Some(hit) => {
+                self.bodies[index] = hit.body.owner as usize;
+                self.body_indices[index] = hit.body.body_index;
+                out[0] = hit.fraction;
+                out[1..4].copy_from_slice(&hit.point.to_array());
+                out[4..7].copy_from_slice(&hit.normal.to_array());
+            }
+            None => {
+                self.bodies[index] = 0;
+                self.body_indices[index] = -1;
+                out[0] = 1.0;
+                out[1..7].fill(0.0);
+            }
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use glam::Vec3;
+use rustc_hash::FxHashMap;
 use wasm_bindgen::prelude::*;
 use super::super::bind;
 
 use super::constraint::{Constraint, ConstraintHandle};
+use super::contact_event::ContactEventBuffer;
+use super::physics_query::{QueryBody, QueryHit, QueryHitWriter, MAX_CONTACT_POINTS};
 use super::rigidbody::{RigidBody,RigidBodyHandle, RigidBodyShadow};
 use super::rigidbody_bundle::{RigidBodyBundle, RigidBodyBundleHandle, RigidBodyBundleShadow};
+use super::world_snapshot::{self, SnapshotReader, SnapshotWriter};
 
 #[cfg(debug_assertions)]
 struct PhysicsWorldHandleInfo {
@@ -23,8 +27,17 @@ pub(crate) struct PhysicsWorld {
     body_bundles: Vec<RigidBodyBundleHandle>,
     shadow_bodies: Vec<RigidBodyShadow>,
     shadow_body_bundles: Vec<RigidBodyBundleShadow>,
+    constraints: Vec<ConstraintHandle>,
     object_count: i32,
     use_motion_state_buffer: bool,
+    // time that is not simulated yet, carried over to the next step
+    #[cfg(feature = "deterministic")]
+    time_accumulator: f32,
+    // bwRigidBody pointer reported by bullet to its owner, includes shadows
+    query_bodies: FxHashMap<*const std::ffi::c_void, QueryBody>,
+    contact_events: Option<ContactEventBuffer>,
+    debug_draw_categories: u32,
+    stats: Option<bind::physics_world::PhysicsWorldStats>,
 }
 
 impl PhysicsWorld {
@@ -44,8 +57,15 @@ impl PhysicsWorld {
             body_bundles: Vec::new(),
             shadow_bodies: Vec::new(),
             shadow_body_bundles: Vec::new(),
+            constraints: Vec::new(),
             object_count: 0,
             use_motion_state_buffer,
+            #[cfg(feature = "deterministic")]
+            time_accumulator: 0.0,
+            query_bodies: FxHashMap::default(),
+            contact_events: None,
+            debug_draw_categories: 0,
+            stats: None,
         }
     }
 
@@ -64,7 +84,7 @@ impl PhysicsWorld {
         }
     }
 
-    pub(crate) fn step_simulation(&mut self, time_step: f32, max_sub_steps: i32, fixed_time_step: f32) {
+    fn commit_kinematic_states(&mut self) {
         let mut world_handle = self.create_handle();
         for body in self.bodies.iter_mut() {
             let body = body.get_mut();
@@ -76,7 +96,163 @@ impl PhysicsWorld {
             bundle.commit_physics_toggle_states(world_handle.clone());
             bundle.update_temporal_kinematic_states(world_handle.clone());
         }
+    }
+
+    pub(crate) fn step_simulation(&mut self, time_step: f32, max_sub_steps: i32, fixed_time_step: f32) {
+        self.commit_kinematic_states();
+        if self.contact_events.is_some() {
+            self.inner.clear_contacts();
+        }
+        #[cfg(not(feature = "deterministic"))]
         self.inner.step_simulation(time_step, max_sub_steps, fixed_time_step);
+
+        // only whole fixed steps are simulated and the remainder is carried to the next call,
+        // bullet is stepped once per sub step to keep its local time accumulator always zero
+        //
+        // steps over `max_sub_steps` are dropped like bullet does,
+        // kinematic bodies still move once per call, not per sub step
+        #[cfg(feature = "deterministic")]
+        {
+            self.time_accumulator += time_step;
+            let step_count = (self.time_accumulator / fixed_time_step).floor() as i32;
+            self.time_accumulator -= step_count as f32 * fixed_time_step;
+            for _ in 0..step_count.min(max_sub_steps.max(1)) {
+                self.inner.step_simulation(fixed_time_step, 1, fixed_time_step);
+            }
+        }
+
+        if let Some(contact_events) = self.contact_events.as_mut() {
+            contact_events.update(&mut self.inner, &self.query_bodies);
+        }
+
+        if self.debug_draw_categories != 0 {
+            self.inner.debug_draw();
+        }
+
+        if let Some(stats) = self.stats.as_mut() {
+            self.inner.get_stats(stats);
+        }
+    }
+
+    /// Steps the world by whole fixed steps and then restores every body except `bundle`
+    ///
+    /// `before_step` is called with the step index before each step to drive the bundle.
+    /// Other dynamic bodies are frozen as kinematic while stepping, constraints except `bundle_constraints` are restored.
+    /// Contact events, debug draw and stats are not updated
+    pub(crate) fn step_isolated(
+        &mut self,
+        bundle: &RigidBodyBundleHandle,
+        bundle_constraints: &[ConstraintHandle],
+        step_count: u32,
+        fixed_time_step: f32,
+        mut before_step: impl FnMut(u32),
+    ) {
+        let mut writer = SnapshotWriter::new(0);
+        for body in self.bodies.iter() {
+            body.get().write_snapshot(&mut writer);
+        }
+        for other in self.body_bundles.iter().filter(|other| *other != bundle) {
+            other.get().write_snapshot(&mut writer);
+        }
+        let constraint_states: Vec<_> = self.constraints.iter()
+            .filter(|constraint| !bundle_constraints.contains(constraint))
+            .map(|constraint| bind::constraint::get_constraint_state(constraint.get().ptr_mut()))
+            .collect();
+
+        for body in self.bodies.iter_mut() {
+            let body = body.get_mut().get_inner_mut();
+            if !body.is_static_or_kinematic() {
+                self.inner.set_body_kinematic_toggle(body, true);
+            }
+        }
+        for other in self.body_bundles.iter_mut().filter(|other| *other != bundle) {
+            for body in other.get_mut().bodies_mut() {
+                if !body.is_static_or_kinematic() {
+                    self.inner.set_body_kinematic_toggle(body, true);
+                }
+            }
+        }
+
+        let mut world_handle = self.create_handle();
+        for step in 0..step_count {
+            before_step(step);
+            // kinematic states of other bodies are left as they are until the snapshot is restored
+            if let Some(own) = self.body_bundles.iter_mut().find(|other| *other == bundle) {
+                let own = own.get_mut();
+                own.commit_physics_toggle_states(world_handle.clone());
+                own.update_temporal_kinematic_states(world_handle.clone());
+            }
+            self.inner.step_simulation(fixed_time_step, 1, fixed_time_step);
+        }
+        if self.contact_events.is_some() {
+            self.inner.clear_contacts();
+        }
+
+        let bytes = writer.into_bytes();
+        let mut reader = SnapshotReader::new(&bytes);
+        for body in self.bodies.iter_mut() {
+            body.get_mut().read_snapshot(&mut reader, world_handle.clone());
+        }
+        for other in self.body_bundles.iter_mut() {
+            if *other == *bundle {
+                if self.use_motion_state_buffer {
+                    other.get_mut().sync_buffered_motion_states();
+                }
+            } else {
+                other.get_mut().read_snapshot(&mut reader, world_handle.clone());
+            }
+        }
+        let foreign_constraints = self.constraints.iter()
+            .filter(|constraint| !bundle_constraints.contains(constraint));
+        for (constraint, state) in foreign_constraints.zip(constraint_states.iter()) {
+            bind::constraint::set_constraint_state(constraint.get().ptr_mut(), state);
+        }
+    }
+
+    pub(crate) fn set_contact_events_enabled(&mut self, enabled: bool) {
+        if enabled == self.contact_events.is_some() {
+            return;
+        }
+        self.inner.set_contact_collection_enabled(enabled);
+        self.contact_events = if enabled { Some(ContactEventBuffer::new()) } else { None };
+    }
+
+    pub(crate) fn contact_events(&self) -> Option<&ContactEventBuffer> {
+        self.contact_events.as_ref()
+    }
+
+    /// Debug geometry is rebuilt after each step while any category is enabled
+    pub(crate) fn set_debug_draw_categories(&mut self, categories: u32) {
+        self.debug_draw_categories = categories;
+        self.inner.set_debug_draw_categories(categories);
+    }
+
+    pub(crate) fn set_stats_enabled(&mut self, enabled: bool) {
+        if enabled == self.stats.is_some() {
+            return;
+        }
+        self.inner.set_stats_enabled(enabled);
+        self.stats = if enabled {
+            // also discards the step counters accumulated while disabled
+            let mut stats = bind::physics_world::PhysicsWorldStats::default();
+            self.inner.get_stats(&mut stats);
+            Some(stats)
+        } else {
+            None
+        };
+    }
+
+    /// Counters after the last step
+    pub(crate) fn stats(&self) -> Option<&bind::physics_world::PhysicsWorldStats> {
+        self.stats.as_ref()
+    }
+
+    pub(crate) fn debug_lines(&self) -> &[f32] {
+        self.inner.debug_lines()
+    }
+
+    pub(crate) fn debug_points(&self) -> &[f32] {
+        self.inner.debug_points()
     }
 
     pub(crate) fn add_rigidbody(&mut self, mut rigidbody: RigidBodyHandle) {
@@ -89,6 +265,7 @@ impl PhysicsWorld {
 
         self.inner.add_rigidbody(rigidbody.get_mut().get_inner_mut());
         self.object_count += 1;
+        self.query_bodies.extend(body_query_entries(rigidbody.get()));
 
         self.bodies.push(rigidbody.clone());
 
@@ -110,6 +287,7 @@ impl PhysicsWorld {
 
         self.inner.remove_rigidbody(rigidbody.get_mut().get_inner_mut());
         self.object_count -= 1;
+        self.remove_query_bodies(body_query_entries(rigidbody.get()));
 
         self.bodies.remove(self.bodies.iter().position(|b| *b == rigidbody).unwrap());
 
@@ -136,7 +314,10 @@ impl PhysicsWorld {
         for i in 0..bundle.get().bodies().len() {
             self.inner.add_rigidbody(&mut bundle.get_mut().bodies_mut()[i]);
         }
+        // bodies with kinematic toggle enter the world as kinematic instead of waiting for the next step
+        bundle.get_mut().commit_physics_toggle_states(self.create_handle());
         self.object_count += 1;
+        self.query_bodies.extend(bundle_query_entries(bundle.get()));
 
         self.body_bundles.push(bundle.clone());
 
@@ -160,6 +341,7 @@ impl PhysicsWorld {
             self.inner.remove_rigidbody(&mut bundle.get_mut().bodies_mut()[i]);
         }
         self.object_count -= 1;
+        self.remove_query_bodies(bundle_query_entries(bundle.get()));
 
         self.body_bundles.remove(self.body_bundles.iter().position(|b| *b == bundle).unwrap());
 
@@ -186,6 +368,7 @@ impl PhysicsWorld {
 
         let mut shadow = rigidbody.create_shadow();
         self.inner.add_rigidbody_shadow(shadow.get_inner_mut());
+        self.query_bodies.extend(body_query_entries(shadow.handle().get()));
         self.shadow_bodies.push(shadow);
         if !weak {
             self.object_count += 1;
@@ -206,6 +389,7 @@ impl PhysicsWorld {
         let index = self.shadow_bodies.iter().position(|s| *s.handle() == rigidbody).unwrap();
         let mut shadow = self.shadow_bodies.remove(index);
         self.inner.remove_rigidbody_shadow(shadow.get_inner_mut());
+        self.remove_query_bodies(body_query_entries(shadow.handle().get()));
         if !weak {
             self.object_count -= 1;
         }
@@ -229,6 +413,7 @@ impl PhysicsWorld {
         for i in 0..shadow.shadows().len() {
             self.inner.add_rigidbody_shadow(&mut shadow.shadows_mut()[i]);
         }
+        self.query_bodies.extend(bundle_query_entries(shadow.handle().get()));
         self.shadow_body_bundles.push(shadow);
         if !weak {
             self.object_count += 1;
@@ -251,6 +436,7 @@ impl PhysicsWorld {
         for i in 0..shadow.shadows().len() {
             self.inner.remove_rigidbody_shadow(&mut shadow.shadows_mut()[i]);
         }
+        self.remove_query_bodies(bundle_query_entries(shadow.handle().get()));
         if !weak {
             self.object_count -= 1;
         }
@@ -273,6 +459,8 @@ impl PhysicsWorld {
         self.inner.add_constraint(constraint.get_mut().ptr_mut(), disable_collisions_between_linked_bodies);
         self.object_count += 1;
 
+        self.constraints.push(constraint.clone());
+
         #[cfg(debug_assertions)]
         self.handle_info.constraints.push(constraint);
     }
@@ -288,6 +476,8 @@ impl PhysicsWorld {
         self.inner.remove_constraint(constraint.get_mut().ptr_mut());
         self.object_count -= 1;
 
+        self.constraints.remove(self.constraints.iter().position(|c| *c == constraint).unwrap());
+
         #[cfg(debug_assertions)]
         {
             let index = self.handle_info.constraints.iter().position(|c| *c == constraint).unwrap();
@@ -381,6 +571,72 @@ impl PhysicsWorld {
         self.use_motion_state_buffer = use_buffer;
     }
 
+    /// Shadows are not included, their state follows the source body
+    pub(super) fn snapshot_counts(&self) -> (u32, u32) {
+        let body_count = self.bodies.len() + self.body_bundles.iter().map(|b| b.get().len()).sum::<usize>();
+        (body_count as u32, self.constraints.len() as u32)
+    }
+
+    pub(super) fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        for body in self.bodies.iter() {
+            body.get().write_snapshot(writer);
+        }
+        for bundle in self.body_bundles.iter() {
+            bundle.get().write_snapshot(writer);
+        }
+        for constraint in self.constraints.iter() {
+            writer.write_constraint(&bind::constraint::get_constraint_state(constraint.get().ptr_mut()));
+        }
+    }
+
+    pub(super) fn read_snapshot(&mut self, reader: &mut SnapshotReader) {
+        let mut world_handle = self.create_handle();
+        for body in self.bodies.iter_mut() {
+            body.get_mut().read_snapshot(reader, world_handle.clone());
+        }
+        for bundle in self.body_bundles.iter_mut() {
+            bundle.get_mut().read_snapshot(reader, world_handle.clone());
+        }
+        for constraint in self.constraints.iter() {
+            bind::constraint::set_constraint_state(constraint.get().ptr_mut(), &reader.read_constraint());
+        }
+    }
+
+    fn remove_query_bodies(&mut self, entries: impl Iterator<Item = (*const std::ffi::c_void, QueryBody)>) {
+        for (ptr, _) in entries {
+            self.query_bodies.remove(&ptr);
+        }
+    }
+
+    /// Find the owner of the bwRigidBody pointer reported by bullet
+    fn resolve_query_body(&self, body: *const std::ffi::c_void) -> Option<QueryBody> {
+        self.query_bodies.get(&body).copied()
+    }
+
+    pub(crate) fn ray_test(&self, from: Vec3, to: Vec3, group: u16, mask: u16) -> Option<QueryHit> {
+        let hit = self.inner.ray_test(from, to, group, mask)?;
+        let body = self.resolve_query_body(hit.body)?;
+        Some(QueryHit::from_raw(body, &hit))
+    }
+
+    pub(crate) fn sphere_sweep_test(&self, from: Vec3, to: Vec3, radius: f32, group: u16, mask: u16) -> Option<QueryHit> {
+        let hit = self.inner.sphere_sweep_test(from, to, radius, group, mask)?;
+        let body = self.resolve_query_body(hit.body)?;
+        Some(QueryHit::from_raw(body, &hit))
+    }
+
+    /// Merge the deepest contact per body into `contacts`
+    pub(crate) fn sphere_contact_test(&mut self, center: Vec3, radius: f32, group: u16, mask: u16, contacts: &mut Vec<QueryHit>) {
+        let mut raw_contacts = Vec::with_capacity(MAX_CONTACT_POINTS);
+        self.inner.sphere_contact_test(center, radius, group, mask, &mut raw_contacts);
+
+        for contact in raw_contacts.iter() {
+            if let Some(body) = self.resolve_query_body(contact.body) {
+                QueryHit::merge_contact(contacts, QueryHit::from_raw(body, contact));
+            }
+        }
+    }
+
     pub(crate) fn create_handle(&mut self) -> PhysicsWorldHandle {
         PhysicsWorldHandle::new(self)
     }
@@ -397,6 +653,20 @@ impl Drop for PhysicsWorld {
     }
 }
 
+fn body_query_entries(rigidbody: &RigidBody) -> impl Iterator<Item = (*const std::ffi::c_void, QueryBody)> {
+    std::iter::once((rigidbody.get_inner().ptr(), QueryBody {
+        owner: rigidbody as *const RigidBody as *const usize,
+        body_index: -1,
+    }))
+}
+
+fn bundle_query_entries(bundle: &RigidBodyBundle) -> impl Iterator<Item = (*const std::ffi::c_void, QueryBody)> + '_ {
+    bundle.bodies().iter().enumerate().map(move |(index, body)| (body.ptr(), QueryBody {
+        owner: bundle as *const RigidBodyBundle as *const usize,
+        body_index: index as i32,
+    }))
+}
+
 pub(crate) struct PhysicsWorldHandle {
     world: &'static mut PhysicsWorld,
 }
@@ -519,3 +789,189 @@ pub fn physics_world_use_motion_state_buffer(world: *mut usize, use_buffer: bool
     let world = unsafe { &mut *(world as *mut PhysicsWorld) };
     world.use_motion_state_buffer(use_buffer);
 }
+
+/// Size of the buffer for `physicsWorldWriteSnapshot`, changes when objects are added or removed
+#[wasm_bindgen(js_name = "physicsWorldGetSnapshotSize")]
+pub fn physics_world_get_snapshot_size(world: *const usize) -> usize {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    world_snapshot::snapshot_size(&[(0, world)])
+}
+
+/// Capture body transforms, velocities, kinematic states and constraint impulses
+///
+/// Returns false without writing if `size` does not match the snapshot size
+#[wasm_bindgen(js_name = "physicsWorldWriteSnapshot")]
+pub fn physics_world_write_snapshot(world: *const usize, out: *mut u8, size: usize) -> bool {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    let snapshot = world_snapshot::write_snapshot(&[(0, world)]);
+    if snapshot.len() != size {
+        return false;
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(out, size) };
+    out.copy_from_slice(&snapshot);
+    true
+}
+
+/// Returns false without changing the world if the snapshot does not match the world layout
+#[wasm_bindgen(js_name = "physicsWorldRestoreSnapshot")]
+pub fn physics_world_restore_snapshot(world: *mut usize, snapshot: *const u8, size: usize) -> bool {
+    let world = unsafe { &mut *(world as *mut PhysicsWorld) };
+    let snapshot = unsafe { std::slice::from_raw_parts(snapshot, size) };
+    world_snapshot::restore_snapshot(&mut [(0, world)], snapshot)
+}
+
+/// Contact events are collected on each step after enabled
+#[wasm_bindgen(js_name = "physicsWorldSetContactEventsEnabled")]
+pub fn physics_world_set_contact_events_enabled(world: *mut usize, enabled: bool) {
+    let world = unsafe { &mut *(world as *mut PhysicsWorld) };
+    world.set_contact_events_enabled(enabled);
+}
+
+/// Pointer is valid until the next step, null if contact events are disabled
+#[wasm_bindgen(js_name = "physicsWorldGetContactEventsPtr")]
+pub fn physics_world_get_contact_events_ptr(world: *const usize) -> *const usize {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    world.contact_events().map_or(std::ptr::null(), |buffer| buffer.events().as_ptr() as *const usize)
+}
+
+#[wasm_bindgen(js_name = "physicsWorldGetContactEventCount")]
+pub fn physics_world_get_contact_event_count(world: *const usize) -> u32 {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    world.contact_events().map_or(0, |buffer| buffer.events().len() as u32)
+}
+
+/// Stats are collected on each step after enabled
+#[wasm_bindgen(js_name = "physicsWorldSetStatsEnabled")]
+pub fn physics_world_set_stats_enabled(world: *mut usize, enabled: bool) {
+    let world = unsafe { &mut *(world as *mut PhysicsWorld) };
+    world.set_stats_enabled(enabled);
+}
+
+/// Object, constraint, overlapping pair, manifold, island, solver iteration and sub step counts as i32
+/// followed by the step time in milliseconds as f32
+///
+/// Null if stats are disabled
+#[wasm_bindgen(js_name = "physicsWorldGetStatsPtr")]
+pub fn physics_world_get_stats_ptr(world: *const usize) -> *const usize {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    world.stats().map_or(std::ptr::null(), |stats| stats as *const bind::physics_world::PhysicsWorldStats as *const usize)
+}
+
+/// `categories` is a bit set of
+///
+/// - 1: collider wireframes
+/// - 2: collider aabbs
+/// - 4: contact points
+/// - 8: constraint frames
+/// - 16: constraint limits
+///
+/// 0 disables debug draw
+#[wasm_bindgen(js_name = "physicsWorldSetDebugDrawCategories")]
+pub fn physics_world_set_debug_draw_categories(world: *mut usize, categories: u32) {
+    let world = unsafe { &mut *(world as *mut PhysicsWorld) };
+    world.set_debug_draw_categories(categories);
+}
+
+/// Line list of vertices with position xyz and color rgb, pointer is valid until the next step
+#[wasm_bindgen(js_name = "physicsWorldGetDebugLinesPtr")]
+pub fn physics_world_get_debug_lines_ptr(world: *const usize) -> *const f32 {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    world.debug_lines().as_ptr()
+}
+
+/// Number of lines, each line is 12 floats
+#[wasm_bindgen(js_name = "physicsWorldGetDebugLineCount")]
+pub fn physics_world_get_debug_line_count(world: *const usize) -> u32 {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    (world.debug_lines().len() / 12) as u32
+}
+
+/// Contact points with position xyz and color rgb, pointer is valid until the next step
+#[wasm_bindgen(js_name = "physicsWorldGetDebugPointsPtr")]
+pub fn physics_world_get_debug_points_ptr(world: *const usize) -> *const f32 {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    world.debug_points().as_ptr()
+}
+
+/// Number of points, each point is 6 floats
+#[wasm_bindgen(js_name = "physicsWorldGetDebugPointCount")]
+pub fn physics_world_get_debug_point_count(world: *const usize) -> u32 {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    (world.debug_points().len() / 6) as u32
+}
+
+/// `rays` is `count` pairs of from xyz and to xyz
+///
+/// Outputs are written per ray, see `QueryHitWriter` for the layout
+#[wasm_bindgen(js_name = "physicsWorldRayTestBatch")]
+#[allow(clippy::too_many_arguments)]
+pub fn physics_world_ray_test_batch(
+    world: *const usize,
+    rays: *const f32,
+    count: u32,
+    group: u16,
+    mask: u16,
+    out_bodies: *mut usize,
+    out_body_indices: *mut i32,
+    out_hits: *mut f32,
+) {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    let rays = unsafe { std::slice::from_raw_parts(rays, count as usize * 6) };
+    let mut writer = unsafe { QueryHitWriter::from_raw(out_bodies, out_body_indices, out_hits, count as usize) };
+
+    for (i, ray) in rays.chunks_exact(6).enumerate() {
+        let hit = world.ray_test(Vec3::from_slice(&ray[0..3]), Vec3::from_slice(&ray[3..6]), group, mask);
+        writer.write(i, hit.as_ref());
+    }
+}
+
+/// `sweeps` is `count` tuples of from xyz, to xyz and radius
+#[wasm_bindgen(js_name = "physicsWorldSphereSweepTestBatch")]
+#[allow(clippy::too_many_arguments)]
+pub fn physics_world_sphere_sweep_test_batch(
+    world: *const usize,
+    sweeps: *const f32,
+    count: u32,
+    group: u16,
+    mask: u16,
+    out_bodies: *mut usize,
+    out_body_indices: *mut i32,
+    out_hits: *mut f32,
+) {
+    let world = unsafe { &*(world as *const PhysicsWorld) };
+    let sweeps = unsafe { std::slice::from_raw_parts(sweeps, count as usize * 7) };
+    let mut writer = unsafe { QueryHitWriter::from_raw(out_bodies, out_body_indices, out_hits, count as usize) };
+
+    for (i, sweep) in sweeps.chunks_exact(7).enumerate() {
+        let hit = world.sphere_sweep_test(Vec3::from_slice(&sweep[0..3]), Vec3::from_slice(&sweep[3..6]), sweep[6], group, mask);
+        writer.write(i, hit.as_ref());
+    }
+}
+
+/// Returns the number of contacts written, at most `capacity`
+#[wasm_bindgen(js_name = "physicsWorldSphereContactTest")]
+#[allow(clippy::too_many_arguments)]
+pub fn physics_world_sphere_contact_test(
+    world: *mut usize,
+    x: f32,
+    y: f32,
+    z: f32,
+    radius: f32,
+    group: u16,
+    mask: u16,
+    out_bodies: *mut usize,
+    out_body_indices: *mut i32,
+    out_hits: *mut f32,
+    capacity: u32,
+) -> u32 {
+    let world = unsafe { &mut *(world as *mut PhysicsWorld) };
+    let mut contacts = Vec::new();
+    world.sphere_contact_test(Vec3::new(x, y, z), radius, group, mask, &mut contacts);
+
+    let count = contacts.len().min(capacity as usize);
+    let mut writer = unsafe { QueryHitWriter::from_raw(out_bodies, out_body_indices, out_hits, count) };
+    for (i, contact) in contacts[..count].iter().enumerate() {
+        writer.write(i, Some(contact));
+    }
+    count as u32
+}
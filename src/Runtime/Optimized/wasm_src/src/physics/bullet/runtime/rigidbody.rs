@@ -9,6 +9,7 @@ use super::collision_shape::{CollisionShape, CollisionShapeHandle};
 use super::physics_world::PhysicsWorldHandle;
 use super::rigidbody_construction_info::RigidBodyConstructionInfo;
 use super::kinematic_state::KinematicState;
+use super::world_snapshot::{SnapshotReader, SnapshotWriter};
 
 pub(crate) struct RigidBody {
     inner: bind::rigidbody::RigidBody,
@@ -185,6 +186,23 @@ impl RigidBody {
         }
     }
 
+    pub(super) fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_body(&self.inner.get_state(), &self.kinematic_state);
+    }
+
+    /// Kinematic flags of bullet body are synced first since restoring dynamic clears the velocities
+    pub(super) fn read_snapshot(&mut self, reader: &mut SnapshotReader, mut world: PhysicsWorldHandle) {
+        let (state, kinematic_state) = reader.read_body();
+        self.kinematic_state = kinematic_state;
+        if !self.get_inner().is_static_or_kinematic() {
+            let toggle = self.kinematic_state.get_toggle_state() == KinematicToggleState::Enabled;
+            let temporal = self.kinematic_state.get_temporal_state() == TemporalKinematicState::WaitForRestore;
+            world.get_mut().set_raw_body_kinematic_toggle(&mut self.inner, toggle);
+            world.get_mut().set_raw_body_temporal_kinematic(&mut self.inner, temporal);
+        }
+        self.inner.set_state(&state);
+    }
+
     pub(crate) fn set_damping(&mut self, linear_damping: f32, angular_damping: f32) {
         self.inner.set_damping(linear_damping, angular_damping);
     }
@@ -197,6 +215,18 @@ impl RigidBody {
         self.inner.get_angular_damping()
     }
 
+    pub(crate) fn set_ccd(&mut self, motion_threshold: f32, swept_sphere_radius: f32) {
+        self.inner.set_ccd(motion_threshold, swept_sphere_radius);
+    }
+
+    pub(crate) fn get_ccd_motion_threshold(&self) -> f32 {
+        self.inner.get_ccd_motion_threshold()
+    }
+
+    pub(crate) fn get_ccd_swept_sphere_radius(&self) -> f32 {
+        self.inner.get_ccd_swept_sphere_radius()
+    }
+
     pub(crate) fn set_mass_props(&mut self, mass: f32, local_inertia: Vec3) {
         self.inner.set_mass_props(mass, local_inertia);
     }
@@ -480,6 +510,24 @@ pub fn rigidbody_get_angular_damping(ptr: *const usize) -> f32 {
     rigidbody.get_angular_damping()
 }
 
+#[wasm_bindgen(js_name = "rigidBodySetCcd")]
+pub fn rigidbody_set_ccd(ptr: *mut usize, motion_threshold: f32, swept_sphere_radius: f32) {
+    let rigidbody = unsafe { &mut *(ptr as *mut RigidBody) };
+    rigidbody.set_ccd(motion_threshold, swept_sphere_radius);
+}
+
+#[wasm_bindgen(js_name = "rigidBodyGetCcdMotionThreshold")]
+pub fn rigidbody_get_ccd_motion_threshold(ptr: *const usize) -> f32 {
+    let rigidbody = unsafe { &*(ptr as *const RigidBody) };
+    rigidbody.get_ccd_motion_threshold()
+}
+
+#[wasm_bindgen(js_name = "rigidBodyGetCcdSweptSphereRadius")]
+pub fn rigidbody_get_ccd_swept_sphere_radius(ptr: *const usize) -> f32 {
+    let rigidbody = unsafe { &*(ptr as *const RigidBody) };
+    rigidbody.get_ccd_swept_sphere_radius()
+}
+
 #[wasm_bindgen(js_name = "rigidBodySetMassProps")]
 pub fn rigidbody_set_mass_props(ptr: *mut usize, mass: f32, local_inertia_x: f32, local_inertia_y: f32, local_inertia_z: f32) {
     let rigidbody = unsafe { &mut *(ptr as *mut RigidBody) };
@@ -1,4 +1,4 @@
-use glam::Vec3;
+use glam::{Mat4, Vec3};
 use wasm_bindgen::prelude::*;
 
 use crate::physics::bullet::runtime::kinematic_state::{KinematicToggleState, TemporalKinematicState};
@@ -9,6 +9,7 @@ use super::collision_shape::{CollisionShape, CollisionShapeHandle};
 use super::physics_world::PhysicsWorldHandle;
 use super::rigidbody_construction_info::RigidBodyConstructionInfo;
 use super::kinematic_state::KinematicState;
+use super::world_snapshot::{SnapshotReader, SnapshotWriter};
 
 pub(crate) struct RigidBodyBundle {
     bodies: Box<[bind::rigidbody::RigidBody]>,
@@ -217,6 +218,32 @@ impl RigidBodyBundle {
         }
     }
 
+    pub(super) fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        for (body, kinematic_state) in self.bodies.iter().zip(self.kinematic_states.iter()) {
+            writer.write_body(&body.get_state(), kinematic_state);
+        }
+    }
+
+    pub(super) fn read_snapshot(&mut self, reader: &mut SnapshotReader, mut world: PhysicsWorldHandle) {
+        for i in 0..self.bodies.len() {
+            let (state, kinematic_state) = reader.read_body();
+            let body = &mut self.bodies[i];
+            if !body.is_static_or_kinematic() {
+                let toggle = kinematic_state.get_toggle_state() == KinematicToggleState::Enabled;
+                let temporal = kinematic_state.get_temporal_state() == TemporalKinematicState::WaitForRestore;
+                world.get_mut().set_raw_body_kinematic_toggle(body, toggle);
+                world.get_mut().set_raw_body_temporal_kinematic(body, temporal);
+            }
+            body.set_state(&state);
+            self.kinematic_states[i] = kinematic_state;
+        }
+    }
+
+    /// Unlike motion states this reads the body itself, so it must not be called while the world is stepping
+    pub(crate) fn get_step_transform(&self, index: usize) -> Mat4 {
+        self.bodies[index].get_step_transform()
+    }
+
     pub(crate) fn set_damping(&mut self, index: usize, linear_damping: f32, angular_damping: f32) {
         self.bodies[index].set_damping(linear_damping, angular_damping);
     }
@@ -229,6 +256,18 @@ impl RigidBodyBundle {
         self.bodies[index].get_angular_damping()
     }
 
+    pub(crate) fn set_ccd(&mut self, index: usize, motion_threshold: f32, swept_sphere_radius: f32) {
+        self.bodies[index].set_ccd(motion_threshold, swept_sphere_radius);
+    }
+
+    pub(crate) fn get_ccd_motion_threshold(&self, index: usize) -> f32 {
+        self.bodies[index].get_ccd_motion_threshold()
+    }
+
+    pub(crate) fn get_ccd_swept_sphere_radius(&self, index: usize) -> f32 {
+        self.bodies[index].get_ccd_swept_sphere_radius()
+    }
+
     pub(crate) fn set_mass_props(&mut self, index: usize, mass: f32, local_inertia: Vec3) {
         self.bodies[index].set_mass_props(mass, local_inertia);
     }
@@ -241,6 +280,42 @@ impl RigidBodyBundle {
         self.bodies[index].get_local_inertia()
     }
 
+    pub(crate) fn set_mass_with_inertia(&mut self, index: usize, mass: f32) {
+        self.bodies[index].set_mass_with_inertia(mass);
+    }
+
+    pub(crate) fn set_friction(&mut self, index: usize, friction: f32) {
+        self.bodies[index].set_friction(friction);
+    }
+
+    pub(crate) fn get_friction(&self, index: usize) -> f32 {
+        self.bodies[index].get_friction()
+    }
+
+    pub(crate) fn set_restitution(&mut self, index: usize, restitution: f32) {
+        self.bodies[index].set_restitution(restitution);
+    }
+
+    pub(crate) fn get_restitution(&self, index: usize) -> f32 {
+        self.bodies[index].get_restitution()
+    }
+
+    pub(crate) fn set_collision_filter(&mut self, index: usize, group: u16, mask: u16) {
+        self.bodies[index].set_collision_filter(group, mask);
+    }
+
+    pub(crate) fn get_collision_group(&self, index: usize) -> u16 {
+        self.bodies[index].get_collision_group()
+    }
+
+    pub(crate) fn get_collision_mask(&self, index: usize) -> u16 {
+        self.bodies[index].get_collision_mask()
+    }
+
+    pub(crate) fn is_static_or_kinematic(&self, index: usize) -> bool {
+        self.bodies[index].is_static_or_kinematic()
+    }
+
     pub(crate) fn get_total_force(&self, index: usize) -> Vec3 {
         self.bodies[index].get_total_force()
     }
@@ -321,6 +396,18 @@ impl RigidBodyBundle {
         self.bodies[index].set_angular_velocity(velocity);
     }
 
+    pub(crate) fn set_sleeping(&mut self, index: usize, enabled: bool, linear_threshold: f32, angular_threshold: f32) {
+        self.bodies[index].set_sleeping(enabled, linear_threshold, angular_threshold);
+    }
+
+    pub(crate) fn is_sleeping(&self, index: usize) -> bool {
+        self.bodies[index].is_sleeping()
+    }
+
+    pub(crate) fn wake_up(&mut self, index: usize) {
+        self.bodies[index].wake_up();
+    }
+
     pub(crate) fn get_velocity_in_local_point(&self, index: usize, relative_position: Vec3) -> Vec3 {
         self.bodies[index].get_velocity_in_local_point(relative_position)
     }
@@ -539,6 +626,24 @@ pub fn rigidbody_bundle_get_angular_damping(ptr: *const usize, index: usize) ->
     bundle.get_angular_damping(index)
 }
 
+#[wasm_bindgen(js_name = "rigidBodyBundleSetCcd")]
+pub fn rigidbody_bundle_set_ccd(ptr: *mut usize, index: usize, motion_threshold: f32, swept_sphere_radius: f32) {
+    let bundle = unsafe { &mut *(ptr as *mut RigidBodyBundle) };
+    bundle.set_ccd(index, motion_threshold, swept_sphere_radius);
+}
+
+#[wasm_bindgen(js_name = "rigidBodyBundleGetCcdMotionThreshold")]
+pub fn rigidbody_bundle_get_ccd_motion_threshold(ptr: *const usize, index: usize) -> f32 {
+    let bundle = unsafe { &*(ptr as *const RigidBodyBundle) };
+    bundle.get_ccd_motion_threshold(index)
+}
+
+#[wasm_bindgen(js_name = "rigidBodyBundleGetCcdSweptSphereRadius")]
+pub fn rigidbody_bundle_get_ccd_swept_sphere_radius(ptr: *const usize, index: usize) -> f32 {
+    let bundle = unsafe { &*(ptr as *const RigidBodyBundle) };
+    bundle.get_ccd_swept_sphere_radius(index)
+}
+
 #[wasm_bindgen(js_name = "rigidBodyBundleSetMassProps")]
 pub fn rigidbody_bundle_set_mass_props(ptr: *mut usize, index: usize, mass: f32, local_inertia_x: f32, local_inertia_y: f32, local_inertia_z: f32) {
     let bundle = unsafe { &mut *(ptr as *mut RigidBodyBundle) };
@@ -32,4 +32,6 @@ pub(crate) struct RigidBodyConstructionInfo {
     // additional_angular_damping_factor: f32,
     pub(crate) no_contact_response: u8,
     pub(crate) disable_deactivation: u8,
+    pub(crate) ccd_motion_threshold: f32,
+    pub(crate) ccd_swept_sphere_radius: f32,
 }
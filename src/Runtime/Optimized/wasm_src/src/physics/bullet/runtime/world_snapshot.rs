@@ -0,0 +1,173 @@
+use super::super::bind;
+
+use super::kinematic_state::KinematicState;
+use super::multi_physics_world::PhysicsWorldId;
+use super::physics_world::PhysicsWorld;
+
+// "MWSS" in little endian
+const SNAPSHOT_MAGIC: u32 = 0x5353_574D;
+const SNAPSHOT_VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = 4 * 3;
+const WORLD_HEADER_SIZE: usize = 4 * 3;
+const BODY_RECORD_SIZE: usize = std::mem::size_of::<bind::rigidbody::RigidBodyState>() + 4;
+const CONSTRAINT_RECORD_SIZE: usize = std::mem::size_of::<bind::constraint::ConstraintState>();
+
+pub(super) struct SnapshotWriter {
+    bytes: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub(super) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(super) fn write_body(&mut self, state: &bind::rigidbody::RigidBodyState, kinematic_state: &KinematicState) {
+        self.write_pod(state);
+        self.bytes.push(kinematic_state.to_raw());
+        self.bytes.extend_from_slice(&[0; 3]); // padding
+    }
+
+    pub(super) fn write_constraint(&mut self, state: &bind::constraint::ConstraintState) {
+        self.write_pod(state);
+    }
+
+    /// State structs are `repr(C)` with only 4 byte fields
+    fn write_pod<T: Copy>(&mut self, value: &T) {
+        let bytes = unsafe {
+            std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>())
+        };
+        self.bytes.extend_from_slice(bytes);
+    }
+}
+
+/// Reads a snapshot that is already validated, so reads never go out of range
+pub(super) struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    pub(super) fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+        }
+    }
+
+    fn skip(&mut self, size: usize) {
+        self.offset += size;
+    }
+
+    pub(super) fn read_body(&mut self) -> (bind::rigidbody::RigidBodyState, KinematicState) {
+        let state = self.read_pod::<bind::rigidbody::RigidBodyState>();
+        let kinematic_state = KinematicState::from_raw(self.bytes[self.offset]).unwrap();
+        self.offset += 4;
+        (state, kinematic_state)
+    }
+
+    pub(super) fn read_constraint(&mut self) -> bind::constraint::ConstraintState {
+        self.read_pod::<bind::constraint::ConstraintState>()
+    }
+
+    fn read_pod<T: Copy>(&mut self) -> T {
+        let bytes = &self.bytes[self.offset..self.offset + std::mem::size_of::<T>()];
+        self.offset += bytes.len();
+        unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) }
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn world_section_size(world: &PhysicsWorld) -> usize {
+    let (body_count, constraint_count) = world.snapshot_counts();
+    WORLD_HEADER_SIZE + body_count as usize * BODY_RECORD_SIZE + constraint_count as usize * CONSTRAINT_RECORD_SIZE
+}
+
+pub(super) fn snapshot_size(worlds: &[(PhysicsWorldId, &PhysicsWorld)]) -> usize {
+    HEADER_SIZE + worlds.iter().map(|(_, world)| world_section_size(world)).sum::<usize>()
+}
+
+/// Layout:
+///
+/// magic u32, version u32, world count u32 and for each world
+/// world id u32, body count u32, constraint count u32, body records, constraint records
+///
+/// body record is the bullet body state followed by the kinematic state u8 and 3 bytes padding
+pub(super) fn write_snapshot(worlds: &[(PhysicsWorldId, &PhysicsWorld)]) -> Vec<u8> {
+    let mut writer = SnapshotWriter::new(snapshot_size(worlds));
+    writer.write_u32(SNAPSHOT_MAGIC);
+    writer.write_u32(SNAPSHOT_VERSION);
+    writer.write_u32(worlds.len() as u32);
+
+    for (id, world) in worlds {
+        let (body_count, constraint_count) = world.snapshot_counts();
+        writer.write_u32(*id);
+        writer.write_u32(body_count);
+        writer.write_u32(constraint_count);
+        world.write_snapshot(&mut writer);
+    }
+    writer.into_bytes()
+}
+
+/// Snapshot must be taken from worlds with the same ids, bodies and constraints in the same order
+///
+/// Nothing is applied if the snapshot does not match
+pub(super) fn restore_snapshot(worlds: &mut [(PhysicsWorldId, &mut PhysicsWorld)], bytes: &[u8]) -> bool {
+    if !validate_snapshot(worlds, bytes) {
+        return false;
+    }
+
+    let mut reader = SnapshotReader::new(bytes);
+    reader.skip(HEADER_SIZE);
+    for (_, world) in worlds.iter_mut() {
+        reader.skip(WORLD_HEADER_SIZE);
+        world.read_snapshot(&mut reader);
+    }
+    true
+}
+
+fn validate_snapshot(worlds: &[(PhysicsWorldId, &mut PhysicsWorld)], bytes: &[u8]) -> bool {
+    if read_u32(bytes, 0) != Some(SNAPSHOT_MAGIC)
+        || read_u32(bytes, 4) != Some(SNAPSHOT_VERSION)
+        || read_u32(bytes, 8) != Some(worlds.len() as u32)
+    {
+        return false;
+    }
+
+    let mut offset = HEADER_SIZE;
+    for (id, world) in worlds.iter() {
+        let (body_count, constraint_count) = world.snapshot_counts();
+        if read_u32(bytes, offset) != Some(*id)
+            || read_u32(bytes, offset + 4) != Some(body_count)
+            || read_u32(bytes, offset + 8) != Some(constraint_count)
+        {
+            return false;
+        }
+        offset += WORLD_HEADER_SIZE;
+
+        for _ in 0..body_count {
+            let kinematic_state_offset = offset + BODY_RECORD_SIZE - 4;
+            match bytes.get(kinematic_state_offset) {
+                Some(value) if KinematicState::from_raw(*value).is_some() => { }
+                _ => return false,
+            }
+            offset += BODY_RECORD_SIZE;
+        }
+        offset += constraint_count as usize * CONSTRAINT_RECORD_SIZE;
+    }
+    offset == bytes.len()
+}
@@ -2,3 +2,4 @@ mod allocator;
 mod atomic;
 mod error_handler;
 mod math;
+mod time;
@@ -0,0 +1,4 @@
+#[unsafe(no_mangle)]
+extern "C" fn bw_performance_now() -> f64 {
+    crate::profiler::now()
+}
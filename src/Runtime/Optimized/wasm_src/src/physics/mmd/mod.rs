@@ -1,19 +1,23 @@
-use glam::{EulerRot, Mat4, Quat, Vec3};
-use physics_model_context::PhysicsModelContext;
+use glam::{EulerRot, Mat4, Quat, Vec3, Vec3A};
+use physics_model_context::{CcdOptions, PhysicsModelContext, Ragdoll};
 use rigidbody_bundle_proxy::{RigidBodyBundleProxy, RigidBodyProxyData};
 
 use crate::diagnostic::DiagnosticWriter;
 use crate::mmd_model::mmd_runtime_bone::MmdRuntimeBone;
 use crate::mmd_model::MmdModel;
-use crate::mmd_model_metadata::{JointKind, RigidBodyMetadata, RigidBodyMetadataReader, RigidBodyPhysicsMode, RigidBodyShapeType};
+use crate::mmd_model_metadata::{JointKind, MetadataError, RagdollMetadata, RigidBodyMetadata, RigidBodyMetadataReader, RigidBodyPhysicsMode, RigidBodyShapeType};
 use crate::physics::bullet::runtime::kinematic_state::KinematicToggleState;
+use crate::profiler::StageTimer;
 
-use super::bullet::runtime::collision_shape::{BoxShape, CapsuleShape, CollisionShape, SphereShape, StaticPlaneShape};
-use super::bullet::runtime::constraint::{Constraint, ConstraintParams, Generic6DofSpringConstraint};
+use super::bullet::runtime::collision_shape::{BoxShape, CapsuleShape, CollisionShape, ConeShape, CylinderShape, SphereShape, StaticPlaneShape};
+use super::bullet::runtime::constraint::{ConeTwistConstraint, Constraint, ConstraintParams, Generic6DofConstraint, Generic6DofSpringConstraint, HingeConstraint, Point2PointConstraint, SliderConstraint};
 use super::bullet::runtime::motion_type::MotionType;
 use super::bullet::runtime::multi_physics_world::MultiPhysicsWorld;
 use super::bullet::runtime::rigidbody_construction_info::RigidBodyConstructionInfo;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 pub(crate) mod rigidbody_bundle_proxy;
 pub(crate) mod physics_model_context;
 
@@ -21,6 +25,8 @@ pub(crate) struct MmdPhysicsRuntime {
     multi_physics_world: MultiPhysicsWorld,
     max_sub_steps: i32,
     fixed_time_step: f32,
+    // time elapsed since the last fixed step, same as the local time of bullet world
+    interpolation_time: f32,
 }
 
 struct CreateRbInfoResult {
@@ -28,9 +34,21 @@ struct CreateRbInfoResult {
     motion_type: MotionType,
     physics_mode: RigidBodyPhysicsMode,
     body_offset_matrix: Mat4,
+    ccd_size: f32,
     construction_info: RigidBodyConstructionInfo,
 }
 
+/// Interpolates two transforms, weight 0 gives `from` and 1 gives `to`
+fn blend_matrix(from: &Mat4, to: &Mat4, weight: f32) -> Mat4 {
+    let (from_scale, from_rotation, from_translation) = from.to_scale_rotation_translation();
+    let (to_scale, to_rotation, to_translation) = to.to_scale_rotation_translation();
+    Mat4::from_scale_rotation_translation(
+        from_scale.lerp(to_scale, weight),
+        from_rotation.slerp(to_rotation, weight),
+        from_translation.lerp(to_translation, weight)
+    )
+}
+
 impl MmdPhysicsRuntime {
     pub(crate) fn new(allow_dynamic_shadow: bool) -> Self {
         let multi_physics_world = MultiPhysicsWorld::new(allow_dynamic_shadow);
@@ -38,9 +56,19 @@ impl MmdPhysicsRuntime {
             multi_physics_world,
             max_sub_steps: 5,
             fixed_time_step: 1.0 / 60.0,
+            interpolation_time: 0.0,
         }
     }
     
+    #[cfg(feature = "deterministic")]
+    pub(crate) fn world(&self) -> &MultiPhysicsWorld {
+        &self.multi_physics_world
+    }
+
+    pub(crate) fn set_stats_enabled(&mut self, enabled: bool) {
+        self.multi_physics_world.set_stats_enabled(enabled);
+    }
+
     pub(crate) fn world_mut_ptr(&mut self) -> *mut MultiPhysicsWorld {
         &mut self.multi_physics_world as *mut MultiPhysicsWorld
     }
@@ -54,7 +82,29 @@ impl MmdPhysicsRuntime {
     }
 
     #[inline(always)]
-    fn sync_bodies(&mut self, model: &mut MmdModel) {
+    fn sync_bodies(model: &mut MmdModel, time_step: f32) {
+        #[inline(always)]
+        fn drive_body_to_target(bundle_proxy: &mut RigidBodyBundleProxy, index: usize, target_transform: &Mat4, force_gain: f32, torque_gain: f32) {
+            let position = target_transform.w_axis.truncate();
+            let rotation = Quat::from_mat4(target_transform);
+            let current_transform = bundle_proxy.get_transform(index);
+            let current_position = current_transform.w_axis.truncate();
+            let current_rotation = Quat::from_mat4(&current_transform);
+
+            // Linear difference
+            bundle_proxy.inner_mut().set_linear_velocity(index, (position - current_position) * force_gain);
+
+            // Angular difference
+            let target_angles = rotation.to_euler(EulerRot::YXZ);
+            let current_angles = current_rotation.to_euler(EulerRot::YXZ);
+            use std::f32::consts::PI;
+            let dx = (target_angles.0 - current_angles.0 + PI) % (2.0 * PI) - PI;
+            let dy = (target_angles.1 - current_angles.1 + PI) % (2.0 * PI) - PI;
+            let dz = (target_angles.2 - current_angles.2 + PI) % (2.0 * PI) - PI;
+            let velocity = Vec3::new(dx, dy, dz) * torque_gain;
+            bundle_proxy.inner_mut().set_angular_velocity(index, velocity);
+        }
+
         #[inline(always)]
         fn init_dynamic_body(model: &mut MmdModel, linked_bone_index: u32, index: usize, world_matrix: &Mat4) {
             let bone_world_matrices = model.bone_arena().world_matrices();
@@ -76,6 +126,7 @@ impl MmdPhysicsRuntime {
 
         let world_matrix = *context.world_matrix();
         let need_init = context.flush_need_init();
+        context.update_blend_weights(time_step, need_init);
 
         if context.need_deoptimize() {
             context.create_or_initialize_body_kinematic_toggle_map();
@@ -105,12 +156,35 @@ impl MmdPhysicsRuntime {
                     // SAFETY: context validity check is done at first line of outer loop
                     let context = unsafe { context.unwrap_unchecked() };
 
-                    context.bundle_proxy_mut().set_transform(mapped_index as usize, world_matrix * bone_world_matrix);
+                    context.set_body_transform(mapped_index as usize, world_matrix * bone_world_matrix);
                 } else if physics_mode == RigidBodyPhysicsMode::Physics || physics_mode == RigidBodyPhysicsMode::PhysicsWithBone {
                     if need_init {
                         init_dynamic_body(model, linked_bone_index, mapped_index as usize, &world_matrix);
                     } else { // we don't need apply physics toggle if the body initialization triggered
-                        if context.synced_rigidbody_states()[index] == 0 {
+                        let blend_weight = context.blend_weight(mapped_index as usize);
+                        if 0.0 < blend_weight && blend_weight < 1.0 { // body is handed over between animation and physics
+                            let bone_world_matrices = model.bone_arena().world_matrices();
+                            let bone_world_matrix = world_matrix * bone_world_matrices[linked_bone_index];
+                            let context = model.physics_model_context_mut().as_mut();
+                            // SAFETY: context validity check is done at first line of outer loop
+                            let context = unsafe { context.unwrap_unchecked() };
+
+                            context.body_kinematic_toggle_map_mut().unwrap()[index] = 2; // target transform method
+
+                            // drive gets weaker as physics takes over
+                            let options = *context.blend_options();
+                            let drive_weight = 1.0 - blend_weight;
+                            context.wake_up_body(mapped_index as usize);
+                            let bundle_proxy = context.bundle_proxy_mut();
+                            bundle_proxy.inner_mut().set_kinematic_toggle(mapped_index as usize, KinematicToggleState::Disabled);
+                            drive_body_to_target(
+                                bundle_proxy,
+                                mapped_index as usize,
+                                &bone_world_matrix,
+                                options.force_gain * drive_weight,
+                                options.torque_gain * drive_weight
+                            );
+                        } else if context.synced_rigidbody_states()[index] == 0 {
                             let use_target_transform_method = || -> bool {
                                 let mut current_body_index = mapped_index;
                                 loop {
@@ -180,38 +254,69 @@ impl MmdPhysicsRuntime {
                             let context = unsafe { context.unwrap_unchecked() };
                             
                             if use_target_transform_method {
+                                let options = *context.blend_options();
+                                context.wake_up_body(mapped_index as usize);
                                 let bundle_proxy = context.bundle_proxy_mut();
                                 bundle_proxy.inner_mut().set_kinematic_toggle(mapped_index as usize, KinematicToggleState::Disabled);
-                                let force_factor = Vec3::splat(30.0);
-                                let torque_factor = Vec3::splat(30.0);
-
-                                let position = bone_world_matrix.w_axis.truncate();
-                                let rotation = Quat::from_mat4(&bone_world_matrix);
-                                let current_transform = bundle_proxy.get_transform(mapped_index as usize);
-                                let current_position = current_transform.w_axis.truncate();
-                                let current_rotation = Quat::from_mat4(&current_transform);
-
-                                // Linear difference
-                                bundle_proxy.inner_mut().set_linear_velocity(mapped_index as usize, (position - current_position) * force_factor);
-                                
-                                // Angular difference
-                                let target_angles = rotation.to_euler(EulerRot::YXZ);
-                                let current_angles = current_rotation.to_euler(EulerRot::YXZ);
-                                use std::f32::consts::PI;
-                                let dx = (target_angles.0 - current_angles.0 + PI) % (2.0 * PI) - PI;
-                                let dy = (target_angles.1 - current_angles.1 + PI) % (2.0 * PI) - PI;
-                                let dz = (target_angles.2 - current_angles.2 + PI) % (2.0 * PI) - PI;
-                                let velocity = Vec3::new(dx, dy, dz) * torque_factor;
-                                bundle_proxy.inner_mut().set_angular_velocity(mapped_index as usize, velocity);
+                                drive_body_to_target(
+                                    bundle_proxy,
+                                    mapped_index as usize,
+                                    &bone_world_matrix,
+                                    options.force_gain,
+                                    options.torque_gain
+                                );
                             } else {
-                                let bundle_proxy = context.bundle_proxy_mut();
-                                bundle_proxy.inner_mut().set_kinematic_toggle(mapped_index as usize, KinematicToggleState::Enabled);
-                                bundle_proxy.set_transform(mapped_index as usize, bone_world_matrix);
+                                context.bundle_proxy_mut().inner_mut().set_kinematic_toggle(mapped_index as usize, KinematicToggleState::Enabled);
+                                context.set_body_transform(mapped_index as usize, bone_world_matrix);
                             }
                         }
                     }
                 }
             }
+
+            // generated ragdoll bodies are not in the index map and have no physics toggle
+            let context = model.physics_model_context().as_ref();
+            // SAFETY: context validity check is done at first line of outer loop
+            let context = unsafe { context.unwrap_unchecked() };
+            for index in context.generated_body_range() {
+                let context = model.physics_model_context().as_ref();
+                // SAFETY: context validity check is done at first line of outer loop
+                let context = unsafe { context.unwrap_unchecked() };
+
+                let linked_bone_index = if let Some(linked_bone_index) = context.bundle_proxy().linked_bone_index(index) {
+                    linked_bone_index
+                } else {
+                    continue;
+                };
+                let physics_mode = context.bundle_proxy().get_physics_mode(index);
+                if physics_mode != RigidBodyPhysicsMode::FollowBone && need_init {
+                    init_dynamic_body(model, linked_bone_index, index, &world_matrix);
+                    continue;
+                }
+
+                let bone_world_matrices = model.bone_arena().world_matrices();
+                let bone_world_matrix = world_matrix * bone_world_matrices[linked_bone_index];
+
+                let context = model.physics_model_context_mut().as_mut();
+                // SAFETY: context validity check is done at first line of outer loop
+                let context = unsafe { context.unwrap_unchecked() };
+
+                let blend_weight = context.blend_weight(index);
+                if physics_mode == RigidBodyPhysicsMode::FollowBone {
+                    context.set_body_transform(index, bone_world_matrix);
+                } else if 0.0 < blend_weight && blend_weight < 1.0 {
+                    let options = *context.blend_options();
+                    let drive_weight = 1.0 - blend_weight;
+                    context.wake_up_body(index);
+                    drive_body_to_target(
+                        context.bundle_proxy_mut(),
+                        index,
+                        &bone_world_matrix,
+                        options.force_gain * drive_weight,
+                        options.torque_gain * drive_weight
+                    );
+                }
+            }
         } else {
             for index in 0..context.bundle_proxy().len() {
                 let context = model.physics_model_context().as_ref();
@@ -233,16 +338,27 @@ impl MmdPhysicsRuntime {
                     // SAFETY: context validity check is done at first line of outer loop
                     let context = unsafe { context.unwrap_unchecked() };
 
-                    context.bundle_proxy_mut().set_transform(index, world_matrix * bone_world_matrix);
+                    context.set_body_transform(index, world_matrix * bone_world_matrix);
                 } else if need_init && (physics_mode == RigidBodyPhysicsMode::Physics || physics_mode == RigidBodyPhysicsMode::PhysicsWithBone) {
                     init_dynamic_body(model, linked_bone_index, index, &world_matrix);
                 }
             }
         }
+
+        let context = model.physics_model_context_mut().as_mut();
+        // SAFETY: context validity check is done at first line of outer loop
+        let context = unsafe { context.unwrap_unchecked() };
+        if context.sleeping_enabled() {
+            if need_init { // every dynamic body is teleported to its bone
+                context.wake_up_dynamic_bodies();
+            } else {
+                context.wake_up_moved_islands();
+            }
+        }
     }
 
     #[inline(always)]
-    fn sync_bones(&mut self, model: &mut MmdModel) {
+    fn sync_bones(model: &mut MmdModel) {
         let context = if let Some(context) = model.physics_model_context() {
             context
         } else {
@@ -250,8 +366,13 @@ impl MmdPhysicsRuntime {
         };      
 
         let world_matrix_inverse = *context.world_matrix_inverse();
+        let body_count = context.bundle_proxy().len();
+        let ragdoll_active = context.ragdoll_active();
+        if ragdoll_active {
+            model.store_ragdoll_follower_bones();
+        }
 
-        for index in 0..context.bundle_proxy().len() {
+        for index in 0..body_count {
             let context = model.physics_model_context().as_ref();
             // SAFETY: context validity check is done at first line of outer loop
             let context = unsafe { context.unwrap_unchecked() };
@@ -268,7 +389,21 @@ impl MmdPhysicsRuntime {
                 continue;
             };
 
-            let mut body_world_matrix = world_matrix_inverse * context.bundle_proxy().get_transform(index);
+            let context = model.physics_model_context_mut().as_mut();
+            // SAFETY: context validity check is done at first line of outer loop
+            let context = unsafe { context.unwrap_unchecked() };
+            let blend_weight = context.blend_weight(index);
+            let sleeping_body_matrix = context.sleeping_body_model_matrix(index, &world_matrix_inverse);
+            if let Some(body_world_matrix) = sleeping_body_matrix
+                && physics_mode == RigidBodyPhysicsMode::Physics
+                && blend_weight == 1.0
+            {
+                // sleeping body does not depend on the animated bone, only the cached pose is written back
+                model.bone_arena_mut().world_matrices_mut()[linked_bone_index] = body_world_matrix;
+                continue;
+            }
+            let mut body_world_matrix = sleeping_body_matrix
+                .unwrap_or_else(|| context.body_model_matrix(index, &world_matrix_inverse));
 
             let mut bone_world_matrices = model.bone_arena_mut().world_matrices_mut();
             if physics_mode == RigidBodyPhysicsMode::PhysicsWithBone {
@@ -276,23 +411,138 @@ impl MmdPhysicsRuntime {
                 body_world_matrix.w_axis = bone_position.extend(body_world_matrix.w_axis.w);
             }
 
+            // bone matrix is still the animated one here, so handed over bodies show a mix of both
+            if 0.0 < blend_weight && blend_weight < 1.0 {
+                body_world_matrix = blend_matrix(&bone_world_matrices[linked_bone_index], &body_world_matrix, blend_weight);
+            }
+
             bone_world_matrices[linked_bone_index] = body_world_matrix;
         }
+
+        if ragdoll_active {
+            model.restore_ragdoll_follower_bones();
+        }
+    }
+
+    /// Tracks the time that is not simulated yet the same way bullet accumulates its local time
+    ///
+    /// Returns whether any fixed step is simulated
+    fn advance_interpolation_time(&mut self, time_step: f32) -> bool {
+        if self.max_sub_steps <= 0 {
+            self.interpolation_time = 0.0;
+            return 0.0 < time_step;
+        }
+
+        self.interpolation_time += time_step;
+        if self.fixed_time_step <= self.interpolation_time {
+            let step_count = (self.interpolation_time / self.fixed_time_step) as i32;
+            self.interpolation_time -= step_count as f32 * self.fixed_time_step;
+            return true;
+        }
+        false
+    }
+
+    /// Weight between the previous and the last fixed step, variable steps are shown as simulated
+    fn interpolation_weight(&self) -> f32 {
+        if self.max_sub_steps <= 0 {
+            1.0
+        } else {
+            self.interpolation_time / self.fixed_time_step
+        }
     }
 
     pub(crate) fn step_simulation(&mut self, time_step: f32, mmd_models: &mut [Box<MmdModel>]) {
         // synchronize kinematic rigid bodies with bone matrices
         for model in mmd_models.iter_mut() {
-            self.sync_bodies(model);
+            let mut timer = StageTimer::new(model.profile().is_some());
+            Self::sync_bodies(model, time_step);
+            let sync_time = timer.lap();
+            if let Some(profile) = model.profile_mut() {
+                profile.sync_bodies = sync_time;
+            }
         }
 
         self.multi_physics_world.sync_buffered_motion_state();
         self.multi_physics_world.step_simulation(time_step, self.max_sub_steps, self.fixed_time_step);
+        let stepped = self.advance_interpolation_time(time_step);
 
         // synchronize bone matrices with dynamic rigid bodies
-        for model in mmd_models.iter_mut() {
-            self.sync_bones(model);
+        // each model only reads its own bundle, so models are independent after the step
+        let interpolation_weight = self.interpolation_weight();
+        let sync_model_bones = |model: &mut Box<MmdModel>| {
+            let mut timer = StageTimer::new(model.profile().is_some());
+            if let Some(context) = model.physics_model_context_mut() {
+                context.interpolate_motion_states(stepped, interpolation_weight);
+            }
+            Self::sync_bones(model);
+            let sync_time = timer.lap();
+            if let Some(profile) = model.profile_mut() {
+                profile.sync_bones = sync_time;
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            if 1 < mmd_models.len() {
+                mmd_models.par_iter_mut().for_each(sync_model_bones);
+            } else if !mmd_models.is_empty() {
+                sync_model_bones(&mut mmd_models[0]);
+            }
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        mmd_models.iter_mut().for_each(sync_model_bones);
+    }
+
+    /// Simulates only this model for `step_count` fixed steps while its bones stay at the current pose
+    ///
+    /// Damping of each body starts at least at `initial_damping` and fades back to its own value over the steps
+    pub(crate) fn pre_roll(&mut self, model: &mut MmdModel, step_count: u32, initial_damping: f32) {
+        let context = if let Some(context) = model.physics_model_context_mut() {
+            context
+        } else {
+            return;
+        };
+
+        let world_id = context.world_id();
+        let bundle = context.bundle_proxy_mut().inner_mut();
+        let original_damping: Vec<(f32, f32)> = (0..bundle.len())
+            .map(|index| (bundle.get_linear_damping(index), bundle.get_angular_damping(index)))
+            .collect();
+        let bundle_handle = bundle.create_handle();
+        let constraint_handles: Vec<_> = context.constraints_mut().iter_mut()
+            .map(|constraint| constraint.create_handle())
+            .collect();
+        let fixed_time_step = self.fixed_time_step;
+
+        self.multi_physics_world.step_world_isolated(world_id, &bundle_handle, &constraint_handles, step_count, fixed_time_step, |step| {
+            if 0.0 < initial_damping {
+                let context = model.physics_model_context_mut().as_mut();
+                // SAFETY: context validity check is done at first line of outer function
+                let context = unsafe { context.unwrap_unchecked() };
+
+                let damping = initial_damping * (1.0 - step as f32 / step_count as f32);
+                let bundle = context.bundle_proxy_mut().inner_mut();
+                for (index, &(linear_damping, angular_damping)) in original_damping.iter().enumerate() {
+                    bundle.set_damping(index, linear_damping.max(damping), angular_damping.max(damping));
+                }
+            }
+            Self::sync_bodies(model, fixed_time_step);
+        });
+        drop(constraint_handles);
+        drop(bundle_handle);
+
+        let context = model.physics_model_context_mut().as_mut();
+        // SAFETY: context validity check is done at first line of outer function
+        let context = unsafe { context.unwrap_unchecked() };
+        if 0.0 < initial_damping {
+            let bundle = context.bundle_proxy_mut().inner_mut();
+            for (index, &(linear_damping, angular_damping)) in original_damping.iter().enumerate() {
+                bundle.set_damping(index, linear_damping, angular_damping);
+            }
         }
+
+        Self::sync_bones(model);
     }
 
     fn create_shape(
@@ -315,6 +565,15 @@ impl MmdPhysicsRuntime {
             let shape = CapsuleShape::new(shape_size.x, shape_size.y);
             let is_zero_volume = shape_size.x == 0.0 || shape_size.y == 0.0;
             (CollisionShape::Capsule(shape), is_zero_volume)
+        } else if metadata.shape_type == RigidBodyShapeType::Cylinder as u8 {
+            // same size layout as capsule: x is radius, y is height
+            let shape = CylinderShape::new(Vec3::new(shape_size.x, shape_size.y * 0.5, shape_size.x));
+            let is_zero_volume = shape_size.x == 0.0 || shape_size.y == 0.0;
+            (CollisionShape::Cylinder(shape), is_zero_volume)
+        } else if metadata.shape_type == RigidBodyShapeType::Cone as u8 {
+            let shape = ConeShape::new(shape_size.x, shape_size.y);
+            let is_zero_volume = shape_size.x == 0.0 || shape_size.y == 0.0;
+            (CollisionShape::Cone(shape), is_zero_volume)
         } else if metadata.shape_type == RigidBodyShapeType::StaticPlane as u8 {
             let shape = StaticPlaneShape::new(Vec3::new(shape_size.x, shape_size.y, shape_size.z), shape_size.w);
             let is_zero_volume = shape_size.x == 0.0 && shape_size.y == 0.0 && shape_size.z == 0.0;
@@ -338,6 +597,7 @@ impl MmdPhysicsRuntime {
         metadata: &RigidBodyMetadata,
         shape: &mut CollisionShape,
         is_zero_volume: bool,
+        ccd_options: &CcdOptions,
     ) -> Option<CreateRbInfoResult> {
         let bone_index = metadata.bone_index;
         let bone_index = if bone_index < 0 || bones.len() <= bone_index as usize {
@@ -389,6 +649,22 @@ impl MmdPhysicsRuntime {
         let position = world_matrix.transform_point3a(position);
         let rotation = world_rotation * rotation;
 
+        // inner radius of the shape, the swept sphere must stay inside the body to not miss contacts
+        let ccd_size = if motion_type == MotionType::Static || is_zero_volume {
+            0.0
+        } else {
+            let shape_size = metadata.shape_size * scaling_factor;
+            if metadata.shape_type == RigidBodyShapeType::Box as u8 {
+                shape_size.truncate().min_element()
+            } else if metadata.shape_type == RigidBodyShapeType::Cylinder as u8 || metadata.shape_type == RigidBodyShapeType::Cone as u8 {
+                shape_size.x.min(shape_size.y * 0.5)
+            } else {
+                // sphere and capsule
+                shape_size.x
+            }
+        };
+        let (ccd_motion_threshold, ccd_swept_sphere_radius) = ccd_options.body_parameters(ccd_size);
+
         let construction_info = RigidBodyConstructionInfo {
             shape: unsafe { std::mem::transmute::<&mut CollisionShape, &'static mut CollisionShape>(shape) },
             initial_transform: Mat4::from_rotation_translation(rotation, position.into()),
@@ -407,6 +683,8 @@ impl MmdPhysicsRuntime {
             additional_damping: true as u8,
             no_contact_response: (metadata.collision_mask == 0x0000 || is_zero_volume) as u8,
             disable_deactivation: true as u8,
+            ccd_motion_threshold,
+            ccd_swept_sphere_radius,
         };
 
         Some(CreateRbInfoResult {
@@ -414,16 +692,138 @@ impl MmdPhysicsRuntime {
             motion_type,
             physics_mode,
             body_offset_matrix,
+            ccd_size,
             construction_info,
         })
     }
 
+    /// Model space transform of the body at creation
+    fn initial_body_matrix(&(position, rotation): &(Vec3A, Vec3A), scaling_factor: f32) -> Mat4 {
+        Mat4::from_rotation_translation(
+            Quat::from_euler(
+                EulerRot::YXZ,
+                rotation.y, rotation.x, rotation.z
+            ),
+            (position * scaling_factor).into()
+        )
+    }
+
+    /// Bone indices sorted so that parents come before their children
+    fn parent_first_bone_order(bones: &[MmdRuntimeBone]) -> Vec<u32> {
+        let depth = |bone_index: u32| {
+            let mut depth = 0;
+            let mut current_bone = bones[bone_index as usize].parent_bone();
+            while let Some(bone_index) = current_bone {
+                depth += 1;
+                current_bone = bones[bone_index as usize].parent_bone();
+            }
+            depth
+        };
+        let mut sorted_bones: Vec<u32> = (0..bones.len() as u32).collect();
+        sorted_bones.sort_by_cached_key(|&bone_index| depth(bone_index));
+        sorted_bones
+    }
+
+    fn bone_rest_position(bone: &MmdRuntimeBone) -> Vec3 {
+        bone.absolute_inverse_bind_matrix().inverse().w_axis.truncate()
+    }
+
+    /// Capsule spanning from the bone to its farthest child, sphere if the capsule is shorter than its diameter
+    ///
+    /// Bones with rigid body, IK and append transform bones, and bones shorter than the minimum length get no body
+    ///
+    /// Returns the shape with model space rotation and position of the body, and the radius of the shape
+    fn create_ragdoll_shape(
+        bones: &[MmdRuntimeBone],
+        scaling_factor: f32,
+        bone_index: u32,
+        ragdoll: &RagdollMetadata,
+    ) -> Option<(CollisionShape, Quat, Vec3, f32)> {
+        let bone = &bones[bone_index as usize];
+        if !bone.rigidbody_indices().is_empty() || bone.has_ik_or_append_transform() {
+            return None;
+        }
+
+        let head = Self::bone_rest_position(bone);
+        let tail = bone.child_bones().iter()
+            .map(|&child_bone| &bones[child_bone as usize])
+            .filter(|child_bone| !child_bone.has_ik_or_append_transform())
+            .map(Self::bone_rest_position)
+            .max_by(|a, b| a.distance_squared(head).total_cmp(&b.distance_squared(head)))?;
+
+        let direction = tail - head;
+        let length = direction.length();
+        if length < ragdoll.min_bone_length {
+            return None;
+        }
+
+        // capsule ends at the bone and the tail bone
+        let radius = length * ragdoll.radius_scale;
+        let height = length - radius * 2.0;
+        let (shape, rotation) = if 0.0 < height {
+            let shape = CapsuleShape::new(radius * scaling_factor, height * scaling_factor);
+            (CollisionShape::Capsule(shape), Quat::from_rotation_arc(Vec3::Y, direction / length))
+        } else {
+            (CollisionShape::Sphere(SphereShape::new(radius * scaling_factor)), Quat::IDENTITY)
+        };
+
+        Some((shape, rotation, (head + tail) * 0.5, radius))
+    }
+
+    fn find_ragdoll_parent_body(bones: &[MmdRuntimeBone], bone_ragdoll_bodies: &[Option<u32>], bone_index: u32) -> Option<u32> {
+        let mut current_bone = bones[bone_index as usize].parent_bone();
+        while let Some(bone_index) = current_bone {
+            if let Some(body_index) = bone_ragdoll_bodies[bone_index as usize] {
+                return Some(body_index);
+            }
+            current_bone = bones[bone_index as usize].parent_bone();
+        }
+        None
+    }
+
+    /// Ragdoll body that the generated body of the bone is attached to
+    ///
+    /// Unlike `find_ragdoll_parent_body`, bones under a body that is not part of the ragdoll
+    /// (e.g. hair driven by physics) are not attached
+    fn find_ragdoll_attach_body(bones: &[MmdRuntimeBone], bone_ragdoll_bodies: &[Option<u32>], bone_index: u32) -> Option<u32> {
+        let mut current_bone = bones[bone_index as usize].parent_bone();
+        while let Some(bone_index) = current_bone {
+            if let Some(body_index) = bone_ragdoll_bodies[bone_index as usize] {
+                return Some(body_index);
+            }
+            if !bones[bone_index as usize].rigidbody_indices().is_empty() {
+                return None;
+            }
+            current_bone = bones[bone_index as usize].parent_bone();
+        }
+        None
+    }
+
+    /// Bones without body whose parent is a ragdoll bone or another follower, parent first order
+    fn collect_ragdoll_follower_bones(bones: &[MmdRuntimeBone], bone_ragdoll_bodies: &[Option<u32>]) -> Box<[(u32, u32)]> {
+        let mut is_follower = vec![false; bones.len()];
+        let mut follower_bones = Vec::new();
+        for bone_index in Self::parent_first_bone_order(bones) {
+            let bone = &bones[bone_index as usize];
+            if bone_ragdoll_bodies[bone_index as usize].is_some() || !bone.rigidbody_indices().is_empty() {
+                continue;
+            }
+            if let Some(parent_bone) = bone.parent_bone()
+                && (bone_ragdoll_bodies[parent_bone as usize].is_some() || is_follower[parent_bone as usize])
+            {
+                is_follower[bone_index as usize] = true;
+                follower_bones.push((bone_index, parent_bone));
+            }
+        }
+        follower_bones.into_boxed_slice()
+    }
+
     pub(crate) fn create_physics_context(
         &mut self,
         bones: &[MmdRuntimeBone],
         mut reader: RigidBodyMetadataReader,
         mut diagnostic: DiagnosticWriter
-    ) -> PhysicsModelContext {
+    ) -> Result<PhysicsModelContext, MetadataError> {
         let world_matrix = *reader.model_initial_world_matrix();
 
         let (scaling_factor, world_rotation) = if world_matrix.determinant() == 0.0 {
@@ -445,14 +845,23 @@ impl MmdPhysicsRuntime {
 
         let world_id = reader.physics_world_id();
         let disable_offset_for_constraint_frame = reader.disable_offset_for_constraint_frame();
+        let ragdoll_metadata = reader.ragdoll()?;
+        let ccd_options = reader.ccd()?.map_or(CcdOptions::DISABLED, |ccd| CcdOptions {
+            motion_threshold_scale: ccd.motion_threshold_scale,
+            swept_sphere_radius_scale: ccd.swept_sphere_radius_scale,
+        });
+        // shapes are referenced by construction info, so the capacity must cover generated bodies too
+        let body_capacity = reader.count() as usize + ragdoll_metadata.as_ref().map_or(0, |_| bones.len());
 
         let mut rigidbody_map = vec![-1; reader.count() as usize];
-        let mut rigidbody_initial_transforms = Vec::with_capacity(reader.count() as usize);
+        let mut rigidbody_initial_transforms = Vec::with_capacity(body_capacity);
 
         let mut kinematic_object_count = 0;
-        let mut shapes = Vec::with_capacity(reader.count() as usize);
-        let mut rb_info_list = Vec::with_capacity(reader.count() as usize);
-        let mut rb_data_list = Vec::with_capacity(reader.count() as usize);
+        let mut shapes = Vec::with_capacity(body_capacity);
+        let mut rb_info_list = Vec::with_capacity(body_capacity);
+        let mut rb_data_list = Vec::with_capacity(body_capacity);
+        let mut ragdoll_bodies = Vec::with_capacity(body_capacity);
+        let mut ccd_sizes = Vec::with_capacity(body_capacity);
         reader.enumerate(|rigidbody_index, metadata| {
             let position = metadata.shape_position;
             let rotation = metadata.shape_rotation;
@@ -469,7 +878,8 @@ impl MmdPhysicsRuntime {
                 motion_type,
                 physics_mode,
                 body_offset_matrix,
-                construction_info
+                ccd_size,
+                mut construction_info
             } = match Self::create_rb_info(
                 bones,
                 world_matrix,
@@ -479,13 +889,34 @@ impl MmdPhysicsRuntime {
                 rigidbody_index,
                 &metadata,
                 shapes.last_mut().unwrap(),
-                is_zero_volume
+                is_zero_volume,
+                &ccd_options
             ) {
                 Some(v) => v,
                 None => return,
             };
 
+            // follow bone bodies of ragdoll model have mass so ragdoll mode can release them,
+            // they are added to the world as kinematic by the toggle and stay kinematic until ragdoll is enabled
+            let mut is_ragdoll_body = false;
+            if let Some(ragdoll) = &ragdoll_metadata
+                && motion_type == MotionType::Kinematic
+                && bone_index.is_some()
+            {
+                if construction_info.mass <= 0.0 {
+                    construction_info.mass = ragdoll.default_mass * scaling_factor;
+                }
+                if 0.0 < construction_info.mass {
+                    construction_info.motion_type = MotionType::Dynamic as u8;
+                    is_ragdoll_body = true;
+                } else {
+                    diagnostic.warning(format!("Zero mass rigid body is excluded from ragdoll: {}", rigidbody_index));
+                }
+            }
+            ragdoll_bodies.push(is_ragdoll_body);
+
             rb_info_list.push(construction_info);
+            ccd_sizes.push(ccd_size);
             rb_data_list.push(RigidBodyProxyData {
                 linked_bone_index: bone_index.map(|v| v as u32),
                 body_offset_matrix,
@@ -499,17 +930,99 @@ impl MmdPhysicsRuntime {
             if MotionType::Dynamic != motion_type {
                 kinematic_object_count += 1;
             }
-        });
+        })?;
+
+        // bone to its ragdoll body, first body wins if a bone has multiple bodies
+        let mut bone_ragdoll_bodies = vec![None; bones.len()];
+        for (index, data) in rb_data_list.iter().enumerate() {
+            if let (true, Some(bone_index)) = (ragdoll_bodies[index], data.linked_bone_index) {
+                bone_ragdoll_bodies[bone_index as usize].get_or_insert(index as u32);
+            }
+        }
+
+        let generated_body_start = rb_info_list.len();
+        // bones without body under the ragdoll get capsules, so limbs without pmx bodies can fall too
+        if let Some(ragdoll) = &ragdoll_metadata {
+            if ragdoll.radius_scale <= 0.0 || ragdoll.default_mass <= 0.0 {
+                diagnostic.warning("Ragdoll radius scale and default mass must be positive, no ragdoll body is generated".to_string());
+            } else {
+                for bone_index in Self::parent_first_bone_order(bones) {
+                    let parent_body = match Self::find_ragdoll_attach_body(bones, &bone_ragdoll_bodies, bone_index) {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    let (shape, rotation, position, radius) = match Self::create_ragdoll_shape(bones, scaling_factor, bone_index, ragdoll) {
+                        Some(v) => v,
+                        None => continue,
+                    };
+
+                    // generated body collides like the body it is attached to
+                    let parent_info = &rb_info_list[parent_body as usize];
+                    let (collision_group, collision_mask) = (parent_info.collision_group, parent_info.collision_mask);
+                    let (linear_damping, angular_damping) = (parent_info.linear_damping, parent_info.angular_damping);
+                    let (friction, restitution) = (parent_info.friction, parent_info.restitution);
+
+                    let ccd_size = radius * scaling_factor;
+                    let (ccd_motion_threshold, ccd_swept_sphere_radius) = ccd_options.body_parameters(ccd_size);
+
+                    shapes.push(shape);
+                    rb_info_list.push(RigidBodyConstructionInfo {
+                        shape: unsafe { std::mem::transmute::<&mut CollisionShape, &'static mut CollisionShape>(shapes.last_mut().unwrap()) },
+                        initial_transform: Mat4::from_rotation_translation(world_rotation * rotation, world_matrix.transform_point3(position)),
+                        data_mask: 0,
+                        motion_type: MotionType::Dynamic as u8,
+                        mass: ragdoll.default_mass * scaling_factor,
+                        local_inertia: Vec3::ZERO,
+                        linear_damping,
+                        angular_damping,
+                        friction,
+                        restitution,
+                        linear_sleeping_threshold: 0.0,
+                        angular_sleeping_threshold: 0.0,
+                        collision_group,
+                        collision_mask,
+                        additional_damping: true as u8,
+                        no_contact_response: false as u8,
+                        disable_deactivation: true as u8,
+                        ccd_motion_threshold,
+                        ccd_swept_sphere_radius,
+                    });
+                    ccd_sizes.push(ccd_size);
+
+                    let body_offset_matrix = *bones[bone_index as usize].absolute_inverse_bind_matrix() * Mat4::from_rotation_translation(rotation, position);
+                    rb_data_list.push(RigidBodyProxyData {
+                        linked_bone_index: Some(bone_index),
+                        body_offset_matrix,
+                        body_offset_inverse_matrix: body_offset_matrix.inverse(),
+                        physics_mode: RigidBodyPhysicsMode::FollowBone,
+                    });
+
+                    bone_ragdoll_bodies[bone_index as usize] = Some(rigidbody_initial_transforms.len() as u32);
+                    let (y, x, z) = rotation.to_euler(EulerRot::YXZ);
+                    rigidbody_initial_transforms.push((position.into(), Vec3A::new(x, y, z)));
+                    ragdoll_bodies.push(true);
+                }
+            }
+        }
+
+        let kinematic_shared_physics_world_ids = reader.take_kinematic_shared_physics_world_ids();
+        // read joint header before modifying the world so that broken data does not leave orphan bodies
+        let mut reader = reader.next()?.unwrap();
+
         let mut rigidbody_bundle_proxy = Box::new(
             RigidBodyBundleProxy::new(&mut rb_info_list, rb_data_list.into_boxed_slice())
         );
+        for (index, &is_ragdoll_body) in ragdoll_bodies.iter().enumerate() {
+            if is_ragdoll_body {
+                rigidbody_bundle_proxy.inner_mut().set_kinematic_toggle(index, KinematicToggleState::Enabled);
+            }
+        }
 
         self.multi_physics_world.add_rigidbody_bundle(
             world_id,
             rigidbody_bundle_proxy.inner_mut().create_handle()
         );
 
-        let kinematic_shared_physics_world_ids = reader.take_kinematic_shared_physics_world_ids();
         for world_id in kinematic_shared_physics_world_ids.iter() {
             self.multi_physics_world.add_rigidbody_bundle_shadow(
                 *world_id,
@@ -517,10 +1030,12 @@ impl MmdPhysicsRuntime {
             );
         }
 
-        let mut reader = reader.next().unwrap();
-        
-        let mut constraints = Vec::with_capacity(reader.count() as usize);
-        reader.enumerate(|constraint_index, metadata| {
+        let ragdoll_body_count = ragdoll_bodies.iter().filter(|&&is_ragdoll_body| is_ragdoll_body).count();
+        let mut constraint_map = vec![-1; reader.count() as usize];
+        // constraint handles point into this vector, so the capacity must cover generated joints too
+        let mut constraints = Vec::with_capacity(reader.count() as usize + ragdoll_body_count);
+        let mut joined_bodies = Vec::with_capacity(reader.count() as usize);
+        let result = reader.enumerate(|constraint_index, metadata| {
             let rigidbody_index_a = metadata.rigidbody_index_a;
             let rigidbody_index_b = metadata.rigidbody_index_b;
 
@@ -556,29 +1071,8 @@ impl MmdPhysicsRuntime {
                 (metadata.position * scaling_factor).into()
             );
 
-            let rigidbody_a_inverse = {
-                let (position, rotation) = rigidbody_initial_transforms[rigidbody_index_a as usize];
-                let world_matrix = Mat4::from_rotation_translation(
-                    Quat::from_euler(
-                        EulerRot::YXZ,
-                        rotation.y, rotation.x, rotation.z
-                    ),
-                    (position * scaling_factor).into()
-                );
-                world_matrix.inverse()
-            };
-
-            let rigidbody_b_inverse = {
-                let (position, rotation) = rigidbody_initial_transforms[rigidbody_index_b as usize];
-                let world_matrix = Mat4::from_rotation_translation(
-                    Quat::from_euler(
-                        EulerRot::YXZ,
-                        rotation.y, rotation.x, rotation.z
-                    ),
-                    (position * scaling_factor).into()
-                );
-                world_matrix.inverse()
-            };
+            let rigidbody_a_inverse = Self::initial_body_matrix(&rigidbody_initial_transforms[rigidbody_index_a as usize], scaling_factor).inverse();
+            let rigidbody_b_inverse = Self::initial_body_matrix(&rigidbody_initial_transforms[rigidbody_index_b as usize], scaling_factor).inverse();
 
             let joint_final_transform_a = rigidbody_a_inverse * joint_transform;
             let joint_final_transform_b = rigidbody_b_inverse * joint_transform;
@@ -633,12 +1127,114 @@ impl MmdPhysicsRuntime {
                 constraint.enable_spring(5, true);
 
                 Constraint::Generic6DofSpring(constraint)
+            } else if metadata.kind == JointKind::SixDof as u8 {
+                let mut constraint = Generic6DofConstraint::from_bundle(
+                    rigidbody_bundle_proxy.inner_mut().create_handle(),
+                    rigidbody_index_a as u32,
+                    rigidbody_index_b as u32,
+                    &joint_final_transform_a,
+                    &joint_final_transform_b,
+                    true
+                );
+                if disable_offset_for_constraint_frame {
+                    constraint.use_frame_offset(false);
+                }
+
+                for axis in 0..6 {
+                    constraint.set_param(ConstraintParams::ConstraintStopERP, 0.475, axis);
+                }
+                constraint.set_linear_lower_limit(metadata.position_min.into());
+                constraint.set_linear_upper_limit(metadata.position_max.into());
+                constraint.set_angular_lower_limit(metadata.rotation_min.into());
+                constraint.set_angular_upper_limit(metadata.rotation_max.into());
+
+                Constraint::Generic6Dof(constraint)
+            } else if metadata.kind == JointKind::P2p as u8 {
+                let constraint = Point2PointConstraint::from_bundle(
+                    rigidbody_bundle_proxy.inner_mut().create_handle(),
+                    rigidbody_index_a as u32,
+                    rigidbody_index_b as u32,
+                    joint_final_transform_a.w_axis.truncate(),
+                    joint_final_transform_b.w_axis.truncate()
+                );
+
+                Constraint::Point2Point(constraint)
+            } else if metadata.kind == JointKind::ConeTwist as u8 {
+                let mut constraint = ConeTwistConstraint::from_bundle(
+                    rigidbody_bundle_proxy.inner_mut().create_handle(),
+                    rigidbody_index_a as u32,
+                    rigidbody_index_b as u32,
+                    &joint_final_transform_a,
+                    &joint_final_transform_b
+                );
+
+                // pmx 2.1 layout
+                // rotation min x, y, z: swing span 1, swing span 2, twist span
+                // position min x, y, z: softness, bias factor, relaxation factor
+                // position max x, y: damping, fix threshold
+                constraint.set_limit(
+                    metadata.rotation_min.x,
+                    metadata.rotation_min.y,
+                    metadata.rotation_min.z,
+                    metadata.position_min.x,
+                    metadata.position_min.y,
+                    metadata.position_min.z
+                );
+                constraint.set_damping(metadata.position_max.x);
+                constraint.set_fix_thresh(metadata.position_max.y);
+
+                Constraint::ConeTwist(constraint)
+            } else if metadata.kind == JointKind::Slider as u8 {
+                let mut constraint = SliderConstraint::from_bundle(
+                    rigidbody_bundle_proxy.inner_mut().create_handle(),
+                    rigidbody_index_a as u32,
+                    rigidbody_index_b as u32,
+                    &joint_final_transform_a,
+                    &joint_final_transform_b,
+                    true
+                );
+
+                // pmx 2.1 layout
+                // position min/max x: linear limit, rotation min/max x: angular limit
+                // spring position/rotation: motor enabled (x != 0), target velocity (y), max force (z)
+                constraint.set_linear_limit(metadata.position_min.x, metadata.position_max.x);
+                constraint.set_angular_limit(metadata.rotation_min.x, metadata.rotation_max.x);
+                constraint.set_linear_motor(metadata.spring_position.x != 0.0, metadata.spring_position.y, metadata.spring_position.z);
+                constraint.set_angular_motor(metadata.spring_rotation.x != 0.0, metadata.spring_rotation.y, metadata.spring_rotation.z);
+
+                Constraint::Slider(constraint)
+            } else if metadata.kind == JointKind::Hinge as u8 {
+                let mut constraint = HingeConstraint::from_bundle(
+                    rigidbody_bundle_proxy.inner_mut().create_handle(),
+                    rigidbody_index_a as u32,
+                    rigidbody_index_b as u32,
+                    &joint_final_transform_a,
+                    &joint_final_transform_b,
+                    true
+                );
+
+                // pmx 2.1 layout
+                // rotation min/max x: limit
+                // position min x, y, z: softness, bias factor, relaxation factor
+                // spring rotation: motor enabled (x != 0), target velocity (y), max motor impulse (z)
+                constraint.set_limit(
+                    metadata.rotation_min.x,
+                    metadata.rotation_max.x,
+                    metadata.position_min.x,
+                    metadata.position_min.y,
+                    metadata.position_min.z
+                );
+                constraint.enable_angular_motor(metadata.spring_rotation.x != 0.0, metadata.spring_rotation.y, metadata.spring_rotation.z);
+
+                Constraint::Hinge(constraint)
             } else {
                 diagnostic.warning(format!("Unsupported joint kind {} for joint {}", metadata.kind, constraint_index));
                 return;
             };
 
+            constraint_map[constraint_index as usize] = constraints.len() as i32;
             constraints.push(constraint);
+            joined_bodies.push((rigidbody_index_a as u32, rigidbody_index_b as u32));
             self.multi_physics_world.add_constraint(
                 world_id,
                 constraints.last_mut().unwrap().create_handle(),
@@ -675,15 +1271,92 @@ impl MmdPhysicsRuntime {
             }
         });
 
-        PhysicsModelContext::new(
+        // joints that hold ragdoll bodies to their parent bodies, enabled only in ragdoll mode
+        let generated_constraint_start = constraints.len();
+        if let Some(ragdoll) = &ragdoll_metadata {
+            for index in 0..ragdoll_bodies.len() as u32 {
+                if !ragdoll_bodies[index as usize] {
+                    continue;
+                }
+                let bone_index = if let Some(bone_index) = rigidbody_bundle_proxy.linked_bone_index(index as usize) {
+                    bone_index
+                } else {
+                    continue;
+                };
+                let parent_index = if let Some(parent_index) = Self::find_ragdoll_parent_body(bones, &bone_ragdoll_bodies, bone_index) {
+                    parent_index
+                } else { // root of the ragdoll
+                    continue;
+                };
+                if joined_bodies.iter().any(|&(a, b)| (a == index && b == parent_index) || (a == parent_index && b == index)) {
+                    continue; // pmx joint already holds the bodies
+                }
+
+                let joint_position = bones[bone_index as usize].absolute_inverse_bind_matrix().inverse().w_axis.truncate();
+                let joint_transform = Mat4::from_translation(joint_position * scaling_factor);
+                let frame_a = Self::initial_body_matrix(&rigidbody_initial_transforms[parent_index as usize], scaling_factor).inverse() * joint_transform;
+                let frame_b = Self::initial_body_matrix(&rigidbody_initial_transforms[index as usize], scaling_factor).inverse() * joint_transform;
+
+                let mut constraint = Generic6DofConstraint::from_bundle(
+                    rigidbody_bundle_proxy.inner_mut().create_handle(),
+                    parent_index,
+                    index,
+                    &frame_a,
+                    &frame_b,
+                    true
+                );
+                if disable_offset_for_constraint_frame {
+                    constraint.use_frame_offset(false);
+                }
+
+                for axis in 0..6 {
+                    constraint.set_param(ConstraintParams::ConstraintStopERP, 0.475, axis);
+                }
+                constraint.set_linear_lower_limit(Vec3::ZERO);
+                constraint.set_linear_upper_limit(Vec3::ZERO);
+                constraint.set_angular_lower_limit(ragdoll.angular_lower_limit.into());
+                constraint.set_angular_upper_limit(ragdoll.angular_upper_limit.into());
+
+                let mut constraint = Constraint::Generic6Dof(constraint);
+                constraint.set_enabled(false);
+                constraints.push(constraint);
+                joined_bodies.push((parent_index, index));
+                // capsules of adjacent bones overlap around the joint
+                self.multi_physics_world.add_constraint(
+                    world_id,
+                    constraints.last_mut().unwrap().create_handle(),
+                    true
+                );
+            }
+        }
+
+        let ragdoll = ragdoll_metadata.map(|_| Ragdoll::new(
+            ragdoll_bodies.into_boxed_slice(),
+            generated_body_start,
+            generated_constraint_start,
+            Self::collect_ragdoll_follower_bones(bones, &bone_ragdoll_bodies),
+        ));
+
+        let context = PhysicsModelContext::new(
             rigidbody_map.into_boxed_slice(),
+            constraint_map.into_boxed_slice(),
             constraints.into_boxed_slice(),
             rigidbody_bundle_proxy,
             shapes.into_boxed_slice(),
             world_id,
             kinematic_shared_physics_world_ids,
             world_matrix,
-        )
+            ccd_sizes.into_boxed_slice(),
+            joined_bodies.into_boxed_slice(),
+            ragdoll,
+        );
+
+        if let Err(err) = result {
+            self.destroy_physics_context(context);
+            return Err(err);
+        }
+
+        Ok(context)
     }
 
     pub(crate) fn destroy_physics_context(&mut self, mut context: PhysicsModelContext) {
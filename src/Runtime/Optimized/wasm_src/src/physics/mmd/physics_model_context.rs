@@ -1,11 +1,12 @@
-use glam::Mat4;
+use glam::{Mat4, Vec3};
 
 use crate::mmd_model_metadata::RigidBodyPhysicsMode;
 use crate::physics::bullet::runtime::collision_shape::CollisionShape;
-use crate::physics::bullet::runtime::constraint::Constraint;
+use crate::physics::bullet::runtime::constraint::{Constraint, ConstraintParams};
 use crate::physics::bullet::runtime::kinematic_state::KinematicToggleState;
 use crate::physics::bullet::runtime::multi_physics_world::PhysicsWorldId;
 use crate::physics::bullet::runtime::rigidbody_bundle::RigidBodyBundle;
+use crate::unchecked_slice::{UncheckedSlice, UncheckedSliceMut};
 
 use super::rigidbody_bundle_proxy::RigidBodyBundleProxy;
 
@@ -16,6 +17,7 @@ unsafe impl Send for Constraint {}
 
 pub(crate) struct PhysicsModelContext {
     rigidbody_index_map: Box<[i32]>,
+    constraint_index_map: Box<[i32]>,
     constraints: Box<[Constraint]>,
     bundle_proxy: Box<RigidBodyBundleProxy>,
     #[allow(dead_code)]
@@ -37,17 +39,181 @@ pub(crate) struct PhysicsModelContext {
 
     // 0: unknown, 1: kinematic, 2: target transform
     body_kinematic_toggle_map: Option<Box<[u8]>>,
+
+    // for thread safety, we need buffer to apply sleeping options
+    sleeping_apply_buffer: Option<SleepingOptions>,
+    sleeping_options: SleepingOptions,
+    // model space matrix of each sleeping body
+    sleeping_body_matrices: Box<[Option<Mat4>]>,
+    // bundle indices of the bodies held by each constraint
+    joined_bodies: Box<[(u32, u32)]>,
+    // bodies moved by bones in this step, only tracked while sleeping is enabled
+    moved_bodies: Box<[bool]>,
+
+    // for thread safety, we need buffer to apply blend options
+    blend_apply_buffer: Option<PhysicsBlendOptions>,
+    blend_options: PhysicsBlendOptions,
+    // 0: follow bone, 1: full physics
+    blend_weights: Box<[f32]>,
+    // number of bodies that are not fully driven by physics
+    blending_rigidbody_count: usize,
+
+    // for thread safety, we need buffer to apply rigid body edits
+    rigidbody_edit_apply_buffer: Vec<(u32, RigidBodyEdit)>,
+
+    // for thread safety, we need buffer to apply joint edits
+    joint_edit_apply_buffer: Vec<(usize, JointEdit)>,
+
+    // for thread safety, we need buffer to apply ccd options
+    ccd_apply_buffer: Option<CcdOptions>,
+    // inner radius of each body that ccd parameters are relative to, zero for static bodies
+    ccd_sizes: Box<[f32]>,
+
+    // for thread safety, we need buffer to apply interpolation mode
+    interpolation_apply_buffer: Option<bool>,
+    interpolation_enabled: bool,
+    // world transforms of each simulated body at the previous and the last fixed step
+    step_transforms: Box<[Option<(Mat4, Mat4)>]>,
+
+    ragdoll: Option<Ragdoll>,
+}
+
+/// Bodies and joints that take part in ragdoll mode, only exists if the metadata has ragdoll data
+pub(super) struct Ragdoll {
+    // indexed by bundle index, true for bodies created as follow bone and generated bodies
+    bodies: Box<[bool]>,
+    // generated bodies and joints are placed after the pmx ones
+    generated_body_start: usize,
+    generated_constraint_start: usize,
+    // (bone, parent bone) of bones without body under ragdoll bones, parent first order
+    follower_bones: Box<[(u32, u32)]>,
+    follower_local_matrices: Box<[Mat4]>,
+
+    // for thread safety, we need buffer to apply ragdoll state
+    apply_buffer: Option<bool>,
+    enabled: bool,
+    // ragdoll bodies are blending back to animation
+    returning: bool,
+}
+
+impl Ragdoll {
+    pub(super) fn new(
+        bodies: Box<[bool]>,
+        generated_body_start: usize,
+        generated_constraint_start: usize,
+        follower_bones: Box<[(u32, u32)]>,
+    ) -> Self {
+        let follower_local_matrices = vec![Mat4::IDENTITY; follower_bones.len()].into_boxed_slice();
+        Self {
+            bodies,
+            generated_body_start,
+            generated_constraint_start,
+            follower_bones,
+            follower_local_matrices,
+
+            apply_buffer: None,
+            enabled: false,
+            returning: false,
+        }
+    }
+
+    /// Keeps animated transforms of follower bones relative to their parents before physics overwrites the parents
+    fn store_follower_local_matrices(&mut self, world_matrices: &UncheckedSlice<'_, Mat4>) {
+        for (i, &(bone, parent)) in self.follower_bones.iter().enumerate() {
+            self.follower_local_matrices[i] = world_matrices[parent].inverse() * world_matrices[bone];
+        }
+    }
+
+    fn restore_follower_world_matrices(&self, world_matrices: &mut UncheckedSliceMut<'_, Mat4>) {
+        for (i, &(bone, parent)) in self.follower_bones.iter().enumerate() {
+            world_matrices[bone] = world_matrices[parent] * self.follower_local_matrices[i];
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct SleepingOptions {
+    pub(crate) enabled: bool,
+    pub(crate) linear_threshold: f32,
+    pub(crate) angular_threshold: f32,
+}
+
+fn wake_up_dynamic_body(bundle_proxy: &mut RigidBodyBundleProxy, index: usize) {
+    let physics_mode = bundle_proxy.get_physics_mode(index);
+    if physics_mode == RigidBodyPhysicsMode::Physics || physics_mode == RigidBodyPhysicsMode::PhysicsWithBone {
+        bundle_proxy.inner_mut().wake_up(index);
+    }
+}
+
+/// Pose between two fixed steps, weight is clamped so the body never passes the last step
+fn interpolate_step_transform(previous: &Mat4, last: &Mat4, weight: f32) -> Mat4 {
+    super::blend_matrix(previous, last, weight.clamp(0.0, 1.0))
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct CcdOptions {
+    /// Relative to body size, zero disables ccd
+    pub(crate) motion_threshold_scale: f32,
+    pub(crate) swept_sphere_radius_scale: f32,
+}
+
+impl CcdOptions {
+    pub(crate) const DISABLED: Self = Self {
+        motion_threshold_scale: 0.0,
+        swept_sphere_radius_scale: 0.0,
+    };
+
+    /// Motion threshold and swept sphere radius of the body
+    pub(super) fn body_parameters(&self, ccd_size: f32) -> (f32, f32) {
+        (ccd_size * self.motion_threshold_scale, ccd_size * self.swept_sphere_radius_scale)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct PhysicsBlendOptions {
+    /// Seconds to blend between animation and physics, zero switches instantly
+    pub(crate) transition_time: f32,
+    pub(crate) force_gain: f32,
+    pub(crate) torque_gain: f32,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum RigidBodyEdit {
+    Mass(f32),
+    Damping(f32, f32),
+    Friction(f32),
+    Restitution(f32),
+    CollisionFilter(u16, u16),
+    PhysicsMode(RigidBodyPhysicsMode),
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum JointEdit {
+    LinearLimit(Vec3, Vec3),
+    AngularLimit(Vec3, Vec3),
+    LinearStiffness(Vec3),
+    AngularStiffness(Vec3),
+    LinearDamping(Vec3),
+    AngularDamping(Vec3),
+    StopErp(f32),
+    StopCfm(f32),
+    Enabled(bool),
 }
 
 impl PhysicsModelContext {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         rigidbody_index_map: Box<[i32]>,
+        constraint_index_map: Box<[i32]>,
         constraints: Box<[Constraint]>,
         bundle_proxy: Box<RigidBodyBundleProxy>,
         shapes: Box<[CollisionShape]>,
         world_id: PhysicsWorldId,
         shared_world_ids: Vec<PhysicsWorldId>,
         world_matrix: Mat4,
+        ccd_sizes: Box<[f32]>,
+        joined_bodies: Box<[(u32, u32)]>,
+        ragdoll: Option<Ragdoll>,
     ) -> Self {
         let invertable = world_matrix.determinant() != 0.0;
 
@@ -58,9 +224,11 @@ impl PhysicsModelContext {
         };
 
         let unmapped_rigidbody_count = rigidbody_index_map.len();
+        let body_count = bundle_proxy.len();
         
         Self {
             rigidbody_index_map,
+            constraint_index_map,
             shapes,
             bundle_proxy,
             constraints,
@@ -78,6 +246,38 @@ impl PhysicsModelContext {
             disabled_rigidbody_count: 0,
 
             body_kinematic_toggle_map: None,
+
+            sleeping_apply_buffer: None,
+            sleeping_options: SleepingOptions {
+                enabled: false,
+                linear_threshold: 0.0,
+                angular_threshold: 0.0,
+            },
+            sleeping_body_matrices: vec![None; body_count].into_boxed_slice(),
+            joined_bodies,
+            moved_bodies: vec![false; body_count].into_boxed_slice(),
+
+            blend_apply_buffer: None,
+            blend_options: PhysicsBlendOptions {
+                transition_time: 0.0,
+                force_gain: 30.0,
+                torque_gain: 30.0,
+            },
+            blend_weights: vec![1.0; body_count].into_boxed_slice(),
+            blending_rigidbody_count: 0,
+
+            rigidbody_edit_apply_buffer: Vec::new(),
+
+            joint_edit_apply_buffer: Vec::new(),
+
+            ccd_apply_buffer: None,
+            ccd_sizes,
+
+            interpolation_apply_buffer: None,
+            interpolation_enabled: false,
+            step_transforms: vec![None; body_count].into_boxed_slice(),
+
+            ragdoll,
         }
     }
 
@@ -109,6 +309,19 @@ impl PhysicsModelContext {
         self.world_matrix_apply_buffer = Some(world_matrix);
     }
 
+    /// Applies every parameter buffered from js since the last update
+    pub(crate) fn apply_buffers(&mut self) {
+        self.apply_world_matrix();
+        self.apply_need_init();
+        self.apply_sleeping();
+        self.apply_ccd();
+        self.apply_interpolation();
+        self.apply_blend();
+        self.apply_ragdoll();
+        self.apply_rigidbody_edits();
+        self.apply_joint_edits();
+    }
+
     pub (crate) fn apply_world_matrix(&mut self) {
         if let Some(world_matrix) = self.world_matrix_apply_buffer {
             let invertable = world_matrix.determinant() != 0.0;
@@ -120,6 +333,8 @@ impl PhysicsModelContext {
                 self.world_matrix_inverse = Mat4::IDENTITY;
             }
             self.world_matrix_apply_buffer = None;
+            // cached model space matrices are relative to the previous world matrix
+            self.sleeping_body_matrices.fill(None);
         }
     }
 
@@ -145,15 +360,640 @@ impl PhysicsModelContext {
     pub(super) fn flush_need_init(&mut self) -> bool {
         let need_init = self.need_init;
         self.need_init = false;
+        if need_init {
+            // bodies are moved to the bones, do not interpolate from the old pose
+            self.step_transforms.fill(None);
+        }
         need_init
     }
 
+    /// Deactivation is disabled by default, so every dynamic body is simulated on every step
+    pub(crate) fn set_sleeping(&mut self, options: SleepingOptions) {
+        self.sleeping_apply_buffer = Some(options);
+    }
+
+    pub(crate) fn apply_sleeping(&mut self) {
+        if let Some(options) = self.sleeping_apply_buffer.take() {
+            for index in 0..self.bundle_proxy.len() {
+                let physics_mode = self.bundle_proxy.get_physics_mode(index);
+                if physics_mode == RigidBodyPhysicsMode::Physics || physics_mode == RigidBodyPhysicsMode::PhysicsWithBone {
+                    self.bundle_proxy.inner_mut().set_sleeping(index, options.enabled, options.linear_threshold, options.angular_threshold);
+                }
+            }
+            self.sleeping_options = options;
+            self.sleeping_body_matrices.fill(None);
+        }
+    }
+
+    /// Parameters of each body are scaled by its inner radius, so thin bodies get small swept spheres
+    pub(crate) fn set_ccd(&mut self, options: CcdOptions) {
+        self.ccd_apply_buffer = Some(options);
+    }
+
+    pub(crate) fn apply_ccd(&mut self) {
+        if let Some(options) = self.ccd_apply_buffer.take() {
+            for (index, &ccd_size) in self.ccd_sizes.iter().enumerate() {
+                if 0.0 < ccd_size {
+                    let (motion_threshold, swept_sphere_radius) = options.body_parameters(ccd_size);
+                    self.bundle_proxy.inner_mut().set_ccd(index, motion_threshold, swept_sphere_radius);
+                }
+            }
+        }
+    }
+
+    pub(super) fn sleeping_enabled(&self) -> bool {
+        self.sleeping_options.enabled
+    }
+
+    /// Wakes every dynamic body, used when the whole model is teleported
+    pub(super) fn wake_up_dynamic_bodies(&mut self) {
+        for index in 0..self.bundle_proxy.len() {
+            wake_up_dynamic_body(&mut self.bundle_proxy, index);
+        }
+        self.moved_bodies.fill(false);
+    }
+
+    /// Moves a bone driven body, bodies that actually moved are remembered for `wake_up_moved_islands`
+    pub(super) fn set_body_transform(&mut self, index: usize, transform: Mat4) {
+        if self.sleeping_options.enabled {
+            if self.bundle_proxy.set_transform_check_moved(index, transform) {
+                self.moved_bodies[index] = true;
+            }
+        } else {
+            self.bundle_proxy.set_transform(index, transform);
+        }
+    }
+
+    /// Bodies driven by velocity do not wake up by themselves
+    pub(super) fn wake_up_body(&mut self, index: usize) {
+        if self.sleeping_options.enabled {
+            wake_up_dynamic_body(&mut self.bundle_proxy, index);
+        }
+    }
+
+    /// Islands do not merge through kinematic bodies, so bullet never wakes the bodies joined to a moving bone
+    ///
+    /// Only the dynamic bodies joined to moved bodies are woken, bullet wakes the rest of their islands
+    pub(super) fn wake_up_moved_islands(&mut self) {
+        if !self.moved_bodies.contains(&true) {
+            return;
+        }
+
+        for &(a, b) in self.joined_bodies.iter() {
+            let (a, b) = (a as usize, b as usize);
+            if self.moved_bodies[a] {
+                wake_up_dynamic_body(&mut self.bundle_proxy, b);
+            }
+            if self.moved_bodies[b] {
+                wake_up_dynamic_body(&mut self.bundle_proxy, a);
+            }
+        }
+
+        // bodies toggled to kinematic are still dynamic bodies, bullet does not read their motion state while sleeping
+        for index in 0..self.moved_bodies.len() {
+            if self.moved_bodies[index] {
+                wake_up_dynamic_body(&mut self.bundle_proxy, index);
+            }
+        }
+        self.moved_bodies.fill(false);
+    }
+
+    pub(crate) fn set_interpolation(&mut self, enabled: bool) {
+        self.interpolation_apply_buffer = Some(enabled);
+    }
+
+    pub(crate) fn apply_interpolation(&mut self) {
+        if let Some(enabled) = self.interpolation_apply_buffer.take() {
+            self.interpolation_enabled = enabled;
+            self.step_transforms.fill(None);
+        }
+    }
+
+    /// In interpolation mode simulated bodies are shown between the previous and the last fixed step by `weight`
+    ///
+    /// Bullet writes the motion states of awake dynamic bodies on every step, so this must be done after each step.
+    /// If several fixed steps are simulated at once, the last step of the previous update is used as the previous step
+    pub(super) fn interpolate_motion_states(&mut self, stepped: bool, weight: f32) {
+        if !self.interpolation_enabled {
+            return;
+        }
+
+        for index in 0..self.bundle_proxy.len() {
+            // kinematic bodies are driven by the motion state
+            if self.bundle_proxy.inner().is_static_or_kinematic(index) {
+                self.step_transforms[index] = None;
+                continue;
+            }
+            // sleeping bodies do not move, show where they stopped
+            if self.bundle_proxy.is_sleeping(index) {
+                if self.step_transforms[index].take().is_some() {
+                    let transform = self.bundle_proxy.get_step_transform(index);
+                    self.bundle_proxy.set_buffered_transform(index, &transform);
+                }
+                continue;
+            }
+
+            let (previous, last) = match self.step_transforms[index] {
+                Some(transforms) if !stepped => transforms,
+                Some((_, last)) => (last, self.bundle_proxy.get_step_transform(index)),
+                None => {
+                    let last = self.bundle_proxy.get_step_transform(index);
+                    (last, last)
+                }
+            };
+            self.step_transforms[index] = Some((previous, last));
+            self.bundle_proxy.set_buffered_transform(index, &interpolate_step_transform(&previous, &last, weight));
+        }
+    }
+
+    /// Body transform in model space
+    pub(super) fn body_model_matrix(&self, index: usize, world_matrix_inverse: &Mat4) -> Mat4 {
+        *world_matrix_inverse * self.bundle_proxy.get_transform(index)
+    }
+
+    /// Model space transform of a sleeping body, cached on the first read after it falls asleep
+    ///
+    /// Returns None for awake bodies, sleeping bodies do not move so they are not read again until they wake up
+    pub(super) fn sleeping_body_model_matrix(&mut self, index: usize, world_matrix_inverse: &Mat4) -> Option<Mat4> {
+        if !self.sleeping_options.enabled {
+            return None;
+        }
+
+        if !self.bundle_proxy.is_sleeping(index) {
+            self.sleeping_body_matrices[index] = None;
+            return None;
+        }
+
+        if let Some(matrix) = self.sleeping_body_matrices[index] {
+            return Some(matrix);
+        }
+        let matrix = self.body_model_matrix(index, world_matrix_inverse);
+        self.sleeping_body_matrices[index] = Some(matrix);
+        Some(matrix)
+    }
+
+    pub(crate) fn set_blend(&mut self, options: PhysicsBlendOptions) {
+        self.blend_apply_buffer = Some(options);
+    }
+
+    pub(crate) fn apply_blend(&mut self) {
+        if let Some(options) = self.blend_apply_buffer.take() {
+            self.blend_options = options;
+        }
+    }
+
+    pub(super) fn blend_options(&self) -> &PhysicsBlendOptions {
+        &self.blend_options
+    }
+
+    pub(super) fn blend_weight(&self, index: usize) -> f32 {
+        self.blend_weights[index]
+    }
+
+    /// Moves blend weights of dynamic bodies toward their physics toggle state
+    ///
+    /// Weights snap to the toggle state if `snap` is true or the transition time is zero
+    pub(super) fn update_blend_weights(&mut self, time_step: f32, snap: bool) {
+        let ragdoll_returning = self.ragdoll.as_ref().is_some_and(|ragdoll| ragdoll.returning);
+        if self.disabled_rigidbody_count == 0 && self.blending_rigidbody_count == 0 && !ragdoll_returning {
+            return;
+        }
+
+        let transition_time = self.blend_options.transition_time;
+        let step = if !snap && 0.0 < transition_time {
+            time_step / transition_time
+        } else {
+            1.0
+        };
+
+        let mut blending_rigidbody_count = 0;
+        for i in 0..self.rigidbody_index_map.len() {
+            let index = self.rigidbody_index_map[i];
+            if index == -1 {
+                continue;
+            }
+
+            let physics_mode = self.bundle_proxy.get_physics_mode(index as usize);
+            if physics_mode != RigidBodyPhysicsMode::Physics && physics_mode != RigidBodyPhysicsMode::PhysicsWithBone {
+                continue;
+            }
+
+            // returning ragdoll bodies go back to animation regardless of the physics toggle
+            let target = if ragdoll_returning && self.is_ragdoll_body(index as usize) {
+                0.0
+            } else if self.synced_rigidbody_states[i] != 0 {
+                1.0
+            } else {
+                0.0
+            };
+            if Self::step_blend_weight(&mut self.blend_weights[index as usize], target, step) {
+                blending_rigidbody_count += 1;
+            }
+        }
+
+        if ragdoll_returning {
+            for index in self.generated_body_range() {
+                if Self::step_blend_weight(&mut self.blend_weights[index], 0.0, step) {
+                    blending_rigidbody_count += 1;
+                }
+            }
+        }
+        self.blending_rigidbody_count = blending_rigidbody_count;
+
+        if ragdoll_returning && self.ragdoll_returned() {
+            self.finish_ragdoll_return();
+        }
+    }
+
+    /// Returns true if the weight is not fully driven by physics after the step
+    fn step_blend_weight(weight: &mut f32, target: f32, step: f32) -> bool {
+        *weight = if *weight < target {
+            (*weight + step).min(target)
+        } else {
+            (*weight - step).max(target)
+        };
+        *weight < 1.0
+    }
+
+    /// Bundle index of the rigid body, `None` if the body is failed to create
+    fn mapped_rigidbody_index(&self, rigidbody_index: u32) -> Option<usize> {
+        match self.rigidbody_index_map.get(rigidbody_index as usize) {
+            Some(&index) if index != -1 => Some(index as usize),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn rigidbody_mass(&self, rigidbody_index: u32) -> Option<f32> {
+        self.mapped_rigidbody_index(rigidbody_index)
+            .map(|index| self.bundle_proxy.inner().get_mass(index))
+    }
+
+    pub(crate) fn rigidbody_damping(&self, rigidbody_index: u32) -> Option<(f32, f32)> {
+        self.mapped_rigidbody_index(rigidbody_index).map(|index| {
+            let bundle = self.bundle_proxy.inner();
+            (bundle.get_linear_damping(index), bundle.get_angular_damping(index))
+        })
+    }
+
+    pub(crate) fn rigidbody_friction(&self, rigidbody_index: u32) -> Option<f32> {
+        self.mapped_rigidbody_index(rigidbody_index)
+            .map(|index| self.bundle_proxy.inner().get_friction(index))
+    }
+
+    pub(crate) fn rigidbody_restitution(&self, rigidbody_index: u32) -> Option<f32> {
+        self.mapped_rigidbody_index(rigidbody_index)
+            .map(|index| self.bundle_proxy.inner().get_restitution(index))
+    }
+
+    pub(crate) fn rigidbody_collision_filter(&self, rigidbody_index: u32) -> Option<(u16, u16)> {
+        self.mapped_rigidbody_index(rigidbody_index).map(|index| {
+            let bundle = self.bundle_proxy.inner();
+            (bundle.get_collision_group(index), bundle.get_collision_mask(index))
+        })
+    }
+
+    pub(crate) fn rigidbody_physics_mode(&self, rigidbody_index: u32) -> Option<RigidBodyPhysicsMode> {
+        self.mapped_rigidbody_index(rigidbody_index)
+            .map(|index| self.bundle_proxy.get_physics_mode(index))
+    }
+
+    /// Returns false if the body does not exist or the edit is not applicable to the body
+    ///
+    /// Mass and physics mode are only editable for bodies created as dynamic,
+    /// and dynamic bodies can not become static
+    pub(crate) fn edit_rigidbody(&mut self, rigidbody_index: u32, edit: RigidBodyEdit) -> bool {
+        let index = if let Some(index) = self.mapped_rigidbody_index(rigidbody_index) {
+            index
+        } else {
+            return false;
+        };
+
+        let dynamic = !self.bundle_proxy.inner().is_static_or_kinematic(index);
+        let applicable = match edit {
+            RigidBodyEdit::Mass(mass) => dynamic && 0.0 < mass,
+            RigidBodyEdit::PhysicsMode(physics_mode) => if dynamic {
+                physics_mode != RigidBodyPhysicsMode::Static
+            } else {
+                physics_mode == self.bundle_proxy.get_physics_mode(index)
+            },
+            _ => true,
+        };
+        if applicable {
+            self.rigidbody_edit_apply_buffer.push((rigidbody_index, edit));
+        }
+        applicable
+    }
+
+    pub(crate) fn apply_rigidbody_edits(&mut self) {
+        if self.rigidbody_edit_apply_buffer.is_empty() {
+            return;
+        }
+
+        let mut edits = std::mem::take(&mut self.rigidbody_edit_apply_buffer);
+        for &(rigidbody_index, edit) in &edits {
+            let index = self.rigidbody_index_map[rigidbody_index as usize] as usize;
+            let bundle = self.bundle_proxy.inner_mut();
+            match edit {
+                RigidBodyEdit::Mass(mass) => bundle.set_mass_with_inertia(index, mass),
+                RigidBodyEdit::Damping(linear_damping, angular_damping) => bundle.set_damping(index, linear_damping, angular_damping),
+                RigidBodyEdit::Friction(friction) => bundle.set_friction(index, friction),
+                RigidBodyEdit::Restitution(restitution) => bundle.set_restitution(index, restitution),
+                RigidBodyEdit::CollisionFilter(group, mask) => bundle.set_collision_filter(index, group, mask),
+                RigidBodyEdit::PhysicsMode(physics_mode) => self.apply_rigidbody_physics_mode(Some(rigidbody_index as usize), index, physics_mode),
+            }
+
+            // sleeping bodies do not react to the new parameters until something wakes them up
+            let physics_mode = self.bundle_proxy.get_physics_mode(index);
+            if self.sleeping_options.enabled && (physics_mode == RigidBodyPhysicsMode::Physics || physics_mode == RigidBodyPhysicsMode::PhysicsWithBone) {
+                self.bundle_proxy.inner_mut().wake_up(index);
+            }
+        }
+        edits.clear();
+        self.rigidbody_edit_apply_buffer = edits;
+    }
+
+    /// `rigidbody_index` is `None` for generated bodies that have no physics toggle
+    fn apply_rigidbody_physics_mode(&mut self, rigidbody_index: Option<usize>, index: usize, physics_mode: RigidBodyPhysicsMode) {
+        let current_physics_mode = self.bundle_proxy.get_physics_mode(index);
+        if current_physics_mode == physics_mode {
+            return;
+        }
+
+        let options = self.sleeping_options;
+        if physics_mode == RigidBodyPhysicsMode::FollowBone {
+            // bone driven bodies are excluded from physics toggle
+            if let Some(rigidbody_index) = rigidbody_index
+                && self.synced_rigidbody_states[rigidbody_index] == 0
+            {
+                self.synced_rigidbody_states[rigidbody_index] = 1;
+                self.disabled_rigidbody_count -= 1;
+            }
+            let bundle = self.bundle_proxy.inner_mut();
+            if options.enabled {
+                bundle.set_sleeping(index, false, options.linear_threshold, options.angular_threshold);
+            }
+            bundle.set_kinematic_toggle(index, KinematicToggleState::Enabled);
+            self.sleeping_body_matrices[index] = None;
+            if self.blend_weights[index] < 1.0 {
+                self.blend_weights[index] = 1.0;
+                self.blending_rigidbody_count -= 1;
+            }
+        } else if current_physics_mode == RigidBodyPhysicsMode::FollowBone {
+            let bundle = self.bundle_proxy.inner_mut();
+            bundle.set_kinematic_toggle(index, KinematicToggleState::Disabled);
+            if options.enabled {
+                bundle.set_sleeping(index, true, options.linear_threshold, options.angular_threshold);
+            }
+        }
+        self.bundle_proxy.set_physics_mode(index, physics_mode);
+    }
+
+    pub(crate) fn ragdoll_enabled(&self) -> Option<bool> {
+        self.ragdoll.as_ref().map(|ragdoll| ragdoll.enabled)
+    }
+
+    /// Ragdoll is active while it is enabled or its bodies are blending back to animation
+    pub(super) fn ragdoll_active(&self) -> bool {
+        self.ragdoll.as_ref().is_some_and(|ragdoll| ragdoll.enabled || ragdoll.returning)
+    }
+
+    pub(crate) fn store_ragdoll_follower_bones(&mut self, world_matrices: &UncheckedSlice<'_, Mat4>) {
+        if let Some(ragdoll) = self.ragdoll.as_mut() {
+            ragdoll.store_follower_local_matrices(world_matrices);
+        }
+    }
+
+    /// Bones without body under ragdoll bones move with their parents instead of staying at the animated pose
+    pub(crate) fn restore_ragdoll_follower_bones(&self, world_matrices: &mut UncheckedSliceMut<'_, Mat4>) {
+        if let Some(ragdoll) = self.ragdoll.as_ref() {
+            ragdoll.restore_follower_world_matrices(world_matrices);
+        }
+    }
+
+    fn is_ragdoll_body(&self, index: usize) -> bool {
+        self.ragdoll.as_ref().is_some_and(|ragdoll| ragdoll.bodies[index])
+    }
+
+    /// Bundle indices of the bodies generated for ragdoll, empty if the model has no ragdoll data
+    pub(super) fn generated_body_range(&self) -> std::ops::Range<usize> {
+        match &self.ragdoll {
+            Some(ragdoll) => ragdoll.generated_body_start..self.bundle_proxy.len(),
+            None => 0..0,
+        }
+    }
+
+    /// Returns false if the model has no ragdoll data
+    pub(crate) fn set_ragdoll(&mut self, enabled: bool) -> bool {
+        if let Some(ragdoll) = self.ragdoll.as_mut() {
+            ragdoll.apply_buffer = Some(enabled);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Entering ragdoll is instant, leaving it blends back to animation over the blend transition time
+    pub(crate) fn apply_ragdoll(&mut self) {
+        let ragdoll = if let Some(ragdoll) = self.ragdoll.as_mut() {
+            ragdoll
+        } else {
+            return;
+        };
+        let enabled = if let Some(enabled) = ragdoll.apply_buffer.take() {
+            enabled
+        } else {
+            return;
+        };
+
+        if enabled {
+            if ragdoll.enabled && !ragdoll.returning {
+                return;
+            }
+            ragdoll.enabled = true;
+            ragdoll.returning = false;
+            self.set_ragdoll_bodies_physics_mode(RigidBodyPhysicsMode::Physics);
+            for index in 0..self.bundle_proxy.len() {
+                if self.is_ragdoll_body(index) && self.blend_weights[index] < 1.0 {
+                    self.blend_weights[index] = 1.0;
+                }
+            }
+            self.set_generated_constraints_enabled(true);
+        } else {
+            if !ragdoll.enabled {
+                return;
+            }
+            ragdoll.enabled = false;
+            if 0.0 < self.blend_options.transition_time {
+                ragdoll.returning = true;
+            } else {
+                self.finish_ragdoll_return();
+            }
+        }
+    }
+
+    fn ragdoll_returned(&self) -> bool {
+        (0..self.bundle_proxy.len()).all(|index| {
+            !self.is_ragdoll_body(index) ||
+                self.bundle_proxy.get_physics_mode(index) == RigidBodyPhysicsMode::FollowBone ||
+                self.blend_weights[index] == 0.0
+        })
+    }
+
+    fn finish_ragdoll_return(&mut self) {
+        self.set_ragdoll_bodies_physics_mode(RigidBodyPhysicsMode::FollowBone);
+        self.set_generated_constraints_enabled(false);
+        if let Some(ragdoll) = self.ragdoll.as_mut() {
+            ragdoll.returning = false;
+        }
+    }
+
+    fn set_ragdoll_bodies_physics_mode(&mut self, physics_mode: RigidBodyPhysicsMode) {
+        for rigidbody_index in 0..self.rigidbody_index_map.len() {
+            let index = self.rigidbody_index_map[rigidbody_index];
+            if index != -1 && self.is_ragdoll_body(index as usize) {
+                self.apply_rigidbody_physics_mode(Some(rigidbody_index), index as usize, physics_mode);
+            }
+        }
+        for index in self.generated_body_range() {
+            self.apply_rigidbody_physics_mode(None, index, physics_mode);
+        }
+    }
+
+    fn set_generated_constraints_enabled(&mut self, enabled: bool) {
+        let start = self.ragdoll.as_ref().map_or(self.constraints.len(), |ragdoll| ragdoll.generated_constraint_start);
+        for constraint in &mut self.constraints[start..] {
+            constraint.set_enabled(enabled);
+        }
+    }
+
+    /// Constraint index of the joint, `None` if the joint is failed to create
+    fn mapped_joint_index(&self, joint_index: u32) -> Option<usize> {
+        match self.constraint_index_map.get(joint_index as usize) {
+            Some(&index) if index != -1 => Some(index as usize),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn joint_enabled(&self, joint_index: u32) -> Option<bool> {
+        self.mapped_joint_index(joint_index)
+            .map(|index| self.constraints[index].is_enabled())
+    }
+
+    /// Returns false if the joint does not exist or the edit is not supported by the joint kind
+    ///
+    /// Limits and stop ERP/CFM are supported by 6dof and spring 6dof joints, stiffness and damping by spring 6dof joints only
+    pub(crate) fn edit_joint(&mut self, joint_index: u32, edit: JointEdit) -> bool {
+        let index = if let Some(index) = self.mapped_joint_index(joint_index) {
+            index
+        } else {
+            return false;
+        };
+
+        let constraint = &self.constraints[index];
+        let supported = match edit {
+            JointEdit::LinearLimit(..) | JointEdit::AngularLimit(..) | JointEdit::StopErp(_) | JointEdit::StopCfm(_) => {
+                matches!(constraint, Constraint::Generic6Dof(_) | Constraint::Generic6DofSpring(_))
+            }
+            JointEdit::LinearStiffness(_) | JointEdit::AngularStiffness(_) | JointEdit::LinearDamping(_) | JointEdit::AngularDamping(_) => {
+                matches!(constraint, Constraint::Generic6DofSpring(_))
+            }
+            JointEdit::Enabled(_) => true,
+        };
+        if supported {
+            self.joint_edit_apply_buffer.push((index, edit));
+        }
+        supported
+    }
+
+    pub(crate) fn apply_joint_edits(&mut self) {
+        if self.joint_edit_apply_buffer.is_empty() {
+            return;
+        }
+
+        let mut edits = std::mem::take(&mut self.joint_edit_apply_buffer);
+        for &(index, edit) in &edits {
+            match (&mut self.constraints[index], edit) {
+                (constraint, JointEdit::Enabled(enabled)) => constraint.set_enabled(enabled),
+                (Constraint::Generic6Dof(constraint), JointEdit::LinearLimit(lower, upper)) => {
+                    constraint.set_linear_lower_limit(lower);
+                    constraint.set_linear_upper_limit(upper);
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::LinearLimit(lower, upper)) => {
+                    constraint.set_linear_lower_limit(lower);
+                    constraint.set_linear_upper_limit(upper);
+                }
+                (Constraint::Generic6Dof(constraint), JointEdit::AngularLimit(lower, upper)) => {
+                    constraint.set_angular_lower_limit(lower);
+                    constraint.set_angular_upper_limit(upper);
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::AngularLimit(lower, upper)) => {
+                    constraint.set_angular_lower_limit(lower);
+                    constraint.set_angular_upper_limit(upper);
+                }
+                (Constraint::Generic6Dof(constraint), JointEdit::StopErp(value)) => {
+                    for axis in 0..6 {
+                        constraint.set_param(ConstraintParams::ConstraintStopERP, value, axis);
+                    }
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::StopErp(value)) => {
+                    for axis in 0..6 {
+                        constraint.set_param(ConstraintParams::ConstraintStopERP, value, axis);
+                    }
+                }
+                (Constraint::Generic6Dof(constraint), JointEdit::StopCfm(value)) => {
+                    for axis in 0..6 {
+                        constraint.set_param(ConstraintParams::ConstraintStopCFM, value, axis);
+                    }
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::StopCfm(value)) => {
+                    for axis in 0..6 {
+                        constraint.set_param(ConstraintParams::ConstraintStopCFM, value, axis);
+                    }
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::LinearStiffness(stiffness)) => {
+                    // same as creation, linear springs are enabled only for non zero stiffness
+                    for axis in 0..3 {
+                        if stiffness[axis] != 0.0 {
+                            constraint.set_stiffness(axis as u8, stiffness[axis]);
+                            constraint.enable_spring(axis as u8, true);
+                        } else {
+                            constraint.enable_spring(axis as u8, false);
+                        }
+                    }
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::AngularStiffness(stiffness)) => {
+                    for axis in 0..3 {
+                        constraint.set_stiffness(axis as u8 + 3, stiffness[axis]);
+                    }
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::LinearDamping(damping)) => {
+                    for axis in 0..3 {
+                        constraint.set_damping(axis as u8, damping[axis]);
+                    }
+                }
+                (Constraint::Generic6DofSpring(constraint), JointEdit::AngularDamping(damping)) => {
+                    for axis in 0..3 {
+                        constraint.set_damping(axis as u8 + 3, damping[axis]);
+                    }
+                }
+                _ => unreachable!("unsupported joint edits are rejected by edit_joint"),
+            }
+        }
+        edits.clear();
+        self.joint_edit_apply_buffer = edits;
+
+        // sleeping bodies do not react to the new joint parameters until something wakes them up
+        if self.sleeping_options.enabled {
+            self.wake_up_dynamic_bodies();
+        }
+    }
+
     pub(super) fn synced_rigidbody_states(&self) -> &[u8] {
         &self.synced_rigidbody_states
     }
 
     pub(crate) fn need_deoptimize(&self) -> bool {
-        0 < self.disabled_rigidbody_count
+        0 < self.disabled_rigidbody_count || 0 < self.blending_rigidbody_count
     }
 
     pub(crate) fn commit_body_states(&mut self, rigidbody_states: &[u8]) {
@@ -206,3 +1046,30 @@ impl PhysicsModelContext {
         self.body_kinematic_toggle_map.as_mut()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Quat, Vec3};
+
+    use super::interpolate_step_transform;
+
+    #[test]
+    fn interpolated_pose_stays_between_steps() {
+        let previous = Mat4::from_rotation_translation(Quat::from_rotation_y(0.2), Vec3::new(0.0, 10.0, 0.0));
+        let last = Mat4::from_rotation_translation(Quat::from_rotation_y(0.6), Vec3::new(1.0, 9.0, 0.5));
+        let (_, previous_rotation, previous_translation) = previous.to_scale_rotation_translation();
+        let (_, last_rotation, last_translation) = last.to_scale_rotation_translation();
+        let step_angle = previous_rotation.angle_between(last_rotation);
+
+        for weight in [-0.5, 0.0, 0.25, 0.5, 0.75, 1.0, 1.5] {
+            let (_, rotation, translation) = interpolate_step_transform(&previous, &last, weight).to_scale_rotation_translation();
+            let (min, max) = (previous_translation.min(last_translation), previous_translation.max(last_translation));
+            assert!(translation.cmpge(min - 1e-5).all() && translation.cmple(max + 1e-5).all(), "translation overshoots at {}", weight);
+            assert!(rotation.angle_between(previous_rotation) <= step_angle + 1e-5, "rotation overshoots at {}", weight);
+            assert!(rotation.angle_between(last_rotation) <= step_angle + 1e-5, "rotation overshoots at {}", weight);
+        }
+
+        assert!(interpolate_step_transform(&previous, &last, 0.0).abs_diff_eq(previous, 1e-5));
+        assert!(interpolate_step_transform(&previous, &last, 1.0).abs_diff_eq(last, 1e-5));
+    }
+}
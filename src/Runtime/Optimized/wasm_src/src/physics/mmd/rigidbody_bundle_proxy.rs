@@ -2,6 +2,9 @@ use glam::Mat4;
 
 use crate::{mmd_model_metadata::RigidBodyPhysicsMode, physics::bullet::runtime::{rigidbody_bundle::RigidBodyBundle, rigidbody_construction_info::RigidBodyConstructionInfo}};
 
+// bone animation is continuous, so tiny changes are treated as no movement to let the model sleep
+const KINEMATIC_MOVE_EPSILON: f32 = 1e-5;
+
 pub(super) struct RigidBodyProxyData {
     pub(super) linked_bone_index: Option<u32>,
     pub(super) body_offset_matrix: Mat4,
@@ -24,6 +27,10 @@ impl RigidBodyBundleProxy {
         Self { inner, data_list }
     }
 
+    pub(super) fn inner(&self) -> &RigidBodyBundle {
+        &self.inner
+    }
+
     pub(super) fn inner_mut(&mut self) -> &mut RigidBodyBundle {
         &mut self.inner
     }
@@ -36,11 +43,34 @@ impl RigidBodyBundleProxy {
         self.inner.get_buffered_motion_states().get_transform(index) * self.data_list[index].body_offset_inverse_matrix
     }
 
+    /// World transform of the body at the end of the last fixed step, without body offset
+    pub(super) fn get_step_transform(&self, index: usize) -> Mat4 {
+        self.inner.get_step_transform(index)
+    }
+
+    /// Overrides the transform read by `get_transform` until the next step, takes world transform without body offset
+    pub(super) fn set_buffered_transform(&mut self, index: usize, transform: &Mat4) {
+        self.inner.get_buffered_motion_states_mut().set_transform(index, transform);
+    }
+
     pub(super) fn set_transform(&mut self, index: usize, transform: Mat4) {
         let transform = transform * self.data_list[index].body_offset_matrix;
         self.inner.get_motion_states_mut().set_transform(index, &transform);
     }
 
+    /// Same as `set_transform`, returns false if the body stays in place
+    pub(super) fn set_transform_check_moved(&mut self, index: usize, transform: Mat4) -> bool {
+        let transform = transform * self.data_list[index].body_offset_matrix;
+        let motion_states = self.inner.get_motion_states_mut();
+        let moved = !motion_states.get_transform(index).abs_diff_eq(transform, KINEMATIC_MOVE_EPSILON);
+        motion_states.set_transform(index, &transform);
+        moved
+    }
+
+    pub(super) fn is_sleeping(&self, index: usize) -> bool {
+        self.inner.is_sleeping(index)
+    }
+
     pub(super) fn get_physics_mode(&self, index: usize) -> RigidBodyPhysicsMode {
         self.data_list[index].physics_mode
     }
@@ -0,0 +1,64 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+/// Milliseconds from an arbitrary origin, only differences on the same thread are meaningful
+pub(crate) fn now() -> f64 {
+    performance_now()
+}
+
+/// Does not touch the clock when disabled
+pub(crate) struct StageTimer {
+    last: Option<f64>,
+}
+
+impl StageTimer {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            last: if enabled { Some(now()) } else { None },
+        }
+    }
+
+    /// Milliseconds since the previous lap, 0 when disabled
+    pub(crate) fn lap(&mut self) -> f32 {
+        match self.last.as_mut() {
+            Some(last) => {
+                let current = now();
+                let elapsed = current - *last;
+                *last = current;
+                elapsed as f32
+            }
+            None => 0.0,
+        }
+    }
+}
+
+/// Stage timings of a model in milliseconds, layout read from js
+///
+/// Reset on each `before_physics`, after physics bone updates are added to the same frame
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ModelProfile {
+    pub(crate) animation: f32,
+    pub(crate) morph: f32,
+    // excludes ik
+    pub(crate) bone_update: f32,
+    pub(crate) ik: f32,
+    pub(crate) sync_bodies: f32,
+    pub(crate) sync_bones: f32,
+}
+
+/// Wall time of the runtime stages in milliseconds, layout read from js
+///
+/// Models are updated in parallel, so per model times do not add up to `models`
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct RuntimeProfile {
+    pub(crate) models: f32,
+    // sync bodies, step and sync bones of all models
+    pub(crate) physics: f32,
+}
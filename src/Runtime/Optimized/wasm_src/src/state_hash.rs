@@ -0,0 +1,33 @@
+/// FNV-1a 64 bit hash, the output is part of the public contract so it must not depend on std hasher
+pub(crate) struct StateHasher {
+    state: u64,
+}
+
+impl StateHasher {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Bit exact, so 0.0 and -0.0 are different
+    pub(crate) fn write_f32_slice(&mut self, values: &[f32]) {
+        for value in values {
+            self.write(&value.to_le_bytes());
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.state
+    }
+}
@@ -0,0 +1,86 @@
+//! Broken metadata must be reported through the diagnostic instead of aborting the wasm instance
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Take error messages from the diagnostic of the runtime
+macro_rules! take_errors {
+    ($runtime:expr) => {{
+        let result = $runtime.acquire_diagnostic_error_result() as *const usize;
+        // DiagnosticResult { ptr: *const StringPtr, len: usize }, StringPtr { ptr: *const u8, len: usize }
+        let errors = unsafe {
+            let strings = std::slice::from_raw_parts(*result as *const [usize; 2], *result.add(1));
+            strings
+                .iter()
+                .map(|&[ptr, len]| String::from_utf8_lossy(std::slice::from_raw_parts(ptr as *const u8, len)).into_owned())
+                .collect::<Vec<_>>()
+        };
+        $runtime.release_diagnostic_result();
+        errors
+    }};
+}
+
+fn header(magic: &[u8; 4], version: u32, section_count: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(magic);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes.extend_from_slice(&section_count.to_le_bytes());
+    bytes
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn truncated_metadata_creates_no_model() {
+    mmd_wasm_runtime::init();
+    let mut runtime = mmd_wasm_runtime::create_mmd_runtime();
+
+    let bytes = header(b"MMDM", 1, 3);
+    for length in 0..bytes.len() {
+        let ptr = runtime.create_mmd_model(bytes.as_ptr(), length);
+        assert!(ptr.is_null());
+
+        let errors = take_errors!(runtime);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Failed to create mmd model: Unexpected end of metadata"), "{}", errors[0]);
+    }
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn invalid_magic_creates_no_model() {
+    mmd_wasm_runtime::init();
+    let mut runtime = mmd_wasm_runtime::create_mmd_runtime();
+
+    let bytes = header(b"PMX ", 1, 0);
+    assert!(runtime.create_mmd_model(bytes.as_ptr(), bytes.len()).is_null());
+
+    let errors = take_errors!(runtime);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Invalid metadata magic"), "{}", errors[0]);
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn unknown_version_creates_no_model() {
+    mmd_wasm_runtime::init();
+    let mut runtime = mmd_wasm_runtime::create_mmd_runtime();
+
+    let bytes = header(b"MMDM", 2, 0);
+    assert!(runtime.create_mmd_model(bytes.as_ptr(), bytes.len()).is_null());
+
+    let errors = take_errors!(runtime);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Unsupported metadata version: 2"), "{}", errors[0]);
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn broken_section_table_fails_validation() {
+    mmd_wasm_runtime::init();
+    let mut runtime = mmd_wasm_runtime::create_mmd_runtime();
+
+    let mut bytes = header(b"MMDM", 1, 1);
+    bytes.extend_from_slice(&0u32.to_le_bytes()); // kind
+    bytes.extend_from_slice(&1024u32.to_le_bytes()); // offset
+    bytes.extend_from_slice(&4u32.to_le_bytes()); // size
+    assert!(runtime.validate_mmd_model_metadata(bytes.as_ptr(), bytes.len()).is_null());
+
+    let errors = take_errors!(runtime);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Invalid metadata section table entry: 0"), "{}", errors[0]);
+}
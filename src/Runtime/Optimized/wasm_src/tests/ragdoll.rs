@@ -0,0 +1,110 @@
+//! Ragdoll is derived from the rigid bodies and the bone hierarchy when the model is created with the ragdoll option
+#![cfg(feature = "physics")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Follow bone body on the root bone and a physics body on its child, held by a joint
+const CONSTRAINT_TEST: &[u8] = include_bytes!("../../../../../res/model/constraint_test.pmx"); // no morphs
+
+/// Physics options metadata of world 0 with the default ragdoll chunk or without it
+///
+/// Same layout as `MmdMetadataEncoder.encodePhysicsOptions` of the TS side
+fn physics_options(ragdoll: bool) -> Vec<u8> {
+    let mut bones = Vec::new();
+    bones.extend_from_slice(&[0u8; 4 * 3]); // bone count, append transform count, ik count
+
+    let morphs = 0u32.to_le_bytes().to_vec(); // morph count
+
+    let mut physics = vec![2, 0, 0, 0]; // full physics, padding
+    physics.extend_from_slice(&0u32.to_le_bytes()); // physics world id
+    physics.extend_from_slice(&0u32.to_le_bytes()); // kinematic shared physics world id count
+    for i in 0..16 {
+        physics.extend_from_slice(&(if i % 5 == 0 { 1.0f32 } else { 0.0 }).to_le_bytes()); // identity model initial world matrix
+    }
+    physics.extend_from_slice(&[0u8; 4]); // disable offset for constraint frame, padding
+    physics.extend_from_slice(&[0u8; 4 * 2]); // rigid body count, joint count
+
+    let mut extensions = Vec::new();
+    if ragdoll {
+        extensions.extend_from_slice(&2u32.to_le_bytes()); // ragdoll tag
+        extensions.extend_from_slice(&(4 * 9u32).to_le_bytes());
+        let limit = std::f32::consts::FRAC_PI_4;
+        for value in [1.0, -limit, -limit, -limit, limit, limit, limit, 0.2, 0.5] {
+            extensions.extend_from_slice(&f32::to_le_bytes(value));
+        }
+    }
+
+    let sections = [bones, morphs, physics, extensions];
+    let section_count = if ragdoll { 4 } else { 3 };
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"MMDM");
+    bytes.extend_from_slice(&1u32.to_le_bytes()); // version
+    bytes.extend_from_slice(&(section_count as u32).to_le_bytes());
+    let mut offset = 4 * 3 + 4 * 3 * section_count;
+    for (kind, section) in sections.iter().enumerate().take(section_count) {
+        bytes.extend_from_slice(&(kind as u32).to_le_bytes());
+        bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+        bytes.extend_from_slice(&(section.len() as u32).to_le_bytes());
+        offset += section.len();
+    }
+    for section in sections {
+        bytes.extend_from_slice(&section);
+    }
+    bytes
+}
+
+/// Y translation of the bone world matrix
+macro_rules! bone_height {
+    ($runtime:expr, $ptr:expr, $bone_index:expr) => {{
+        let world_matrices = $runtime.get_bone_world_matrix_arena($ptr);
+        unsafe { *world_matrices.add($bone_index * 16 + 13) }
+    }};
+}
+
+macro_rules! step {
+    ($runtime:expr, $step_count:expr) => {
+        for frame in 0..$step_count {
+            $runtime.before_physics(Some(frame as f32), Some(1.0 / 60.0));
+            $runtime.after_physics();
+        }
+    };
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn model_without_ragdoll_option_can_not_switch() {
+    mmd_wasm_runtime::init();
+    let mut runtime = mmd_wasm_runtime::create_mmd_runtime();
+
+    let options = physics_options(false);
+    let ptr = runtime.create_mmd_model_from_pmx(CONSTRAINT_TEST.as_ptr(), CONSTRAINT_TEST.len(), options.as_ptr(), options.len(), std::ptr::null_mut(), 0);
+    assert!(!ptr.is_null());
+
+    assert!(!runtime.set_mmd_model_ragdoll(ptr, true));
+    assert_eq!(runtime.is_mmd_model_ragdoll_enabled(ptr), None);
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn loaded_model_falls_in_ragdoll_and_returns_to_animation() {
+    mmd_wasm_runtime::init();
+    let mut runtime = mmd_wasm_runtime::create_mmd_runtime();
+
+    let options = physics_options(true);
+    let ptr = runtime.create_mmd_model_from_pmx(CONSTRAINT_TEST.as_ptr(), CONSTRAINT_TEST.len(), options.as_ptr(), options.len(), std::ptr::null_mut(), 0);
+    assert!(!ptr.is_null());
+    assert_eq!(runtime.is_mmd_model_ragdoll_enabled(ptr), Some(false));
+
+    // root bone is held by animation while its follow bone body is kinematic
+    step!(runtime, 30);
+    let rest_height = bone_height!(runtime, ptr, 0);
+
+    assert!(runtime.set_mmd_model_ragdoll(ptr, true));
+    step!(runtime, 30);
+    assert_eq!(runtime.is_mmd_model_ragdoll_enabled(ptr), Some(true));
+    assert!(bone_height!(runtime, ptr, 0) < rest_height - 0.5, "root bone did not fall in ragdoll");
+
+    // without blend transition time, bodies snap back to the bones
+    assert!(runtime.set_mmd_model_ragdoll(ptr, false));
+    step!(runtime, 30);
+    assert_eq!(runtime.is_mmd_model_ragdoll_enabled(ptr), Some(false));
+    assert!((bone_height!(runtime, ptr, 0) - rest_height).abs() < 0.001, "root bone did not return to animation");
+}